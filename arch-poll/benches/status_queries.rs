@@ -0,0 +1,53 @@
+// Status query benchmarks.
+// Each contract holds many live polls of which only a few are still open, the shape a long-running
+// deployment settles into. Listing the active polls and sweeping expirations should cost about the
+// same whatever the number of closed polls, since the status index only visits open ones.
+// Run with `cargo bench --bench status_queries`.
+use arch_poll::address::Address;
+use arch_poll::clock::MockClock;
+use arch_poll::VotingContract;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// Polls kept open in every contract, however many have closed
+const OPEN_POLLS: usize = 10;
+
+// Build a contract holding `closed_polls` closed polls and OPEN_POLLS open ones
+fn contract_with_polls(closed_polls: usize, clock: &MockClock) -> VotingContract {
+    let admin_address: Address = "wallet_admin".parse().unwrap();
+    let mut contract = VotingContract::new(admin_address.clone());
+    contract.set_clock(Box::new(clock.clone()));
+    for poll_number in 0..closed_polls + OPEN_POLLS {
+        let poll_duration_seconds = if poll_number < closed_polls { 60 } else { 3_600 };
+        contract
+            .create_poll(
+                format!("wallet_creator{}", poll_number).parse().unwrap(),
+                format!("Poll {}", poll_number),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                poll_duration_seconds,
+            )
+            .unwrap();
+    }
+    clock.advance(60);
+    contract.process_expired_polls(&admin_address).unwrap();
+    contract
+}
+
+fn bench_status_queries(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("status_queries");
+    let admin_address: Address = "wallet_admin".parse().unwrap();
+    for closed_polls in [1_000, 10_000, 100_000] {
+        let clock = MockClock::new(1_000);
+        let mut contract = contract_with_polls(closed_polls, &clock);
+        group.bench_with_input(BenchmarkId::new("get_active_polls", closed_polls), &closed_polls, |bencher, _| {
+            bencher.iter(|| contract.get_active_polls().len())
+        });
+        group.bench_with_input(BenchmarkId::new("process_expired_polls", closed_polls), &closed_polls, |bencher, _| {
+            bencher.iter(|| contract.process_expired_polls(&admin_address).unwrap().len())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_status_queries);
+criterion_main!(benches);
\ No newline at end of file
@@ -0,0 +1,139 @@
+// Vote throughput benchmarks.
+// Measures the calls whose cost grows with a poll's size: casting a vote into a poll that already
+// holds many ballots, sweeping thousands of due polls at once, and tallying ranked and weighted
+// polls. Run with `cargo bench --bench vote_throughput` and compare against a previous run to
+// catch regressions from new features. Building the largest polls takes a while.
+use arch_poll::address::Address;
+use arch_poll::clock::MockClock;
+use arch_poll::VotingContract;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+// Options on every benchmarked ballot
+const OPTIONS: [&str; 4] = ["Red", "Green", "Blue", "Yellow"];
+
+// Create a contract on a frozen clock, so no poll ends while it is measured
+fn frozen_contract() -> VotingContract {
+    let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+    contract.set_clock(Box::new(MockClock::new(1_000)));
+    contract
+}
+
+fn voter(voter_number: usize) -> Address {
+    format!("wallet_voter{}", voter_number).parse().unwrap()
+}
+
+fn voting_options() -> Vec<String> {
+    OPTIONS.iter().map(|option| option.to_string()).collect()
+}
+
+fn bench_vote(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("vote");
+    group.sample_size(10);
+    for participants in [10_000, 100_000, 1_000_000] {
+        let mut contract = frozen_contract();
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Colour".to_string(),
+                String::new(),
+                voting_options(),
+                3_600,
+            )
+            .unwrap();
+        for voter_number in 0..participants {
+            let selected_option = OPTIONS[voter_number % OPTIONS.len()];
+            contract.vote(&poll_id, voter(voter_number), selected_option).unwrap();
+        }
+        
+        let mut next_voter_number = participants;
+        group.bench_with_input(BenchmarkId::from_parameter(participants), &participants, |bencher, _| {
+            bencher.iter(|| {
+                next_voter_number += 1;
+                contract.vote(&poll_id, voter(next_voter_number), "Red").unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_process_expired_polls(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("process_expired_polls");
+    group.sample_size(10);
+    let admin_address: Address = "wallet_admin".parse().unwrap();
+    for due_polls in [1_000, 5_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(due_polls), &due_polls, |bencher, &due_polls| {
+            bencher.iter_batched(
+                || {
+                    let clock = MockClock::new(1_000);
+                    let mut contract = VotingContract::new(admin_address.clone());
+                    contract.set_clock(Box::new(clock.clone()));
+                    for poll_number in 0..due_polls {
+                        contract
+                            .create_poll(
+                                format!("wallet_creator{}", poll_number).parse().unwrap(),
+                                format!("Poll {}", poll_number),
+                                String::new(),
+                                voting_options(),
+                                60,
+                            )
+                            .unwrap();
+                    }
+                    clock.advance(60);
+                    contract
+                },
+                |mut contract| contract.process_expired_polls(&admin_address).unwrap().len(),
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_tally(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("tally");
+    group.sample_size(10);
+    let admin_address: Address = "wallet_admin".parse().unwrap();
+    for ballots in [1_000, 10_000] {
+        let mut contract = frozen_contract();
+        let ranked_poll_id = contract
+            .create_ranked_poll(
+                "wallet_creator".parse().unwrap(),
+                "Ranked".to_string(),
+                String::new(),
+                voting_options(),
+                3_600,
+            )
+            .unwrap();
+        let weighted_poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Weighted".to_string(),
+                String::new(),
+                voting_options(),
+                3_600,
+            )
+            .unwrap();
+        for voter_number in 0..ballots {
+            let voter_address = voter(voter_number);
+            let mut ranking = voting_options();
+            ranking.rotate_left(voter_number % OPTIONS.len());
+            contract.vote_ranked(&ranked_poll_id, voter_address.clone(), ranking).unwrap();
+            contract.set_voter_weight(&voter_address, 1 + voter_number as u128 % 100, &admin_address).unwrap();
+            contract.vote(&weighted_poll_id, voter_address, OPTIONS[voter_number % 3]).unwrap();
+        }
+        
+        group.bench_with_input(BenchmarkId::new("ranked", ballots), &ballots, |bencher, _| {
+            bencher.iter(|| contract.get_poll_results(&ranked_poll_id).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("weighted", ballots), &ballots, |bencher, _| {
+            bencher.iter(|| contract.get_weighted_results(&weighted_poll_id).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("weighted_winner", ballots), &ballots, |bencher, _| {
+            bencher.iter(|| contract.get_poll_winner(&weighted_poll_id).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_vote, bench_process_expired_polls, bench_tally);
+criterion_main!(benches);
\ No newline at end of file
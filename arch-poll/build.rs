@@ -0,0 +1,13 @@
+// Build script.
+// Compiles proto/voting.proto into the gRPC messages and service (feature `grpc`). Other builds
+// have nothing to generate.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    
+    #[cfg(feature = "grpc")]
+    {
+        let protoc_path = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc is missing");
+        std::env::set_var("PROTOC", protoc_path);
+        tonic_build::compile_protos("proto/voting.proto").expect("proto/voting.proto does not compile");
+    }
+}
\ No newline at end of file
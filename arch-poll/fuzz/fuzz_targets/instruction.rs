@@ -0,0 +1,31 @@
+// Instruction fuzzing.
+// Instruction data arrives from untrusted transactions, so decoding it and applying it to an
+// initialized contract must fail cleanly on any input rather than panic or corrupt the state
+// account. Run with `cargo +nightly fuzz run instruction`.
+#![no_main]
+
+use arch_poll::clock::MockClock;
+use arch_poll::encoding;
+use arch_poll::program::{load_state, process_instruction, AccountInfo, VotingInstruction};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|instruction_data: &[u8]| {
+    // Whatever decodes must encode back to the same bytes
+    if let Ok(instruction) = encoding::from_bytes::<VotingInstruction>(instruction_data) {
+        assert_eq!(instruction.to_instruction_data(), instruction_data);
+    }
+    
+    let program_id = [7u8; 32];
+    let state_account = AccountInfo::new([1; 32], program_id, false, true, Vec::new());
+    let signer_account = AccountInfo::new([2; 32], [0; 32], true, false, Vec::new());
+    let accounts = [state_account, signer_account];
+    let initialize = VotingInstruction::Initialize.to_instruction_data();
+    process_instruction(&program_id, &accounts, &initialize, Box::new(MockClock::new(1_000))).unwrap();
+    
+    // Failed instructions leave the account untouched, and the account always loads afterwards
+    let state_before = accounts[0].data.borrow().clone();
+    if process_instruction(&program_id, &accounts, instruction_data, Box::new(MockClock::new(1_000))).is_err() {
+        assert_eq!(*accounts[0].data.borrow(), state_before);
+    }
+    load_state(&accounts[0]).unwrap();
+});
\ No newline at end of file
@@ -0,0 +1,216 @@
+// Role-based permissions for contract administration.
+// Addresses hold roles, roles grant permissions, and every privileged contract method checks a
+// permission rather than comparing against a fixed admin address.
+use crate::address::Address;
+use crate::collections::{HashMap, HashSet};
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::prelude::*;
+use crate::{Result, VotingError};
+
+// Define the roles an address can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Role {
+    SuperAdmin,  // Every permission, including managing roles
+    Moderator,   // Closes and moderates other creators' polls
+    PollManager, // Closes, configures, starts and expires other creators' polls
+}
+
+// Define the privileged actions roles are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ManageRoles,           // Grant and revoke roles
+    SetVoterWeights,       // Register voting weights
+    ClosePolls,            // Close polls created by someone else
+    ConfigurePolls,        // Change settings of polls created by someone else
+    ProcessExpiredPolls,   // Close polls that have passed their end time
+    ProcessScheduledPolls, // Mark scheduled polls started once their start time passes
+    ModeratePolls,         // Moderation actions on polls and their content
+    ManageTreasury,        // Set the creation fee and withdraw collected fees
+    PauseContract,         // Stop and restart poll creation and voting contract-wide
+    ReopenPolls,           // Take votes in ended polls again
+    PurgePolls,            // Compact archived polls down to their results
+}
+
+impl Role {
+    // Check whether the role grants a permission
+    pub fn grants(&self, permission: Permission) -> bool {
+        match self {
+            Role::SuperAdmin => true,
+            Role::Moderator => matches!(permission, Permission::ClosePolls | Permission::ModeratePolls),
+            Role::PollManager => matches!(
+                permission,
+                Permission::ClosePolls
+                    | Permission::ConfigurePolls
+                    | Permission::ProcessExpiredPolls
+                    | Permission::ProcessScheduledPolls
+            ),
+        }
+    }
+}
+
+impl Encode for Role {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            Role::SuperAdmin => 0,
+            Role::Moderator => 1,
+            Role::PollManager => 2,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for Role {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(Role::SuperAdmin),
+            1 => Ok(Role::Moderator),
+            2 => Ok(Role::PollManager),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+// Define the role assignments of a contract. There is always at least one SuperAdmin until the
+// last one renounces, which leaves no roles at all.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessControl {
+    role_members: HashMap<Role, HashSet<Address>>, // Addresses holding each role
+}
+
+impl AccessControl {
+    // Create the role assignments with a single SuperAdmin
+    pub fn new(super_admin_address: Address) -> Self {
+        AccessControl {
+            role_members: HashMap::from([(Role::SuperAdmin, HashSet::from([super_admin_address]))]),
+        }
+    }
+    
+    pub fn has_role(&self, address: &Address, role: Role) -> bool {
+        self.role_members.get(&role).is_some_and(|members| members.contains(address))
+    }
+    
+    // Check whether any role held by the address grants the permission
+    pub fn has_permission(&self, address: &Address, permission: Permission) -> bool {
+        self.role_members
+            .iter()
+            .any(|(role, members)| role.grants(permission) && members.contains(address))
+    }
+    
+    // Get the addresses holding a role, sorted
+    pub fn members(&self, role: Role) -> Vec<&Address> {
+        let mut members: Vec<&Address> = self.role_members.get(&role).into_iter().flatten().collect();
+        members.sort();
+        members
+    }
+    
+    // Give an address a role, returning whether it was newly granted
+    pub fn grant(&mut self, address: Address, role: Role) -> bool {
+        self.role_members.entry(role).or_default().insert(address)
+    }
+    
+    // Take a role from an address, returning whether it was held.
+    // The last SuperAdmin cannot be removed.
+    pub fn revoke(&mut self, address: &Address, role: Role) -> Result<bool> {
+        if !self.has_role(address, role) {
+            return Ok(false);
+        }
+        
+        if role == Role::SuperAdmin && self.members(Role::SuperAdmin).len() == 1 {
+            return Err(VotingError::CannotRemoveLastAdmin);
+        }
+        
+        if let Some(members) = self.role_members.get_mut(&role) {
+            members.remove(address);
+            if members.is_empty() {
+                self.role_members.remove(&role);
+            }
+        }
+        Ok(true)
+    }
+    
+    // Take SuperAdmin from an address even when it is the last one, returning whether it was
+    // held. Without a SuperAdmin nobody could manage roles again, so the last one renouncing
+    // drops every other role as well.
+    pub fn renounce(&mut self, address: &Address) -> bool {
+        if !self.has_role(address, Role::SuperAdmin) {
+            return false;
+        }
+        
+        if self.members(Role::SuperAdmin).len() == 1 {
+            self.role_members.clear();
+        } else if let Some(members) = self.role_members.get_mut(&Role::SuperAdmin) {
+            members.remove(address);
+        }
+        true
+    }
+    
+    // Rewrite every member in canonical form, as after a change of address format. Members the
+    // canonical function cannot parse keep their stored form.
+    pub fn canonicalize(&mut self, canonical: impl Fn(&str) -> Option<Address>) {
+        for members in self.role_members.values_mut() {
+            *members = core::mem::take(members)
+                .into_iter()
+                .map(|address| canonical(&address).unwrap_or(address))
+                .collect();
+        }
+    }
+    
+    // Check whether any address still holds SuperAdmin
+    pub fn has_admin(&self) -> bool {
+        self.role_members.contains_key(&Role::SuperAdmin)
+    }
+}
+
+impl Encode for AccessControl {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.role_members);
+    }
+}
+
+impl Decode for AccessControl {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let role_members: HashMap<Role, HashSet<Address>> = decoder.read()?;
+        
+        // Empty role sets are never stored, and a SuperAdmin must exist unless every role was renounced
+        let renounced = role_members.is_empty();
+        if role_members.values().any(HashSet::is_empty) || !(renounced || role_members.contains_key(&Role::SuperAdmin)) {
+            return Err(VotingError::CorruptState);
+        }
+        Ok(AccessControl { role_members })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_role_permissions() {
+        let mut access_control = AccessControl::new("wallet_admin".parse().unwrap());
+        assert!(access_control.grant("wallet_manager".parse().unwrap(), Role::PollManager));
+        assert!(!access_control.grant("wallet_manager".parse().unwrap(), Role::PollManager));
+        access_control.grant("wallet_moderator".parse().unwrap(), Role::Moderator);
+        
+        assert!(access_control.has_permission(&"wallet_admin".parse().unwrap(), Permission::ManageRoles));
+        assert!(access_control.has_permission(&"wallet_manager".parse().unwrap(), Permission::ProcessExpiredPolls));
+        assert!(!access_control.has_permission(&"wallet_manager".parse().unwrap(), Permission::ManageRoles));
+        assert!(access_control.has_permission(&"wallet_moderator".parse().unwrap(), Permission::ModeratePolls));
+        assert!(!access_control.has_permission(&"wallet_moderator".parse().unwrap(), Permission::ConfigurePolls));
+        assert!(!access_control.has_permission(&"wallet_stranger".parse().unwrap(), Permission::ClosePolls));
+        
+        // The last SuperAdmin stays; other roles can be emptied
+        let result = access_control.revoke(&"wallet_admin".parse().unwrap(), Role::SuperAdmin);
+        assert!(matches!(result, Err(VotingError::CannotRemoveLastAdmin)));
+        assert!(access_control.revoke(&"wallet_manager".parse().unwrap(), Role::PollManager).unwrap());
+        assert!(!access_control.revoke(&"wallet_manager".parse().unwrap(), Role::PollManager).unwrap());
+        assert!(access_control.members(Role::PollManager).is_empty());
+        
+        // Renouncing the last SuperAdmin leaves no roles, which still round-trips
+        assert!(access_control.renounce(&"wallet_admin".parse().unwrap()));
+        assert!(
+            !access_control.has_admin()
+                && !access_control.has_role(&"wallet_moderator".parse().unwrap(), Role::Moderator)
+        );
+        assert_eq!(crate::encoding::from_bytes(&crate::encoding::to_bytes(&access_control)), Ok(access_control));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,152 @@
+// Storage of a single poll in its own fixed-size account.
+// Runtimes such as Arch and Solana allocate account data up front, so a poll is written at the
+// start of the account behind a version byte and the unused tail is kept zeroed. The encoding
+// follows borsh layout (see encoding.rs), so off-chain borsh readers can decode the same bytes.
+// Accounts written in an older layout are upgraded when read and written back in the current one.
+use crate::encoding::{self, Decode, Decoder, Encode, Encoder};
+use crate::state::SNAPSHOT_SCHEMA_VERSION;
+use crate::{Poll, Result, VotingError};
+
+// Layout version written as the first byte of every poll account
+pub const POLL_ACCOUNT_VERSION: u8 = 14;
+
+// Get the snapshot schema version whose poll layout an account version holds
+fn poll_layout(version: u8) -> Result<u16> {
+    match version {
+        1 => Ok(1),
+        2 => Ok(2), // Polls gained metadata
+        3 => Ok(3), // Polls gained a vote fee
+        4 => Ok(4), // Polls gained runoff rounds
+        5 => Ok(5), // Polls gained a tabulation method
+        6 => Ok(6), // Polls gained conviction state
+        7 => Ok(7), // Polls gained dependencies
+        8 => Ok(8), // Polls gained anonymous ballots
+        9 => Ok(9), // Polls gained encrypted ballots
+        10 => Ok(10), // Polls gained a results visibility
+        11 => Ok(11), // Polls gained compact participant sets
+        12 => Ok(12), // Polls gained a link to the poll they re-run
+        13 => Ok(13), // Polls gained vote rationales
+        14 => Ok(14), // Polls keyed their tallies by option ID
+        _ => Err(VotingError::CorruptState),
+    }
+}
+
+// Define the contents of a poll account
+#[derive(Debug, Clone)]
+pub struct PollAccount {
+    pub version: u8, // Layout version of the stored poll
+    pub poll: Poll,
+}
+
+impl PollAccount {
+    // Wrap a poll in the current account layout
+    pub fn new(poll: Poll) -> Self {
+        PollAccount {
+            version: POLL_ACCOUNT_VERSION,
+            poll,
+        }
+    }
+    
+    // Get the number of account bytes the poll needs, version byte included
+    pub fn serialized_size(&self) -> usize {
+        1 + self.poll.serialized_size()
+    }
+    
+    // Write the account into account data, zeroing the bytes after it.
+    // Fails with InvalidAccount when the data is too small to hold the poll.
+    pub fn pack_into(&self, data: &mut [u8]) -> Result<()> {
+        let bytes = encoding::to_bytes(self);
+        if bytes.len() > data.len() {
+            return Err(VotingError::InvalidAccount);
+        }
+        
+        let (used, unused) = data.split_at_mut(bytes.len());
+        used.copy_from_slice(&bytes);
+        unused.fill(0);
+        Ok(())
+    }
+    
+    // Read an account back from account data, rejecting unknown versions, inconsistent polls
+    // and anything but zeros after the encoded poll
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        let mut decoder = Decoder::new(data);
+        let account: PollAccount = decoder.read()?;
+        let padding = decoder.read_bytes(data.len() - decoder.position())?;
+        if padding.iter().any(|&byte| byte != 0) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        account.poll.validate_invariants()?;
+        Ok(account)
+    }
+}
+
+impl Encode for PollAccount {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.version);
+        encoder.write(&self.poll);
+    }
+}
+
+impl Decode for PollAccount {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let version: u8 = decoder.read()?;
+        decoder.set_layout(poll_layout(version)?);
+        let poll = decoder.read()?;
+        decoder.set_layout(SNAPSHOT_SCHEMA_VERSION);
+        Ok(PollAccount {
+            version: POLL_ACCOUNT_VERSION,
+            poll,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VotingContract;
+    
+    #[test]
+    fn test_pack_and_unpack() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Stored Poll".to_string(),
+                "Kept in its own account".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+        let account = PollAccount::new(contract.get_poll(&poll_id).unwrap().clone());
+        
+        // Accounts are allocated with room to spare; the tail stays zeroed
+        let mut data = vec![0xff; account.serialized_size() + 32];
+        account.pack_into(&mut data).unwrap();
+        assert!(data[account.serialized_size()..].iter().all(|&byte| byte == 0));
+        
+        let restored = PollAccount::unpack(&data).unwrap();
+        assert_eq!(restored.poll.get_results(), account.poll.get_results());
+        assert!(restored.poll.participant_addresses.contains(&"wallet_voter".parse().unwrap()));
+        
+        let mut too_small = vec![0; account.serialized_size() - 1];
+        assert!(matches!(account.pack_into(&mut too_small), Err(VotingError::InvalidAccount)));
+        
+        // Accounts from before polls had metadata are upgraded when read
+        let mut encoder = Encoder::with_layout(1);
+        encoder.write(&1u8);
+        encoder.write(&account.poll);
+        let mut old_data = encoder.finish();
+        old_data.resize(old_data.len() + 8, 0);
+        assert_eq!(PollAccount::unpack(&old_data).unwrap().version, POLL_ACCOUNT_VERSION);
+        
+        // Unknown versions and garbage after the poll are rejected
+        let mut unknown_version = data.clone();
+        unknown_version[0] = POLL_ACCOUNT_VERSION + 1;
+        assert!(matches!(PollAccount::unpack(&unknown_version), Err(VotingError::CorruptState)));
+        let last = data.len() - 1;
+        data[last] = 1;
+        assert!(matches!(PollAccount::unpack(&data), Err(VotingError::CorruptState)));
+    }
+}
\ No newline at end of file
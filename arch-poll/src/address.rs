@@ -0,0 +1,398 @@
+// Wallet addresses.
+// Every address the contract takes, from creators, voters, callers and allowlists alike, is parsed
+// into an Address before it is used. Parsing trims surrounding whitespace, rejects empty and
+// overlong addresses, and hands the rest to the contract's AddressFormat, which decides what a
+// valid address looks like and gives its canonical form, so two spellings of one wallet always
+// meet as the same voter. AnyAddress, the default, accepts any address without whitespace or
+// control characters. HexAddress and Base58Address check an encoding and its length, and
+// Base58Address can also check a Base58Check checksum as used by legacy Bitcoin addresses. Other
+// schemes, such as bech32, plug in their own AddressFormat. Polls and events keep the canonical
+// string an Address holds, so snapshots do not change with the format.
+// Signed votes take the voter from an ed25519 public key, and the format decides how a key is
+// written as an address: in hex by default, or base58-encoded by Base58Address. A format that
+// cannot express a 32-byte key, such as HexAddress with another length, refuses signed votes.
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::hash::{sha256, to_hex};
+use crate::prelude::*;
+use crate::{Result, VotingError};
+
+// Longest address accepted in any format, in bytes
+pub const MAX_ADDRESS_LENGTH: usize = 128;
+
+// Digits of the base58 alphabet, in value order
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Bytes of the checksum a Base58Check address ends with
+const BASE58_CHECKSUM_LENGTH: usize = 4;
+
+// Decides which addresses are valid and gives their canonical form
+pub trait AddressFormat: Send + Sync {
+    // Get the canonical form of a trimmed, non-empty address, or None if it is not valid
+    fn canonicalize(&self, address: &str) -> Option<String>;
+    
+    // Get the canonical address of an ed25519 public key, or None if the format has none. The
+    // key in hex is used unless a format overrides this.
+    fn key_address(&self, public_key: &[u8; 32]) -> Option<String> {
+        self.canonicalize(&to_hex(public_key))
+    }
+}
+
+// Accepts any address without whitespace or control characters. Addresses are NOT case-normalized:
+// base58 encodings are case-sensitive, so folding case could merge two distinct wallets into one
+// voter.
+pub struct AnyAddress;
+
+impl AddressFormat for AnyAddress {
+    fn canonicalize(&self, address: &str) -> Option<String> {
+        if address.chars().any(|character| character.is_whitespace() || character.is_control()) {
+            return None;
+        }
+        Some(address.to_string())
+    }
+}
+
+// Accepts hex addresses of a fixed number of bytes, with or without a 0x prefix. Hex is not
+// case-sensitive, so the canonical form is lowercase without the prefix.
+pub struct HexAddress {
+    pub length: usize, // Bytes the address encodes
+}
+
+impl AddressFormat for HexAddress {
+    fn canonicalize(&self, address: &str) -> Option<String> {
+        let digits = address.strip_prefix("0x").unwrap_or(address);
+        if digits.len() != self.length * 2 || !digits.chars().all(|character| character.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(digits.to_ascii_lowercase())
+    }
+}
+
+// Accepts base58 addresses decoding to a fixed number of bytes. With a checksum the address
+// carries 4 more bytes, which must start the double SHA-256 of the rest (Base58Check).
+pub struct Base58Address {
+    pub length: usize,  // Bytes the address encodes, not counting the checksum
+    pub checksum: bool, // Whether the address ends with a Base58Check checksum
+}
+
+impl Base58Address {
+    // Accept legacy Bitcoin addresses: a version byte and a 20-byte hash, with a checksum
+    pub fn bitcoin() -> Self {
+        Base58Address {
+            length: 21,
+            checksum: true,
+        }
+    }
+}
+
+impl AddressFormat for Base58Address {
+    fn canonicalize(&self, address: &str) -> Option<String> {
+        let decoded = base58_decode(address)?;
+        if !self.checksum {
+            return (decoded.len() == self.length).then(|| address.to_string());
+        }
+        if decoded.len() != self.length + BASE58_CHECKSUM_LENGTH {
+            return None;
+        }
+        
+        let (payload, checksum) = decoded.split_at(self.length);
+        let expected_checksum = sha256(&sha256(payload));
+        (checksum == &expected_checksum[..BASE58_CHECKSUM_LENGTH]).then(|| address.to_string())
+    }
+    
+    // The key itself is the payload, so only formats of 32 bytes have key addresses
+    fn key_address(&self, public_key: &[u8; 32]) -> Option<String> {
+        if self.length != public_key.len() {
+            return None;
+        }
+        let mut payload = public_key.to_vec();
+        if self.checksum {
+            payload.extend_from_slice(&sha256(&sha256(public_key))[..BASE58_CHECKSUM_LENGTH]);
+        }
+        Some(base58_encode(&payload))
+    }
+}
+
+// Encode bytes as base58 text, the inverse of base58_decode
+fn base58_encode(bytes: &[u8]) -> String {
+    // Bytes accumulate into a little-endian number, one base58 digit per element
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    
+    // Each leading zero byte is written as a '1'
+    let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    let mut encoded = "1".repeat(leading_zeros);
+    encoded.extend(digits.iter().rev().map(|&digit| BASE58_ALPHABET[digit as usize] as char));
+    encoded
+}
+
+// Decode base58 text into bytes, or None if it holds a character outside the alphabet
+fn base58_decode(encoded: &str) -> Option<Vec<u8>> {
+    // Digits accumulate into a little-endian number, one byte per element
+    let mut decoded: Vec<u8> = Vec::new();
+    for character in encoded.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&digit| digit == character)? as u32;
+        for byte in decoded.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            decoded.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    
+    // Each leading '1' stands for a leading zero byte
+    let leading_zeros = encoded.bytes().take_while(|&character| character == b'1').count();
+    decoded.resize(decoded.len() + leading_zeros, 0);
+    decoded.reverse();
+    Some(decoded)
+}
+
+// Define a wallet address that has been validated and put in canonical form. Parsing with
+// FromStr uses the default format; the contract checks every Address it is given again under its
+// own format, so one parsed elsewhere is still put in the contract's canonical form.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct Address(String);
+
+impl Address {
+    // Parse an address in a format, trimming surrounding whitespace
+    pub fn parse(address: &str, address_format: &dyn AddressFormat) -> Result<Address> {
+        let trimmed_address = address.trim();
+        if trimmed_address.is_empty() || trimmed_address.len() > MAX_ADDRESS_LENGTH {
+            return Err(VotingError::InvalidAddress);
+        }
+        
+        address_format
+            .canonicalize(trimmed_address)
+            .map(Address)
+            .ok_or(VotingError::InvalidAddress)
+    }
+    
+    // Wrap text that was not checked, for lookups that must match nothing when it is invalid
+    pub(crate) fn unchecked(address: String) -> Address {
+        Address(address)
+    }
+    
+    // Get the canonical address
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    
+    // Take the canonical address as a string
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl FromStr for Address {
+    type Err = VotingError;
+    
+    fn from_str(address: &str) -> Result<Self> {
+        Address::parse(address, &AnyAddress)
+    }
+}
+
+// Debug prints the quoted text, so events and logs naming a wallet read as they did with plain
+// addresses
+impl fmt::Debug for Address {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, formatter)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Address {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// Lets maps keyed by Address be searched with the address as text
+impl Borrow<str> for Address {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Address {
+    type Target = str;
+    
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Address {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Address {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = VotingError;
+    
+    fn try_from(address: String) -> Result<Self> {
+        address.parse()
+    }
+}
+
+impl From<Address> for String {
+    fn from(address: Address) -> String {
+        address.0
+    }
+}
+
+impl Encode for Address {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.0);
+    }
+}
+
+// Snapshots do not record the format their addresses were canonical in, so they are read back as
+// they were written
+impl Decode for Address {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(Address(decoder.read()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VotingContract;
+    
+    #[test]
+    fn test_address_formats() {
+        // The default format trims addresses but keeps their case, and rejects inner whitespace
+        let address = Address::parse(" wallet_A ", &AnyAddress).unwrap();
+        assert_eq!(address.as_str(), "wallet_A");
+        assert!(matches!(Address::parse("wallet a", &AnyAddress), Err(VotingError::InvalidAddress)));
+        let overlong_address = "a".repeat(MAX_ADDRESS_LENGTH + 1);
+        assert!(matches!(Address::parse(&overlong_address, &AnyAddress), Err(VotingError::InvalidAddress)));
+        
+        // Hex addresses meet in lowercase without their prefix
+        let hex_format = HexAddress { length: 4 };
+        assert_eq!(Address::parse("0xDEADbeef", &hex_format).unwrap().as_str(), "deadbeef");
+        assert!(Address::parse("deadbee", &hex_format).is_err());
+        assert!(Address::parse("deadbeeg", &hex_format).is_err());
+        
+        // Base58Check catches a mistyped Bitcoin address
+        let bitcoin_format = Base58Address::bitcoin();
+        assert!(Address::parse("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", &bitcoin_format).is_ok());
+        assert!(Address::parse("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb", &bitcoin_format).is_err());
+        assert!(Address::parse("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfN0", &bitcoin_format).is_err());
+        
+        // Key addresses are valid in their own format, and absent where a key does not fit
+        let mut public_key = [7u8; 32];
+        public_key[0] = 0;
+        assert_eq!(AnyAddress.key_address(&public_key), Some(to_hex(&public_key)));
+        assert_eq!(hex_format.key_address(&public_key), None);
+        assert_eq!(bitcoin_format.key_address(&public_key), None);
+        for checksum in [false, true] {
+            let key_format = Base58Address { length: 32, checksum };
+            let key_address = key_format.key_address(&public_key).unwrap();
+            assert!(key_address.starts_with('1'));
+            assert_eq!(key_format.canonicalize(&key_address), Some(key_address.clone()));
+            assert_eq!(base58_decode(&key_address).unwrap()[..32], public_key);
+        }
+        
+        // The contract checks every address it takes against its format
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_address_format(Box::new(hex_format)).unwrap();
+        let result = contract.create_poll(
+            "wallet_creator".parse().unwrap(),
+            "Hex only".to_string(),
+            String::new(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        );
+        assert!(matches!(result, Err(VotingError::InvalidAddress)));
+        let poll_id = contract
+            .create_poll(
+                "0xCAFEF00D".parse().unwrap(),
+                "Hex only".to_string(),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().poll_creator_address, "cafef00d");
+        contract.vote(&poll_id, "0x0BADF00D".parse().unwrap(), "Yes").unwrap();
+        let result = contract.vote(&poll_id, "0badf00d".parse().unwrap(), "No");
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        
+        // Stored addresses are in the old format's canonical form, so the format is fixed by now
+        let result = contract.set_address_format(Box::new(AnyAddress));
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+    }
+    
+    #[test]
+    fn test_roles_follow_address_format() {
+        // The admin given at construction is rewritten when the format changes, and either
+        // spelling of it passes permission checks
+        let mut contract = VotingContract::new("0xABCDABCD".parse().unwrap());
+        contract.set_address_format(Box::new(HexAddress { length: 4 })).unwrap();
+        assert!(contract.is_admin(&"0xABCDABCD".parse().unwrap()) && contract.is_admin(&"abcdabcd".parse().unwrap()));
+        let poll_id = contract
+            .create_poll(
+                "0x11111111".parse().unwrap(),
+                "Hex roles".to_string(),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        
+        // Managing and reopening another creator's poll, with events naming the canonical caller
+        contract.close_poll(&poll_id, &"0xABCDABCD".parse().unwrap()).unwrap();
+        contract.reopen_poll(&poll_id, 60, false, &"0xABCDABCD".parse().unwrap()).unwrap();
+        let reopened_by = match &contract.events_since(0).last().unwrap().event {
+            crate::ContractEvent::PollReopened { reopened_by, .. } => reopened_by.clone(),
+            event => panic!("unexpected event {}", event.name()),
+        };
+        assert_eq!(reopened_by, "abcdabcd");
+        
+        // Roles granted under the format are found whichever spelling the holder uses
+        contract
+            .grant_role(
+                &"0xFEEDFEED".parse().unwrap(),
+                crate::access_control::Role::PollManager,
+                &"abcdabcd".parse().unwrap(),
+            )
+            .unwrap();
+        contract.pause_poll(&poll_id, &"feedfeed".parse().unwrap()).unwrap();
+        contract.close_poll(&poll_id, &"0xfeedfeed".parse().unwrap()).unwrap();
+        let result = contract.reopen_poll(&poll_id, 60, false, &"0xFEEDFEED".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,166 @@
+// Admin handover.
+// Granting SuperAdmin to a mistyped address hands the contract to nobody, so handing it over takes
+// two steps: the current admin proposes an address, and the change only happens once that address
+// accepts, proving it holds its key. Accepting gives the new admin SuperAdmin and takes it from
+// the proposer; until then the proposer stays in charge and may propose someone else instead.
+// An admin can also renounce SuperAdmin outright. When the last admin renounces, every other role
+// goes with it: nobody can manage roles or act on other creators' polls again, and each poll is
+// governed by its creator alone.
+use crate::access_control::Role;
+use crate::address::Address;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::{ContractEvent, Result, VotingContract, VotingError};
+
+// Define a proposed handover of admin rights
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminTransfer {
+    pub current_admin: Address,  // The SuperAdmin handing over
+    pub proposed_admin: Address, // The address that must accept
+}
+
+impl Encode for AdminTransfer {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.current_admin);
+        encoder.write(&self.proposed_admin);
+    }
+}
+
+impl Decode for AdminTransfer {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(AdminTransfer {
+            current_admin: decoder.read()?,
+            proposed_admin: decoder.read()?,
+        })
+    }
+}
+
+impl VotingContract {
+    // Propose handing the caller's admin rights to another address, replacing any earlier
+    // proposal. The caller must be a SuperAdmin and cannot propose itself.
+    pub fn propose_admin_transfer(&mut self, new_admin_address: &Address, caller: &Address) -> Result<()> {
+        let new_admin_address = self.parse_address(new_admin_address)?;
+        let caller = self.parse_address(caller)?;
+        if !self.is_admin(&caller) {
+            return Err(VotingError::NotAuthorized);
+        }
+        if new_admin_address == caller {
+            return Err(VotingError::InvalidAddress);
+        }
+        
+        self.pending_admin_transfer = Some(AdminTransfer {
+            current_admin: caller.clone(),
+            proposed_admin: new_admin_address.clone(),
+        });
+        self.emit(ContractEvent::AdminTransferProposed {
+            current_admin: caller,
+            proposed_admin: new_admin_address,
+        });
+        Ok(())
+    }
+    
+    // Accept a proposed handover as the proposed address. NotAuthorized when nothing was
+    // proposed to the caller, or the proposer is no longer a SuperAdmin.
+    pub fn accept_admin_transfer(&mut self, caller: &Address) -> Result<()> {
+        let caller = self.parse_address(caller)?;
+        let transfer = match &self.pending_admin_transfer {
+            Some(transfer) if transfer.proposed_admin == caller && self.is_admin(&transfer.current_admin) => {
+                transfer.clone()
+            }
+            _ => return Err(VotingError::NotAuthorized),
+        };
+        
+        self.pending_admin_transfer = None;
+        self.access_control.grant(caller.clone(), Role::SuperAdmin);
+        self.access_control.revoke(&transfer.current_admin, Role::SuperAdmin)?;
+        self.emit(ContractEvent::AdminTransferAccepted {
+            previous_admin: transfer.current_admin,
+            new_admin: caller,
+        });
+        Ok(())
+    }
+    
+    // Give up the caller's admin rights. The last admin renouncing drops every role, which cannot
+    // be undone; a pending handover is cancelled either way.
+    pub fn renounce_admin(&mut self, caller: &Address) -> Result<()> {
+        let caller = self.parse_address(caller)?;
+        if !self.access_control.renounce(&caller) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        self.pending_admin_transfer = None;
+        self.emit(ContractEvent::AdminRenounced {
+            admin_address: caller,
+            last_admin: !self.access_control.has_admin(),
+        });
+        Ok(())
+    }
+    
+    // Get the handover waiting to be accepted, if any
+    pub fn pending_admin_transfer(&self) -> Option<&AdminTransfer> {
+        self.pending_admin_transfer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_admin_handover() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let result =
+            contract.propose_admin_transfer(&"wallet_new".parse().unwrap(), &"wallet_stranger".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.propose_admin_transfer(&"wallet_typo".parse().unwrap(), &"wallet_admin".parse().unwrap()).unwrap();
+        contract.propose_admin_transfer(&"wallet_new".parse().unwrap(), &"wallet_admin".parse().unwrap()).unwrap();
+        
+        // Only the latest proposed address can accept, and the admin keeps control until it does
+        assert!(matches!(
+            contract.accept_admin_transfer(&"wallet_typo".parse().unwrap()),
+            Err(VotingError::NotAuthorized)
+        ));
+        assert!(
+            contract.is_admin(&"wallet_admin".parse().unwrap()) && !contract.is_admin(&"wallet_new".parse().unwrap())
+        );
+        contract.accept_admin_transfer(&"wallet_new".parse().unwrap()).unwrap();
+        assert!(
+            contract.is_admin(&"wallet_new".parse().unwrap()) && !contract.is_admin(&"wallet_admin".parse().unwrap())
+        );
+        assert_eq!(contract.pending_admin_transfer(), None);
+        assert!(matches!(
+            contract.accept_admin_transfer(&"wallet_new".parse().unwrap()),
+            Err(VotingError::NotAuthorized)
+        ));
+        
+        // The last admin renouncing leaves creators in charge of their own polls, across a restore
+        contract
+            .grant_role(
+                &"wallet_moderator".parse().unwrap(),
+                Role::Moderator,
+                &"wallet_new".parse().unwrap(),
+            )
+            .unwrap();
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Lunch".to_string(),
+                String::new(),
+                vec!["Pizza".to_string(), "Tacos".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.renounce_admin(&"wallet_new".parse().unwrap()).unwrap();
+        let last_event = &contract.events_since(0).last().unwrap().event;
+        assert_eq!(last_event.name(), "AdminRenounced");
+        let mut contract = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert!(
+            !contract.is_admin(&"wallet_new".parse().unwrap())
+                && !contract.has_role(&"wallet_moderator".parse().unwrap(), Role::Moderator)
+        );
+        assert!(matches!(
+            contract.close_poll(&poll_id, &"wallet_moderator".parse().unwrap()),
+            Err(VotingError::NotAuthorized)
+        ));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+    }
+}
\ No newline at end of file
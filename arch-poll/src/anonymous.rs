@@ -0,0 +1,309 @@
+// Anonymous voting.
+// An anonymous poll never records who voted. Before voting starts its creator registers a set of
+// commitments, one per eligible voter, each the hash of a secret only that voter holds
+// (anonymous_commitment). A voter votes by presenting a nullifier, the hash of their secret bound
+// to the poll (nullifier), with a proof that it comes from the secret behind one of the registered
+// commitments. The poll keeps each used nullifier with the option it chose, so a secret votes
+// once per poll, but no address is stored. Proofs are checked by a pluggable ProofVerifier, such
+// as a zero-knowledge set-membership verifier; without one every anonymous vote fails closed.
+// RevealedSecretVerifier takes the secret itself as the proof, which shows the mechanics but tells
+// anyone reading the transaction which commitment voted.
+// Anonymous ballots carry no address, so rate limits, vote fees, balance gates and delegation do
+// not apply to them, and the commitment set takes the place of the allowlist.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::hash::{sha256, to_hex};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::receipt::VoteReceipt;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError, VotingMethod};
+
+// Domain tags hashed ahead of commitments and nullifiers
+pub const COMMITMENT_DOMAIN: &[u8] = b"arch-poll:commitment:v1";
+pub const NULLIFIER_DOMAIN: &[u8] = b"arch-poll:nullifier:v1";
+
+// Most commitments one poll may register
+pub const MAX_ANONYMOUS_COMMITMENTS: usize = 10_000;
+
+// Compute the commitment a voter registers for their secret
+pub fn anonymous_commitment(secret: &[u8]) -> [u8; 32] {
+    let mut hashed_bytes = COMMITMENT_DOMAIN.to_vec();
+    hashed_bytes.extend_from_slice(secret);
+    sha256(&hashed_bytes)
+}
+
+// Compute the nullifier a secret votes with in a poll
+pub fn nullifier(secret: &[u8], poll_id: &PollId) -> [u8; 32] {
+    let mut encoder = Encoder::new();
+    encoder.write(poll_id);
+    encoder.write_bytes(secret);
+    let mut hashed_bytes = NULLIFIER_DOMAIN.to_vec();
+    hashed_bytes.extend_from_slice(&encoder.finish());
+    sha256(&hashed_bytes)
+}
+
+// Checks that a nullifier comes from the secret behind one of a poll's commitments
+pub trait ProofVerifier: Send + Sync {
+    fn verify(
+        &self,
+        poll_id: &PollId,
+        commitments: &[[u8; 32]],
+        nullifier: &[u8; 32],
+        selected_option: &str,
+        proof_bytes: &[u8],
+    ) -> bool;
+}
+
+// Takes the voter's secret as the proof. It checks the secret is committed and produces the
+// nullifier, but reveals which commitment voted, so it only suits tests and trusted setups.
+pub struct RevealedSecretVerifier;
+
+impl ProofVerifier for RevealedSecretVerifier {
+    fn verify(
+        &self,
+        poll_id: &PollId,
+        commitments: &[[u8; 32]],
+        nullifier_hash: &[u8; 32],
+        _selected_option: &str,
+        proof_bytes: &[u8],
+    ) -> bool {
+        commitments.contains(&anonymous_commitment(proof_bytes)) && nullifier(proof_bytes, poll_id) == *nullifier_hash
+    }
+}
+
+// Define the commitment set and used nullifiers of an anonymous poll
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnonymousState {
+    pub commitments: Vec<[u8; 32]>,            // Registered commitments, in registration order
+    pub nullifiers: HashMap<[u8; 32], String>, // Option chosen with each used nullifier
+}
+
+impl AnonymousState {
+    // Start a commitment set with no ballots
+    pub fn new(commitments: Vec<[u8; 32]>) -> Self {
+        AnonymousState {
+            commitments,
+            nullifiers: HashMap::new(),
+        }
+    }
+    
+    // Check the state fits its poll: a single-choice poll holding no addressed ballots, distinct
+    // commitments, no more ballots than commitments and only ballot options chosen
+    pub(crate) fn is_valid(&self, poll: &Poll) -> bool {
+        let mut sorted_commitments = self.commitments.clone();
+        sorted_commitments.sort();
+        sorted_commitments.dedup();
+        
+        poll.voting_method == VotingMethod::SingleChoice
+            && poll.commit_end_timestamp.is_none()
+            && poll.participant_addresses.is_empty()
+            && poll.delegated_votes.is_empty()
+            && sorted_commitments.len() == self.commitments.len()
+            && self.nullifiers.len() <= self.commitments.len()
+            && self.nullifiers.values().all(|option| poll.voting_options.contains(option))
+    }
+}
+
+impl Encode for AnonymousState {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.commitments);
+        encoder.write(&self.nullifiers);
+    }
+}
+
+impl Decode for AnonymousState {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(AnonymousState {
+            commitments: decoder.read()?,
+            nullifiers: decoder.read()?,
+        })
+    }
+}
+
+impl Poll {
+    // Get the number of anonymous ballots cast
+    pub fn anonymous_ballots(&self) -> usize {
+        self.anonymous.as_ref().map_or(0, |state| state.nullifiers.len())
+    }
+    
+    // Get every anonymous ballot as (hex nullifier, options backed), the form ballot leaves take
+    pub(crate) fn anonymous_leaves(&self) -> Vec<(Address, Vec<String>)> {
+        self.anonymous
+            .iter()
+            .flat_map(|state| state.nullifiers.iter())
+            .map(|(nullifier_hash, option)| (Address::unchecked(to_hex(nullifier_hash)), vec![option.clone()]))
+            .collect()
+    }
+}
+
+impl VotingContract {
+    // Create a single-choice poll that only takes anonymous votes. Commitments are registered
+    // with register_commitment before voting starts.
+    pub fn create_anonymous_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.anonymous = Some(AnonymousState::default());
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Replace the proof verifier (None rejects every anonymous vote)
+    pub fn set_proof_verifier(&mut self, proof_verifier: Option<Box<dyn ProofVerifier>>) {
+        self.proof_verifier = proof_verifier;
+    }
+    
+    // Add a voter's commitment to an anonymous poll (creator or ConfigurePolls). Like other
+    // eligibility rules, the set is fixed once voting starts.
+    pub fn register_commitment(&mut self, poll_id: &PollId, commitment: [u8; 32], caller: &Address) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        let state = poll.anonymous.as_mut().ok_or(VotingError::WrongVotingMethod)?;
+        if state.commitments.contains(&commitment) || state.commitments.len() >= MAX_ANONYMOUS_COMMITMENTS {
+            return Err(VotingError::InvalidParameter);
+        }
+        
+        state.commitments.push(commitment);
+        self.emit(ContractEvent::CommitmentRegistered {
+            poll_id: poll_id.clone(),
+            commitment,
+        });
+        Ok(())
+    }
+    
+    // Cast an anonymous vote with a nullifier and the proof that it belongs to a registered
+    // commitment. The receipt names the hex nullifier as its voter.
+    pub fn vote_anonymous(
+        &mut self,
+        poll_id: &PollId,
+        nullifier_hash: [u8; 32],
+        selected_option: &str,
+        proof_bytes: &[u8],
+    ) -> Result<VoteReceipt> {
+        self.check_not_paused()?;
+        let current_timestamp = self.now();
+        let poll = self.live_poll_mut(poll_id)?;
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        let state = poll.anonymous.as_ref().ok_or(VotingError::WrongVotingMethod)?;
+        if state.nullifiers.contains_key(&nullifier_hash) {
+            return Err(VotingError::AlreadyVoted);
+        }
+        let option_id = poll.option_id(selected_option).ok_or(VotingError::InvalidOption)?;
+        
+        // Verify against the commitment set in place; the poll is only borrowed mutably again once
+        // the proof holds
+        let poll = self.get_poll(poll_id)?;
+        let commitments = &poll.anonymous.as_ref().ok_or(VotingError::WrongVotingMethod)?.commitments;
+        let verified = self.proof_verifier.as_ref().is_some_and(|proof_verifier| {
+            proof_verifier.verify(poll_id, commitments, &nullifier_hash, selected_option, proof_bytes)
+        });
+        if !verified {
+            return Err(VotingError::InvalidProof);
+        }
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        poll.add_to_tally(option_id, 1, true)?;
+        if let Some(state) = poll.anonymous.as_mut() {
+            state.nullifiers.insert(nullifier_hash, selected_option.to_string());
+        }
+        // The receipt names the nullifier where other receipts name the voter
+        let receipt = poll.append_receipt(&Address::unchecked(to_hex(&nullifier_hash)), selected_option);
+        
+        self.track_tallies(poll_id);
+        self.emit(ContractEvent::AnonymousVoteCast {
+            poll_id: poll_id.clone(),
+            nullifier: nullifier_hash,
+            selected_option: selected_option.to_string(),
+        });
+        Ok(receipt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_anonymous_voting() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_anonymous_poll(
+                "wallet_creator".parse().unwrap(),
+                "Secret ballot".to_string(),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        for secret in [b"secret a", b"secret b"] {
+            contract
+                .register_commitment(
+                    &poll_id,
+                    anonymous_commitment(secret),
+                    &"wallet_creator".parse().unwrap(),
+                )
+                .unwrap();
+        }
+        let result = contract.register_commitment(
+            &poll_id,
+            anonymous_commitment(b"secret a"),
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::InvalidParameter)));
+        
+        // Without a verifier, and with a secret outside the set, votes fail closed
+        let nullifier_a = nullifier(b"secret a", &poll_id);
+        let result = contract.vote_anonymous(&poll_id, nullifier_a, "Yes", b"secret a");
+        assert!(matches!(result, Err(VotingError::InvalidProof)));
+        contract.set_proof_verifier(Some(Box::new(RevealedSecretVerifier)));
+        let outsider_nullifier = nullifier(b"secret c", &poll_id);
+        let result = contract.vote_anonymous(&poll_id, outsider_nullifier, "Yes", b"secret c");
+        assert!(matches!(result, Err(VotingError::InvalidProof)));
+        
+        // A committed secret votes once, and the poll keeps no address
+        let receipt = contract.vote_anonymous(&poll_id, nullifier_a, "Yes", b"secret a").unwrap();
+        assert_eq!(receipt.voter.as_str(), to_hex(&nullifier_a));
+        let result = contract.vote_anonymous(&poll_id, nullifier_a, "No", b"secret a");
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        let result = contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        let result = contract.register_commitment(
+            &poll_id,
+            anonymous_commitment(b"secret c"),
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.total_votes(), poll.get_results()["Yes"]), (1, 1));
+        assert!(poll.participant_addresses.is_empty());
+        
+        // Nullifiers are saved with the poll and their ballots sit under the ballot root
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        restored.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let ballot_root = restored.get_poll(&poll_id).unwrap().ballot_root.unwrap();
+        let proof = restored.get_ballot_proof(&poll_id, &to_hex(&nullifier_a).parse().unwrap()).unwrap();
+        assert!(proof.verify(&ballot_root));
+    }
+}
\ No newline at end of file
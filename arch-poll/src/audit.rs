@@ -0,0 +1,184 @@
+// Tally audits (feature `audit`).
+// Every poll keeps its individual ballots next to the aggregated vote_counts and voter_counts: the
+// option of each single-choice voter, each ranked, approval and quadratic ballot, and the power
+// delegated through them. An audit recounts a poll from those ballots alone and compares the
+// result with the stored tallies, so auditors can check a poll without trusting the counters and
+// operators can catch state corrupted outside the contract's own calls.
+use alloc::collections::{BTreeMap, BTreeSet};
+
+use crate::address::Address;
+use crate::id::{OptionId, PollId};
+use crate::prelude::*;
+use crate::{OptionTally, Poll, Result, VotingContract};
+
+// Define one option whose stored tally differs from the recount
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TallyDiscrepancy {
+    pub option: String,
+    pub recorded: OptionTally,   // From vote_counts and voter_counts (0 when missing)
+    pub recomputed: OptionTally, // From the individual ballots
+}
+
+// Define the outcome of recounting a poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TallyAudit {
+    pub poll_id: PollId,
+    pub ballots_counted: usize,               // Ballots recounted, one per participant
+    pub discrepancies: Vec<TallyDiscrepancy>, // Options that differ, sorted by option
+    pub unknown_options: Vec<String>,         // Options tallied or voted for that are not on the ballot ("#id" for tallies)
+}
+
+impl TallyAudit {
+    // Check whether the stored tallies match the ballots exactly
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty() && self.unknown_options.is_empty()
+    }
+}
+
+impl Poll {
+    // Recount the weighted tally and backing voters of every option from the individual ballots.
+    // Regular voters add their weight (1 unless recorded otherwise) to their only or first choice,
+    // delegated power is added to the option its delegate chose, quadratic voters add each
+    // allocation, approval voters one per approved option and anonymous ballots one each.
+    pub fn recount(&self) -> BTreeMap<String, OptionTally> {
+        let mut tallies: BTreeMap<String, OptionTally> = BTreeMap::new();
+        let mut count = |option: &String, weight: u128, voters: usize| {
+            let tally = tallies.entry(option.clone()).or_insert(OptionTally { weight: 0, voters: 0 });
+            tally.weight = tally.weight.saturating_add(weight);
+            tally.voters = tally.voters.saturating_add(voters);
+        };
+        
+        let voter_weight = |voter_address: &Address| self.weighted_voters.get(voter_address).copied().unwrap_or(1);
+        for (voter_address, selected_option) in &self.votes_by_address {
+            count(selected_option, voter_weight(voter_address), 1);
+        }
+        for (voter_address, ranking) in &self.ranked_ballots {
+            if let Some(first_choice) = ranking.first() {
+                count(first_choice, voter_weight(voter_address), 1);
+            }
+        }
+        for delegated_vote in self.delegated_votes.values() {
+            count(&delegated_vote.selected_option, delegated_vote.weight, 0);
+        }
+        for allocations in self.quadratic_allocations.values() {
+            for (option, &votes) in allocations {
+                count(option, votes as u128, 1);
+            }
+        }
+        for approved_options in self.approval_ballots.values() {
+            for option in approved_options {
+                count(option, 1, 1);
+            }
+        }
+        for option in self.anonymous.iter().flat_map(|state| state.nullifiers.values()) {
+            count(option, 1, 1);
+        }
+        tallies
+    }
+    
+    // Recount the poll and compare the result with its stored tallies
+    pub fn audit_tally(&self) -> TallyAudit {
+        let recomputed_tallies = self.recount();
+        
+        // Name the stored tallies by option; a tally under an ID past the ballot is named "#id"
+        let option_name = |option_id: &OptionId| {
+            self.option(*option_id).map_or_else(|_| format!("#{}", option_id), str::to_string)
+        };
+        let empty_tally = OptionTally { weight: 0, voters: 0 };
+        let mut recorded_tallies: BTreeMap<String, OptionTally> = BTreeMap::new();
+        for (option_id, &weight) in &self.vote_counts {
+            recorded_tallies.entry(option_name(option_id)).or_insert(empty_tally).weight = weight;
+        }
+        for (option_id, &voters) in &self.voter_counts {
+            recorded_tallies.entry(option_name(option_id)).or_insert(empty_tally).voters = voters;
+        }
+        let options: BTreeSet<&String> = self
+            .voting_options
+            .iter()
+            .chain(recorded_tallies.keys())
+            .chain(recomputed_tallies.keys())
+            .collect();
+            
+        let mut discrepancies = Vec::new();
+        let mut unknown_options = Vec::new();
+        for option in options {
+            if !self.voting_options.contains(option) {
+                unknown_options.push(option.clone());
+            }
+            let recorded = recorded_tallies.get(option).copied().unwrap_or(empty_tally);
+            let recomputed = recomputed_tallies.get(option).copied().unwrap_or(empty_tally);
+            if recorded != recomputed {
+                discrepancies.push(TallyDiscrepancy {
+                    option: option.clone(),
+                    recorded,
+                    recomputed,
+                });
+            }
+        }
+        
+        TallyAudit {
+            poll_id: self.poll_id.clone(),
+            ballots_counted: self.total_votes(),
+            discrepancies,
+            unknown_options,
+        }
+    }
+}
+
+impl VotingContract {
+    // Recount a live or archived poll from its ballots and report where its tallies differ
+    pub fn audit_tally(&self, poll_id: &PollId) -> Result<TallyAudit> {
+        Ok(self.get_poll(poll_id)?.audit_tally())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_audit_tally() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let options = || vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()];
+        let creator = || "wallet_creator".to_string();
+        let single_poll_id = contract
+            .create_poll(creator().parse().unwrap(), "Single".to_string(), String::new(), options(), 60)
+            .unwrap();
+        let ranked_poll_id = contract
+            .create_ranked_poll(creator().parse().unwrap(), "Ranked".to_string(), String::new(), options(), 60)
+            .unwrap();
+        let approval_poll_id = contract
+            .create_approval_poll(creator().parse().unwrap(), "Approval".to_string(), String::new(), options(), 60, 2)
+            .unwrap();
+            
+        contract.set_voter_weight(&"wallet_a".parse().unwrap(), 5, &"wallet_admin".parse().unwrap()).unwrap();
+        contract.vote(&single_poll_id, "wallet_a".parse().unwrap(), "Red").unwrap();
+        contract.vote(&single_poll_id, "wallet_b".parse().unwrap(), "Blue").unwrap();
+        let ranking = vec!["Green".to_string(), "Red".to_string()];
+        contract.vote_ranked(&ranked_poll_id, "wallet_a".parse().unwrap(), ranking).unwrap();
+        let approvals = vec!["Red".to_string(), "Blue".to_string()];
+        contract.vote_multi(&approval_poll_id, "wallet_b".parse().unwrap(), approvals).unwrap();
+        
+        // Tallies kept by the contract's own calls always match the ballots
+        for poll_id in [&single_poll_id, &ranked_poll_id, &approval_poll_id] {
+            let audit = contract.audit_tally(poll_id).unwrap();
+            assert!(audit.is_clean(), "{:?}", audit);
+        }
+        let audit = contract.audit_tally(&single_poll_id).unwrap();
+        assert_eq!(audit.ballots_counted, 2);
+        let recount = contract.get_poll(&single_poll_id).unwrap().recount();
+        assert_eq!(recount["Red"], OptionTally { weight: 5, voters: 1 });
+        
+        // Tallies edited behind the contract's back are reported option by option
+        let poll = contract.active_polls.get_mut(&single_poll_id).unwrap();
+        *poll.vote_counts.get_mut(&OptionId(2)).unwrap() += 10;
+        poll.voter_counts.insert(OptionId(5), 1);
+        let audit = contract.audit_tally(&single_poll_id).unwrap();
+        assert!(!audit.is_clean());
+        assert_eq!(audit.unknown_options, vec!["#5".to_string()]);
+        assert_eq!(audit.discrepancies.len(), 2);
+        assert_eq!(audit.discrepancies[1].option, "Blue");
+        assert_eq!(audit.discrepancies[1].recorded, OptionTally { weight: 11, voters: 1 });
+        assert_eq!(audit.discrepancies[1].recomputed, OptionTally { weight: 1, voters: 1 });
+    }
+}
\ No newline at end of file
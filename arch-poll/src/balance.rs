@@ -0,0 +1,126 @@
+// Token-gated polls.
+// The contract can consult a BalanceProvider for the token balance of an address. Polls can
+// require a minimum balance of their voters, and the contract can require one of poll creators.
+// On-chain deployments read token accounts; tests and off-chain tools can use a map. Without a
+// provider every balance is 0, so gates fail closed. Balances are read when a ballot is cast or
+// committed and are not locked, so a gate limits who may vote rather than weighing votes.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Source of token balances
+pub trait BalanceProvider: Send + Sync {
+    fn balance_of(&self, address: &Address) -> u128;
+}
+
+// A fixed map of balances; unlisted addresses hold nothing
+impl BalanceProvider for HashMap<Address, u128> {
+    fn balance_of(&self, address: &Address) -> u128 {
+        self.get(address).copied().unwrap_or(0)
+    }
+}
+
+impl VotingContract {
+    // Replace the balance source (None treats every balance as 0)
+    pub fn set_balance_provider(&mut self, balance_provider: Option<Box<dyn BalanceProvider>>) {
+        self.balance_provider = balance_provider;
+    }
+    
+    // Get an address's balance according to the provider
+    pub fn balance_of(&self, address: &Address) -> u128 {
+        self.balance_provider
+            .as_ref()
+            .map_or(0, |balance_provider| balance_provider.balance_of(&self.lookup_address(address)))
+    }
+    
+    // Set the balance a poll's voters must hold (creator or ConfigurePolls, 0 disables). The gate
+    // can only change before voting starts, so every ballot is held to the same rule.
+    pub fn set_min_balance_to_vote(&mut self, poll_id: &PollId, min_balance: u128, caller: &Address) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.min_balance_to_vote = min_balance;
+        self.emit(ContractEvent::MinBalanceToVoteSet {
+            poll_id: poll_id.clone(),
+            min_balance,
+        });
+        Ok(())
+    }
+    
+    // Check an address holds the balance a poll requires of its voters
+    pub(crate) fn holds_voting_balance(&self, poll: &Poll, address: &Address) -> bool {
+        poll.min_balance_to_vote == 0 || self.balance_of(address) >= poll.min_balance_to_vote
+    }
+    
+    // Check a voter may vote in a poll by balance, asking the provider only when the poll is gated
+    // (missing polls pass here and are reported by the caller)
+    pub(crate) fn voter_holds_balance(&self, poll_id: &PollId, voter_address: &Address) -> bool {
+        self.get_poll(poll_id).map_or(true, |poll| self.holds_voting_balance(poll, voter_address))
+    }
+    
+    // Check a creator holds the balance the contract requires to create polls
+    pub(crate) fn check_creation_balance(&self, creator_address: &Address) -> Result<()> {
+        let min_balance = self.config.min_balance_to_create;
+        if min_balance > 0 && self.balance_of(creator_address) < min_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_balance_gates() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.config.min_balance_to_create = 100;
+        let create = |contract: &mut VotingContract, creator_address: &str| {
+            contract.create_poll(
+                creator_address.parse().unwrap(),
+                "Treasury".to_string(),
+                "Holders only".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+        };
+        
+        // Without a provider every balance is 0, so gates fail closed
+        assert!(matches!(create(&mut contract, "wallet_creator"), Err(VotingError::InsufficientBalance)));
+        let balances: HashMap<Address, u128> =
+            [("wallet_creator", 100), ("wallet_whale", 50), ("wallet_minnow", 5), ("wallet_delegator", 1)]
+                .into_iter()
+                .map(|(address, balance)| (address.parse().unwrap(), balance))
+                .collect();
+        contract.set_balance_provider(Some(Box::new(balances)));
+        let poll_id = create(&mut contract, "wallet_creator").unwrap();
+        assert!(matches!(create(&mut contract, "wallet_minnow"), Err(VotingError::InsufficientBalance)));
+        
+        // Voters need the poll's minimum balance, and so do delegators carried by their ballots
+        contract.set_min_balance_to_vote(&poll_id, 10, &"wallet_creator".parse().unwrap()).unwrap();
+        let result = contract.set_min_balance_to_vote(&poll_id, 10, &"wallet_whale".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        let result = contract.vote(&poll_id, "wallet_minnow".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::InsufficientBalance)));
+        contract.delegate_vote(&"wallet_delegator".parse().unwrap(), &"wallet_whale".parse().unwrap()).unwrap();
+        contract.vote(&poll_id, "wallet_whale".parse().unwrap(), "Yes").unwrap();
+        assert_eq!(contract.get_poll_results(&poll_id).unwrap()["Yes"], 1);
+        
+        // The gate is fixed once voting starts, and saved with the poll and contract
+        let result = contract.set_min_balance_to_vote(&poll_id, 0, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&poll_id).unwrap().min_balance_to_vote, 10);
+        assert_eq!(restored.config.min_balance_to_create, 100);
+    }
+}
\ No newline at end of file
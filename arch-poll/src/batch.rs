@@ -0,0 +1,175 @@
+// Bulk vote ingestion.
+// Relayers collect signed ballots off-chain and submit them together, so vote_batch applies many
+// single-choice votes to one poll in one call and reports each vote's outcome in input order.
+// Every vote in a batch is cast at the same timestamp, so a poll cannot close halfway through.
+// In AllOrNothing mode a failure anywhere rolls the whole batch back: the poll, the rate limiter,
+// the voter index and the event log are restored, vote fees are refunded, and events only reach
+// the event handler and notifiers once the batch has succeeded.
+use core::mem;
+
+use crate::address::Address;
+use crate::clock::MockClock;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::receipt::VoteReceipt;
+use crate::{Poll, Result, VotingContract, VotingError};
+
+// Most votes one batch may hold
+pub const MAX_BATCH_VOTES: usize = 1_000;
+
+// Define how a batch treats failing votes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchMode {
+    AllOrNothing, // Any failure rolls back every vote; the others report BatchAborted
+    BestEffort,   // Valid votes are kept and failing ones report their own error
+}
+
+impl VotingContract {
+    // Cast many (voter, option) votes in a single-choice poll, returning one result per vote in
+    // input order. Batches over MAX_BATCH_VOTES fail every vote with InvalidParameter.
+    pub fn vote_batch(
+        &mut self,
+        poll_id: &PollId,
+        votes: Vec<(Address, String)>,
+        mode: BatchMode,
+    ) -> Vec<Result<VoteReceipt>> {
+        if votes.len() > MAX_BATCH_VOTES {
+            return votes.iter().map(|_| Err(VotingError::InvalidParameter)).collect();
+        }
+        
+        // Close the poll first if it is due, so the batch sees the state every vote will see
+        self.expire_if_due(poll_id);
+        let batch_clock = MockClock::new(self.now());
+        let clock = mem::replace(&mut self.clock, Box::new(batch_clock));
+        let results = match mode {
+            BatchMode::BestEffort => self.cast_votes(poll_id, votes),
+            BatchMode::AllOrNothing => {
+                self.cast_atomically(core::slice::from_ref(poll_id), |contract| contract.cast_votes(poll_id, votes))
+            }
+        };
+        self.clock = clock;
+        results
+    }
+    
+    // Cast each vote in turn
+    fn cast_votes(&mut self, poll_id: &PollId, votes: Vec<(Address, String)>) -> Vec<Result<VoteReceipt>> {
+        votes
+            .into_iter()
+            .map(|(voter_address, selected_option)| self.vote(poll_id, voter_address, &selected_option))
+            .collect()
+    }
+    
+    // Cast votes in the given polls with event delivery held back, then keep all of them or none.
+    // Successful votes in a rolled-back group report BatchAborted.
+    pub(crate) fn cast_atomically(
+        &mut self,
+        poll_ids: &[PollId],
+        cast: impl FnOnce(&mut Self) -> Vec<Result<VoteReceipt>>,
+    ) -> Vec<Result<VoteReceipt>> {
+        let poll_checkpoints: Vec<(PollId, Option<Poll>)> = poll_ids
+            .iter()
+            .map(|poll_id| (poll_id.clone(), self.active_polls.get(poll_id).cloned()))
+            .collect();
+        let rate_limiter_checkpoint = self.rate_limiter.clone();
+        let event_log_length = self.event_log.len();
+        let next_event_sequence = self.next_event_sequence;
+        let on_event = self.on_event.take();
+        let notifiers = mem::take(&mut self.notifiers);
+        
+        let mut results = cast(self);
+        
+        self.on_event = on_event;
+        self.notifiers = notifiers;
+        if results.iter().all(Result::is_ok) {
+            for logged_event in &self.event_log[event_log_length..] {
+                if let Some(handler) = &self.on_event {
+                    handler(&logged_event.event);
+                }
+                self.notify(&logged_event.event);
+            }
+            return results;
+        }
+        
+        for result in &mut results {
+            if let Ok(receipt) = result {
+                let vote_fee = poll_checkpoints
+                    .iter()
+                    .find(|(poll_id, _)| *poll_id == receipt.poll_id)
+                    .and_then(|(_, poll)| poll.as_ref())
+                    .map_or(0, |poll| poll.vote_fee);
+                self.poll_index.remove_voter(&receipt.poll_id, &receipt.voter);
+                self.refund_fee(&receipt.poll_id, &receipt.voter, vote_fee);
+                *result = Err(VotingError::BatchAborted);
+            }
+        }
+        for (poll_id, poll_checkpoint) in poll_checkpoints {
+            if let Some(poll) = poll_checkpoint {
+                self.status_index.refresh(&poll);
+                self.active_polls.insert(poll_id, poll);
+            }
+        }
+        self.rate_limiter = rate_limiter_checkpoint;
+        self.event_log.truncate(event_log_length);
+        self.next_event_sequence = next_event_sequence;
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractEvent;
+    use std::sync::{Arc, Mutex};
+    
+    #[test]
+    fn test_vote_batch() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let delivered = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&delivered);
+        contract.add_notifier(Box::new(move |_: &ContractEvent| *counter.lock().unwrap() += 1));
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Relayed".to_string(),
+                "Ballots collected off-chain".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let ballots = |votes: &[(&str, &str)]| -> Vec<(Address, String)> {
+            votes.iter().map(|(voter, option)| (voter.parse().unwrap(), option.to_string())).collect()
+        };
+        
+        // One bad vote rolls back the whole all-or-nothing batch, and nothing is announced
+        let snapshot = contract.to_snapshot();
+        let results = contract.vote_batch(
+            &poll_id,
+            ballots(&[("wallet_a", "Yes"), ("wallet_b", "Maybe"), ("wallet_c", "No")]),
+            BatchMode::AllOrNothing,
+        );
+        assert!(matches!(results[0], Err(VotingError::BatchAborted)));
+        assert!(matches!(results[1], Err(VotingError::InvalidOption)));
+        assert!(matches!(results[2], Err(VotingError::BatchAborted)));
+        assert_eq!(contract.to_snapshot(), snapshot);
+        assert!(contract.get_votes_by_address(&"wallet_a".parse().unwrap()).is_empty());
+        assert_eq!(*delivered.lock().unwrap(), 1);
+        
+        // A clean batch is applied and its events are delivered afterwards
+        let votes = ballots(&[("wallet_a", "Yes"), ("wallet_c", "No")]);
+        let results = contract.vote_batch(&poll_id, votes, BatchMode::AllOrNothing);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(*delivered.lock().unwrap(), 3);
+        
+        // Best effort keeps the valid votes; a voter repeated within the batch is AlreadyVoted
+        let results = contract.vote_batch(
+            &poll_id,
+            ballots(&[("wallet_d", "No"), ("wallet_a", "No"), ("wallet_e", "Yes"), ("wallet_e", "Yes")]),
+            BatchMode::BestEffort,
+        );
+        assert_eq!(results[2].as_ref().unwrap().voter, "wallet_e");
+        assert!(matches!(results[1], Err(VotingError::AlreadyVoted)));
+        assert!(matches!(results[3], Err(VotingError::AlreadyVoted)));
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.votes_for("Yes"), poll.votes_for("No")), (2, 2));
+    }
+}
\ No newline at end of file
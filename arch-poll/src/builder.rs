@@ -0,0 +1,237 @@
+// Poll builder.
+// create_poll and its variants take their settings as positional arguments, one constructor per
+// combination. PollBuilder names each setting instead and creates the poll in one step:
+//
+//     contract.poll("Lunch").creator("wallet").option("Pizza").option("Tacos").duration_secs(3600).build()
+//
+// Nothing is checked until build, which validates the poll exactly as create_poll does and
+// returns the same errors, so a missing creator is InvalidAddress and a missing duration
+// InvalidTimeSettings. Settings left out keep create_poll's defaults.
+use crate::address::Address;
+use crate::id::PollId;
+use crate::participants::ParticipantSet;
+use crate::prelude::*;
+use crate::visibility::ResultsVisibility;
+use crate::{delay_start, normalize_label, normalize_tags, PollStatus, QuorumConfig, Result, VotingContract, VotingError};
+
+// Define a poll being configured for creation
+pub struct PollBuilder<'a> {
+    contract: &'a mut VotingContract,
+    creator_address: Option<Address>, // Required; building without one returns InvalidAddress
+    poll_title: String,
+    poll_description: String,
+    voting_options: Vec<String>,
+    poll_duration_seconds: u64,
+    start_delay_seconds: u64,         // Delay before voting opens (0 opens it at once)
+    draft: bool,                      // Create the poll as a draft instead of publishing it
+    quorum_config: Option<QuorumConfig>,
+    allow_vote_changes: bool,
+    creator_can_vote: bool,
+    tags: Vec<String>,
+    category: Option<String>,
+    results_visibility: ResultsVisibility,
+    compact_participants: bool,
+}
+
+impl<'a> PollBuilder<'a> {
+    // Set the address creating the poll
+    pub fn creator(mut self, creator_address: &Address) -> Self {
+        self.creator_address = Some(creator_address.clone());
+        self
+    }
+    
+    // Set the poll's description
+    pub fn description(mut self, poll_description: &str) -> Self {
+        self.poll_description = poll_description.to_string();
+        self
+    }
+    
+    // Add an option to the end of the ballot
+    pub fn option(mut self, voting_option: &str) -> Self {
+        self.voting_options.push(voting_option.to_string());
+        self
+    }
+    
+    // Add several options to the end of the ballot
+    pub fn options<I: IntoIterator<Item = S>, S: Into<String>>(mut self, voting_options: I) -> Self {
+        self.voting_options.extend(voting_options.into_iter().map(Into::into));
+        self
+    }
+    
+    // Set how long voting stays open
+    pub fn duration_secs(mut self, poll_duration_seconds: u64) -> Self {
+        self.poll_duration_seconds = poll_duration_seconds;
+        self
+    }
+    
+    // Open voting this many seconds after creation, as create_scheduled_poll does
+    pub fn starts_in_secs(mut self, start_delay_seconds: u64) -> Self {
+        self.start_delay_seconds = start_delay_seconds;
+        self
+    }
+    
+    // Create the poll as an editable draft, as create_draft_poll does
+    pub fn draft(mut self) -> Self {
+        self.draft = true;
+        self
+    }
+    
+    // Require a quorum for the poll to pass, as create_poll_with_quorum does
+    pub fn quorum(mut self, quorum_config: QuorumConfig) -> Self {
+        self.quorum_config = Some(quorum_config);
+        self
+    }
+    
+    // Let single-choice voters change or retract their vote until the poll ends
+    pub fn allow_vote_changes(mut self) -> Self {
+        self.allow_vote_changes = true;
+        self
+    }
+    
+    // Keep the creator from voting in their own poll
+    pub fn without_creator_vote(mut self) -> Self {
+        self.creator_can_vote = false;
+        self
+    }
+    
+    // Add a tag to the poll
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+    
+    // File the poll under a category
+    pub fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_string());
+        self
+    }
+    
+    // Choose who may see the poll's results before it ends
+    pub fn results_visibility(mut self, results_visibility: ResultsVisibility) -> Self {
+        self.results_visibility = results_visibility;
+        self
+    }
+    
+    // Track the poll's voters by address hash (see ParticipantSet)
+    pub fn compact_participants(mut self) -> Self {
+        self.compact_participants = true;
+        self
+    }
+    
+    // Validate the settings and create the poll, returning its ID
+    pub fn build(self) -> Result<PollId> {
+        if self.draft && self.start_delay_seconds > 0 {
+            return Err(VotingError::InvalidTimeSettings);
+        }
+        if let Some(quorum_config) = &self.quorum_config {
+            quorum_config.validate()?;
+        }
+        let tags = normalize_tags(&self.tags)?;
+        let category = self.category.as_deref().map(normalize_label).transpose()?;
+        
+        let contract = self.contract;
+        let mut new_poll = contract.build_poll(
+            contract.next_poll_id,
+            self.creator_address.ok_or(VotingError::InvalidAddress)?,
+            self.poll_title,
+            self.poll_description,
+            self.voting_options,
+            self.poll_duration_seconds,
+        )?;
+        delay_start(&mut new_poll, self.start_delay_seconds)?;
+        if self.draft {
+            new_poll.status = PollStatus::Draft;
+        }
+        new_poll.quorum_config = self.quorum_config;
+        new_poll.allow_vote_changes = self.allow_vote_changes;
+        new_poll.creator_can_vote = self.creator_can_vote;
+        new_poll.tags = tags;
+        new_poll.category = category;
+        new_poll.results_visibility = self.results_visibility;
+        if self.compact_participants {
+            new_poll.participant_addresses = ParticipantSet::compact();
+        }
+        
+        Ok(contract.store_poll(new_poll))
+    }
+}
+
+impl VotingContract {
+    // Start configuring a poll with the given title (see PollBuilder)
+    pub fn poll(&mut self, poll_title: &str) -> PollBuilder<'_> {
+        PollBuilder {
+            contract: self,
+            creator_address: None,
+            poll_title: poll_title.to_string(),
+            poll_description: String::new(),
+            voting_options: Vec::new(),
+            poll_duration_seconds: 0,
+            start_delay_seconds: 0,
+            draft: false,
+            quorum_config: None,
+            allow_vote_changes: false,
+            creator_can_vote: true,
+            tags: Vec::new(),
+            category: None,
+            results_visibility: ResultsVisibility::Live,
+            compact_participants: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_poll_builder() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let result = contract.poll("Lunch").option("Pizza").option("Tacos").duration_secs(60).build();
+        assert!(matches!(result, Err(VotingError::InvalidAddress)));
+        let result = contract
+            .poll("Lunch")
+            .creator(&"wallet_creator".parse().unwrap())
+            .options(["Pizza", "Tacos"])
+            .build();
+        assert!(matches!(result, Err(VotingError::InvalidTimeSettings)));
+        let invalid_quorum = QuorumConfig {
+            pass_threshold_pct: 101,
+            ..QuorumConfig::default()
+        };
+        let result = contract
+            .poll("Lunch")
+            .creator(&"wallet_creator".parse().unwrap())
+            .options(["Pizza", "Tacos"])
+            .duration_secs(60)
+            .quorum(invalid_quorum)
+            .build();
+        assert!(matches!(result, Err(VotingError::InvalidQuorumConfig)));
+        
+        // A built poll carries every setting, and is found by its tag
+        let quorum_config = QuorumConfig {
+            min_participants: 2,
+            ..QuorumConfig::default()
+        };
+        let poll_id = contract
+            .poll("Lunch")
+            .creator(&"wallet_creator".parse().unwrap())
+            .description("Friday")
+            .options(["Pizza", "Tacos"])
+            .duration_secs(3_600)
+            .starts_in_secs(60)
+            .quorum(quorum_config)
+            .allow_vote_changes()
+            .without_creator_vote()
+            .tag(" food ")
+            .results_visibility(ResultsVisibility::AfterClose)
+            .build()
+            .unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.status, PollStatus::Scheduled);
+        assert_eq!(poll.poll_end_timestamp - poll.poll_start_timestamp, 3_600);
+        assert_eq!(poll.quorum_config, Some(quorum_config));
+        assert!(poll.allow_vote_changes && !poll.creator_can_vote);
+        assert_eq!(poll.results_visibility, ResultsVisibility::AfterClose);
+        assert_eq!(contract.list_polls_by_tag("food").len(), 1);
+    }
+}
\ No newline at end of file
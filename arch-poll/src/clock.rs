@@ -0,0 +1,88 @@
+// Time sources for the contract.
+// Every time check goes through a Clock so the contract can follow block time in a runtime,
+// and tests can move time deterministically instead of sleeping.
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::prelude::*;
+
+// Source of the current Unix timestamp in seconds. Clocks are shareable between threads so a
+// contract can be used behind a lock (see shared.rs).
+pub trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+// Clock backed by the system time (the default with std)
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+}
+
+// Clock that only moves when told to. Clones share the same time, so a test can keep a
+// handle while the contract owns another.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    current_timestamp: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new(start_timestamp: u64) -> Self {
+        MockClock {
+            current_timestamp: Arc::new(AtomicU64::new(start_timestamp)),
+        }
+    }
+    
+    pub fn set(&self, timestamp: u64) {
+        self.current_timestamp.store(timestamp, Ordering::SeqCst);
+    }
+    
+    pub fn advance(&self, seconds: u64) {
+        self.current_timestamp.fetch_add(seconds, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.current_timestamp.load(Ordering::SeqCst)
+    }
+}
+
+// Clock a new contract starts with: the system time with std
+#[cfg(feature = "std")]
+pub fn default_clock() -> Box<dyn Clock> {
+    Box::new(SystemClock)
+}
+
+// Without std there is no system time, so the clock stays at zero until the runtime injects its
+// own with set_clock
+#[cfg(not(feature = "std"))]
+pub fn default_clock() -> Box<dyn Clock> {
+    Box::new(MockClock::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_mock_clock_clones_share_time() {
+        let clock = MockClock::new(1_000);
+        let contract_clock = clock.clone();
+        
+        clock.advance(30);
+        assert_eq!(contract_clock.now(), 1_030);
+        clock.set(5);
+        assert_eq!(contract_clock.now(), 5);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,11 @@
+// Map and set types used throughout the contract.
+// With std these are the standard hash collections. alloc has no hash maps, so no_std builds use
+// the B-tree collections under the same names; every key type is ordered, and nothing depends on
+// iteration order (encoding and reports sort their keys), so behaviour is the same either way.
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap, HashSet};
+
+#[cfg(not(feature = "std"))]
+pub type HashMap<K, V> = alloc::collections::BTreeMap<K, V>;
+#[cfg(not(feature = "std"))]
+pub type HashSet<T> = alloc::collections::BTreeSet<T>;
\ No newline at end of file
@@ -0,0 +1,255 @@
+// Archive compaction.
+// Archiving takes a poll out of the live map but keeps it whole, ballots and all, so a contract
+// that runs for years keeps growing. purge_archived replaces archived polls that ended before a
+// cutoff with a CompactedPoll: the title, creator, time window, options and final results, with
+// every ballot and the participant set dropped. The poll's ballot Merkle root is kept, so a voter
+// holding an inclusion proof from before the purge can still check it against the record.
+// get_poll_results and get_poll_outcome keep answering for compacted polls; get_poll and the
+// queries that need ballots return PollNotFound. Polls whose reward pool has not been split yet,
+// and prerequisites of polls that have not ended, are kept until they no longer need their ballots.
+// The rounds of a runoff election are compacted together, so get_linked_polls never walks from a
+// whole round into a compacted one.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::{HashMap, HashSet};
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, PollOutcome, Result, VotingContract, VotingError};
+
+// Define what is kept of a purged poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactedPoll {
+    pub poll_id: PollId,
+    pub poll_number: u64,
+    pub poll_title: String,
+    pub poll_creator_address: Address,
+    pub poll_start_timestamp: u64,
+    pub poll_end_timestamp: u64,
+    pub voting_options: Vec<String>,
+    pub results: HashMap<String, u128>,          // Final results, as get_poll_results returned them
+    pub reserved_results: HashMap<String, u128>, // Final abstain and none-of-the-above votes
+    pub total_votes: u64,
+    pub outcome: Option<PollOutcome>,
+    pub ballot_root: Option<[u8; 32]>, // Merkle root over the dropped ballots
+    pub compacted_at: u64,
+}
+
+impl CompactedPoll {
+    // Keep the aggregates of an archived poll
+    pub fn new(poll: &Poll, compacted_at: u64) -> Self {
+        CompactedPoll {
+            poll_id: poll.poll_id.clone(),
+            poll_number: poll.poll_number,
+            poll_title: poll.poll_title.clone(),
+            poll_creator_address: poll.poll_creator_address.clone(),
+            poll_start_timestamp: poll.poll_start_timestamp,
+            poll_end_timestamp: poll.poll_end_timestamp,
+            voting_options: poll.voting_options.clone(),
+            results: poll.get_results(),
+            reserved_results: poll.reserved_results(),
+            total_votes: poll.total_votes() as u64,
+            outcome: poll.outcome(compacted_at),
+            ballot_root: poll.ballot_root,
+            compacted_at,
+        }
+    }
+}
+
+impl Encode for CompactedPoll {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.poll_id);
+        encoder.write(&self.poll_number);
+        encoder.write(&self.poll_title);
+        encoder.write(&self.poll_creator_address);
+        encoder.write(&self.poll_start_timestamp);
+        encoder.write(&self.poll_end_timestamp);
+        encoder.write(&self.voting_options);
+        encoder.write(&self.results);
+        encoder.write(&self.reserved_results);
+        encoder.write(&self.total_votes);
+        encoder.write(&self.outcome);
+        encoder.write(&self.ballot_root);
+        encoder.write(&self.compacted_at);
+    }
+}
+
+impl Decode for CompactedPoll {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(CompactedPoll {
+            poll_id: decoder.read()?,
+            poll_number: decoder.read()?,
+            poll_title: decoder.read()?,
+            poll_creator_address: decoder.read()?,
+            poll_start_timestamp: decoder.read()?,
+            poll_end_timestamp: decoder.read()?,
+            voting_options: decoder.read()?,
+            results: decoder.read()?,
+            reserved_results: decoder.read()?,
+            total_votes: decoder.read()?,
+            outcome: decoder.read()?,
+            ballot_root: decoder.read()?,
+            compacted_at: decoder.read()?,
+        })
+    }
+}
+
+impl VotingContract {
+    // Compact every archived poll that ended before older_than (requires PurgePolls), returning
+    // how many were compacted. Compacting cannot be undone.
+    pub fn purge_archived(&mut self, older_than: u64, caller: &Address) -> Result<usize> {
+        if !self.has_permission(caller, Permission::PurgePolls) {
+            return Err(VotingError::NotAuthorized);
+        }
+        let current_timestamp = self.now();
+        
+        let mut purgeable_poll_ids: HashSet<PollId> = self
+            .archived_polls
+            .values()
+            .filter(|poll| poll.poll_end_timestamp < older_than && !self.needs_ballots(&poll.poll_id))
+            .map(|poll| poll.poll_id.clone())
+            .collect();
+        // Keep a round while another round of its election stays whole, until none is left to keep
+        loop {
+            let kept_poll_ids: Vec<PollId> = purgeable_poll_ids
+                .iter()
+                .filter(|poll_id| {
+                    let poll = &self.archived_polls[*poll_id];
+                    let mut linked_poll_ids = poll.previous_round.iter().chain(&poll.next_round);
+                    linked_poll_ids.any(|linked_poll_id| {
+                        !purgeable_poll_ids.contains(linked_poll_id) && !self.compacted_polls.contains_key(linked_poll_id)
+                    })
+                })
+                .cloned()
+                .collect();
+            if kept_poll_ids.is_empty() {
+                break;
+            }
+            for poll_id in &kept_poll_ids {
+                purgeable_poll_ids.remove(poll_id);
+            }
+        }
+        let mut purged_poll_ids: Vec<PollId> = purgeable_poll_ids.into_iter().collect();
+        purged_poll_ids.sort();
+        for poll_id in &purged_poll_ids {
+            if let Some(poll) = self.archived_polls.remove(poll_id) {
+                self.poll_index.delete(&poll);
+                self.compacted_polls.insert(poll_id.clone(), CompactedPoll::new(&poll, current_timestamp));
+                self.emit(ContractEvent::PollCompacted { poll_id: poll_id.clone() });
+            }
+        }
+        Ok(purged_poll_ids.len())
+    }
+    
+    // Get what is kept of a compacted poll
+    pub fn get_compacted_poll(&self, poll_id: &PollId) -> Result<&CompactedPoll> {
+        self.compacted_polls.get(poll_id).ok_or(VotingError::PollNotFound)
+    }
+    
+    // Iterate over the compacted polls, in no particular order
+    pub fn iter_compacted_polls(&self) -> impl Iterator<Item = &CompactedPoll> {
+        self.compacted_polls.values()
+    }
+    
+    // Check whether an archived poll's ballots are still needed: for a reward pool not yet split,
+    // or by a poll waiting on it as a prerequisite
+    fn needs_ballots(&self, poll_id: &PollId) -> bool {
+        let current_timestamp = self.now();
+        self.reward_pools.get(poll_id).is_some_and(|pool| !pool.shares_computed)
+            || self.active_polls.values().any(|poll| {
+                !poll.has_ended(current_timestamp)
+                    && poll.depends_on.iter().any(|dependency| dependency.poll_id == *poll_id)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::runoff::RunoffConfig;
+    
+    #[test]
+    fn test_purge_archived() {
+        let clock = MockClock::new(1_000);
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(clock.clone()));
+        let create = |contract: &mut VotingContract, poll_title: &str| {
+            let poll_id = contract
+                .poll(poll_title)
+                .creator(&"wallet_creator".parse().unwrap())
+                .options(["Yes", "No"])
+                .duration_secs(60)
+                .build()
+                .unwrap();
+            contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+            poll_id
+        };
+        let old_poll_id = create(&mut contract, "Old");
+        clock.advance(3_600);
+        let recent_poll_id = create(&mut contract, "Recent");
+        clock.advance(60);
+        contract.process_expired_polls(&"wallet_admin".parse().unwrap()).unwrap();
+        contract.archive_poll(&old_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.archive_poll(&recent_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let ballot_root = contract.get_poll(&old_poll_id).unwrap().ballot_root;
+        
+        // Only archived polls that ended before the cutoff are compacted
+        assert!(matches!(
+            contract.purge_archived(2_000, &"wallet_creator".parse().unwrap()),
+            Err(VotingError::NotAuthorized)
+        ));
+        assert_eq!(contract.purge_archived(2_000, &"wallet_admin".parse().unwrap()).unwrap(), 1);
+        assert!(matches!(contract.get_poll(&old_poll_id), Err(VotingError::PollNotFound)));
+        assert!(contract.get_poll(&recent_poll_id).is_ok());
+        
+        // Results, outcome and ballot root stay queryable, across a restore
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll_results(&old_poll_id).unwrap()["Yes"], 1);
+        assert_eq!(restored.get_poll_outcome(&old_poll_id).unwrap(), Some(PollOutcome::Passed));
+        let compacted_poll = restored.get_compacted_poll(&old_poll_id).unwrap();
+        assert_eq!((compacted_poll.total_votes, compacted_poll.ballot_root), (1, ballot_root));
+        assert_eq!(restored.get_votes_by_address(&"wallet_voter".parse().unwrap()).len(), 1);
+    }
+    
+    #[test]
+    fn test_runoff_rounds_are_purged_together() {
+        let clock = MockClock::new(1_000);
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = contract
+            .poll("Chair")
+            .creator(&"wallet_creator".parse().unwrap())
+            .options(["Ada", "Grace", "Linus"])
+            .duration_secs(60)
+            .build()
+            .unwrap();
+        let runoff = RunoffConfig {
+            threshold_bps: 5_000,
+            runoff_duration_seconds: 60,
+        };
+        contract.set_runoff(&poll_id, Some(runoff), &"wallet_creator".parse().unwrap()).unwrap();
+        for (voter_address, option) in [("wallet_a", "Ada"), ("wallet_b", "Grace"), ("wallet_c", "Linus")] {
+            contract.vote(&poll_id, voter_address.parse().unwrap(), option).unwrap();
+        }
+        clock.advance(60);
+        contract.tick();
+        let runoff_poll_id = contract.get_linked_polls(&poll_id).unwrap()[1].poll_id.clone();
+        clock.advance(60);
+        contract.tick();
+        
+        // The first round stays whole while the runoff is not archived, so the chain still reads
+        contract.archive_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(contract.purge_archived(10_000, &"wallet_admin".parse().unwrap()).unwrap(), 0);
+        let rounds = contract.get_linked_polls(&runoff_poll_id).unwrap();
+        assert_eq!(rounds.iter().map(|round| &round.poll_id).collect::<Vec<_>>(), [&poll_id, &runoff_poll_id]);
+        
+        // Once both rounds are archived they are compacted together
+        contract.archive_poll(&runoff_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(contract.purge_archived(10_000, &"wallet_admin".parse().unwrap()).unwrap(), 2);
+        for round_poll_id in [&poll_id, &runoff_poll_id] {
+            assert!(contract.get_compacted_poll(round_poll_id).is_ok());
+            assert!(matches!(contract.get_linked_polls(round_poll_id), Err(VotingError::PollNotFound)));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,166 @@
+// Contract-wide limits on poll contents.
+// Every poll is stored and serialized in full, so the limits keep a single creator from filling
+// the contract with oversized titles, descriptions or option lists, or with polls. Lengths are
+// measured in bytes. Limits apply to new polls, draft updates and multi-option ballots; existing
+// polls are left alone when the limits are tightened.
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::prelude::*;
+use crate::rate_limit::RateLimit;
+use crate::{Result, VotingError, DEFAULT_MAX_POLL_DURATION_SECONDS};
+
+// Default number of options a poll may offer
+pub const DEFAULT_MAX_OPTIONS_PER_POLL: usize = 64;
+
+// Default longest title, in bytes
+pub const DEFAULT_MAX_TITLE_LEN: usize = 200;
+
+// Default longest description, in bytes
+pub const DEFAULT_MAX_DESCRIPTION_LEN: usize = 10_000;
+
+// Default number of open polls one creator may run at once
+pub const DEFAULT_MAX_POLLS_PER_CREATOR: usize = 100;
+
+// Define the limits applied to poll input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractConfig {
+    pub max_options_per_poll: usize,  // Options a poll may offer, and entries a ballot may list
+    pub max_title_len: usize,         // Longest title, in bytes
+    pub max_description_len: usize,   // Longest description, in bytes
+    pub max_polls_per_creator: usize, // Polls one creator may have open (not yet ended) at once
+    pub max_poll_duration: u64,       // Longest duration a poll may be created with, in seconds
+    pub min_balance_to_create: u128,  // Token balance a creator must hold (0 disables, see balance)
+    pub poll_creation_limit: Option<RateLimit>, // Polls one address may create per window (see rate_limit)
+    pub vote_limit: Option<RateLimit>, // Ballots one address may cast or commit per window
+}
+
+impl Default for ContractConfig {
+    fn default() -> Self {
+        ContractConfig {
+            max_options_per_poll: DEFAULT_MAX_OPTIONS_PER_POLL,
+            max_title_len: DEFAULT_MAX_TITLE_LEN,
+            max_description_len: DEFAULT_MAX_DESCRIPTION_LEN,
+            max_polls_per_creator: DEFAULT_MAX_POLLS_PER_CREATOR,
+            max_poll_duration: DEFAULT_MAX_POLL_DURATION_SECONDS,
+            min_balance_to_create: 0,
+            poll_creation_limit: None,
+            vote_limit: None,
+        }
+    }
+}
+
+impl ContractConfig {
+    // Check a poll's title, description and options against the limits
+    pub fn check_poll_contents(&self, poll_title: &str, poll_description: &str, poll_options: &[String]) -> Result<()> {
+        if poll_title.len() > self.max_title_len {
+            return Err(VotingError::TitleTooLong);
+        }
+        if poll_description.len() > self.max_description_len {
+            return Err(VotingError::DescriptionTooLong);
+        }
+        self.check_option_count(poll_options.len())
+    }
+    
+    // Check the number of options a poll offers or a ballot lists
+    pub fn check_option_count(&self, option_count: usize) -> Result<()> {
+        if option_count > self.max_options_per_poll {
+            return Err(VotingError::TooManyOptions);
+        }
+        Ok(())
+    }
+    
+    // Check a poll duration: zero is invalid, and longer than the limit is too long
+    pub fn check_duration(&self, poll_duration_seconds: u64) -> Result<()> {
+        if poll_duration_seconds == 0 {
+            return Err(VotingError::InvalidTimeSettings);
+        }
+        if poll_duration_seconds > self.max_poll_duration {
+            return Err(VotingError::DurationTooLong);
+        }
+        Ok(())
+    }
+}
+
+impl Encode for ContractConfig {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.max_options_per_poll);
+        encoder.write(&self.max_title_len);
+        encoder.write(&self.max_description_len);
+        encoder.write(&self.max_polls_per_creator);
+        encoder.write(&self.max_poll_duration);
+        encoder.write(&self.min_balance_to_create);
+        encoder.write(&self.poll_creation_limit);
+        encoder.write(&self.vote_limit);
+    }
+}
+
+impl Decode for ContractConfig {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(ContractConfig {
+            max_options_per_poll: decoder.read()?,
+            max_title_len: decoder.read()?,
+            max_description_len: decoder.read()?,
+            max_polls_per_creator: decoder.read()?,
+            max_poll_duration: decoder.read()?,
+            min_balance_to_create: decoder.read()?,
+            poll_creation_limit: decoder.read()?,
+            vote_limit: decoder.read()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::id::PollId;
+    use crate::{Result, VotingContract, VotingError};
+    
+    fn create(
+        contract: &mut VotingContract,
+        poll_title: &str,
+        poll_description: &str,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        contract.create_poll(
+            "wallet_creator".parse().unwrap(),
+            poll_title.to_string(),
+            poll_description.to_string(),
+            poll_options,
+            poll_duration_seconds,
+        )
+    }
+    
+    #[test]
+    fn test_contract_limits() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.config.max_options_per_poll = 3;
+        contract.config.max_title_len = 8;
+        contract.config.max_description_len = 16;
+        contract.config.max_polls_per_creator = 1;
+        contract.config.max_poll_duration = 100;
+        let options = |count: usize| (0..count).map(|index| format!("Option {}", index)).collect::<Vec<_>>();
+        
+        assert!(matches!(create(&mut contract, "Too long a title", "", options(2), 60), Err(VotingError::TitleTooLong)));
+        let result = create(&mut contract, "Lunch", &"x".repeat(17), options(2), 60);
+        assert!(matches!(result, Err(VotingError::DescriptionTooLong)));
+        assert!(matches!(create(&mut contract, "Lunch", "", options(4), 60), Err(VotingError::TooManyOptions)));
+        assert!(matches!(create(&mut contract, "Lunch", "", options(2), 101), Err(VotingError::DurationTooLong)));
+        assert!(matches!(create(&mut contract, "Lunch", "", options(2), 0), Err(VotingError::InvalidTimeSettings)));
+        
+        // Limits are inclusive, and a creator at the poll limit must wait for a poll to end
+        let poll_id = create(&mut contract, "Lunch", &"x".repeat(16), options(3), 100).unwrap();
+        assert!(matches!(create(&mut contract, "Dinner", "", options(2), 60), Err(VotingError::TooManyPolls)));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let ranked_poll_id = contract
+            .create_ranked_poll("wallet_creator".parse().unwrap(), "Dinner".to_string(), String::new(), options(3), 60)
+            .unwrap();
+            
+        // Ballots may not list more entries than a poll could offer
+        let result = contract.vote_ranked(&ranked_poll_id, "wallet_voter".parse().unwrap(), options(4));
+        assert!(matches!(result, Err(VotingError::TooManyOptions)));
+        contract.vote_ranked(&ranked_poll_id, "wallet_voter".parse().unwrap(), options(3)).unwrap();
+        
+        // The limits are saved with the contract
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.config, contract.config);
+    }
+}
\ No newline at end of file
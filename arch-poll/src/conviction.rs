@@ -0,0 +1,434 @@
+// Conviction voting for continuous proposals.
+// In a conviction poll each option is a standing proposal. Voters stake their voting weight on one
+// option and may move or withdraw the stake at any time. Every period an option keeps decay_bps of
+// its conviction and gains the weight staked on it, so conviction grows toward staked * 10000 /
+// (10000 - decay_bps) the longer a stake stays put and fades once it moves away. An option passes
+// the first time its conviction reaches the threshold, which suits ongoing funding requests better
+// than a fixed deadline. Conviction is recomputed whole periods at a time, by update_convictions
+// (which tick runs as well) and before every stake change, and never accrues past the poll's end.
+// Stakes are not ballots: they leave the poll's tallies and participants untouched, and the
+// poll's results are its convictions as of the last recomputation.
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::rate_limit::RateLimitedAction;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError, VotingMethod};
+
+// Basis points of conviction an option can keep per period
+const RETENTION_SCALE: u128 = 10_000;
+
+// Fixed-point scale of the retained share after several periods
+const FACTOR_SCALE: u128 = 1_000_000_000_000;
+
+// Define how conviction accrues in a poll and when an option passes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvictionConfig {
+    pub decay_bps: u16,      // Share of its conviction an option keeps each period (below 10000)
+    pub period_seconds: u64, // Length of one accrual period
+    pub threshold: u128,     // Conviction an option needs to pass
+}
+
+impl ConvictionConfig {
+    // Check the config can accrue conviction and be passed
+    pub fn is_valid(&self) -> bool {
+        (self.decay_bps as u128) < RETENTION_SCALE && self.period_seconds > 0 && self.threshold > 0
+    }
+}
+
+// Define a voter's stake in a conviction poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvictionStake {
+    pub option: String, // Option the weight is staked on
+    pub amount: u128,   // Voting weight staked
+}
+
+// Define the conviction state of a poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvictionState {
+    pub config: ConvictionConfig,
+    pub stakes: HashMap<Address, ConvictionStake>, // Current stake of each voter
+    pub convictions: HashMap<String, u128>,        // Conviction of each option as of updated_at
+    pub updated_at: u64,                           // Start of the period conviction has accrued to
+    pub passed_options: Vec<String>,               // Options that reached the threshold, in passing order
+}
+
+// Multiply by numerator / denominator, rounding down, without overflowing for numerators up to
+// FACTOR_SCALE
+fn mul_div(value: u128, numerator: u128, denominator: u128) -> u128 {
+    (value / denominator).saturating_mul(numerator).saturating_add(value % denominator * numerator / denominator)
+}
+
+// Get the share of its conviction an option keeps over several periods, scaled by FACTOR_SCALE
+fn retained_factor(decay_bps: u16, periods: u64) -> u128 {
+    let mut factor = FACTOR_SCALE;
+    let mut base = decay_bps as u128 * (FACTOR_SCALE / RETENTION_SCALE);
+    let mut remaining_periods = periods;
+    while remaining_periods > 0 && factor > 0 {
+        if remaining_periods & 1 == 1 {
+            factor = factor * base / FACTOR_SCALE;
+        }
+        base = base * base / FACTOR_SCALE;
+        remaining_periods >>= 1;
+    }
+    factor
+}
+
+// Accrue an option's conviction over several periods with a constant stake. Conviction moves from
+// where it was toward the stake's asymptote, closing all but the retained share of the gap.
+fn accrue(conviction: u128, staked: u128, decay_bps: u16, periods: u64) -> u128 {
+    let asymptote = mul_div(staked, RETENTION_SCALE, RETENTION_SCALE - decay_bps as u128);
+    let factor = retained_factor(decay_bps, periods);
+    if conviction >= asymptote {
+        asymptote.saturating_add(mul_div(conviction - asymptote, factor, FACTOR_SCALE))
+    } else {
+        asymptote - mul_div(asymptote - conviction, factor, FACTOR_SCALE)
+    }
+}
+
+impl ConvictionState {
+    // Start a poll's conviction state with nothing staked
+    pub fn new(config: ConvictionConfig, started_at: u64) -> Self {
+        ConvictionState {
+            config,
+            stakes: HashMap::new(),
+            convictions: HashMap::new(),
+            updated_at: started_at,
+            passed_options: Vec::new(),
+        }
+    }
+    
+    // Get the total weight staked on an option
+    pub fn staked_on(&self, option: &str) -> u128 {
+        self.stakes
+            .values()
+            .filter(|stake| stake.option == option)
+            .fold(0u128, |total, stake| total.saturating_add(stake.amount))
+    }
+    
+    // Check the state is consistent with the poll holding it
+    pub(crate) fn is_valid(&self, poll: &Poll) -> bool {
+        self.config.is_valid()
+            && self.stakes.iter().all(|(voter_address, stake)| {
+                poll.voting_options.contains(&stake.option) && stake.amount > 0 && poll.is_eligible(voter_address)
+            })
+            && self.convictions.keys().chain(&self.passed_options).all(|option| poll.voting_options.contains(option))
+    }
+}
+
+impl Encode for ConvictionConfig {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.decay_bps);
+        encoder.write(&self.period_seconds);
+        encoder.write(&self.threshold);
+    }
+}
+
+impl Decode for ConvictionConfig {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(ConvictionConfig {
+            decay_bps: decoder.read()?,
+            period_seconds: decoder.read()?,
+            threshold: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for ConvictionStake {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.option);
+        encoder.write(&self.amount);
+    }
+}
+
+impl Decode for ConvictionStake {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(ConvictionStake {
+            option: decoder.read()?,
+            amount: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for ConvictionState {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.config);
+        encoder.write(&self.stakes);
+        encoder.write(&self.convictions);
+        encoder.write(&self.updated_at);
+        encoder.write(&self.passed_options);
+    }
+}
+
+impl Decode for ConvictionState {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(ConvictionState {
+            config: decoder.read()?,
+            stakes: decoder.read()?,
+            convictions: decoder.read()?,
+            updated_at: decoder.read()?,
+            passed_options: decoder.read()?,
+        })
+    }
+}
+
+impl Poll {
+    // Accrue conviction for the whole periods elapsed by now (or the poll's end, if earlier),
+    // returning the options that passed as a result, in ballot order
+    pub fn update_convictions(&mut self, now: u64) -> Vec<String> {
+        let accrue_until = now.min(self.poll_end_timestamp);
+        let Some(state) = self.conviction.as_mut() else {
+            return Vec::new();
+        };
+        let periods = accrue_until.saturating_sub(state.updated_at) / state.config.period_seconds;
+        if periods == 0 {
+            return Vec::new();
+        }
+        
+        let mut passed_now = Vec::new();
+        for option in &self.voting_options {
+            let staked = state.staked_on(option);
+            let conviction = state.convictions.get(option).copied().unwrap_or(0);
+            let conviction = accrue(conviction, staked, state.config.decay_bps, periods);
+            if conviction >= state.config.threshold && !state.passed_options.contains(option) {
+                state.passed_options.push(option.clone());
+                passed_now.push(option.clone());
+            }
+            state.convictions.insert(option.clone(), conviction);
+        }
+        state.updated_at += periods * state.config.period_seconds;
+        self.invalidate_results();
+        passed_now
+    }
+    
+    // Get the options of a conviction poll that have passed, in passing order
+    pub fn passed_options(&self) -> &[String] {
+        self.conviction.as_ref().map_or(&[], |state| state.passed_options.as_slice())
+    }
+}
+
+impl VotingContract {
+    // Create a conviction poll, in which each option passes once enough conviction builds up
+    // behind it. The duration bounds how long stakes can accrue.
+    pub fn create_conviction_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        config: ConvictionConfig,
+    ) -> Result<PollId> {
+        if !config.is_valid() {
+            return Err(VotingError::PollCreationFailed);
+        }
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.voting_method = VotingMethod::Conviction;
+        new_poll.conviction = Some(ConvictionState::new(config, new_poll.poll_start_timestamp));
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Stake a voter's weight on an option of a conviction poll, moving any stake they already
+    // hold. Conviction accrued so far stays with the options it built up on.
+    pub fn stake_conviction(&mut self, poll_id: &PollId, voter_address: Address, selected_option: &str) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let voter_weight = self.ballot_weight(poll_id, &voter_address)?;
+        let current_timestamp = self.now();
+        let holds_balance = self.voter_holds_balance(poll_id, &voter_address);
+        let vote_fee = self.vote_fee_of(poll_id);
+        self.check_rate_limit(RateLimitedAction::Vote, &voter_address, 1)?;
+        self.check_fee(&voter_address, vote_fee)?;
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_method != VotingMethod::Conviction {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        if !poll.creator_can_vote && voter_address == poll.poll_creator_address {
+            return Err(VotingError::CreatorCannotVote);
+        }
+        if !poll.is_eligible(&voter_address) {
+            return Err(VotingError::NotEligible);
+        }
+        if !holds_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        if !poll.voting_options.iter().any(|option| option == selected_option) {
+            return Err(VotingError::InvalidOption);
+        }
+        if voter_weight == 0 {
+            return Err(VotingError::InvalidParameter);
+        }
+        
+        self.update_poll_convictions(poll_id, current_timestamp);
+        let poll = self.live_poll_mut(poll_id)?;
+        let state = poll.conviction.as_mut().ok_or(VotingError::WrongVotingMethod)?;
+        state.stakes.insert(voter_address.clone(), ConvictionStake {
+            option: selected_option.to_string(),
+            amount: voter_weight,
+        });
+        
+        self.record_rate_limited(RateLimitedAction::Vote, &voter_address);
+        self.collect_fee(poll_id, &voter_address, vote_fee);
+        self.emit(ContractEvent::ConvictionStaked {
+            poll_id: poll_id.clone(),
+            voter_address,
+            selected_option: selected_option.to_string(),
+            amount: voter_weight,
+        });
+        Ok(())
+    }
+    
+    // Withdraw a voter's stake from a conviction poll
+    pub fn unstake_conviction(&mut self, poll_id: &PollId, voter_address: &Address) -> Result<()> {
+        let voter_address = self.parse_address(voter_address)?;
+        let current_timestamp = self.now();
+        let poll = self.live_poll_mut(poll_id)?;
+        if poll.voting_method != VotingMethod::Conviction {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        if !poll.conviction.as_ref().is_some_and(|state| state.stakes.contains_key(&voter_address)) {
+            return Err(VotingError::NotVoted);
+        }
+        
+        self.update_poll_convictions(poll_id, current_timestamp);
+        let poll = self.live_poll_mut(poll_id)?;
+        if let Some(state) = poll.conviction.as_mut() {
+            state.stakes.remove(&voter_address);
+        }
+        self.emit(ContractEvent::ConvictionUnstaked {
+            poll_id: poll_id.clone(),
+            voter_address,
+        });
+        Ok(())
+    }
+    
+    // Recompute the conviction of every open conviction poll up to now, returning the poll and
+    // option of each option that passed as a result, sorted
+    pub fn update_convictions(&mut self, now: u64) -> Vec<(PollId, String)> {
+        let mut poll_ids: Vec<PollId> = self
+            .active_polls
+            .iter()
+            .filter(|(_, poll)| poll.conviction.is_some() && !poll.is_closed())
+            .map(|(poll_id, _)| poll_id.clone())
+            .collect();
+        poll_ids.sort();
+        
+        let mut passed = Vec::new();
+        for poll_id in poll_ids {
+            for option in self.update_poll_convictions(&poll_id, now) {
+                passed.push((poll_id.clone(), option));
+            }
+        }
+        passed
+    }
+    
+    // Recompute one poll's conviction up to now, announcing the options that passed
+    fn update_poll_convictions(&mut self, poll_id: &PollId, now: u64) -> Vec<String> {
+        let Some(poll) = self.active_polls.get_mut(poll_id) else {
+            return Vec::new();
+        };
+        let passed_options = poll.update_convictions(now);
+        let convictions: Vec<u128> = passed_options
+            .iter()
+            .map(|option| poll.conviction.as_ref().and_then(|state| state.convictions.get(option)).copied().unwrap_or(0))
+            .collect();
+        for (option, conviction) in passed_options.iter().zip(convictions) {
+            self.emit(ContractEvent::ConvictionPassed {
+                poll_id: poll_id.clone(),
+                selected_option: option.clone(),
+                conviction,
+            });
+        }
+        passed_options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, MockClock};
+    
+    #[test]
+    fn test_conviction_voting() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        contract.set_voter_weight(&"wallet_a".parse().unwrap(), 100, &"wallet_admin".parse().unwrap()).unwrap();
+        contract.set_voter_weight(&"wallet_b".parse().unwrap(), 100, &"wallet_admin".parse().unwrap()).unwrap();
+        let config = ConvictionConfig {
+            decay_bps: 5_000,
+            period_seconds: 10,
+            threshold: 150,
+        };
+        let result = contract.create_conviction_poll(
+            "wallet_creator".parse().unwrap(),
+            "Grants".to_string(),
+            String::new(),
+            vec!["Docs".to_string(), "Audit".to_string()],
+            600,
+            ConvictionConfig { decay_bps: 10_000, ..config },
+        );
+        assert!(matches!(result, Err(VotingError::PollCreationFailed)));
+        let poll_id = contract
+            .create_conviction_poll(
+                "wallet_creator".parse().unwrap(),
+                "Grants".to_string(),
+                "Ongoing funding".to_string(),
+                vec!["Docs".to_string(), "Audit".to_string()],
+                600,
+                config,
+            )
+            .unwrap();
+        let result = contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Docs");
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        
+        // A stake of 100 keeps half its conviction per period, so conviction climbs 100, 150, ...
+        // toward 200 and Docs passes in the second period
+        contract.stake_conviction(&poll_id, "wallet_a".parse().unwrap(), "Docs").unwrap();
+        clock.advance(15);
+        assert!(contract.update_convictions(clock.now()).is_empty());
+        assert_eq!(contract.get_poll(&poll_id).unwrap().get_results()["Docs"], 100);
+        clock.advance(5);
+        let passed = contract.update_convictions(clock.now());
+        assert_eq!(passed, vec![(poll_id.clone(), "Docs".to_string())]);
+        
+        // Moving a stake lets the old option's conviction decay while the new one builds up
+        contract.stake_conviction(&poll_id, "wallet_a".parse().unwrap(), "Audit").unwrap();
+        contract.stake_conviction(&poll_id, "wallet_b".parse().unwrap(), "Audit").unwrap();
+        clock.advance(10);
+        contract.tick();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.get_results()["Docs"], poll.get_results()["Audit"]), (75, 200));
+        assert_eq!(poll.passed_options(), ["Docs".to_string(), "Audit".to_string()]);
+        assert_eq!(poll.participant_addresses.len(), 0);
+        
+        // Stakes can be withdrawn once, and survive a restore
+        contract.unstake_conviction(&poll_id, &"wallet_b".parse().unwrap()).unwrap();
+        let result = contract.unstake_conviction(&poll_id, &"wallet_b".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotVoted)));
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&poll_id).unwrap().conviction, contract.get_poll(&poll_id).unwrap().conviction);
+    }
+    
+    #[test]
+    fn test_accrue() {
+        assert_eq!(accrue(0, 100, 5_000, 1), 100);
+        assert_eq!(accrue(0, 100, 5_000, 64), 200);
+        assert_eq!(accrue(200, 0, 5_000, 1), 100);
+        assert_eq!(accrue(u128::MAX / 2, u128::MAX / 2, 9_999, u64::MAX), u128::MAX);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,227 @@
+// Creator registry and poll quotas.
+// A creator may only have so many polls open at once, so one address cannot flood the contract:
+// config.max_polls_per_creator sets the quota for everyone, and moderators can give a creator a
+// quota of their own, tighter for a flooder or looser for a trusted organizer. Polls count
+// against the quota until they end. Creating past the config's quota returns TooManyPolls, and
+// creating past a creator's own quota returns QuotaExceeded.
+// The registry also remembers how many polls each creator has made, which outlives archived and
+// deleted polls, and creator_stats adds the figures derived from the creator's stored polls.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Define what the registry keeps about one creator
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CreatorRecord {
+    pub polls_created: u64,        // Polls the creator has made, deleted ones included
+    pub last_created_at: u64,      // When the creator last made a poll (0 before the first)
+    pub poll_quota: Option<usize>, // Open polls allowed, overriding config.max_polls_per_creator
+}
+
+impl Encode for CreatorRecord {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.polls_created);
+        encoder.write(&self.last_created_at);
+        encoder.write(&self.poll_quota);
+    }
+}
+
+impl Decode for CreatorRecord {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(CreatorRecord {
+            polls_created: decoder.read()?,
+            last_created_at: decoder.read()?,
+            poll_quota: decoder.read()?,
+        })
+    }
+}
+
+// Define a creator's figures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatorStats {
+    pub creator_address: Address,
+    pub polls_created: u64,
+    pub last_created_at: u64,
+    pub open_polls: usize,       // Stored polls that have not ended
+    pub total_participants: u64, // Participants across the creator's stored polls
+    pub poll_quota: usize,       // Open polls allowed, from the creator's quota or the config
+}
+
+// Build the registry from stored polls, for snapshots written before it existed
+pub(crate) fn count_creators<'a>(polls: impl Iterator<Item = &'a Poll>) -> HashMap<Address, CreatorRecord> {
+    let mut creators: HashMap<Address, CreatorRecord> = HashMap::new();
+    for poll in polls {
+        let record = creators.entry(poll.poll_creator_address.clone()).or_default();
+        record.polls_created += 1;
+        record.last_created_at = record.last_created_at.max(poll.poll_start_timestamp);
+    }
+    creators
+}
+
+impl VotingContract {
+    // Get the number of open polls a creator may have
+    pub fn poll_quota_of(&self, creator_address: &Address) -> usize {
+        self.creators
+            .get(&self.lookup_address(creator_address))
+            .and_then(|record| record.poll_quota)
+            .unwrap_or(self.config.max_polls_per_creator)
+    }
+    
+    // Give a creator their own quota of open polls, or None to follow the config again
+    // (requires ModeratePolls). Polls already open stay open.
+    pub fn set_creator_quota(
+        &mut self,
+        creator_address: &Address,
+        poll_quota: Option<usize>,
+        caller: &Address,
+    ) -> Result<()> {
+        let creator_address = self.parse_address(creator_address)?;
+        if !self.has_permission(caller, Permission::ModeratePolls) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        self.creators.entry(creator_address.clone()).or_default().poll_quota = poll_quota;
+        self.emit(ContractEvent::CreatorQuotaSet {
+            creator_address,
+            poll_quota,
+        });
+        Ok(())
+    }
+    
+    // Get a creator's figures (None for an address that never created a poll or had a quota set)
+    pub fn creator_stats(&self, creator_address: &Address) -> Option<CreatorStats> {
+        let creator_address = self.lookup_address(creator_address);
+        let record = self.creators.get(&creator_address)?;
+        let current_timestamp = self.now();
+        let creator_polls = self
+            .active_polls
+            .values()
+            .chain(self.archived_polls.values())
+            .filter(|poll| poll.poll_creator_address == creator_address);
+        
+        let mut open_polls = 0;
+        let mut total_participants = 0;
+        for poll in creator_polls {
+            if !poll.has_ended(current_timestamp) {
+                open_polls += 1;
+            }
+            total_participants += poll.total_votes() as u64;
+        }
+        Some(CreatorStats {
+            creator_address: creator_address.clone(),
+            polls_created: record.polls_created,
+            last_created_at: record.last_created_at,
+            open_polls,
+            total_participants,
+            poll_quota: self.poll_quota_of(&creator_address),
+        })
+    }
+    
+    // Get every registered creator's figures, most polls created first
+    pub fn list_creators(&self) -> Vec<CreatorStats> {
+        let mut creators: Vec<CreatorStats> =
+            self.creators.keys().filter_map(|creator_address| self.creator_stats(creator_address)).collect();
+        creators.sort_by(|first, second| {
+            second
+                .polls_created
+                .cmp(&first.polls_created)
+                .then_with(|| first.creator_address.cmp(&second.creator_address))
+        });
+        creators
+    }
+    
+    // Refuse new polls that would take a creator past their quota of open polls
+    pub(crate) fn check_poll_quota(&self, creator_address: &Address, poll_count: usize) -> Result<()> {
+        let current_timestamp = self.now();
+        let open_polls = self
+            .active_polls
+            .values()
+            .filter(|poll| poll.poll_creator_address == *creator_address && !poll.has_ended(current_timestamp))
+            .count();
+        let own_quota = self
+            .creators
+            .get(&self.lookup_address(creator_address))
+            .and_then(|record| record.poll_quota);
+        if open_polls + poll_count > own_quota.unwrap_or(self.config.max_polls_per_creator) {
+            return Err(if own_quota.is_some() { VotingError::QuotaExceeded } else { VotingError::TooManyPolls });
+        }
+        Ok(())
+    }
+    
+    // Count a new poll in its creator's record
+    pub(crate) fn record_poll_created(&mut self, creator_address: &Address) {
+        let current_timestamp = self.now();
+        let record = self.creators.entry(creator_address.clone()).or_default();
+        record.polls_created += 1;
+        record.last_created_at = current_timestamp;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_control::Role;
+    
+    #[test]
+    fn test_creator_quotas() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let create = |contract: &mut VotingContract, poll_title: &str| {
+            contract
+                .poll(poll_title)
+                .creator(&"wallet_creator".parse().unwrap())
+                .options(["Yes", "No"])
+                .duration_secs(60)
+                .build()
+        };
+        let poll_id = create(&mut contract, "First").unwrap();
+        create(&mut contract, "Second").unwrap();
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+        
+        // A moderator tightens one creator's quota; polls already open stay open
+        contract
+            .grant_role(
+                &"wallet_moderator".parse().unwrap(),
+                Role::Moderator,
+                &"wallet_admin".parse().unwrap(),
+            )
+            .unwrap();
+        let result = contract.set_creator_quota(
+            &"wallet_creator".parse().unwrap(),
+            Some(2),
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract
+            .set_creator_quota(
+                &"wallet_creator".parse().unwrap(),
+                Some(2),
+                &"wallet_moderator".parse().unwrap(),
+            )
+            .unwrap();
+        assert!(matches!(create(&mut contract, "Third"), Err(VotingError::QuotaExceeded)));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        create(&mut contract, "Third").unwrap();
+        
+        // Without a quota of their own, the creator falls back to the config's limit
+        contract.config.max_polls_per_creator = 2;
+        contract
+            .set_creator_quota(
+                &"wallet_creator".parse().unwrap(),
+                None,
+                &"wallet_moderator".parse().unwrap(),
+            )
+            .unwrap();
+        assert!(matches!(create(&mut contract, "Fourth"), Err(VotingError::TooManyPolls)));
+        
+        // The registry keeps counting after polls are closed, and survives a restore
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let stats = restored.creator_stats(&"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!((stats.polls_created, stats.open_polls, stats.total_participants), (3, 2, 1));
+        assert_eq!(stats.poll_quota, 2);
+        assert_eq!(restored.list_creators().len(), 1);
+        assert_eq!(restored.creator_stats(&"wallet_voter".parse().unwrap()), None);
+    }
+}
\ No newline at end of file
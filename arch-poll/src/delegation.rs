@@ -0,0 +1,150 @@
+// Liquid democracy support: addresses can hand their voting power to another address.
+// Delegations are resolved when a delegate votes, carrying the power of every address that
+// delegated to them (directly or through a chain) and has not voted in that poll itself.
+use crate::address::Address;
+use crate::collections::{HashMap, HashSet};
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::prelude::*;
+use crate::{Result, VotingError};
+
+// Define the registry of standing delegations
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DelegationRegistry {
+    delegations: HashMap<Address, Address>, // Maps delegators to the address they delegated to
+}
+
+impl DelegationRegistry {
+    pub fn new() -> Self {
+        DelegationRegistry::default()
+    }
+    
+    // Delegate an address's voting power, replacing any previous delegation.
+    // Self-delegation and delegations that would form a cycle are rejected.
+    pub fn delegate(&mut self, delegator_address: Address, delegate_address: Address) -> Result<()> {
+        if delegator_address == delegate_address || self.resolves_to(&delegate_address, &delegator_address) {
+            return Err(VotingError::DelegationCycle);
+        }
+        
+        self.delegations.insert(delegator_address, delegate_address);
+        Ok(())
+    }
+    
+    // Remove an address's delegation, returning whether one existed
+    pub fn revoke(&mut self, delegator_address: &Address) -> bool {
+        self.delegations.remove(delegator_address).is_some()
+    }
+    
+    // Get the address a delegator handed their power to
+    pub fn delegate_of(&self, delegator_address: &Address) -> Option<&Address> {
+        self.delegations.get(delegator_address)
+    }
+    
+    // Check whether following the delegation chain from an address reaches the target
+    fn resolves_to(&self, start_address: &str, target_address: &str) -> bool {
+        let mut current_address = start_address;
+        // The registry never holds a cycle, so every chain ends
+        while let Some(next_address) = self.delegations.get(current_address) {
+            if next_address == target_address {
+                return true;
+            }
+            current_address = next_address.as_str();
+        }
+        false
+    }
+    
+    // Collect every address whose voting power flows to the given address, following chains
+    // backwards. Addresses for which `voted_directly` is true keep their own power (and that of
+    // their own delegators), so the walk does not continue through them.
+    pub fn delegators_of(&self, delegate_address: &Address, voted_directly: impl Fn(&Address) -> bool) -> Vec<Address> {
+        let mut delegators_by_delegate: HashMap<&Address, Vec<&Address>> = HashMap::new();
+        for (delegator_address, delegate) in &self.delegations {
+            delegators_by_delegate.entry(delegate).or_default().push(delegator_address);
+        }
+        
+        let mut collected = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![delegate_address];
+        while let Some(current_address) = pending.pop() {
+            for &delegator_address in delegators_by_delegate.get(current_address).into_iter().flatten() {
+                if voted_directly(delegator_address) || !visited.insert(delegator_address) {
+                    continue;
+                }
+                collected.push(delegator_address.clone());
+                pending.push(delegator_address);
+            }
+        }
+        
+        collected.sort();
+        collected
+    }
+    
+    pub fn len(&self) -> usize {
+        self.delegations.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.delegations.is_empty()
+    }
+}
+
+impl Encode for DelegationRegistry {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.delegations);
+    }
+}
+
+impl Decode for DelegationRegistry {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let delegations: HashMap<Address, Address> = decoder.read()?;
+        
+        // Rebuild through delegate() so a tampered registry cannot smuggle in a cycle
+        let mut registry = DelegationRegistry::new();
+        for (delegator_address, delegate_address) in delegations {
+            registry
+                .delegate(delegator_address, delegate_address)
+                .map_err(|_| VotingError::CorruptState)?;
+        }
+        Ok(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_cycle_detection() {
+        let mut registry = DelegationRegistry::new();
+        registry.delegate("wallet_a".parse().unwrap(), "wallet_b".parse().unwrap()).unwrap();
+        registry.delegate("wallet_b".parse().unwrap(), "wallet_c".parse().unwrap()).unwrap();
+        
+        let result = registry.delegate("wallet_c".parse().unwrap(), "wallet_a".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::DelegationCycle)));
+        let result = registry.delegate("wallet_a".parse().unwrap(), "wallet_a".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::DelegationCycle)));
+        
+        // Redirecting an existing delegation away from the chain is fine
+        registry.delegate("wallet_a".parse().unwrap(), "wallet_d".parse().unwrap()).unwrap();
+        assert_eq!(registry.delegate_of(&"wallet_a".parse().unwrap()).unwrap(), "wallet_d");
+        registry.delegate("wallet_c".parse().unwrap(), "wallet_a".parse().unwrap()).unwrap();
+    }
+    
+    #[test]
+    fn test_delegators_of_follows_chains() {
+        let mut registry = DelegationRegistry::new();
+        registry.delegate("wallet_a".parse().unwrap(), "wallet_b".parse().unwrap()).unwrap();
+        registry.delegate("wallet_b".parse().unwrap(), "wallet_c".parse().unwrap()).unwrap();
+        registry.delegate("wallet_d".parse().unwrap(), "wallet_c".parse().unwrap()).unwrap();
+        
+        assert_eq!(
+            registry.delegators_of(&"wallet_c".parse().unwrap(), |_| false),
+            vec!["wallet_a", "wallet_b", "wallet_d"]
+        );
+        
+        // A delegator that voted directly keeps its own subtree
+        assert_eq!(
+            registry.delegators_of(&"wallet_c".parse().unwrap(), |address| address == "wallet_b"),
+            vec!["wallet_d"]
+        );
+    }
+}
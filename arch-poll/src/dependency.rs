@@ -0,0 +1,250 @@
+// Poll dependencies.
+// A scheduled poll can depend on other polls reaching a required outcome, e.g. "ratify budget"
+// only opening if "approve roadmap" passed. Until every prerequisite has ended with its required
+// outcome the poll is held: it takes no votes and does not count as ended, whatever its window
+// says. The scheduler (tick or process_scheduled_polls) evaluates dependencies when it activates
+// polls. Once they are met the poll starts, and a poll whose start time has already passed keeps
+// its duration but starts then. A prerequisite that ends with another outcome, or disappears,
+// closes the dependent poll without it ever opening.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, PollOutcome, PollStatus, Result, VotingContract, VotingError};
+
+// Most prerequisites one poll may declare
+pub const MAX_POLL_DEPENDENCIES: usize = 16;
+
+// Define a prerequisite a poll waits for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollDependency {
+    pub poll_id: PollId,               // Poll that must end first
+    pub required_outcome: PollOutcome, // Outcome it must end with
+}
+
+impl Encode for PollDependency {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.poll_id);
+        encoder.write(&self.required_outcome);
+    }
+}
+
+impl Decode for PollDependency {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(PollDependency {
+            poll_id: decoder.read()?,
+            required_outcome: decoder.read()?,
+        })
+    }
+}
+
+// Define where a poll's prerequisites stand
+enum DependencyState {
+    Pending,        // Some prerequisite has not ended yet
+    Met,            // Every prerequisite ended with its required outcome
+    Failed(PollId), // This prerequisite ended otherwise or no longer exists
+}
+
+impl VotingContract {
+    // Make a scheduled poll wait for other polls to end with the given outcomes (creator or
+    // ConfigurePolls). An empty list removes the dependencies. Only possible before the poll
+    // starts, and dependencies may not lead back to the poll itself.
+    pub fn set_poll_dependencies(
+        &mut self,
+        poll_id: &PollId,
+        depends_on: Vec<PollDependency>,
+        caller: &Address,
+    ) -> Result<()> {
+        if depends_on.len() > MAX_POLL_DEPENDENCIES {
+            return Err(VotingError::InvalidParameter);
+        }
+        for (index, dependency) in depends_on.iter().enumerate() {
+            self.get_poll(&dependency.poll_id)?;
+            if depends_on[..index].iter().any(|other| other.poll_id == dependency.poll_id)
+                || self.depends_on_poll(&dependency.poll_id, poll_id)
+            {
+                return Err(VotingError::InvalidParameter);
+            }
+        }
+        
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.status != PollStatus::Scheduled || (poll.dependencies_met && !poll.is_scheduled(current_timestamp)) {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.dependencies_met = depends_on.is_empty();
+        poll.depends_on = depends_on.clone();
+        self.emit(ContractEvent::PollDependenciesSet {
+            poll_id: poll_id.clone(),
+            depends_on,
+        });
+        Ok(())
+    }
+    
+    // Check whether a poll is, or waits on a chain of dependencies reaching, another poll
+    fn depends_on_poll(&self, poll_id: &PollId, other_poll_id: &str) -> bool {
+        let mut pending = vec![poll_id.clone()];
+        let mut visited = Vec::new();
+        while let Some(current_poll_id) = pending.pop() {
+            if current_poll_id == other_poll_id {
+                return true;
+            }
+            if visited.contains(&current_poll_id) {
+                continue;
+            }
+            if let Ok(poll) = self.get_poll(&current_poll_id) {
+                pending.extend(poll.depends_on.iter().map(|dependency| dependency.poll_id.clone()));
+            }
+            visited.push(current_poll_id);
+        }
+        false
+    }
+    
+    // Evaluate the prerequisites of one poll
+    fn dependency_state(&self, poll_id: &PollId, current_timestamp: u64) -> DependencyState {
+        let Ok(poll) = self.get_poll(poll_id) else {
+            return DependencyState::Pending;
+        };
+        let mut state = DependencyState::Met;
+        for dependency in &poll.depends_on {
+            let outcome = self.get_poll(&dependency.poll_id).map(|prerequisite| prerequisite.outcome(current_timestamp));
+            match outcome {
+                Ok(None) => state = DependencyState::Pending,
+                Ok(Some(outcome)) if outcome == dependency.required_outcome => {}
+                Ok(Some(_)) | Err(_) => return DependencyState::Failed(dependency.poll_id.clone()),
+            }
+        }
+        state
+    }
+    
+    // Release the held polls whose start time has passed and whose prerequisites are met, and
+    // close the ones whose prerequisites failed. Released polls are started by the scheduler.
+    pub(crate) fn resolve_dependencies(&mut self) {
+        let current_timestamp = self.now();
+        let mut held_poll_ids: Vec<PollId> = self
+            .active_polls
+            .iter()
+            .filter(|(_, poll)| {
+                poll.status == PollStatus::Scheduled
+                    && !poll.dependencies_met
+                    && current_timestamp >= poll.poll_start_timestamp
+            })
+            .map(|(poll_id, _)| poll_id.clone())
+            .collect();
+        held_poll_ids.sort();
+        
+        for poll_id in held_poll_ids {
+            match self.dependency_state(&poll_id, current_timestamp) {
+                DependencyState::Pending => {}
+                DependencyState::Met => {
+                    if let Some(poll) = self.active_polls.get_mut(&poll_id) {
+                        let poll_duration_seconds = poll.poll_end_timestamp - poll.poll_start_timestamp;
+                        poll.poll_start_timestamp = current_timestamp;
+                        poll.poll_end_timestamp = current_timestamp.saturating_add(poll_duration_seconds);
+                        poll.dependencies_met = true;
+                    }
+                    self.reindex_status(&poll_id);
+                }
+                DependencyState::Failed(dependency_poll_id) => {
+                    let closed = self.active_polls.get_mut(&poll_id).is_some_and(|poll| poll.close().is_ok());
+                    if closed {
+                        self.reindex_status(&poll_id);
+                        self.emit(ContractEvent::PollDependencyFailed {
+                            poll_id,
+                            dependency_poll_id,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_poll_dependencies() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let options = || vec!["Yes".to_string(), "No".to_string()];
+        let roadmap_poll_id = contract
+            .create_poll("wallet_creator".parse().unwrap(), "Roadmap".to_string(), String::new(), options(), 60)
+            .unwrap();
+        let budget_poll_id = contract
+            .create_scheduled_poll(
+                "wallet_creator".parse().unwrap(),
+                "Budget".to_string(),
+                String::new(),
+                options(),
+                10,
+                60,
+            )
+            .unwrap();
+        let hiring_poll_id = contract
+            .create_scheduled_poll(
+                "wallet_creator".parse().unwrap(),
+                "Hiring".to_string(),
+                String::new(),
+                options(),
+                10,
+                60,
+            )
+            .unwrap();
+        let passed = |poll_id: &PollId| PollDependency {
+            poll_id: poll_id.clone(),
+            required_outcome: PollOutcome::Passed,
+        };
+        contract
+            .set_poll_dependencies(
+                &budget_poll_id,
+                vec![passed(&roadmap_poll_id)],
+                &"wallet_creator".parse().unwrap(),
+            )
+            .unwrap();
+        contract
+            .set_poll_dependencies(&hiring_poll_id, vec![passed(&budget_poll_id)], &"wallet_creator".parse().unwrap())
+            .unwrap();
+        let result = contract.set_poll_dependencies(
+            &budget_poll_id,
+            vec![passed(&hiring_poll_id)],
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::InvalidParameter)));
+        let result = contract.set_poll_dependencies(&roadmap_poll_id, Vec::new(), &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        
+        // Past its start time, Budget is still held while the roadmap vote runs
+        clock.advance(30);
+        assert!(contract.tick().started_poll_ids.is_empty());
+        let result = contract.vote(&budget_poll_id, "wallet_a".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+        
+        // The roadmap passes, so Budget opens with its full duration from then on
+        contract.vote(&roadmap_poll_id, "wallet_a".parse().unwrap(), "Yes").unwrap();
+        clock.advance(30);
+        let summary = contract.tick();
+        assert_eq!(summary.started_poll_ids, vec![budget_poll_id.clone()]);
+        let budget_poll = contract.get_poll(&budget_poll_id).unwrap();
+        assert_eq!((budget_poll.poll_start_timestamp, budget_poll.poll_end_timestamp), (1_060, 1_120));
+        contract.vote(&budget_poll_id, "wallet_a".parse().unwrap(), "No").unwrap();
+        contract.vote(&budget_poll_id, "wallet_b".parse().unwrap(), "Yes").unwrap();
+        
+        // Budget ties and is rejected, so Hiring closes without opening, and dependencies survive
+        // a restore
+        clock.advance(60);
+        contract.tick();
+        assert!(contract.get_poll(&hiring_poll_id).unwrap().is_closed());
+        assert!(contract.event_log.iter().any(|logged_event| matches!(
+            &logged_event.event,
+            ContractEvent::PollDependencyFailed { dependency_poll_id, .. } if *dependency_poll_id == budget_poll_id
+        )));
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&hiring_poll_id).unwrap().depends_on, vec![passed(&budget_poll_id)]);
+    }
+}
\ No newline at end of file
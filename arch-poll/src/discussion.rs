@@ -0,0 +1,228 @@
+// Poll discussions.
+// Each poll carries a discussion thread so the debate behind a decision stays in the same state
+// as the decision itself rather than on an external forum. Anyone can comment on a stored poll or
+// reply to a comment, and replies can be answered in turn. A comment can be deleted by its author,
+// the poll's creator or a holder of ModeratePolls; deletion blanks the body but leaves the comment
+// in place, so the replies under it keep their thread. Comments are numbered from 1 within their
+// poll and read back page by page, oldest first. The discussion outlives compaction of its poll,
+// so it stays readable next to the final results, but is removed with a deleted poll.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Result, VotingContract, VotingError};
+
+// Longest comment body, in bytes
+pub const MAX_COMMENT_LEN: usize = 2_000;
+
+// Define one comment in a poll's discussion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub comment_id: u64,              // Position in the poll's discussion, starting at 1
+    pub author: Address,
+    pub body: String,                 // Empty once deleted
+    pub parent_id: Option<u64>,       // Comment this one replies to (None at the top of the thread)
+    pub posted_at: u64,
+    pub deleted_by: Option<Address>,  // Who deleted the comment, if anyone
+}
+
+impl Comment {
+    // Check whether the comment has been deleted
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_by.is_some()
+    }
+}
+
+impl Encode for Comment {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.comment_id);
+        encoder.write(&self.author);
+        encoder.write(&self.body);
+        encoder.write(&self.parent_id);
+        encoder.write(&self.posted_at);
+        encoder.write(&self.deleted_by);
+    }
+}
+
+impl Decode for Comment {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(Comment {
+            comment_id: decoder.read()?,
+            author: decoder.read()?,
+            body: decoder.read()?,
+            parent_id: decoder.read()?,
+            posted_at: decoder.read()?,
+            deleted_by: decoder.read()?,
+        })
+    }
+}
+
+impl VotingContract {
+    // Start a new thread in a poll's discussion, returning the comment's ID
+    pub fn post_comment(&mut self, poll_id: &PollId, author: &Address, body: &str) -> Result<u64> {
+        self.add_comment(poll_id, None, author, body)
+    }
+    
+    // Reply to a comment in a poll's discussion, returning the reply's ID. Deleted comments can
+    // still be replied to.
+    pub fn reply_to_comment(&mut self, poll_id: &PollId, parent_id: u64, author: &Address, body: &str) -> Result<u64> {
+        self.add_comment(poll_id, Some(parent_id), author, body)
+    }
+    
+    // Delete a comment (its author, the poll's creator or ModeratePolls). InvalidTransition when it
+    // is already deleted.
+    pub fn delete_comment(&mut self, poll_id: &PollId, comment_id: u64, caller: &Address) -> Result<()> {
+        let caller = self.parse_address(caller)?;
+        let poll_creator_address = self.get_poll(poll_id)?.poll_creator_address.clone();
+        let caller_can_moderate = self.has_permission(&caller, Permission::ModeratePolls);
+        let comment = self
+            .discussions
+            .get_mut(poll_id)
+            .and_then(|comments| comment_mut(comments, comment_id))
+            .ok_or(VotingError::CommentNotFound)?;
+        
+        if !caller_can_moderate && caller != comment.author && caller != poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        if comment.is_deleted() {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        comment.body.clear();
+        comment.deleted_by = Some(caller.clone());
+        self.emit(ContractEvent::CommentDeleted {
+            poll_id: poll_id.clone(),
+            comment_id,
+            deleted_by: caller,
+        });
+        Ok(())
+    }
+    
+    // Get a page of the comments opening threads in a poll's discussion, oldest first
+    pub fn get_comments(&self, poll_id: &PollId, offset: usize, limit: usize) -> Result<Vec<&Comment>> {
+        Ok(self
+            .discussion(poll_id)?
+            .iter()
+            .filter(|comment| comment.parent_id.is_none())
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+    
+    // Get a page of the direct replies to a comment, oldest first
+    pub fn get_replies(&self, poll_id: &PollId, comment_id: u64, offset: usize, limit: usize) -> Result<Vec<&Comment>> {
+        let comments = self.discussion(poll_id)?;
+        if comment_index(comments, comment_id).is_none() {
+            return Err(VotingError::CommentNotFound);
+        }
+        Ok(comments
+            .iter()
+            .filter(|comment| comment.parent_id == Some(comment_id))
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+    
+    // Get a comment by its ID
+    pub fn get_comment(&self, poll_id: &PollId, comment_id: u64) -> Result<&Comment> {
+        let comments = self.discussion(poll_id)?;
+        comment_index(comments, comment_id)
+            .map(|index| &comments[index])
+            .ok_or(VotingError::CommentNotFound)
+    }
+    
+    fn add_comment(&mut self, poll_id: &PollId, parent_id: Option<u64>, author: &Address, body: &str) -> Result<u64> {
+        let author = self.parse_address(author)?;
+        let body = body.trim();
+        if body.is_empty() || body.len() > MAX_COMMENT_LEN {
+            return Err(VotingError::InvalidComment);
+        }
+        self.get_poll(poll_id)?;
+        let current_timestamp = self.now();
+        let comments = self.discussions.entry(poll_id.clone()).or_default();
+        if parent_id.is_some_and(|parent_id| comment_index(comments, parent_id).is_none()) {
+            return Err(VotingError::CommentNotFound);
+        }
+        
+        let comment_id = comments.len() as u64 + 1;
+        comments.push(Comment {
+            comment_id,
+            author: author.clone(),
+            body: body.to_string(),
+            parent_id,
+            posted_at: current_timestamp,
+            deleted_by: None,
+        });
+        self.emit(ContractEvent::CommentPosted {
+            poll_id: poll_id.clone(),
+            comment_id,
+            author,
+        });
+        Ok(comment_id)
+    }
+    
+    // Get a poll's comments, for stored and compacted polls
+    fn discussion(&self, poll_id: &PollId) -> Result<&[Comment]> {
+        if self.get_poll(poll_id).is_err() && !self.compacted_polls.contains_key(poll_id) {
+            return Err(VotingError::PollNotFound);
+        }
+        Ok(self.discussions.get(poll_id).map_or(&[], Vec::as_slice))
+    }
+}
+
+// Find a comment's position; comments are stored in ID order and never removed
+fn comment_index(comments: &[Comment], comment_id: u64) -> Option<usize> {
+    let index = usize::try_from(comment_id).ok()?.checked_sub(1)?;
+    (index < comments.len()).then_some(index)
+}
+
+// Find a comment to change it
+fn comment_mut(comments: &mut [Comment], comment_id: u64) -> Option<&mut Comment> {
+    comment_index(comments, comment_id).map(|index| &mut comments[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_discussion_threads() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .poll("Treasury grant")
+            .creator(&"wallet_creator".parse().unwrap())
+            .options(["Fund", "Reject"])
+            .duration_secs(3_600)
+            .build()
+            .unwrap();
+        let result = contract.post_comment(&poll_id, &"wallet_a".parse().unwrap(), "   ");
+        assert!(matches!(result, Err(VotingError::InvalidComment)));
+        let result = contract.post_comment(&"missing".parse().unwrap(), &"wallet_a".parse().unwrap(), "Hi");
+        assert!(matches!(result, Err(VotingError::PollNotFound)));
+        
+        // Threads open at the top level and replies nest under any comment
+        let question = contract.post_comment(&poll_id, &"wallet_a".parse().unwrap(), "Who audits the spend?").unwrap();
+        let answer = contract
+            .reply_to_comment(&poll_id, question, &"wallet_creator".parse().unwrap(), "The council")
+            .unwrap();
+        contract.reply_to_comment(&poll_id, answer, &"wallet_a".parse().unwrap(), "Thanks").unwrap();
+        contract.post_comment(&poll_id, &"wallet_b".parse().unwrap(), "Spam").unwrap();
+        let result = contract.reply_to_comment(&poll_id, 9, &"wallet_a".parse().unwrap(), "Hello?");
+        assert!(matches!(result, Err(VotingError::CommentNotFound)));
+        assert_eq!(contract.get_comments(&poll_id, 1, 10).unwrap()[0].body, "Spam");
+        assert_eq!(contract.get_replies(&poll_id, answer, 0, 10).unwrap()[0].body, "Thanks");
+        
+        // Only the author, the creator or a moderator deletes, and replies keep their thread
+        let result = contract.delete_comment(&poll_id, question, &"wallet_b".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.delete_comment(&poll_id, question, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.delete_comment(&poll_id, 4, &"wallet_admin".parse().unwrap()).unwrap();
+        let result = contract.delete_comment(&poll_id, 4, &"wallet_admin".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidTransition)));
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let deleted_comment = restored.get_comment(&poll_id, question).unwrap();
+        assert!(deleted_comment.is_deleted() && deleted_comment.body.is_empty());
+        assert_eq!(restored.get_replies(&poll_id, question, 0, 10).unwrap().len(), 1);
+    }
+}
\ No newline at end of file
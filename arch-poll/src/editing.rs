@@ -0,0 +1,190 @@
+// Poll editing.
+// A typo in a published poll used to mean abandoning it and creating another. update_poll lets the
+// creator (or a caller with ConfigurePolls) correct the title, description, options or end time of
+// any poll nobody has voted in yet. Once a ballot, commitment or stake is recorded the poll is
+// fixed, since voters chose from what it said at the time. A scheduled poll is also fixed from its
+// start time on, as its voters were told in advance what would open and when. Every change is
+// validated as at creation, and the update is applied whole or not at all.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::id::{OptionId, PollId};
+use crate::prelude::*;
+use crate::{normalize_options, titles_match, with_reserved_options};
+use crate::{ContractEvent, PollStatus, Result, VotingContract, VotingError, VotingMethod};
+
+// Define the changes to make to a poll. Fields left as None keep their current value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PollUpdate {
+    pub poll_title: Option<String>,
+    pub poll_description: Option<String>,
+    pub poll_options: Option<Vec<String>>, // Replaces the ballot options; reserved options stay on the ballot
+    pub poll_end_timestamp: Option<u64>,   // New end time; a draft keeps the length of its window when published
+}
+
+impl VotingContract {
+    // Change a poll before anyone votes in it (creator or ConfigurePolls). WrongPhase once voting
+    // has started or a scheduled poll has reached its start time, PollClosed once it has ended.
+    pub fn update_poll(&mut self, poll_id: &PollId, poll_update: PollUpdate, caller: &Address) -> Result<()> {
+        let updated_by = self.parse_address(caller)?;
+        let config = self.config;
+        let case_insensitive_options = self.case_insensitive_options;
+        let current_timestamp = self.now();
+        let duplicate_title = match &poll_update.poll_title {
+            Some(poll_title) if self.reject_duplicate_titles => {
+                let creator_address = &self.get_poll(poll_id)?.poll_creator_address;
+                self.active_polls.values().any(|poll| {
+                    poll.poll_id != *poll_id
+                        && (poll.is_active(current_timestamp) || poll.is_scheduled(current_timestamp))
+                        && poll.poll_creator_address == *creator_address
+                        && titles_match(&poll.poll_title, poll_title)
+                })
+            }
+            _ => false,
+        };
+        let poll = self.managed_poll_mut(poll_id, &updated_by, Permission::ConfigurePolls)?;
+        
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_started()
+            || poll.conviction.as_ref().is_some_and(|state| !state.passed_options.is_empty())
+            || (poll.status == PollStatus::Scheduled && !poll.is_scheduled(current_timestamp))
+        {
+            return Err(VotingError::WrongPhase);
+        }
+        if duplicate_title {
+            return Err(VotingError::DuplicateTitle);
+        }
+        
+        // Validate the poll as it would read after the update
+        let poll_title = poll_update.poll_title.unwrap_or_else(|| poll.poll_title.clone());
+        let poll_description = poll_update.poll_description.unwrap_or_else(|| poll.poll_description.clone());
+        let poll_options = match poll_update.poll_options {
+            Some(poll_options) => {
+                // Governance polls decide on their fixed approve and reject options
+                if poll.parameter_change.is_some() {
+                    return Err(VotingError::InvalidParameter);
+                }
+                if poll_options.len() < 2 {
+                    return Err(VotingError::PollCreationFailed);
+                }
+                let poll_options = normalize_options(poll_options, case_insensitive_options)?;
+                if let VotingMethod::Approval { max_selections } = poll.voting_method {
+                    if max_selections > poll_options.len() {
+                        return Err(VotingError::PollCreationFailed);
+                    }
+                }
+                Some(poll_options)
+            }
+            None => None,
+        };
+        config.check_poll_contents(
+            &poll_title,
+            &poll_description,
+            poll_options.as_ref().unwrap_or(&poll.voting_options),
+        )?;
+        
+        let poll_end_timestamp = poll_update.poll_end_timestamp.unwrap_or(poll.poll_end_timestamp);
+        let poll_duration_seconds = poll_end_timestamp
+            .checked_sub(poll.poll_start_timestamp)
+            .ok_or(VotingError::InvalidTimeSettings)?;
+        config.check_duration(poll_duration_seconds)?;
+        let ends_too_soon = poll_end_timestamp <= current_timestamp
+            || poll.commit_end_timestamp.is_some_and(|commit_end_timestamp| poll_end_timestamp <= commit_end_timestamp);
+        if poll.status != PollStatus::Draft && ends_too_soon {
+            return Err(VotingError::InvalidTimeSettings);
+        }
+        
+        // Nobody has voted, so the tallies are simply rebuilt for new options
+        if let Some(poll_options) = poll_options {
+            let poll_options = with_reserved_options(poll_options, poll.allow_abstain, poll.allow_nota)?;
+            poll.vote_counts = (0..poll_options.len() as u32).map(|position| (OptionId(position), 0)).collect();
+            poll.voter_counts = (0..poll_options.len() as u32).map(|position| (OptionId(position), 0)).collect();
+            if let Some(state) = &mut poll.conviction {
+                state.convictions.retain(|voting_option, _| poll_options.contains(voting_option));
+            }
+            poll.history.clear();
+            poll.voting_options = poll_options;
+        }
+        poll.poll_title = poll_title;
+        poll.poll_description = poll_description;
+        poll.poll_end_timestamp = poll_end_timestamp;
+        poll.invalidate_results();
+        self.reindex_status(poll_id);
+        
+        self.emit(ContractEvent::PollUpdated {
+            poll_id: poll_id.clone(),
+            updated_by,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_update_poll() {
+        let clock = MockClock::new(1_000);
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = contract
+            .create_scheduled_poll(
+                "wallet_creator".parse().unwrap(),
+                "Lnch".to_string(),
+                String::new(),
+                vec!["Pizza".to_string(), "Tacso".to_string()],
+                60,
+                3_600,
+            )
+            .unwrap();
+        
+        // Only the creator can edit, and an invalid update changes nothing
+        let fix_typos = PollUpdate {
+            poll_title: Some("Lunch".to_string()),
+            poll_options: Some(vec!["Pizza".to_string(), "Tacos".to_string(), "Sushi".to_string()]),
+            poll_end_timestamp: Some(8_260),
+            ..PollUpdate::default()
+        };
+        let result = contract.update_poll(&poll_id, fix_typos.clone(), &"wallet_stranger".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        let one_option = PollUpdate {
+            poll_options: Some(vec!["Pizza".to_string()]),
+            ..fix_typos.clone()
+        };
+        let result = contract.update_poll(&poll_id, one_option, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::PollCreationFailed)));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().poll_title, "Lnch");
+        
+        contract.update_poll(&poll_id, fix_typos.clone(), &"wallet_creator".parse().unwrap()).unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.poll_title.as_str(), poll.voting_options.len()), ("Lunch", 3));
+        assert_eq!(poll.time_remaining(1_000), 7_260);
+        assert_eq!(contract.get_poll_results(&poll_id).unwrap()["Sushi"], 0);
+        assert_eq!(contract.events_since(0).last().unwrap().event.name(), "PollUpdated");
+        
+        // A scheduled poll is fixed once it starts, and any poll once it has votes
+        clock.advance(60);
+        let result = contract.update_poll(&poll_id, fix_typos, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Dinner".to_string(),
+                String::new(),
+                vec!["Soup".to_string(), "Salad".to_string()],
+                3_600,
+            )
+            .unwrap();
+        let describe = PollUpdate {
+            poll_description: Some("Friday".to_string()),
+            ..PollUpdate::default()
+        };
+        contract.update_poll(&poll_id, describe.clone(), &"wallet_creator".parse().unwrap()).unwrap();
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Soup").unwrap();
+        let result = contract.update_poll(&poll_id, describe, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,238 @@
+// Multi-question ballots.
+// An election groups several single-choice polls, one per question, under one election ID. The
+// polls are created together with the same voting window and the same allowlist, and they remain
+// ordinary polls for results, closing and finalization. vote_ballot casts a voter's answers to
+// any of the questions in one call: every answer is cast at the same timestamp and the ballot is
+// kept whole or not at all, with the same rollback as an all-or-nothing vote batch. Each answer
+// counts as one vote against the voter's rate limit and pays the poll's vote fee.
+use core::mem;
+
+use crate::address::Address;
+use crate::clock::MockClock;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::rate_limit::RateLimitedAction;
+use crate::receipt::VoteReceipt;
+use crate::{titles_match, ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Most questions one election may hold
+pub const MAX_ELECTION_QUESTIONS: usize = 50;
+
+// Define one question of an election
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElectionQuestion {
+    pub title: String,        // Title of the question's poll
+    pub options: Vec<String>, // Answers the question offers
+}
+
+// Define an election and the polls of its questions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Election {
+    pub election_id: String,
+    pub creator_address: Address,
+    pub title: String,
+    pub poll_ids: Vec<PollId>, // Poll of each question, in ballot order
+}
+
+impl Encode for Election {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.election_id);
+        encoder.write(&self.creator_address);
+        encoder.write(&self.title);
+        encoder.write(&self.poll_ids);
+    }
+}
+
+impl Decode for Election {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(Election {
+            election_id: decoder.read()?,
+            creator_address: decoder.read()?,
+            title: decoder.read()?,
+            poll_ids: decoder.read()?,
+        })
+    }
+}
+
+impl VotingContract {
+    // Create an election with one single-choice poll per question, sharing a voting window and
+    // an allowlist (None lets anyone vote). Every question is validated before any poll is stored,
+    // and the whole election counts against the creator's rate limit and creation fee.
+    pub fn create_election(
+        &mut self,
+        creator_address: Address,
+        election_title: String,
+        questions: Vec<ElectionQuestion>,
+        poll_duration_seconds: u64,
+        allowlist: Option<Vec<Address>>,
+    ) -> Result<String> {
+        if questions.is_empty() || questions.len() > MAX_ELECTION_QUESTIONS {
+            return Err(VotingError::PollCreationFailed);
+        }
+        let allowlist = match allowlist {
+            Some(addresses) => {
+                Some(addresses.iter().map(|address| self.parse_address(address)).collect::<Result<_>>()?)
+            }
+            None => None,
+        };
+        
+        let mut new_polls: Vec<Poll> = Vec::new();
+        for (question_index, question) in questions.into_iter().enumerate() {
+            if self.reject_duplicate_titles
+                && new_polls.iter().any(|new_poll| titles_match(&new_poll.poll_title, &question.title))
+            {
+                return Err(VotingError::DuplicateTitle);
+            }
+            
+            let mut new_poll = self.build_poll(
+                self.next_poll_id + question_index as u64,
+                creator_address.clone(),
+                question.title,
+                election_title.clone(),
+                question.options,
+                poll_duration_seconds,
+            )?;
+            if new_polls.iter().any(|other_poll| other_poll.poll_id == new_poll.poll_id) {
+                return Err(VotingError::PollCreationFailed);
+            }
+            new_poll.allowlist = allowlist.clone();
+            new_polls.push(new_poll);
+        }
+        let creator_address = new_polls[0].poll_creator_address.clone();
+        self.check_rate_limit(RateLimitedAction::PollCreation, &creator_address, new_polls.len())?;
+        self.check_creation_fee(&creator_address, new_polls.len())?;
+        
+        let poll_ids = new_polls.into_iter().map(|new_poll| self.store_poll(new_poll)).collect();
+        let election_id = format!("election-{}", self.elections.len() + 1);
+        self.elections.insert(election_id.clone(), Election {
+            election_id: election_id.clone(),
+            creator_address: creator_address.clone(),
+            title: election_title,
+            poll_ids,
+        });
+        self.emit(ContractEvent::ElectionCreated {
+            election_id: election_id.clone(),
+            creator_address,
+        });
+        Ok(election_id)
+    }
+    
+    // Get an election by its ID
+    pub fn get_election(&self, election_id: &str) -> Result<&Election> {
+        self.elections.get(election_id).ok_or(VotingError::ElectionNotFound)
+    }
+    
+    // Cast a voter's (poll ID, option) answers to an election's questions, returning a receipt per
+    // answer in input order. Questions may be left unanswered, but each may be answered once. If
+    // any answer fails, none is kept and its error is returned.
+    pub fn vote_ballot(
+        &mut self,
+        election_id: &str,
+        voter_address: Address,
+        answers: Vec<(PollId, String)>,
+    ) -> Result<Vec<VoteReceipt>> {
+        let election = self.get_election(election_id)?;
+        let poll_ids = election.poll_ids.clone();
+        if answers.is_empty()
+            || answers.iter().any(|(poll_id, _)| !poll_ids.contains(poll_id))
+            || answers.iter().enumerate().any(|(index, (poll_id, _))| {
+                answers[..index].iter().any(|(answered_poll_id, _)| answered_poll_id == poll_id)
+            })
+        {
+            return Err(VotingError::InvalidParameter);
+        }
+        
+        // Close due polls first, then cast every answer at the same timestamp
+        for poll_id in &poll_ids {
+            self.expire_if_due(poll_id);
+        }
+        let ballot_clock = MockClock::new(self.now());
+        let clock = mem::replace(&mut self.clock, Box::new(ballot_clock));
+        let results = self.cast_atomically(&poll_ids, |contract| {
+            answers
+                .iter()
+                .map(|(poll_id, selected_option)| contract.vote(poll_id, voter_address.clone(), selected_option))
+                .collect()
+        });
+        self.clock = clock;
+        
+        // Answers that succeeded before the failing one report BatchAborted
+        let failure = results
+            .iter()
+            .find_map(|result| result.as_ref().err().filter(|error| !matches!(error, VotingError::BatchAborted)));
+        if let Some(error) = failure {
+            return Err(*error);
+        }
+        let receipts: Vec<VoteReceipt> = results.into_iter().collect::<Result<_>>()?;
+        self.emit(ContractEvent::BallotCast {
+            election_id: election_id.to_string(),
+            voter_address: receipts[0].voter.clone(),
+            answers: receipts.len() as u32,
+        });
+        Ok(receipts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_elections() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let questions = vec![
+            ElectionQuestion {
+                title: "Chair".to_string(),
+                options: vec!["Ada".to_string(), "Grace".to_string()],
+            },
+            ElectionQuestion {
+                title: "Budget".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+            },
+        ];
+        let election_id = contract
+            .create_election(
+                "wallet_creator".parse().unwrap(),
+                "Annual meeting".to_string(),
+                questions,
+                60,
+                Some(vec!["wallet_a".parse().unwrap(), "wallet_b".parse().unwrap()]),
+            )
+            .unwrap();
+        let election = contract.get_election(&election_id).unwrap().clone();
+        let (chair_poll_id, budget_poll_id) = (election.poll_ids[0].clone(), election.poll_ids[1].clone());
+        let chair_poll = contract.get_poll(&chair_poll_id).unwrap();
+        let budget_poll = contract.get_poll(&budget_poll_id).unwrap();
+        assert_eq!(chair_poll.poll_end_timestamp, budget_poll.poll_end_timestamp);
+        assert_eq!(budget_poll.allowlist, chair_poll.allowlist);
+        
+        // A bad answer keeps the whole ballot out
+        let answers = |budget_answer: &str| {
+            vec![
+                (chair_poll_id.clone(), "Ada".to_string()),
+                (budget_poll_id.clone(), budget_answer.to_string()),
+            ]
+        };
+        let result = contract.vote_ballot(&election_id, "wallet_a".parse().unwrap(), answers("Maybe"));
+        assert!(matches!(result, Err(VotingError::InvalidOption)));
+        assert_eq!(contract.get_poll(&chair_poll_id).unwrap().total_votes(), 0);
+        let result = contract.vote_ballot(&election_id, "wallet_outsider".parse().unwrap(), answers("Yes"));
+        assert!(matches!(result, Err(VotingError::NotEligible)));
+        
+        // A valid ballot answers every question at once, and questions may be skipped
+        let receipts = contract.vote_ballot(&election_id, "wallet_a".parse().unwrap(), answers("Yes")).unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(contract.get_poll(&budget_poll_id).unwrap().get_results()["Yes"], 1);
+        let skipped = vec![(budget_poll_id.clone(), "No".to_string())];
+        contract.vote_ballot(&election_id, "wallet_b".parse().unwrap(), skipped).unwrap();
+        let repeated = vec![(chair_poll_id.clone(), "Ada".to_string()), (chair_poll_id.clone(), "Grace".to_string())];
+        let result = contract.vote_ballot(&election_id, "wallet_b".parse().unwrap(), repeated);
+        assert!(matches!(result, Err(VotingError::InvalidParameter)));
+        
+        // The election is saved with the contract
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_election(&election_id).unwrap(), &election);
+        assert!(matches!(restored.get_election("election-9"), Err(VotingError::ElectionNotFound)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,346 @@
+// Binary encoding used to persist contract state.
+// The layout follows borsh conventions: little-endian integers, u32 length prefixes for
+// strings and sequences, usize written as u64, and maps/sets written in sorted key order
+// so the same state always produces the same bytes. Encoders and decoders carry the snapshot
+// schema version whose layout they write or read, so types that gained fields can still read
+// and write older layouts (see migrations.rs).
+#[cfg(feature = "std")]
+use alloc::collections::BTreeMap;
+use core::hash::Hash;
+
+use crate::collections::{HashMap, HashSet};
+use crate::prelude::*;
+use crate::state::SNAPSHOT_SCHEMA_VERSION;
+use crate::{Result, VotingError};
+
+// Accumulates encoded bytes
+#[derive(Debug)]
+pub struct Encoder {
+    buffer: Vec<u8>,
+    layout: u16, // Schema version whose layout is written
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Encoder::with_layout(SNAPSHOT_SCHEMA_VERSION)
+    }
+    
+    // Create an encoder writing an older schema version's layout
+    pub fn with_layout(layout: u16) -> Self {
+        Encoder { buffer: Vec::new(), layout }
+    }
+    
+    pub fn layout(&self) -> u16 {
+        self.layout
+    }
+    
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+    
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) {
+        value.encode(self);
+    }
+    
+    pub fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Encoder::new()
+    }
+}
+
+// Reads encoded values back, failing with CorruptState on malformed input
+#[derive(Debug)]
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    layout: u16, // Schema version whose layout is read
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Decoder::with_layout(data, SNAPSHOT_SCHEMA_VERSION)
+    }
+    
+    // Create a decoder reading an older schema version's layout
+    pub fn with_layout(data: &'a [u8], layout: u16) -> Self {
+        Decoder { data, position: 0, layout }
+    }
+    
+    pub fn layout(&self) -> u16 {
+        self.layout
+    }
+    
+    // Switch the layout read from here on, e.g. after a version byte
+    pub fn set_layout(&mut self, layout: u16) {
+        self.layout = layout;
+    }
+    
+    // Get how many bytes have been read
+    pub fn position(&self) -> usize {
+        self.position
+    }
+    
+    pub fn read_bytes(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(length).ok_or(VotingError::CorruptState)?;
+        let bytes = self.data.get(self.position..end).ok_or(VotingError::CorruptState)?;
+        self.position = end;
+        Ok(bytes)
+    }
+    
+    pub fn read<T: Decode>(&mut self) -> Result<T> {
+        T::decode(self)
+    }
+    
+    // Ensure every byte was consumed
+    pub fn finish(self) -> Result<()> {
+        if self.position != self.data.len() {
+            return Err(VotingError::CorruptState);
+        }
+        Ok(())
+    }
+}
+
+pub trait Encode {
+    fn encode(&self, encoder: &mut Encoder);
+}
+
+pub trait Decode: Sized {
+    fn decode(decoder: &mut Decoder) -> Result<Self>;
+}
+
+// Encode a value into a fresh byte vector
+pub fn to_bytes<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+    encoder.write(value);
+    encoder.finish()
+}
+
+// Decode a value that must span the whole input
+pub fn from_bytes<T: Decode>(data: &[u8]) -> Result<T> {
+    let mut decoder = Decoder::new(data);
+    let value = decoder.read()?;
+    decoder.finish()?;
+    Ok(value)
+}
+
+macro_rules! impl_int {
+    ($($int:ty),*) => {
+        $(
+            impl Encode for $int {
+                fn encode(&self, encoder: &mut Encoder) {
+                    encoder.write_bytes(&self.to_le_bytes());
+                }
+            }
+            
+            impl Decode for $int {
+                fn decode(decoder: &mut Decoder) -> Result<Self> {
+                    let bytes = decoder.read_bytes(core::mem::size_of::<$int>())?;
+                    Ok(<$int>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8, u16, u32, u64, u128);
+
+impl Encode for usize {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&(*self as u64));
+    }
+}
+
+impl Decode for usize {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let value: u64 = decoder.read()?;
+        usize::try_from(value).map_err(|_| VotingError::CorruptState)
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&(*self as u8));
+    }
+}
+
+impl Decode for bool {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+// Fixed-size byte arrays (such as hashes) are written without a length prefix
+impl<const N: usize> Encode for [u8; N] {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_bytes(self);
+    }
+}
+
+impl<const N: usize> Decode for [u8; N] {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let bytes = decoder.read_bytes(N)?;
+        Ok(bytes.try_into().unwrap())
+    }
+}
+
+// Write a collection length as a u32 prefix
+fn write_length(encoder: &mut Encoder, length: usize) {
+    encoder.write(&(length as u32));
+}
+
+fn read_length(decoder: &mut Decoder) -> Result<usize> {
+    let length: u32 = decoder.read()?;
+    Ok(length as usize)
+}
+
+impl Encode for str {
+    fn encode(&self, encoder: &mut Encoder) {
+        write_length(encoder, self.len());
+        encoder.write_bytes(self.as_bytes());
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, encoder: &mut Encoder) {
+        self.as_str().encode(encoder);
+    }
+}
+
+impl Decode for String {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let length = read_length(decoder)?;
+        let bytes = decoder.read_bytes(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| VotingError::CorruptState)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            None => encoder.write(&0u8),
+            Some(value) => {
+                encoder.write(&1u8);
+                encoder.write(value);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(None),
+            1 => Ok(Some(decoder.read()?)),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        write_length(encoder, self.len());
+        for item in self {
+            encoder.write(item);
+        }
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let length = read_length(decoder)?;
+        // Never trust the length prefix for preallocation
+        let mut items = Vec::new();
+        for _ in 0..length {
+            items.push(decoder.read()?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: Encode + Ord> Encode for HashSet<T> {
+    fn encode(&self, encoder: &mut Encoder) {
+        let mut items: Vec<&T> = self.iter().collect();
+        items.sort();
+        write_length(encoder, items.len());
+        for item in items {
+            encoder.write(item);
+        }
+    }
+}
+
+impl<T: Decode + Ord + Hash> Decode for HashSet<T> {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let length = read_length(decoder)?;
+        let mut items = HashSet::new();
+        for _ in 0..length {
+            if !items.insert(decoder.read()?) {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        Ok(items)
+    }
+}
+
+impl<K: Encode + Ord, V: Encode> Encode for HashMap<K, V> {
+    fn encode(&self, encoder: &mut Encoder) {
+        let mut entries: Vec<(&K, &V)> = self.iter().collect();
+        entries.sort_by(|left, right| left.0.cmp(right.0));
+        write_length(encoder, entries.len());
+        for (key, value) in entries {
+            encoder.write(key);
+            encoder.write(value);
+        }
+    }
+}
+
+impl<K: Decode + Ord + Hash, V: Decode> Decode for HashMap<K, V> {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let length = read_length(decoder)?;
+        let mut entries = HashMap::new();
+        for _ in 0..length {
+            let key = decoder.read()?;
+            let value = decoder.read()?;
+            if entries.insert(key, value).is_some() {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+// Without std HashMap is a BTreeMap, so the impls above already cover it
+#[cfg(feature = "std")]
+impl<K: Encode + Ord, V: Encode> Encode for BTreeMap<K, V> {
+    fn encode(&self, encoder: &mut Encoder) {
+        write_length(encoder, self.len());
+        for (key, value) in self {
+            encoder.write(key);
+            encoder.write(value);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let length = read_length(decoder)?;
+        let mut entries = BTreeMap::new();
+        for _ in 0..length {
+            let key = decoder.read()?;
+            let value = decoder.read()?;
+            if entries.insert(key, value).is_some() {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        Ok(entries)
+    }
+}
@@ -0,0 +1,693 @@
+// Encrypted ballots.
+// An encrypted poll takes ballots sealed to a poll-specific public key and keeps them opaque until
+// the poll has ended. The secret key is split among trustees by Shamir secret sharing: trustee i
+// (numbered from 1 in listing order) holds f(i) for a secret polynomial f of degree threshold - 1
+// whose constant term is the key, and publishes f(i)·B as its public share. Any threshold trustees
+// together recover the key, and fewer learn nothing about it. The public shares are checked
+// against each other and the public key when the poll is created.
+// Ballots use hashed ElGamal on the ed25519 group, with the point and scalar arithmetic done by
+// curve25519-dalek: the voter picks a random r and sends r·B with the option XORed against a
+// SHA-256 keystream derived from r·Y, plus a tag over the ciphertext and the voter's address. The
+// tag binds a ballot to the address that cast it, so a ballot copied from another voter is
+// spoiled rather than counted a second time.
+// Once the poll has ended, trustees submit their key shares with submit_decryption_share, each
+// checked against its public share. When threshold shares are in, the key is recombined and every
+// ballot is decrypted and counted as an ordinary single-choice vote with the weight its voter had
+// when casting it. Ballots that do not decrypt to a ballot option are spoiled and not counted.
+// The outcome and finalization wait for decryption, and delegations are not carried. The key is
+// public once recombined, so ballots stay secret while voting runs, not afterwards.
+use alloc::collections::BTreeMap;
+
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::IsIdentity;
+
+use crate::address::Address;
+use crate::encoding::{self, Decode, Decoder, Encode, Encoder};
+use crate::hash::sha256;
+use crate::id::{OptionId, PollId};
+use crate::prelude::*;
+use crate::rate_limit::RateLimitedAction;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError, VotingMethod};
+
+// Domain tags hashed ahead of keystream blocks and ciphertext tags
+pub const KEYSTREAM_DOMAIN: &[u8] = b"arch-poll:ballot-keystream:v1";
+pub const TAG_DOMAIN: &[u8] = b"arch-poll:ballot-tag:v2";
+
+// Most trustees one poll may split its key among
+pub const MAX_TRUSTEES: usize = 16;
+
+// Longest ciphertext a ballot may carry
+pub const MAX_CIPHERTEXT_LEN: usize = 1024;
+
+// Define a trustee holding one share of a poll's key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trustee {
+    pub address: Address,
+    pub public_share: [u8; 32], // f(i)·B for the trustee's share f(i)
+}
+
+// Define the key ballots are encrypted to and how it is shared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptionConfig {
+    pub public_key: [u8; 32], // Y = f(0)·B
+    pub threshold: u32,       // Key shares needed to decrypt
+    pub trustees: Vec<Trustee>,
+}
+
+impl EncryptionConfig {
+    // Check the config can be decrypted: 1 <= threshold <= trustees <= MAX_TRUSTEES, distinct
+    // trustees, and the public key and every public share on one polynomial of degree
+    // threshold - 1
+    pub(crate) fn is_valid(&self) -> bool {
+        let threshold = self.threshold as usize;
+        if threshold == 0 || threshold > self.trustees.len() || self.trustees.len() > MAX_TRUSTEES {
+            return false;
+        }
+        if self.trustees.iter().enumerate().any(|(index, trustee)| {
+            self.trustees[..index].iter().any(|other| other.address == trustee.address)
+        }) {
+            return false;
+        }
+        if decode_point(&self.public_key).is_none_or(|public_key| public_key.is_identity()) {
+            return false;
+        }
+        let Some(public_shares) = self
+            .trustees
+            .iter()
+            .map(|trustee| decode_point(&trustee.public_share))
+            .collect::<Option<Vec<EdwardsPoint>>>()
+        else {
+            return false;
+        };
+        
+        // The first threshold shares fix f·B, so interpolate it at 0 and at every later trustee
+        let basis: Vec<u64> = (1..=threshold as u64).collect();
+        let interpolate = |at: u64| {
+            let point: EdwardsPoint = basis
+                .iter()
+                .map(|&index| public_shares[index as usize - 1] * lagrange_coefficient(&basis, index, at))
+                .sum();
+            point.compress().to_bytes()
+        };
+        interpolate(0) == self.public_key
+            && (threshold..self.trustees.len())
+                .all(|index| interpolate(index as u64 + 1) == self.trustees[index].public_share)
+    }
+}
+
+// Define a sealed ballot as the voter sends it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BallotCiphertext {
+    pub ephemeral_key: [u8; 32], // r·B
+    pub ciphertext: Vec<u8>,     // Option XORed with the keystream from r·Y
+    pub tag: [u8; 32],           // Hash binding the ciphertext and voter address to r·Y
+}
+
+// Define a cast ballot awaiting decryption
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedBallot {
+    pub voter_address: Address,
+    pub weight: u128, // Voter's weight when the ballot was cast
+    pub ballot: BallotCiphertext,
+}
+
+// Define the key and decryption progress of an encrypted poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BallotEncryption {
+    pub config: EncryptionConfig,
+    pub key_shares: BTreeMap<Address, [u8; 32]>, // Key shares submitted so far, by trustee
+    pub decrypted: bool,                         // Whether the ballots were decrypted and counted
+    pub spoiled_voters: Vec<Address>,            // Voters whose ballots did not decrypt to an option
+}
+
+impl BallotEncryption {
+    // Check the state fits its poll: a single-choice poll whose ballots are only counted after
+    // decryption, each encrypted ballot then counted or spoiled
+    pub(crate) fn is_valid(&self, poll: &Poll) -> bool {
+        let voters = &poll.encrypted_voters;
+        let threshold = self.config.threshold as usize;
+        let shares_valid = self
+            .key_shares
+            .keys()
+            .all(|trustee_address| self.config.trustees.iter().any(|trustee| trustee.address == *trustee_address));
+        let ballots_valid = if self.decrypted {
+            self.key_shares.len() >= threshold
+                && poll.participant_addresses.len() + self.spoiled_voters.len() == voters.len()
+                && poll.voters().chain(&self.spoiled_voters).all(|voter| voters.contains(voter))
+        } else {
+            self.key_shares.len() < threshold && poll.participant_addresses.is_empty() && self.spoiled_voters.is_empty()
+        };
+        
+        poll.voting_method == VotingMethod::SingleChoice
+            && poll.commit_end_timestamp.is_none()
+            && poll.anonymous.is_none()
+            && poll.delegated_votes.is_empty()
+            && voters.len() == poll.ciphertexts.len()
+            && poll.ciphertexts.iter().all(|ballot| voters.contains(&ballot.voter_address))
+            && shares_valid
+            && ballots_valid
+    }
+}
+
+impl Encode for Trustee {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.address);
+        encoder.write(&self.public_share);
+    }
+}
+
+impl Decode for Trustee {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(Trustee {
+            address: decoder.read()?,
+            public_share: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for EncryptionConfig {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.public_key);
+        encoder.write(&self.threshold);
+        encoder.write(&self.trustees);
+    }
+}
+
+impl Decode for EncryptionConfig {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(EncryptionConfig {
+            public_key: decoder.read()?,
+            threshold: decoder.read()?,
+            trustees: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for BallotCiphertext {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.ephemeral_key);
+        encoder.write(&self.ciphertext);
+        encoder.write(&self.tag);
+    }
+}
+
+impl Decode for BallotCiphertext {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(BallotCiphertext {
+            ephemeral_key: decoder.read()?,
+            ciphertext: decoder.read()?,
+            tag: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for EncryptedBallot {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.voter_address);
+        encoder.write(&self.weight);
+        encoder.write(&self.ballot);
+    }
+}
+
+impl Decode for EncryptedBallot {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(EncryptedBallot {
+            voter_address: decoder.read()?,
+            weight: decoder.read()?,
+            ballot: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for BallotEncryption {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.config);
+        encoder.write(&self.key_shares);
+        encoder.write(&self.decrypted);
+        encoder.write(&self.spoiled_voters);
+    }
+}
+
+impl Decode for BallotEncryption {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(BallotEncryption {
+            config: decoder.read()?,
+            key_shares: decoder.read()?,
+            decrypted: decoder.read()?,
+            spoiled_voters: decoder.read()?,
+        })
+    }
+}
+
+// Get the public point of a secret key or key share
+pub fn public_key_of(secret: &[u8; 32]) -> [u8; 32] {
+    encode_point(&EdwardsPoint::mul_base(&Scalar::from_bytes_mod_order(*secret)))
+}
+
+// Deal Shamir shares of a secret key to trustee_count trustees, for setups with a trusted dealer.
+// The coefficients are the random higher terms of f, threshold - 1 of them.
+pub fn split_key(secret_key: &[u8; 32], coefficients: &[[u8; 32]], trustee_count: usize) -> Vec<[u8; 32]> {
+    (1..=trustee_count as u64)
+        .map(|index| {
+            let at = Scalar::from(index);
+            // Horner's rule from the highest term down
+            coefficients
+                .iter()
+                .rev()
+                .chain([secret_key])
+                .fold(Scalar::ZERO, |value, coefficient| value * at + Scalar::from_bytes_mod_order(*coefficient))
+                .to_bytes()
+        })
+        .collect()
+}
+
+// Encrypt an option to a poll's public key for the voter's address, as the contract stores it,
+// with the voter's random scalar (None when the key is not a point or the randomness reduces to 0)
+pub fn encrypt_ballot(
+    public_key: &[u8; 32],
+    voter_address: &Address,
+    selected_option: &str,
+    randomness: &[u8; 32],
+) -> Option<BallotCiphertext> {
+    let ephemeral_secret = Scalar::from_bytes_mod_order(*randomness);
+    if ephemeral_secret == Scalar::ZERO {
+        return None;
+    }
+    let ephemeral_key = encode_point(&EdwardsPoint::mul_base(&ephemeral_secret));
+    let shared_point = encode_point(&(decode_point(public_key)? * ephemeral_secret));
+    let ciphertext = apply_keystream(&shared_point, &ephemeral_key, selected_option.as_bytes());
+    let tag = ballot_tag(&shared_point, &ephemeral_key, voter_address, &ciphertext);
+    Some(BallotCiphertext { ephemeral_key, ciphertext, tag })
+}
+
+// Decrypt a voter's ballot with the poll's secret key (None when it was not sealed to that key
+// for that voter)
+fn decrypt_ballot(secret_key: &Scalar, voter_address: &Address, ballot: &BallotCiphertext) -> Option<String> {
+    let shared_point = encode_point(&(decode_point(&ballot.ephemeral_key)? * secret_key));
+    if ballot_tag(&shared_point, &ballot.ephemeral_key, voter_address, &ballot.ciphertext) != ballot.tag {
+        return None;
+    }
+    String::from_utf8(apply_keystream(&shared_point, &ballot.ephemeral_key, &ballot.ciphertext)).ok()
+}
+
+// XOR data with the keystream SHA-256(domain || shared point || ephemeral key || block counter)
+fn apply_keystream(shared_point: &[u8; 32], ephemeral_key: &[u8; 32], data: &[u8]) -> Vec<u8> {
+    data.chunks(32)
+        .enumerate()
+        .flat_map(|(block, chunk)| {
+            let mut hashed_bytes = KEYSTREAM_DOMAIN.to_vec();
+            hashed_bytes.extend_from_slice(shared_point);
+            hashed_bytes.extend_from_slice(ephemeral_key);
+            hashed_bytes.extend_from_slice(&(block as u32).to_le_bytes());
+            let keystream = sha256(&hashed_bytes);
+            chunk.iter().zip(keystream).map(|(byte, key_byte)| byte ^ key_byte).collect::<Vec<u8>>()
+        })
+        .collect()
+}
+
+// Hash SHA-256(domain || shared point || ephemeral key || encoded voter address || ciphertext)
+fn ballot_tag(
+    shared_point: &[u8; 32],
+    ephemeral_key: &[u8; 32],
+    voter_address: &Address,
+    ciphertext: &[u8],
+) -> [u8; 32] {
+    let mut hashed_bytes = TAG_DOMAIN.to_vec();
+    hashed_bytes.extend_from_slice(shared_point);
+    hashed_bytes.extend_from_slice(ephemeral_key);
+    hashed_bytes.extend_from_slice(&encoding::to_bytes(voter_address));
+    hashed_bytes.extend_from_slice(ciphertext);
+    sha256(&hashed_bytes)
+}
+
+// Compute the Lagrange coefficient of share index over the basis, evaluated at `at`: the product
+// of (at - j) / (index - j) over the other indexes j
+fn lagrange_coefficient(basis: &[u64], index: u64, at: u64) -> Scalar {
+    let (at, index_scalar) = (Scalar::from(at), Scalar::from(index));
+    basis.iter().filter(|&&other| other != index).fold(Scalar::ONE, |coefficient, &other| {
+        let other = Scalar::from(other);
+        coefficient * (at - other) * (index_scalar - other).invert()
+    })
+}
+
+// Decode a compressed point (None when the encoding is not a point)
+fn decode_point(encoding: &[u8; 32]) -> Option<EdwardsPoint> {
+    CompressedEdwardsY(*encoding).decompress()
+}
+
+fn encode_point(point: &EdwardsPoint) -> [u8; 32] {
+    point.compress().to_bytes()
+}
+
+impl Poll {
+    // Check whether an encrypted poll still waits for its ballots to be decrypted
+    pub fn decryption_pending(&self) -> bool {
+        self.encryption.as_ref().is_some_and(|encryption| !encryption.decrypted)
+    }
+    
+    // Recombine the key from the submitted shares and count every ballot it decrypts, returning
+    // the ballots counted and spoiled
+    fn decrypt_ballots(&mut self) -> (u32, u32) {
+        let Some(encryption) = &self.encryption else {
+            return (0, 0);
+        };
+        let shares: Vec<(u64, [u8; 32])> = encryption
+            .config
+            .trustees
+            .iter()
+            .enumerate()
+            .filter_map(|(position, trustee)| Some((position as u64 + 1, *encryption.key_shares.get(&trustee.address)?)))
+            .take(encryption.config.threshold as usize)
+            .collect();
+        let basis: Vec<u64> = shares.iter().map(|(index, _)| *index).collect();
+        let secret_key: Scalar = shares
+            .iter()
+            .map(|(index, key_share)| {
+                Scalar::from_bytes_mod_order(*key_share) * lagrange_coefficient(&basis, *index, 0)
+            })
+            .sum();
+        
+        let ballots: Vec<(Address, u128, Option<OptionId>)> = self
+            .ciphertexts
+            .iter()
+            .map(|encrypted_ballot| {
+                let voter_address = &encrypted_ballot.voter_address;
+                let selected_option = decrypt_ballot(&secret_key, voter_address, &encrypted_ballot.ballot)
+                    .and_then(|selected_option| self.option_id(&selected_option));
+                (voter_address.clone(), encrypted_ballot.weight, selected_option)
+            })
+            .collect();
+        let mut spoiled_voters = Vec::new();
+        for (voter_address, weight, selected_option) in ballots {
+            match selected_option {
+                Some(option_id) if self.add_to_tally(option_id, weight, true).is_ok() => {
+                    let selected_option = self.voting_options[option_id.0 as usize].clone();
+                    if weight != 1 {
+                        self.weighted_voters.insert(voter_address.clone(), weight);
+                    }
+                    self.participant_addresses.insert(voter_address.clone());
+                    self.votes_by_address.insert(voter_address, selected_option);
+                }
+                _ => spoiled_voters.push(voter_address),
+            }
+        }
+        
+        // A poll closed before decryption fixed its root over no ballots
+        if self.ballot_root.is_some() {
+            self.ballot_root = Some(self.compute_ballot_root());
+        }
+        let counted = self.participant_addresses.len() as u32;
+        let spoiled = spoiled_voters.len() as u32;
+        if let Some(encryption) = self.encryption.as_mut() {
+            encryption.decrypted = true;
+            encryption.spoiled_voters = spoiled_voters;
+        }
+        (counted, spoiled)
+    }
+}
+
+impl VotingContract {
+    // Create a single-choice poll whose ballots are encrypted to the config's public key and
+    // decrypted by its trustees once the poll has ended. Returns PollCreationFailed when the
+    // trustees' public shares do not fit the key and threshold.
+    pub fn create_encrypted_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        mut encryption: EncryptionConfig,
+    ) -> Result<PollId> {
+        for trustee in encryption.trustees.iter_mut() {
+            trustee.address = self.parse_address(&trustee.address)?;
+        }
+        if !encryption.is_valid() {
+            return Err(VotingError::PollCreationFailed);
+        }
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.encryption = Some(BallotEncryption {
+            config: encryption,
+            key_shares: BTreeMap::new(),
+            decrypted: false,
+            spoiled_voters: Vec::new(),
+        });
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Cast an encrypted ballot. It is stored as sent and only counted once the trustees decrypt
+    // the poll, so a ballot that does not decrypt to an option is accepted here and spoiled then.
+    pub fn vote_encrypted(&mut self, poll_id: &PollId, voter_address: Address, ballot: BallotCiphertext) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let voter_weight = self.ballot_weight(poll_id, &voter_address)?;
+        let current_timestamp = self.now();
+        let holds_balance = self.voter_holds_balance(poll_id, &voter_address);
+        let vote_fee = self.vote_fee_of(poll_id);
+        self.check_rate_limit(RateLimitedAction::Vote, &voter_address, 1)?;
+        self.check_fee(&voter_address, vote_fee)?;
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        if poll.encryption.is_none() {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        if !poll.creator_can_vote && voter_address == poll.poll_creator_address {
+            return Err(VotingError::CreatorCannotVote);
+        }
+        
+        if !poll.is_eligible(&voter_address) {
+            return Err(VotingError::NotEligible);
+        }
+        
+        if !holds_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        
+        if poll.encrypted_voters.contains(&voter_address) {
+            return Err(VotingError::AlreadyVoted);
+        }
+        
+        if ballot.ciphertext.len() > MAX_CIPHERTEXT_LEN {
+            return Err(VotingError::InvalidParameter);
+        }
+        
+        poll.encrypted_voters.insert(voter_address.clone());
+        poll.ciphertexts.push(EncryptedBallot {
+            voter_address: voter_address.clone(),
+            weight: voter_weight,
+            ballot,
+        });
+        self.record_rate_limited(RateLimitedAction::Vote, &voter_address);
+        self.collect_fee(poll_id, &voter_address, vote_fee);
+        self.emit(ContractEvent::EncryptedVoteCast {
+            poll_id: poll_id.clone(),
+            voter_address,
+        });
+        Ok(())
+    }
+    
+    // Submit a trustee's key share for an encrypted poll that has ended. The share must match the
+    // trustee's public share, and the share completing the threshold decrypts and counts the ballots.
+    pub fn submit_decryption_share(
+        &mut self,
+        poll_id: &PollId,
+        trustee_address: &Address,
+        key_share: [u8; 32],
+    ) -> Result<()> {
+        let trustee_address = self.parse_address(trustee_address)?;
+        let current_timestamp = self.now();
+        let poll = self.live_poll_mut(poll_id)?;
+        let ended = poll.has_ended(current_timestamp);
+        let encryption = poll.encryption.as_mut().ok_or(VotingError::WrongVotingMethod)?;
+        if !ended || encryption.decrypted {
+            return Err(VotingError::WrongPhase);
+        }
+        let trustee = encryption
+            .config
+            .trustees
+            .iter()
+            .find(|trustee| trustee.address == trustee_address)
+            .ok_or(VotingError::NotAuthorized)?;
+        if encryption.key_shares.contains_key(&trustee_address) {
+            return Err(VotingError::InvalidParameter);
+        }
+        let key_share_scalar = Option::<Scalar>::from(Scalar::from_canonical_bytes(key_share));
+        let public_share = key_share_scalar.map(|key_share| encode_point(&EdwardsPoint::mul_base(&key_share)));
+        if public_share != Some(trustee.public_share) {
+            return Err(VotingError::InvalidKeyShare);
+        }
+        
+        encryption.key_shares.insert(trustee_address.clone(), key_share);
+        let decrypted = if encryption.key_shares.len() >= encryption.config.threshold as usize {
+            Some(poll.decrypt_ballots())
+        } else {
+            None
+        };
+        
+        self.emit(ContractEvent::DecryptionShareSubmitted {
+            poll_id: poll_id.clone(),
+            trustee_address,
+        });
+        if let Some((counted, spoiled)) = decrypted {
+            self.emit(ContractEvent::BallotsDecrypted {
+                poll_id: poll_id.clone(),
+                counted,
+                spoiled,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, MockClock};
+    use crate::PollOutcome;
+    
+    #[test]
+    fn test_encrypted_ballots() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        // Three trustees share the key, any two of them can decrypt
+        let secret_key = sha256(b"poll key");
+        let key_shares = split_key(&secret_key, &[sha256(b"coefficient")], 3);
+        let trustees = ["wallet_t1", "wallet_t2", "wallet_t3"]
+            .iter()
+            .zip(&key_shares)
+            .map(|(address, key_share)| Trustee {
+                address: address.parse().unwrap(),
+                public_share: public_key_of(key_share),
+            })
+            .collect();
+        let mut encryption = EncryptionConfig {
+            public_key: public_key_of(&secret_key),
+            threshold: 2,
+            trustees,
+        };
+        let mut create = |encryption: EncryptionConfig| {
+            let options = vec!["Yes".to_string(), "No".to_string()];
+            contract.create_encrypted_poll(
+                "wallet_creator".parse().unwrap(),
+                "Sealed".to_string(),
+                String::new(),
+                options,
+                60,
+                encryption,
+            )
+        };
+        let mut mismatched = encryption.clone();
+        mismatched.trustees[2].public_share = public_key_of(&key_shares[1]);
+        assert!(matches!(create(mismatched), Err(VotingError::PollCreationFailed)));
+        let poll_id = create(encryption.clone()).unwrap();
+        
+        // Ballots stay opaque while voting runs; a tampered one is accepted but will not count
+        let public_key = encryption.public_key;
+        for (voter_number, selected_option) in ["Yes", "No", "Yes"].into_iter().enumerate() {
+            let randomness = sha256(&[voter_number as u8]);
+            let voter_address = format!("wallet_{}", voter_number);
+            let ballot = encrypt_ballot(
+                &public_key,
+                &voter_address.parse().unwrap(),
+                selected_option,
+                &randomness,
+            )
+            .unwrap();
+            contract.vote_encrypted(&poll_id, voter_address.parse().unwrap(), ballot).unwrap();
+        }
+        let mut tampered =
+            encrypt_ballot(&public_key, &"wallet_3".parse().unwrap(), "No", &sha256(b"tampered")).unwrap();
+        tampered.ciphertext[0] ^= 1;
+        contract.vote_encrypted(&poll_id, "wallet_3".parse().unwrap(), tampered).unwrap();
+        let result = contract.vote(&poll_id, "wallet_4".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().get_results()["Yes"], 0);
+        let result = contract.submit_decryption_share(&poll_id, &"wallet_t1".parse().unwrap(), key_shares[0]);
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        
+        // After the end, shares are checked and the second valid one decrypts the poll
+        clock.advance(60);
+        let result = contract.submit_decryption_share(&poll_id, &"wallet_t2".parse().unwrap(), key_shares[0]);
+        assert!(matches!(result, Err(VotingError::InvalidKeyShare)));
+        let result = contract.submit_decryption_share(&poll_id, &"wallet_4".parse().unwrap(), key_shares[0]);
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.submit_decryption_share(&poll_id, &"wallet_t1".parse().unwrap(), key_shares[0]).unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().outcome(clock.now()), None);
+        contract.submit_decryption_share(&poll_id, &"wallet_t3".parse().unwrap(), key_shares[2]).unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        let results = poll.get_results();
+        assert_eq!((results["Yes"], results["No"]), (2, 1));
+        assert_eq!(poll.encryption.as_ref().unwrap().spoiled_voters, ["wallet_3"]);
+        assert_eq!(poll.outcome(clock.now()), Some(PollOutcome::Passed));
+        
+        // The ciphertexts and the decrypted ballots are saved with the poll
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let restored_poll = restored.get_poll(&poll_id).unwrap();
+        assert_eq!(restored_poll.ciphertexts.len(), 4);
+        assert_eq!(restored_poll.votes_by_address["wallet_0"], "Yes");
+        encryption.threshold = 4;
+        assert!(!encryption.is_valid());
+    }
+    
+    #[test]
+    fn test_replayed_ballot_is_spoiled() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let secret_key = sha256(b"poll key");
+        let encryption = EncryptionConfig {
+            public_key: public_key_of(&secret_key),
+            threshold: 1,
+            trustees: vec![Trustee {
+                address: "wallet_trustee".parse().unwrap(),
+                public_share: public_key_of(&secret_key),
+            }],
+        };
+        let options = vec!["Yes".to_string(), "No".to_string()];
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_id = contract
+            .create_encrypted_poll(creator_address, "Sealed".to_string(), String::new(), options, 60, encryption)
+            .unwrap();
+        
+        // A copy of another voter's ballot is accepted, but its tag names the original voter
+        let ballot = encrypt_ballot(
+            &public_key_of(&secret_key),
+            &"wallet_a".parse().unwrap(),
+            "Yes",
+            &sha256(b"randomness"),
+        )
+        .unwrap();
+        contract.vote_encrypted(&poll_id, "wallet_a".parse().unwrap(), ballot.clone()).unwrap();
+        contract.vote_encrypted(&poll_id, "wallet_b".parse().unwrap(), ballot.clone()).unwrap();
+        
+        // Each voter casts one ballot, also after a restore rebuilds the set of encrypted voters
+        let mut contract = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        contract.set_clock(Box::new(clock.clone()));
+        let result = contract.vote_encrypted(&poll_id, "wallet_a".parse().unwrap(), ballot);
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        clock.advance(60);
+        let key_share = Scalar::from_bytes_mod_order(secret_key).to_bytes();
+        contract.submit_decryption_share(&poll_id, &"wallet_trustee".parse().unwrap(), key_share).unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.get_results()["Yes"], 1);
+        assert_eq!(poll.encryption.as_ref().unwrap().spoiled_voters, ["wallet_b"]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,580 @@
+// Contract errors.
+// Every variant has a stable numeric code so RPC layers and on-chain logs can report errors
+// consistently: codes are never reused or renumbered, and new variants take the next free code.
+// Errors stay plain values so callers can match on them; ContextualError adds the poll, option
+// and caller an error concerns for reporting.
+use core::error::Error;
+use core::fmt;
+
+use crate::prelude::*;
+
+// Define the error types for our contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VotingError {
+    PollNotFound,
+    PollClosed,
+    AlreadyVoted,
+    NotAuthorized,
+    InvalidOption,
+    PollCreationFailed,
+    InvalidTimeSettings,
+    InvalidAddress,
+    InsufficientCredits,
+    ThresholdNotMet,
+    CorruptState,
+    CannotRemoveLastAdmin,
+    VoteCountOverflow,
+    CreatorCannotVote,
+    DuplicateTitle,
+    AlreadyClosed,
+    WrongVotingMethod,
+    InvalidRanking,
+    DelegationCycle,
+    WrongPhase,
+    NoCommitment,
+    CommitmentMismatch,
+    InvalidQuorumConfig,
+    InvalidInstruction,
+    InvalidAccount,
+    NotEligible,
+    VoteChangesDisabled,
+    NotVoted,
+    InvalidTransition,
+    FractionalVotes,
+    InvalidSignature,
+    InvalidNonce,
+    DuplicateSelection,
+    TooManySelections,
+    InvalidTag,
+    ExtensionLimitReached,
+    TooManyOptions,
+    TitleTooLong,
+    DescriptionTooLong,
+    TooManyPolls,
+    DurationTooLong,
+    DuplicateOption,
+    EmptyOption,
+    InvalidHistoryTracking,
+    ActionAlreadyExecuted,
+    ProposalNotPassed,
+    NoAction,
+    InvalidParameter,
+    InsufficientBalance,
+    SnapshotUnavailable,
+    RateLimited,
+    AlreadyFlagged,
+    InvalidFlagReason,
+    PollFinalized,
+    UnsupportedSnapshotVersion,
+    InvalidMetadata,
+    BatchAborted,
+    PaymentFailed,
+    NoReward,
+    ElectionNotFound,
+    InvalidProof,
+    InvalidKeyShare,
+    ResultsHidden,
+    LedgerWriteFailed,
+    ContractPaused,
+    InvalidRationale,
+    InvalidComment,
+    CommentNotFound,
+    QuotaExceeded,
+}
+
+// Define the broad kind of an error, which API layers map to their own status codes (gRPC codes
+// and HTTP statuses)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    NotFound,           // The poll, election or record named does not exist
+    AlreadyExists,      // The action was already taken
+    PermissionDenied,   // The caller may not take the action
+    FailedPrecondition, // The poll's state does not allow the action
+    ResourceExhausted,  // A rate, poll or extension limit is used up
+    Aborted,            // The action was rolled back with others
+    OutOfRange,         // A tally would overflow
+    Unavailable,        // An external provider failed
+    DataLoss,           // Stored state is corrupt
+    InvalidArgument,    // The request's contents are invalid
+}
+
+impl VotingError {
+    // Every variant, in code order
+    pub const ALL: [VotingError; 69] = [
+        VotingError::PollNotFound,
+        VotingError::PollClosed,
+        VotingError::AlreadyVoted,
+        VotingError::NotAuthorized,
+        VotingError::InvalidOption,
+        VotingError::PollCreationFailed,
+        VotingError::InvalidTimeSettings,
+        VotingError::InvalidAddress,
+        VotingError::InsufficientCredits,
+        VotingError::ThresholdNotMet,
+        VotingError::CorruptState,
+        VotingError::CannotRemoveLastAdmin,
+        VotingError::VoteCountOverflow,
+        VotingError::CreatorCannotVote,
+        VotingError::DuplicateTitle,
+        VotingError::AlreadyClosed,
+        VotingError::WrongVotingMethod,
+        VotingError::InvalidRanking,
+        VotingError::DelegationCycle,
+        VotingError::WrongPhase,
+        VotingError::NoCommitment,
+        VotingError::CommitmentMismatch,
+        VotingError::InvalidQuorumConfig,
+        VotingError::InvalidInstruction,
+        VotingError::InvalidAccount,
+        VotingError::NotEligible,
+        VotingError::VoteChangesDisabled,
+        VotingError::NotVoted,
+        VotingError::InvalidTransition,
+        VotingError::FractionalVotes,
+        VotingError::InvalidSignature,
+        VotingError::InvalidNonce,
+        VotingError::DuplicateSelection,
+        VotingError::TooManySelections,
+        VotingError::InvalidTag,
+        VotingError::ExtensionLimitReached,
+        VotingError::TooManyOptions,
+        VotingError::TitleTooLong,
+        VotingError::DescriptionTooLong,
+        VotingError::TooManyPolls,
+        VotingError::DurationTooLong,
+        VotingError::DuplicateOption,
+        VotingError::EmptyOption,
+        VotingError::InvalidHistoryTracking,
+        VotingError::ActionAlreadyExecuted,
+        VotingError::ProposalNotPassed,
+        VotingError::NoAction,
+        VotingError::InvalidParameter,
+        VotingError::InsufficientBalance,
+        VotingError::SnapshotUnavailable,
+        VotingError::RateLimited,
+        VotingError::AlreadyFlagged,
+        VotingError::InvalidFlagReason,
+        VotingError::PollFinalized,
+        VotingError::UnsupportedSnapshotVersion,
+        VotingError::InvalidMetadata,
+        VotingError::BatchAborted,
+        VotingError::PaymentFailed,
+        VotingError::NoReward,
+        VotingError::ElectionNotFound,
+        VotingError::InvalidProof,
+        VotingError::InvalidKeyShare,
+        VotingError::ResultsHidden,
+        VotingError::LedgerWriteFailed,
+        VotingError::ContractPaused,
+        VotingError::InvalidRationale,
+        VotingError::InvalidComment,
+        VotingError::CommentNotFound,
+        VotingError::QuotaExceeded,
+    ];
+    
+    // Get the variant's stable code
+    pub fn code(&self) -> u16 {
+        match self {
+            VotingError::PollNotFound => 1,
+            VotingError::PollClosed => 2,
+            VotingError::AlreadyVoted => 3,
+            VotingError::NotAuthorized => 4,
+            VotingError::InvalidOption => 5,
+            VotingError::PollCreationFailed => 6,
+            VotingError::InvalidTimeSettings => 7,
+            VotingError::InvalidAddress => 8,
+            VotingError::InsufficientCredits => 9,
+            VotingError::ThresholdNotMet => 10,
+            VotingError::CorruptState => 11,
+            VotingError::CannotRemoveLastAdmin => 12,
+            VotingError::VoteCountOverflow => 13,
+            VotingError::CreatorCannotVote => 14,
+            VotingError::DuplicateTitle => 15,
+            VotingError::AlreadyClosed => 16,
+            VotingError::WrongVotingMethod => 17,
+            VotingError::InvalidRanking => 18,
+            VotingError::DelegationCycle => 19,
+            VotingError::WrongPhase => 20,
+            VotingError::NoCommitment => 21,
+            VotingError::CommitmentMismatch => 22,
+            VotingError::InvalidQuorumConfig => 23,
+            VotingError::InvalidInstruction => 24,
+            VotingError::InvalidAccount => 25,
+            VotingError::NotEligible => 26,
+            VotingError::VoteChangesDisabled => 27,
+            VotingError::NotVoted => 28,
+            VotingError::InvalidTransition => 29,
+            VotingError::FractionalVotes => 30,
+            VotingError::InvalidSignature => 31,
+            VotingError::InvalidNonce => 32,
+            VotingError::DuplicateSelection => 33,
+            VotingError::TooManySelections => 34,
+            VotingError::InvalidTag => 35,
+            VotingError::ExtensionLimitReached => 36,
+            VotingError::TooManyOptions => 37,
+            VotingError::TitleTooLong => 38,
+            VotingError::DescriptionTooLong => 39,
+            VotingError::TooManyPolls => 40,
+            VotingError::DurationTooLong => 41,
+            VotingError::DuplicateOption => 42,
+            VotingError::EmptyOption => 43,
+            VotingError::InvalidHistoryTracking => 44,
+            VotingError::ActionAlreadyExecuted => 45,
+            VotingError::ProposalNotPassed => 46,
+            VotingError::NoAction => 47,
+            VotingError::InvalidParameter => 48,
+            VotingError::InsufficientBalance => 49,
+            VotingError::SnapshotUnavailable => 50,
+            VotingError::RateLimited => 51,
+            VotingError::AlreadyFlagged => 52,
+            VotingError::InvalidFlagReason => 53,
+            VotingError::PollFinalized => 54,
+            VotingError::UnsupportedSnapshotVersion => 55,
+            VotingError::InvalidMetadata => 56,
+            VotingError::BatchAborted => 57,
+            VotingError::PaymentFailed => 58,
+            VotingError::NoReward => 59,
+            VotingError::ElectionNotFound => 60,
+            VotingError::InvalidProof => 61,
+            VotingError::InvalidKeyShare => 62,
+            VotingError::ResultsHidden => 63,
+            VotingError::LedgerWriteFailed => 64,
+            VotingError::ContractPaused => 65,
+            VotingError::InvalidRationale => 66,
+            VotingError::InvalidComment => 67,
+            VotingError::CommentNotFound => 68,
+            VotingError::QuotaExceeded => 69,
+        }
+    }
+    
+    // Look up a variant by its code
+    pub fn from_code(code: u16) -> Option<VotingError> {
+        VotingError::ALL.into_iter().find(|error| error.code() == code)
+    }
+    
+    // Get the variant's name, as reported by the RPC server
+    pub fn name(&self) -> &'static str {
+        match self {
+            VotingError::PollNotFound => "PollNotFound",
+            VotingError::PollClosed => "PollClosed",
+            VotingError::AlreadyVoted => "AlreadyVoted",
+            VotingError::NotAuthorized => "NotAuthorized",
+            VotingError::InvalidOption => "InvalidOption",
+            VotingError::PollCreationFailed => "PollCreationFailed",
+            VotingError::InvalidTimeSettings => "InvalidTimeSettings",
+            VotingError::InvalidAddress => "InvalidAddress",
+            VotingError::InsufficientCredits => "InsufficientCredits",
+            VotingError::ThresholdNotMet => "ThresholdNotMet",
+            VotingError::CorruptState => "CorruptState",
+            VotingError::CannotRemoveLastAdmin => "CannotRemoveLastAdmin",
+            VotingError::VoteCountOverflow => "VoteCountOverflow",
+            VotingError::CreatorCannotVote => "CreatorCannotVote",
+            VotingError::DuplicateTitle => "DuplicateTitle",
+            VotingError::AlreadyClosed => "AlreadyClosed",
+            VotingError::WrongVotingMethod => "WrongVotingMethod",
+            VotingError::InvalidRanking => "InvalidRanking",
+            VotingError::DelegationCycle => "DelegationCycle",
+            VotingError::WrongPhase => "WrongPhase",
+            VotingError::NoCommitment => "NoCommitment",
+            VotingError::CommitmentMismatch => "CommitmentMismatch",
+            VotingError::InvalidQuorumConfig => "InvalidQuorumConfig",
+            VotingError::InvalidInstruction => "InvalidInstruction",
+            VotingError::InvalidAccount => "InvalidAccount",
+            VotingError::NotEligible => "NotEligible",
+            VotingError::VoteChangesDisabled => "VoteChangesDisabled",
+            VotingError::NotVoted => "NotVoted",
+            VotingError::InvalidTransition => "InvalidTransition",
+            VotingError::FractionalVotes => "FractionalVotes",
+            VotingError::InvalidSignature => "InvalidSignature",
+            VotingError::InvalidNonce => "InvalidNonce",
+            VotingError::DuplicateSelection => "DuplicateSelection",
+            VotingError::TooManySelections => "TooManySelections",
+            VotingError::InvalidTag => "InvalidTag",
+            VotingError::ExtensionLimitReached => "ExtensionLimitReached",
+            VotingError::TooManyOptions => "TooManyOptions",
+            VotingError::TitleTooLong => "TitleTooLong",
+            VotingError::DescriptionTooLong => "DescriptionTooLong",
+            VotingError::TooManyPolls => "TooManyPolls",
+            VotingError::DurationTooLong => "DurationTooLong",
+            VotingError::DuplicateOption => "DuplicateOption",
+            VotingError::EmptyOption => "EmptyOption",
+            VotingError::InvalidHistoryTracking => "InvalidHistoryTracking",
+            VotingError::ActionAlreadyExecuted => "ActionAlreadyExecuted",
+            VotingError::ProposalNotPassed => "ProposalNotPassed",
+            VotingError::NoAction => "NoAction",
+            VotingError::InvalidParameter => "InvalidParameter",
+            VotingError::InsufficientBalance => "InsufficientBalance",
+            VotingError::SnapshotUnavailable => "SnapshotUnavailable",
+            VotingError::RateLimited => "RateLimited",
+            VotingError::AlreadyFlagged => "AlreadyFlagged",
+            VotingError::InvalidFlagReason => "InvalidFlagReason",
+            VotingError::PollFinalized => "PollFinalized",
+            VotingError::UnsupportedSnapshotVersion => "UnsupportedSnapshotVersion",
+            VotingError::InvalidMetadata => "InvalidMetadata",
+            VotingError::BatchAborted => "BatchAborted",
+            VotingError::PaymentFailed => "PaymentFailed",
+            VotingError::NoReward => "NoReward",
+            VotingError::ElectionNotFound => "ElectionNotFound",
+            VotingError::InvalidProof => "InvalidProof",
+            VotingError::InvalidKeyShare => "InvalidKeyShare",
+            VotingError::ResultsHidden => "ResultsHidden",
+            VotingError::LedgerWriteFailed => "LedgerWriteFailed",
+            VotingError::ContractPaused => "ContractPaused",
+            VotingError::InvalidRationale => "InvalidRationale",
+            VotingError::InvalidComment => "InvalidComment",
+            VotingError::CommentNotFound => "CommentNotFound",
+            VotingError::QuotaExceeded => "QuotaExceeded",
+        }
+    }
+    
+    // Get the kind of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            VotingError::PollNotFound
+            | VotingError::ElectionNotFound
+            | VotingError::NoCommitment
+            | VotingError::NotVoted
+            | VotingError::NoAction
+            | VotingError::NoReward
+            | VotingError::CommentNotFound => ErrorKind::NotFound,
+            VotingError::AlreadyVoted | VotingError::DuplicateTitle | VotingError::AlreadyFlagged => {
+                ErrorKind::AlreadyExists
+            }
+            VotingError::NotAuthorized
+            | VotingError::NotEligible
+            | VotingError::CreatorCannotVote
+            | VotingError::InsufficientBalance => ErrorKind::PermissionDenied,
+            VotingError::PollClosed
+            | VotingError::AlreadyClosed
+            | VotingError::WrongPhase
+            | VotingError::InvalidTransition
+            | VotingError::VoteChangesDisabled
+            | VotingError::WrongVotingMethod
+            | VotingError::ThresholdNotMet
+            | VotingError::ActionAlreadyExecuted
+            | VotingError::ProposalNotPassed
+            | VotingError::PollFinalized
+            | VotingError::ResultsHidden
+            | VotingError::CannotRemoveLastAdmin
+            | VotingError::InvalidAccount
+            | VotingError::UnsupportedSnapshotVersion => ErrorKind::FailedPrecondition,
+            VotingError::RateLimited
+            | VotingError::TooManyPolls
+            | VotingError::ExtensionLimitReached
+            | VotingError::QuotaExceeded => ErrorKind::ResourceExhausted,
+            VotingError::BatchAborted => ErrorKind::Aborted,
+            VotingError::VoteCountOverflow => ErrorKind::OutOfRange,
+            VotingError::SnapshotUnavailable
+            | VotingError::PaymentFailed
+            | VotingError::LedgerWriteFailed
+            | VotingError::ContractPaused => ErrorKind::Unavailable,
+            VotingError::CorruptState => ErrorKind::DataLoss,
+            VotingError::InvalidOption
+            | VotingError::PollCreationFailed
+            | VotingError::InvalidTimeSettings
+            | VotingError::InvalidAddress
+            | VotingError::InsufficientCredits
+            | VotingError::InvalidRanking
+            | VotingError::DelegationCycle
+            | VotingError::CommitmentMismatch
+            | VotingError::InvalidQuorumConfig
+            | VotingError::InvalidInstruction
+            | VotingError::FractionalVotes
+            | VotingError::InvalidSignature
+            | VotingError::InvalidNonce
+            | VotingError::DuplicateSelection
+            | VotingError::TooManySelections
+            | VotingError::InvalidTag
+            | VotingError::TooManyOptions
+            | VotingError::TitleTooLong
+            | VotingError::DescriptionTooLong
+            | VotingError::DurationTooLong
+            | VotingError::DuplicateOption
+            | VotingError::EmptyOption
+            | VotingError::InvalidHistoryTracking
+            | VotingError::InvalidParameter
+            | VotingError::InvalidFlagReason
+            | VotingError::InvalidRationale
+            | VotingError::InvalidComment
+            | VotingError::InvalidMetadata
+            | VotingError::InvalidProof
+            | VotingError::InvalidKeyShare => ErrorKind::InvalidArgument,
+        }
+    }
+    
+    // Attach the poll the error concerns
+    pub fn in_poll(self, poll_id: &str) -> ContextualError {
+        ContextualError::from(self).in_poll(poll_id)
+    }
+}
+
+impl fmt::Display for VotingError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            VotingError::PollNotFound => "poll not found",
+            VotingError::PollClosed => "poll is not accepting votes",
+            VotingError::AlreadyVoted => "voter has already voted in this poll",
+            VotingError::NotAuthorized => "caller is not authorized for this action",
+            VotingError::InvalidOption => "option is not on the ballot",
+            VotingError::PollCreationFailed => "poll parameters are invalid",
+            VotingError::InvalidTimeSettings => "poll time settings are invalid",
+            VotingError::InvalidAddress => "address is empty or not valid in the address format",
+            VotingError::InsufficientCredits => "votes exceed the voter's credit budget",
+            VotingError::ThresholdNotMet => "leading option is below the winning threshold",
+            VotingError::CorruptState => "stored state is malformed or inconsistent",
+            VotingError::CannotRemoveLastAdmin => "cannot remove the last admin",
+            VotingError::VoteCountOverflow => "vote would overflow the option's tally",
+            VotingError::CreatorCannotVote => "creator may not vote in this poll",
+            VotingError::DuplicateTitle => "creator already has an active poll with this title",
+            VotingError::AlreadyClosed => "poll is already closed",
+            VotingError::WrongVotingMethod => "ballot does not match the poll's voting method",
+            VotingError::InvalidRanking => "ranked ballot is empty or repeats an option",
+            VotingError::DelegationCycle => "delegation would form a cycle",
+            VotingError::WrongPhase => "action is not allowed in the poll's current phase",
+            VotingError::NoCommitment => "no vote commitment to reveal",
+            VotingError::CommitmentMismatch => "revealed vote does not match the commitment",
+            VotingError::InvalidQuorumConfig => "quorum configuration is invalid",
+            VotingError::InvalidInstruction => "instruction data cannot be decoded",
+            VotingError::InvalidAccount => "account is missing, foreign or uninitialized",
+            VotingError::NotEligible => "voter is not eligible for this poll",
+            VotingError::VoteChangesDisabled => "poll does not allow changing votes",
+            VotingError::NotVoted => "voter has not voted in this poll",
+            VotingError::InvalidTransition => "action does not apply to the poll's status",
+            VotingError::FractionalVotes => "credits do not buy a whole number of votes",
+            VotingError::InvalidSignature => "signature does not match the message and key",
+            VotingError::InvalidNonce => "message does not carry the signer's next nonce",
+            VotingError::DuplicateSelection => "ballot selects an option twice",
+            VotingError::TooManySelections => "ballot selects too many options",
+            VotingError::InvalidTag => "tag or category is empty",
+            VotingError::ExtensionLimitReached => "poll cannot be extended again",
+            VotingError::TooManyOptions => "too many options",
+            VotingError::TitleTooLong => "title is too long",
+            VotingError::DescriptionTooLong => "description is too long",
+            VotingError::TooManyPolls => "creator has too many open polls",
+            VotingError::DurationTooLong => "poll duration is too long",
+            VotingError::DuplicateOption => "option is listed twice",
+            VotingError::EmptyOption => "option is empty",
+            VotingError::InvalidHistoryTracking => "history tracking needs a vote or time interval",
+            VotingError::ActionAlreadyExecuted => "poll action already ran",
+            VotingError::ProposalNotPassed => "poll did not pass",
+            VotingError::NoAction => "poll has no pending action",
+            VotingError::InvalidParameter => "parameter value is out of range",
+            VotingError::InsufficientBalance => "balance is below the required minimum",
+            VotingError::SnapshotUnavailable => "voting weight snapshot is unavailable",
+            VotingError::RateLimited => "too many actions in the rate limit window",
+            VotingError::AlreadyFlagged => "address has already flagged this poll",
+            VotingError::InvalidFlagReason => "flag reason is empty or too long",
+            VotingError::PollFinalized => "poll results are finalized",
+            VotingError::UnsupportedSnapshotVersion => "snapshot needs a newer schema version",
+            VotingError::InvalidMetadata => "metadata key or value is invalid or too long",
+            VotingError::BatchAborted => "vote was rolled back because another vote in its batch failed",
+            VotingError::PaymentFailed => "fee could not be paid or fees could not be paid out",
+            VotingError::NoReward => "no reward pool or no reward to claim",
+            VotingError::ElectionNotFound => "election not found",
+            VotingError::InvalidProof => "proof does not show the nullifier belongs to a registered commitment",
+            VotingError::InvalidKeyShare => "key share does not match the trustee's public share",
+            VotingError::ResultsHidden => "poll results are hidden until it ends",
+            VotingError::LedgerWriteFailed => "call took effect but a ledger sink failed to record it",
+            VotingError::ContractPaused => "contract is paused",
+            VotingError::InvalidRationale => "rationale is empty or too long",
+            VotingError::InvalidComment => "comment is empty or too long",
+            VotingError::CommentNotFound => "comment not found",
+            VotingError::QuotaExceeded => "creator has used up their own quota of open polls",
+        };
+        write!(formatter, "{}", message)
+    }
+}
+
+impl Error for VotingError {}
+
+// Define an error together with what it concerns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextualError {
+    pub error: VotingError,
+    pub poll_id: Option<String>,
+    pub option: Option<String>, // Option the request named
+    pub caller: Option<String>, // Address the request acted for
+}
+
+impl ContextualError {
+    pub fn in_poll(mut self, poll_id: &str) -> Self {
+        self.poll_id = Some(poll_id.to_string());
+        self
+    }
+    
+    pub fn with_option(mut self, option: &str) -> Self {
+        self.option = Some(option.to_string());
+        self
+    }
+    
+    pub fn with_caller(mut self, caller: &str) -> Self {
+        self.caller = Some(caller.to_string());
+        self
+    }
+}
+
+impl From<VotingError> for ContextualError {
+    fn from(error: VotingError) -> Self {
+        ContextualError {
+            error,
+            poll_id: None,
+            option: None,
+            caller: None,
+        }
+    }
+}
+
+// Write the error followed by whatever context is known, e.g.
+// "[E5 InvalidOption] option is not on the ballot (poll poll_1, option \"Maybe\", caller wallet_a)"
+impl fmt::Display for ContextualError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "[E{} {}] {}", self.error.code(), self.error.name(), self.error)?;
+        let mut context = Vec::new();
+        if let Some(poll_id) = &self.poll_id {
+            context.push(format!("poll {}", poll_id));
+        }
+        if let Some(option) = &self.option {
+            context.push(format!("option {:?}", option));
+        }
+        if let Some(caller) = &self.caller {
+            context.push(format!("caller {}", caller));
+        }
+        if !context.is_empty() {
+            write!(formatter, " ({})", context.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ContextualError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_error_codes_and_display() {
+        // Codes are unique, start at 1 and map back to their variant
+        for (index, error) in VotingError::ALL.iter().enumerate() {
+            assert_eq!(error.code() as usize, index + 1);
+            assert_eq!(VotingError::from_code(error.code()), Some(*error));
+            assert_eq!(error.name(), format!("{:?}", error));
+        }
+        assert_eq!(VotingError::from_code(0), None);
+        assert_eq!(VotingError::AlreadyVoted.kind(), ErrorKind::AlreadyExists);
+        
+        assert_eq!(VotingError::PollNotFound.to_string(), "poll not found");
+        let error = VotingError::InvalidOption.in_poll("poll_1").with_option("Maybe").with_caller("wallet_a");
+        assert_eq!(
+            error.to_string(),
+            "[E5 InvalidOption] option is not on the ballot (poll poll_1, option \"Maybe\", caller wallet_a)"
+        );
+        assert_eq!(error.source().unwrap().to_string(), "option is not on the ballot");
+        let error = ContextualError::from(VotingError::NotVoted);
+        assert_eq!(error.to_string(), "[E28 NotVoted] voter has not voted in this poll");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,189 @@
+// Proposal execution hooks.
+// A poll can carry an action, such as a treasury transfer or a parameter change, that runs when
+// the poll passes. Actions only run once the poll is closed, so its tallies are final, and only
+// when its outcome is Passed under its quorum configuration. A successful run is recorded on the
+// poll and saved with it, so an action never runs twice, even after a restore. Hooks themselves
+// are code and are not persisted; they have to be attached again after a restore.
+// Closing a poll runs its action straight away. If the action fails, the close still stands and
+// the action stays pending until execute_action is called again. A parameter-change poll's action
+// is its change (see governance), so no hook can be attached to it.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, PollOutcome, Result, VotingContract, VotingError};
+
+// Action run when a poll passes. The poll is closed and its results are final.
+pub trait ExecutionHook: Send + Sync {
+    fn execute(&self, poll: &Poll) -> Result<()>;
+}
+
+impl<F> ExecutionHook for F
+where
+    F: Fn(&Poll) -> Result<()> + Send + Sync,
+{
+    fn execute(&self, poll: &Poll) -> Result<()> {
+        self(poll)
+    }
+}
+
+impl VotingContract {
+    // Attach the action a poll runs when it passes (creator or ConfigurePolls), replacing any
+    // action attached before. Actions can only be attached while voting is still open.
+    pub fn attach_action(&mut self, poll_id: &PollId, hook: Box<dyn ExecutionHook>, caller: &Address) -> Result<()> {
+        let attached_by = self.parse_address(caller)?;
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, &attached_by, Permission::ConfigurePolls)?;
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.parameter_change.is_some() {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        self.execution_hooks.insert(poll_id.clone(), hook);
+        self.emit(ContractEvent::ActionAttached {
+            poll_id: poll_id.clone(),
+            attached_by,
+        });
+        Ok(())
+    }
+    
+    // Check whether a poll has an action waiting to run
+    pub fn has_pending_action(&self, poll_id: &PollId) -> bool {
+        self.execution_hooks.contains_key(poll_id)
+            || self
+                .get_poll(poll_id)
+                .is_ok_and(|poll| poll.parameter_change.is_some() && poll.action_executed_at.is_none())
+    }
+    
+    // Run a closed poll's action, e.g. to retry one that failed when the poll closed. Anyone may
+    // call it: the poll's outcome decides whether the action runs, not the caller.
+    pub fn execute_action(&mut self, poll_id: &PollId) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.get_poll(poll_id)?;
+        if poll.action_executed_at.is_some() {
+            return Err(VotingError::ActionAlreadyExecuted);
+        }
+        if !poll.is_closed() {
+            return Err(VotingError::WrongPhase);
+        }
+        if poll.outcome(current_timestamp) != Some(PollOutcome::Passed) {
+            return Err(VotingError::ProposalNotPassed);
+        }
+        
+        if let Some(change) = poll.parameter_change {
+            self.apply_parameter_change(poll_id, change)?;
+        } else {
+            let hook = self.execution_hooks.remove(poll_id).ok_or(VotingError::NoAction)?;
+            
+            // Put the hook back if it fails so the action can be retried
+            let poll = self.get_poll(poll_id)?;
+            if let Err(error) = hook.execute(poll) {
+                self.execution_hooks.insert(poll_id.clone(), hook);
+                return Err(error);
+            }
+        }
+        
+        if let Some(poll) = self.active_polls.get_mut(poll_id).or(self.archived_polls.get_mut(poll_id)) {
+            poll.action_executed_at = Some(current_timestamp);
+        }
+        self.emit(ContractEvent::ActionExecuted {
+            poll_id: poll_id.clone(),
+            executed_at: current_timestamp,
+        });
+        Ok(())
+    }
+    
+    // Run the actions of polls that were just closed. Polls without an action or that did not
+    // pass are skipped, and failed actions stay pending.
+    pub(crate) fn execute_closed_actions(&mut self, poll_ids: &[PollId]) {
+        for poll_id in poll_ids {
+            if self.has_pending_action(poll_id) {
+                let _ = self.execute_action(poll_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    
+    fn create(contract: &mut VotingContract, poll_title: &str) -> PollId {
+        contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                poll_title.to_string(),
+                "Fund the proposal?".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap()
+    }
+    
+    #[test]
+    fn test_execution_hooks() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counting_hook = |runs: &Arc<AtomicUsize>| {
+            let runs = Arc::clone(runs);
+            Box::new(move |poll: &Poll| {
+                assert!(poll.is_closed());
+                runs.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+        };
+        
+        // A passing poll runs its action once, when it closes
+        let passed_poll_id = create(&mut contract, "Passes");
+        contract.attach_action(&passed_poll_id, counting_hook(&runs), &"wallet_creator".parse().unwrap()).unwrap();
+        let result = contract.attach_action(&passed_poll_id, counting_hook(&runs), &"wallet_random".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.vote(&passed_poll_id, "wallet_a".parse().unwrap(), "Yes").unwrap();
+        let result = contract.execute_action(&passed_poll_id);
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        contract.close_poll(&passed_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(contract.get_poll(&passed_poll_id).unwrap().action_executed_at, Some(1_000));
+        let result = contract.execute_action(&passed_poll_id);
+        assert!(matches!(result, Err(VotingError::ActionAlreadyExecuted)));
+        
+        // A tied poll is rejected and its action never runs
+        let tied_poll_id = create(&mut contract, "Ties");
+        contract.attach_action(&tied_poll_id, counting_hook(&runs), &"wallet_creator".parse().unwrap()).unwrap();
+        contract.vote(&tied_poll_id, "wallet_a".parse().unwrap(), "Yes").unwrap();
+        contract.vote(&tied_poll_id, "wallet_b".parse().unwrap(), "No").unwrap();
+        clock.advance(60);
+        contract.tick();
+        let result = contract.execute_action(&tied_poll_id);
+        assert!(matches!(result, Err(VotingError::ProposalNotPassed)));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        
+        // A failing action leaves the close in place and can be retried
+        let retried_poll_id = create(&mut contract, "Retries");
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let flaky_attempts = Arc::clone(&attempts);
+        let flaky_hook = move |_: &Poll| match flaky_attempts.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(VotingError::InvalidAccount),
+            _ => Ok(()),
+        };
+        contract.attach_action(&retried_poll_id, Box::new(flaky_hook), &"wallet_creator".parse().unwrap()).unwrap();
+        contract.vote(&retried_poll_id, "wallet_a".parse().unwrap(), "No").unwrap();
+        contract.close_poll(&retried_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert!(contract.has_pending_action(&retried_poll_id));
+        contract.execute_action(&retried_poll_id).unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        
+        // The executed mark survives a restore, so a re-attached hook cannot fire again
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert!(restored.get_poll(&retried_poll_id).unwrap().action_executed_at.is_some());
+        let bare_poll_id = create(&mut contract, "Bare");
+        assert!(matches!(contract.execute_action(&bare_poll_id), Err(VotingError::WrongPhase)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,198 @@
+// Certified poll results.
+// Closing a poll stops voting, but results are still recomputed from the tallies on every query.
+// Finalizing a closed poll computes its results and outcome once and stamps them in a
+// FinalizationRecord, together with who finalized it, when, and a SHA-256 hash over the record and
+// the poll's ballot root. From then on the poll cannot change: every call that would modify it
+// fails with PollFinalized. Archiving only moves a poll and running its action only marks that the
+// action ran, so both stay possible and neither touches the certified result. Auditors can check a
+// record against its hash with verify_finalization, and restoring a snapshot checks it as well.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::hash::sha256;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, PollOutcome, Result, VotingContract, VotingError};
+
+// Define the certified result of a finalized poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizationRecord {
+    pub finalized_at: u64,
+    pub finalized_by: Address,
+    pub results: HashMap<String, u128>, // Tallies as returned by get_results when finalized
+    pub outcome: Option<PollOutcome>,   // Outcome under the poll's quorum configuration
+    pub result_hash: [u8; 32],          // SHA-256 over the poll ID, ballot root and the fields above
+}
+
+impl FinalizationRecord {
+    // Build the record of a closed poll and hash it
+    fn new(poll: &Poll, finalized_at: u64, finalized_by: Address) -> Self {
+        let mut record = FinalizationRecord {
+            finalized_at,
+            finalized_by,
+            results: poll.get_results(),
+            outcome: poll.outcome(finalized_at),
+            result_hash: [0; 32],
+        };
+        record.result_hash = record.compute_hash(poll);
+        record
+    }
+    
+    // Hash the record's fields with the poll's identity and ballots (maps encode in key order, so
+    // the hash is the same on every platform)
+    pub fn compute_hash(&self, poll: &Poll) -> [u8; 32] {
+        let mut encoder = Encoder::new();
+        encoder.write(&poll.poll_id);
+        encoder.write(&poll.ballot_root);
+        encoder.write(&self.finalized_at);
+        encoder.write(&self.finalized_by);
+        encoder.write(&self.results);
+        encoder.write(&self.outcome);
+        sha256(&encoder.finish())
+    }
+    
+    // Check the record matches its hash and the poll it was made for
+    pub fn verify(&self, poll: &Poll) -> bool {
+        self.result_hash == self.compute_hash(poll) && self.results == poll.get_results()
+    }
+}
+
+impl VotingContract {
+    // Finalize a closed poll (creator or ClosePolls), freezing it and returning its record. Encrypted
+    // polls must be decrypted first.
+    pub fn finalize(&mut self, poll_id: &PollId, caller: &Address) -> Result<FinalizationRecord> {
+        let finalized_by = self.parse_address(caller)?;
+        let current_timestamp = self.now();
+        let caller_has_permission = self.has_permission(&finalized_by, Permission::ClosePolls);
+        self.expire_if_due(poll_id);
+        let poll = match self.active_polls.get_mut(poll_id) {
+            Some(poll) => poll,
+            None => self.archived_polls.get_mut(poll_id).ok_or(VotingError::PollNotFound)?,
+        };
+        if !caller_has_permission && finalized_by != poll.poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        if poll.finalization.is_some() {
+            return Err(VotingError::PollFinalized);
+        }
+        if !poll.is_closed() || poll.decryption_pending() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        let record = FinalizationRecord::new(poll, current_timestamp, finalized_by.clone());
+        poll.finalization = Some(record.clone());
+        self.emit(ContractEvent::PollResultsFinalized {
+            poll_id: poll_id.clone(),
+            finalized_by,
+            result_hash: record.result_hash,
+        });
+        Ok(record)
+    }
+    
+    // Get a poll's finalization record (None until it is finalized)
+    pub fn get_finalization(&self, poll_id: &PollId) -> Result<Option<&FinalizationRecord>> {
+        Ok(self.get_poll(poll_id)?.finalization.as_ref())
+    }
+    
+    // Check a finalized poll's record against its hash and the poll's tallies
+    pub fn verify_finalization(&self, poll_id: &PollId) -> Result<bool> {
+        let poll = self.get_poll(poll_id)?;
+        let record = poll.finalization.as_ref().ok_or(VotingError::WrongPhase)?;
+        Ok(record.verify(poll))
+    }
+}
+
+impl Encode for PollOutcome {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            PollOutcome::Passed => 0,
+            PollOutcome::Rejected => 1,
+            PollOutcome::QuorumNotMet => 2,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for PollOutcome {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(PollOutcome::Passed),
+            1 => Ok(PollOutcome::Rejected),
+            2 => Ok(PollOutcome::QuorumNotMet),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+impl Encode for FinalizationRecord {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.finalized_at);
+        encoder.write(&self.finalized_by);
+        encoder.write(&self.results);
+        encoder.write(&self.outcome);
+        encoder.write(&self.result_hash);
+    }
+}
+
+impl Decode for FinalizationRecord {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(FinalizationRecord {
+            finalized_at: decoder.read()?,
+            finalized_by: decoder.read()?,
+            results: decoder.read()?,
+            outcome: decoder.read()?,
+            result_hash: decoder.read()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_finalization() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Budget".to_string(),
+                "Certified".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Yes").unwrap();
+        
+        // Only closed polls can be finalized, by their creator or a ClosePolls holder
+        assert!(matches!(
+            contract.finalize(&poll_id, &"wallet_creator".parse().unwrap()),
+            Err(VotingError::WrongPhase)
+        ));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert!(matches!(contract.finalize(&poll_id, &"wallet_a".parse().unwrap()), Err(VotingError::NotAuthorized)));
+        let record = contract.finalize(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!((record.results["Yes"], record.outcome), (1, Some(PollOutcome::Passed)));
+        assert!(contract.verify_finalization(&poll_id).unwrap());
+        
+        // A finalized poll rejects every change but can still be archived
+        assert!(matches!(
+            contract.finalize(&poll_id, &"wallet_admin".parse().unwrap()),
+            Err(VotingError::PollFinalized)
+        ));
+        let result = contract.set_poll_tags(&poll_id, vec!["late".to_string()], &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::PollFinalized)));
+        let result = contract.delete_poll(&poll_id, "cleanup", &"wallet_admin".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::PollFinalized)));
+        contract.archive_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        
+        // The record survives a restore, and a tampered record no longer verifies
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_finalization(&poll_id).unwrap(), Some(&record));
+        let poll = restored.archived_polls.get_mut(&poll_id).unwrap();
+        poll.finalization.as_mut().unwrap().results.insert("No".to_string(), 5);
+        assert!(!restored.verify_finalization(&poll_id).unwrap());
+        assert!(matches!(VotingContract::from_snapshot(&restored.to_snapshot()), Err(VotingError::CorruptState)));
+    }
+}
\ No newline at end of file
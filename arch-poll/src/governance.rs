@@ -0,0 +1,240 @@
+// Governance polls that change the contract's configuration.
+// A parameter-change poll proposes a new value for one ContractConfig limit and offers
+// APPROVE_OPTION and REJECT_OPTION. When it closes with the outcome Passed and approval in the
+// lead, the change is applied as the poll's action (see execution), so the contract's limits can
+// be set by its voters instead of an admin. Anyone may propose a change, so proposals must carry
+// a quorum that needs at least one voter and a majority; the change is checked again when it is
+// applied.
+use crate::address::Address;
+use crate::config::ContractConfig;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, QuorumConfig, Result, VotingContract, VotingError};
+
+// Option voters choose to approve a parameter change
+pub const APPROVE_OPTION: &str = "Yes";
+
+// Option voters choose to reject a parameter change
+pub const REJECT_OPTION: &str = "No";
+
+// Define the configuration values governance polls can change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKey {
+    MaxOptionsPerPoll,
+    MaxTitleLen,
+    MaxDescriptionLen,
+    MaxPollsPerCreator,
+    MaxPollDuration,
+}
+
+impl ConfigKey {
+    // Get the key's current value
+    pub fn get(&self, config: &ContractConfig) -> u64 {
+        match self {
+            ConfigKey::MaxOptionsPerPoll => config.max_options_per_poll as u64,
+            ConfigKey::MaxTitleLen => config.max_title_len as u64,
+            ConfigKey::MaxDescriptionLen => config.max_description_len as u64,
+            ConfigKey::MaxPollsPerCreator => config.max_polls_per_creator as u64,
+            ConfigKey::MaxPollDuration => config.max_poll_duration,
+        }
+    }
+    
+    fn set(&self, config: &mut ContractConfig, value: u64) -> Result<()> {
+        let count = || usize::try_from(value).map_err(|_| VotingError::InvalidParameter);
+        match self {
+            ConfigKey::MaxOptionsPerPoll => config.max_options_per_poll = count()?,
+            ConfigKey::MaxTitleLen => config.max_title_len = count()?,
+            ConfigKey::MaxDescriptionLen => config.max_description_len = count()?,
+            ConfigKey::MaxPollsPerCreator => config.max_polls_per_creator = count()?,
+            ConfigKey::MaxPollDuration => config.max_poll_duration = value,
+        }
+        Ok(())
+    }
+}
+
+// Define a proposed configuration change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterChange {
+    pub key: ConfigKey,
+    pub new_value: u64,
+}
+
+impl ParameterChange {
+    // Check the change could be applied. A zero limit would stop every new poll, and fewer than
+    // two options would stop every ballot.
+    pub fn validate(&self) -> Result<()> {
+        let least_value = if self.key == ConfigKey::MaxOptionsPerPoll { 2 } else { 1 };
+        if self.new_value < least_value {
+            return Err(VotingError::InvalidParameter);
+        }
+        self.key.set(&mut ContractConfig::default(), self.new_value)
+    }
+    
+    // Apply the change, returning the value it replaced
+    pub fn apply(&self, config: &mut ContractConfig) -> Result<u64> {
+        self.validate()?;
+        let previous_value = self.key.get(config);
+        self.key.set(config, self.new_value)?;
+        Ok(previous_value)
+    }
+}
+
+impl VotingContract {
+    // Propose a configuration change, decided by a yes/no poll under the given quorum. The quorum
+    // must need at least one voter and a pass threshold above 50%.
+    pub fn create_parameter_change_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        change: ParameterChange,
+        poll_duration_seconds: u64,
+        quorum_config: QuorumConfig,
+    ) -> Result<PollId> {
+        change.validate()?;
+        quorum_config.validate()?;
+        if quorum_config.min_participants == 0 || quorum_config.pass_threshold_pct <= 50 {
+            return Err(VotingError::InvalidQuorumConfig);
+        }
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            vec![APPROVE_OPTION.to_string(), REJECT_OPTION.to_string()],
+            poll_duration_seconds,
+        )?;
+        new_poll.quorum_config = Some(quorum_config);
+        new_poll.parameter_change = Some(change);
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Apply a passed poll's parameter change if voters approved it
+    pub(crate) fn apply_parameter_change(&mut self, poll_id: &PollId, change: ParameterChange) -> Result<()> {
+        let (leading_options, _) = self.get_poll(poll_id)?.leading_options();
+        if leading_options != [APPROVE_OPTION] {
+            return Err(VotingError::ProposalNotPassed);
+        }
+        
+        let previous_value = change.apply(&mut self.config)?;
+        self.emit(ContractEvent::ParameterChanged {
+            poll_id: poll_id.clone(),
+            key: change.key,
+            previous_value,
+            new_value: change.new_value,
+        });
+        Ok(())
+    }
+}
+
+impl Encode for ConfigKey {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            ConfigKey::MaxOptionsPerPoll => 0,
+            ConfigKey::MaxTitleLen => 1,
+            ConfigKey::MaxDescriptionLen => 2,
+            ConfigKey::MaxPollsPerCreator => 3,
+            ConfigKey::MaxPollDuration => 4,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for ConfigKey {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(ConfigKey::MaxOptionsPerPoll),
+            1 => Ok(ConfigKey::MaxTitleLen),
+            2 => Ok(ConfigKey::MaxDescriptionLen),
+            3 => Ok(ConfigKey::MaxPollsPerCreator),
+            4 => Ok(ConfigKey::MaxPollDuration),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+impl Encode for ParameterChange {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.key);
+        encoder.write(&self.new_value);
+    }
+}
+
+impl Decode for ParameterChange {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(ParameterChange {
+            key: decoder.read()?,
+            new_value: decoder.read()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_parameter_change_polls() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let quorum_config = QuorumConfig { min_participants: 2, pass_threshold_pct: 60, ..QuorumConfig::default() };
+        let propose = |contract: &mut VotingContract, change: ParameterChange, quorum_config: QuorumConfig| {
+            contract.create_parameter_change_poll(
+                "wallet_proposer".parse().unwrap(),
+                format!("Set {:?}", change.key),
+                "Governance proposal".to_string(),
+                change,
+                60,
+                quorum_config,
+            )
+        };
+        let shorter_polls = ParameterChange { key: ConfigKey::MaxPollDuration, new_value: 3_600 };
+        
+        // Proposals need a meaningful value and a quorum that needs a majority of real voters
+        let invalid_change = ParameterChange { key: ConfigKey::MaxOptionsPerPoll, new_value: 1 };
+        let result = propose(&mut contract, invalid_change, quorum_config);
+        assert!(matches!(result, Err(VotingError::InvalidParameter)));
+        let result = propose(&mut contract, shorter_polls, QuorumConfig::default());
+        assert!(matches!(result, Err(VotingError::InvalidQuorumConfig)));
+        
+        // An approved change is applied when the poll closes
+        let approved_poll_id = propose(&mut contract, shorter_polls, quorum_config).unwrap();
+        for voter_address in ["wallet_a", "wallet_b", "wallet_c"] {
+            contract.vote(&approved_poll_id, voter_address.parse().unwrap(), APPROVE_OPTION).unwrap();
+        }
+        let result = contract.attach_action(
+            &approved_poll_id,
+            Box::new(|_: &crate::Poll| Ok(())),
+            &"wallet_proposer".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::InvalidTransition)));
+        clock.advance(60);
+        contract.tick();
+        assert_eq!(contract.config.max_poll_duration, 3_600);
+        assert!(matches!(
+            &contract.events_since(0).iter().rev().nth(1).unwrap().event,
+            ContractEvent::ParameterChanged { previous_value, new_value: 3_600, .. }
+                if *previous_value == crate::DEFAULT_MAX_POLL_DURATION_SECONDS
+        ));
+        
+        // A passed poll where rejection leads changes nothing
+        let titles = ParameterChange { key: ConfigKey::MaxTitleLen, new_value: 20 };
+        let rejected_poll_id = propose(&mut contract, titles, quorum_config).unwrap();
+        for voter_address in ["wallet_a", "wallet_b"] {
+            contract.vote(&rejected_poll_id, voter_address.parse().unwrap(), REJECT_OPTION).unwrap();
+        }
+        contract.close_poll(&rejected_poll_id, &"wallet_proposer".parse().unwrap()).unwrap();
+        assert_eq!(contract.config.max_title_len, crate::config::DEFAULT_MAX_TITLE_LEN);
+        let result = contract.execute_action(&rejected_poll_id);
+        assert!(matches!(result, Err(VotingError::ProposalNotPassed)));
+        
+        // The proposal is saved with its poll
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&rejected_poll_id).unwrap().parameter_change, Some(titles));
+    }
+}
\ No newline at end of file
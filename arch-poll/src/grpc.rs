@@ -0,0 +1,325 @@
+// gRPC server (feature `grpc`).
+// Backend services that already speak gRPC reach the contract through the Voting service in
+// proto/voting.proto: unary calls to create polls, vote, close polls and query them, and a stream
+// of contract events. Unlike the JSON-RPC server, which uses only the standard library, it is built
+// on tonic and tokio. Requests name the acting address and are not authenticated, so the server
+// belongs behind a gateway, as the JSON-RPC server does.
+// Event streams replay the event log from the requested sequence number, then wait for new
+// events: a notifier registered on the contract bumps a watch channel on every event, and each
+// stream reads whatever was logged since its last event.
+use std::net::SocketAddr;
+
+use tokio::sync::{mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Server;
+use tonic::{Code, Request, Response, Status};
+
+use crate::address::Address;
+use crate::encoding;
+use crate::error::ErrorKind;
+use crate::id::PollId;
+use crate::notify::describe_event;
+use crate::receipt::VoteReceipt;
+use crate::reporting::status_label;
+use crate::shared::SharedVotingContract;
+use crate::{ContractEvent, Poll, SequencedEvent, VotingError};
+
+// Messages and service generated from proto/voting.proto
+pub mod proto {
+    tonic::include_proto!("arch_poll.v1");
+}
+
+use proto::voting_server::{Voting, VotingServer};
+
+// Events a stream buffers before waiting for its client to read them
+const EVENT_BUFFER: usize = 64;
+
+// Map a contract error to the canonical gRPC status code for its kind
+pub fn status_code(error: &VotingError) -> Code {
+    match error.kind() {
+        ErrorKind::NotFound => Code::NotFound,
+        ErrorKind::AlreadyExists => Code::AlreadyExists,
+        ErrorKind::PermissionDenied => Code::PermissionDenied,
+        ErrorKind::FailedPrecondition => Code::FailedPrecondition,
+        ErrorKind::ResourceExhausted => Code::ResourceExhausted,
+        ErrorKind::Aborted => Code::Aborted,
+        ErrorKind::OutOfRange => Code::OutOfRange,
+        ErrorKind::Unavailable => Code::Unavailable,
+        ErrorKind::DataLoss => Code::DataLoss,
+        ErrorKind::InvalidArgument => Code::InvalidArgument,
+    }
+}
+
+// Turn a contract error into a status carrying its name and stable code as metadata
+pub fn error_status(error: VotingError) -> Status {
+    let mut status = Status::new(status_code(&error), error.to_string());
+    status.metadata_mut().insert("voting-error", MetadataValue::from_static(error.name()));
+    status.metadata_mut().insert("voting-error-code", MetadataValue::from(error.code()));
+    status
+}
+
+// Render the public details of a poll
+fn poll_message(poll: &Poll, current_timestamp: u64) -> proto::Poll {
+    proto::Poll {
+        poll_id: poll.poll_id.to_string(),
+        poll_title: poll.poll_title.clone(),
+        poll_description: poll.poll_description.clone(),
+        creator_address: poll.poll_creator_address.to_string(),
+        voting_options: poll.voting_options.clone(),
+        status: status_label(poll, current_timestamp),
+        voting_method: poll.voting_method.name().to_string(),
+        poll_start_timestamp: poll.poll_start_timestamp,
+        poll_end_timestamp: poll.poll_end_timestamp,
+        participants: poll.total_votes() as u64,
+    }
+}
+
+fn receipt_message(receipt: VoteReceipt) -> proto::VoteReceipt {
+    proto::VoteReceipt {
+        poll_id: receipt.poll_id.into_string(),
+        voter: receipt.voter.into(),
+        option_hash: receipt.option_hash.to_vec(),
+        sequence: receipt.sequence,
+        receipt_hash: receipt.receipt_hash.to_vec(),
+    }
+}
+
+fn event_message(sequenced_event: &SequencedEvent) -> proto::Event {
+    let event = &sequenced_event.event;
+    proto::Event {
+        sequence: sequenced_event.sequence,
+        name: event.name().to_string(),
+        poll_id: event.poll_id().unwrap_or_default().to_string(),
+        description: describe_event(event),
+        data: encoding::to_bytes(event),
+    }
+}
+
+// Define the gRPC service over a shared contract
+#[derive(Clone)]
+pub struct GrpcServer {
+    contract: SharedVotingContract,
+    events: watch::Receiver<u64>, // Bumped by the contract's notifier on every event
+}
+
+impl GrpcServer {
+    // Create the service, registering the notifier that wakes event streams
+    pub fn new(contract: SharedVotingContract) -> Self {
+        let (event_sender, events) = watch::channel(0);
+        contract.add_notifier(Box::new(move |_: &ContractEvent| {
+            event_sender.send_modify(|emitted_events| *emitted_events += 1);
+        }));
+        GrpcServer { contract, events }
+    }
+    
+    // Get the service for mounting on a tonic server next to other services
+    pub fn into_service(self) -> VotingServer<GrpcServer> {
+        VotingServer::new(self)
+    }
+    
+    // Serve the Voting service on an address until the server fails
+    pub async fn serve(self, address: SocketAddr) -> Result<(), tonic::transport::Error> {
+        Server::builder().add_service(self.into_service()).serve(address).await
+    }
+}
+
+#[tonic::async_trait]
+impl Voting for GrpcServer {
+    type SubscribeEventsStream = ReceiverStream<Result<proto::Event, Status>>;
+    
+    async fn create_poll(
+        &self,
+        request: Request<proto::CreatePollRequest>,
+    ) -> Result<Response<proto::CreatePollResponse>, Status> {
+        let request = request.into_inner();
+        let creator_address: Address = request.creator_address.parse().map_err(error_status)?;
+        let poll_id = self
+            .contract
+            .create_poll(
+                creator_address,
+                request.poll_title,
+                request.poll_description,
+                request.poll_options,
+                request.poll_duration_seconds,
+            )
+            .map_err(error_status)?;
+        Ok(Response::new(proto::CreatePollResponse { poll_id: poll_id.into_string() }))
+    }
+    
+    async fn vote(&self, request: Request<proto::VoteRequest>) -> Result<Response<proto::VoteReceipt>, Status> {
+        let request = request.into_inner();
+        let poll_id: PollId = request.poll_id.parse().map_err(error_status)?;
+        let voter_address: Address = request.voter_address.parse().map_err(error_status)?;
+        let receipt = self
+            .contract
+            .vote(&poll_id, voter_address, &request.selected_option)
+            .map_err(error_status)?;
+        Ok(Response::new(receipt_message(receipt)))
+    }
+    
+    async fn close_poll(
+        &self,
+        request: Request<proto::ClosePollRequest>,
+    ) -> Result<Response<proto::ClosePollResponse>, Status> {
+        let request = request.into_inner();
+        let poll_id: PollId = request.poll_id.parse().map_err(error_status)?;
+        let caller: Address = request.caller.parse().map_err(error_status)?;
+        self.contract.close_poll(&poll_id, &caller).map_err(error_status)?;
+        Ok(Response::new(proto::ClosePollResponse {}))
+    }
+    
+    async fn get_poll(&self, request: Request<proto::GetPollRequest>) -> Result<Response<proto::Poll>, Status> {
+        let poll_id: PollId = request.into_inner().poll_id.parse().map_err(error_status)?;
+        let poll = self
+            .contract
+            .read(|contract| Ok(poll_message(contract.get_poll(&poll_id)?, contract.now())))
+            .map_err(error_status)?;
+        Ok(Response::new(poll))
+    }
+    
+    async fn get_poll_results(
+        &self,
+        request: Request<proto::GetPollResultsRequest>,
+    ) -> Result<Response<proto::PollResults>, Status> {
+        let poll_id: PollId = request.into_inner().poll_id.parse().map_err(error_status)?;
+        let results = self
+            .contract
+            .read(|contract| {
+                let results = contract.get_poll_results(&poll_id)?;
+                let poll = contract.get_poll(&poll_id)?;
+                Ok(poll
+                    .voting_options
+                    .iter()
+                    .filter_map(|option| {
+                        let votes = results.get(option)?;
+                        Some(proto::OptionResult {
+                            option: option.clone(),
+                            votes: votes.to_string(),
+                        })
+                    })
+                    .collect())
+            })
+            .map_err(error_status)?;
+        Ok(Response::new(proto::PollResults { poll_id: poll_id.into_string(), results }))
+    }
+    
+    async fn list_active_polls(
+        &self,
+        _request: Request<proto::ListActivePollsRequest>,
+    ) -> Result<Response<proto::ListActivePollsResponse>, Status> {
+        let polls = self.contract.read(|contract| {
+            let current_timestamp = contract.now();
+            contract.iter_active_polls().map(|poll| poll_message(poll, current_timestamp)).collect()
+        });
+        Ok(Response::new(proto::ListActivePollsResponse { polls }))
+    }
+    
+    async fn subscribe_events(
+        &self,
+        request: Request<proto::SubscribeEventsRequest>,
+    ) -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        let request = request.into_inner();
+        let contract = self.contract.clone();
+        let mut events = self.events.clone();
+        let (sender, receiver) = mpsc::channel(EVENT_BUFFER);
+        
+        tokio::spawn(async move {
+            let mut last_sequence = request.since_sequence;
+            loop {
+                // Mark the events seen before reading the log, so one emitted meanwhile wakes us again
+                events.borrow_and_update();
+                for sequenced_event in contract.events_since(last_sequence) {
+                    last_sequence = sequenced_event.sequence;
+                    let about_poll = sequenced_event.event.poll_id() == Some(request.poll_id.as_str());
+                    if !request.poll_id.is_empty() && !about_poll {
+                        continue;
+                    }
+                    if sender.send(Ok(event_message(&sequenced_event))).await.is_err() {
+                        return;
+                    }
+                }
+                
+                // Stop once the client hangs up or the contract's notifier is gone
+                tokio::select! {
+                    changed = events.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                    }
+                    _ = sender.closed() => return,
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VotingContract;
+    use proto::voting_client::VotingClient;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    
+    #[tokio::test]
+    async fn test_grpc_round_trip() {
+        let contract = SharedVotingContract::new(VotingContract::new("wallet_admin".parse().unwrap()));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let service = GrpcServer::new(contract).into_service();
+        tokio::spawn(Server::builder().add_service(service).serve_with_incoming(TcpListenerStream::new(listener)));
+        let mut client = VotingClient::connect(format!("http://{}", address)).await.unwrap();
+        
+        let poll_id = client
+            .create_poll(proto::CreatePollRequest {
+                creator_address: "wallet_creator".to_string(),
+                poll_title: "Lunch".to_string(),
+                poll_description: String::new(),
+                poll_options: vec!["Pizza".to_string(), "Tacos".to_string()],
+                poll_duration_seconds: 3_600,
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .poll_id;
+        let mut events = client
+            .subscribe_events(proto::SubscribeEventsRequest {
+                since_sequence: 0,
+                poll_id: poll_id.clone(),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(events.message().await.unwrap().unwrap().name, "PollCreated");
+        
+        // A vote returns its receipt and reaches the open stream
+        let vote = proto::VoteRequest {
+            poll_id: poll_id.clone(),
+            voter_address: "wallet_voter".to_string(),
+            selected_option: "Tacos".to_string(),
+        };
+        let receipt = client.vote(vote.clone()).await.unwrap().into_inner();
+        assert_eq!(receipt.sequence, 1);
+        let vote_event = events.message().await.unwrap().unwrap();
+        assert_eq!(vote_event.name, "VoteCast");
+        assert_eq!(encoding::from_bytes::<ContractEvent>(&vote_event.data).unwrap().name(), "VoteCast");
+        
+        // Contract errors map to canonical codes and keep their name
+        let status = client.vote(vote).await.unwrap_err();
+        assert_eq!(status.code(), Code::AlreadyExists);
+        assert_eq!(status.metadata().get("voting-error").unwrap(), "AlreadyVoted");
+        let request = proto::GetPollRequest {
+            poll_id: "missing".to_string(),
+        };
+        assert_eq!(client.get_poll(request).await.unwrap_err().code(), Code::NotFound);
+        
+        let request = proto::GetPollResultsRequest { poll_id: poll_id.clone() };
+        let results = client.get_poll_results(request).await.unwrap().into_inner().results;
+        assert_eq!(results[1].option, "Tacos");
+        assert_eq!(results[1].votes, "1");
+        let polls = client.list_active_polls(proto::ListActivePollsRequest {}).await.unwrap().into_inner().polls;
+        assert_eq!(polls[0].participants, 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,184 @@
+// Tally history.
+// Polls can opt in to recording snapshots of their tallies while voting runs, so dashboards can
+// chart how results evolved. A snapshot is taken when tracking is enabled and then whenever a
+// vote, change or retraction lands at least every_votes ballots or every_seconds seconds after
+// the previous snapshot. Snapshots are only taken when the tallies change, so a quiet poll does
+// not record the time interval passing; the next vote records it.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Define how often a poll records its tallies (0 disables either trigger)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryTracking {
+    pub every_votes: usize, // Record once the voter count moved this far from the last snapshot
+    pub every_seconds: u64, // Record once this long has passed since the last snapshot
+}
+
+// Define the tallies of a poll at one moment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TallySnapshot {
+    pub timestamp: u64,
+    pub total_votes: usize,                 // Voters counted at the time
+    pub vote_counts: HashMap<String, u128>, // Weighted votes per option at the time
+}
+
+impl Poll {
+    // Record a snapshot if tracking is enabled and a trigger is due
+    pub(crate) fn track_tallies(&mut self, current_timestamp: u64) {
+        let Some(tracking) = self.track_history else {
+            return;
+        };
+        let snapshot_due = match self.history.last() {
+            None => true,
+            Some(last_snapshot) => {
+                let votes_since = self.total_votes().abs_diff(last_snapshot.total_votes);
+                (tracking.every_votes > 0 && votes_since >= tracking.every_votes)
+                    || (tracking.every_seconds > 0
+                        && current_timestamp.saturating_sub(last_snapshot.timestamp) >= tracking.every_seconds)
+            }
+        };
+        if snapshot_due {
+            self.history.push(TallySnapshot {
+                timestamp: current_timestamp,
+                total_votes: self.total_votes(),
+                vote_counts: self.option_votes().map(|(option, votes)| (option.clone(), votes)).collect(),
+            });
+        }
+    }
+}
+
+impl VotingContract {
+    // Start, change or stop (None) recording a poll's tally history (creator or ConfigurePolls).
+    // Enabling takes a snapshot straight away. Stopping keeps the snapshots already recorded.
+    pub fn set_history_tracking(
+        &mut self,
+        poll_id: &PollId,
+        tracking: Option<HistoryTracking>,
+        caller: &Address,
+    ) -> Result<()> {
+        if tracking.is_some_and(|tracking| tracking.every_votes == 0 && tracking.every_seconds == 0) {
+            return Err(VotingError::InvalidHistoryTracking);
+        }
+        let current_timestamp = self.now();
+        
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        poll.track_history = tracking;
+        if tracking.is_some() {
+            poll.history.clear();
+            poll.track_tallies(current_timestamp);
+        }
+        
+        self.emit(ContractEvent::HistoryTrackingSet {
+            poll_id: poll_id.clone(),
+            tracking,
+        });
+        Ok(())
+    }
+    
+    // Get a poll's tally snapshots, oldest first (archived polls included)
+    pub fn get_tally_history(&self, poll_id: &PollId) -> Result<&[TallySnapshot]> {
+        Ok(&self.get_poll(poll_id)?.history)
+    }
+    
+    // Record a snapshot of a live poll's tallies after they changed
+    pub(crate) fn track_tallies(&mut self, poll_id: &PollId) {
+        let current_timestamp = self.now();
+        if let Some(poll) = self.active_polls.get_mut(poll_id) {
+            poll.track_tallies(current_timestamp);
+        }
+    }
+}
+
+impl Encode for HistoryTracking {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.every_votes);
+        encoder.write(&self.every_seconds);
+    }
+}
+
+impl Decode for HistoryTracking {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(HistoryTracking {
+            every_votes: decoder.read()?,
+            every_seconds: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for TallySnapshot {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.timestamp);
+        encoder.write(&self.total_votes);
+        encoder.write(&self.vote_counts);
+    }
+}
+
+impl Decode for TallySnapshot {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(TallySnapshot {
+            timestamp: decoder.read()?,
+            total_votes: decoder.read()?,
+            vote_counts: decoder.read()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_tally_history() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = contract
+            .create_poll_with_vote_changes(
+                "wallet_creator".parse().unwrap(),
+                "Trend".to_string(),
+                "Watch the tallies move".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                600,
+            )
+            .unwrap();
+        let tracking = HistoryTracking { every_votes: 2, every_seconds: 60 };
+        contract.set_history_tracking(&poll_id, Some(tracking), &"wallet_creator".parse().unwrap()).unwrap();
+        
+        // Every second ballot triggers a snapshot
+        for (index, voter_address) in ["wallet_a", "wallet_b", "wallet_c"].iter().enumerate() {
+            clock.advance(1);
+            let selected_option = if index == 2 { "No" } else { "Yes" };
+            contract.vote(&poll_id, voter_address.parse().unwrap(), selected_option).unwrap();
+        }
+        let history = contract.get_tally_history(&poll_id).unwrap();
+        assert_eq!(history.iter().map(|snapshot| (snapshot.timestamp, snapshot.total_votes)).collect::<Vec<_>>(), vec![
+            (1_000, 0),
+            (1_002, 2)
+        ]);
+        assert_eq!(history[1].vote_counts["Yes"], 2);
+        
+        // So does the first tally change after the interval, here a vote change
+        clock.advance(60);
+        contract.change_vote(&poll_id, "wallet_a".parse().unwrap(), "No").unwrap();
+        let last_snapshot = contract.get_tally_history(&poll_id).unwrap().last().unwrap().clone();
+        assert_eq!((last_snapshot.timestamp, last_snapshot.vote_counts["No"]), (1_063, 2));
+        
+        // The history is saved with the poll, and tracking needs a trigger
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_tally_history(&poll_id).unwrap().len(), 3);
+        let tracking = HistoryTracking { every_votes: 0, every_seconds: 0 };
+        let result = contract.set_history_tracking(&poll_id, Some(tracking), &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidHistoryTracking)));
+        let result = contract.set_history_tracking(&poll_id, None, &"wallet_random".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,454 @@
+// REST API (feature `http`).
+// Web frontends that would rather not speak JSON-RPC or gRPC reach the contract through plain
+// resources: polls are created, read and closed under /polls, and votes are posted to a poll's
+// votes collection. The routes are served by axum over a SharedVotingContract, and the OpenAPI
+// document describing them is generated from the handlers by utoipa and served at /openapi.json.
+// Request bodies are checked before the contract sees them: a body that is too large, is not
+// JSON, misses a field or carries an unknown one is refused with 400 or 422. Every error, from
+// the request or the contract, comes back as the same body naming the error, its stable code and
+// its message, with the HTTP status given by the error's kind. Like the other servers it does not
+// authenticate the addresses requests name, so it belongs behind a gateway.
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::address::Address;
+use crate::error::ErrorKind;
+use crate::hash::to_hex;
+use crate::id::PollId;
+use crate::receipt::VoteReceipt;
+use crate::reporting::status_label;
+use crate::shared::SharedVotingContract;
+use crate::{Poll, VotingError};
+
+// Limit on the size of a request body
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
+// Define the body of every error response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,     // The contract error's name, or InvalidRequest for a malformed request
+    pub code: Option<u16>, // The contract error's stable code (None for a malformed request)
+    pub message: String,
+}
+
+// Define an error response
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub body: ErrorBody,
+}
+
+// Map an error kind to the HTTP status describing it
+pub fn http_status(kind: ErrorKind) -> StatusCode {
+    match kind {
+        ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        ErrorKind::AlreadyExists | ErrorKind::FailedPrecondition | ErrorKind::Aborted => StatusCode::CONFLICT,
+        ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+        ErrorKind::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        ErrorKind::OutOfRange => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorKind::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorKind::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorKind::InvalidArgument => StatusCode::BAD_REQUEST,
+    }
+}
+
+impl From<VotingError> for ApiError {
+    fn from(error: VotingError) -> Self {
+        ApiError {
+            status: http_status(error.kind()),
+            body: ErrorBody {
+                error: error.name().to_string(),
+                code: Some(error.code()),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError {
+            status: rejection.status(),
+            body: ErrorBody {
+                error: "InvalidRequest".to_string(),
+                code: None,
+                message: rejection.body_text(),
+            },
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+// Define the body of a request creating a poll
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CreatePollRequest {
+    pub creator_address: String,
+    pub poll_title: String,
+    #[serde(default)]
+    pub poll_description: String,
+    pub poll_options: Vec<String>,
+    pub poll_duration_seconds: u64,
+}
+
+// Define the response to a created poll
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatedPoll {
+    pub poll_id: String,
+}
+
+// Define the body of a request casting a vote
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct VoteRequest {
+    pub voter_address: String,
+    pub selected_option: String,
+}
+
+// Define the body of a request closing a poll
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ClosePollRequest {
+    pub caller: String,
+}
+
+// Define the public details of a poll
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PollView {
+    pub poll_id: String,
+    pub poll_title: String,
+    pub poll_description: String,
+    pub creator_address: String,
+    pub voting_options: Vec<String>,
+    pub status: String,
+    pub voting_method: String,
+    pub poll_start_timestamp: u64,
+    pub poll_end_timestamp: u64,
+    pub participants: u64,
+}
+
+impl PollView {
+    fn new(poll: &Poll, current_timestamp: u64) -> Self {
+        PollView {
+            poll_id: poll.poll_id.to_string(),
+            poll_title: poll.poll_title.clone(),
+            poll_description: poll.poll_description.clone(),
+            creator_address: poll.poll_creator_address.to_string(),
+            voting_options: poll.voting_options.clone(),
+            status: status_label(poll, current_timestamp),
+            voting_method: poll.voting_method.name().to_string(),
+            poll_start_timestamp: poll.poll_start_timestamp,
+            poll_end_timestamp: poll.poll_end_timestamp,
+            participants: poll.total_votes() as u64,
+        }
+    }
+}
+
+// Define a vote receipt with its hashes in hex
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReceiptView {
+    pub poll_id: String,
+    pub voter: String,
+    pub option_hash: String,
+    pub sequence: u64,
+    pub receipt_hash: String,
+}
+
+impl From<VoteReceipt> for ReceiptView {
+    fn from(receipt: VoteReceipt) -> Self {
+        ReceiptView {
+            poll_id: receipt.poll_id.into_string(),
+            voter: receipt.voter.into(),
+            option_hash: to_hex(&receipt.option_hash),
+            sequence: receipt.sequence,
+            receipt_hash: to_hex(&receipt.receipt_hash),
+        }
+    }
+}
+
+// Define the votes for one option, as a decimal string since tallies can exceed JSON's integers
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OptionResult {
+    pub option: String,
+    pub votes: String,
+}
+
+// Define a poll's results, in ballot order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PollResults {
+    pub poll_id: String,
+    pub results: Vec<OptionResult>,
+}
+
+// List the polls open for voting
+#[utoipa::path(get, path = "/polls", responses((status = 200, description = "Open polls", body = [PollView])))]
+async fn list_active_polls(State(contract): State<SharedVotingContract>) -> Json<Vec<PollView>> {
+    Json(contract.read(|contract| {
+        let current_timestamp = contract.now();
+        contract.iter_active_polls().map(|poll| PollView::new(poll, current_timestamp)).collect()
+    }))
+}
+
+// Create a poll
+#[utoipa::path(
+    post,
+    path = "/polls",
+    request_body = CreatePollRequest,
+    responses(
+        (status = 201, description = "Poll created", body = CreatedPoll),
+        (status = 400, description = "Invalid poll", body = ErrorBody),
+        (status = 422, description = "Malformed request", body = ErrorBody),
+        (status = 429, description = "Poll limit reached", body = ErrorBody),
+    )
+)]
+async fn create_poll(
+    State(contract): State<SharedVotingContract>,
+    request: Result<Json<CreatePollRequest>, JsonRejection>,
+) -> Result<(StatusCode, Json<CreatedPoll>), ApiError> {
+    let Json(request) = request?;
+    let creator_address: Address = request.creator_address.parse()?;
+    let poll_id = contract.create_poll(
+        creator_address,
+        request.poll_title,
+        request.poll_description,
+        request.poll_options,
+        request.poll_duration_seconds,
+    )?;
+    Ok((StatusCode::CREATED, Json(CreatedPoll { poll_id: poll_id.into_string() })))
+}
+
+// Get a poll's public details
+#[utoipa::path(
+    get,
+    path = "/polls/{poll_id}",
+    params(("poll_id" = String, Path, description = "ID of the poll")),
+    responses(
+        (status = 200, description = "The poll", body = PollView),
+        (status = 400, description = "Malformed poll ID", body = ErrorBody),
+        (status = 404, description = "No such poll", body = ErrorBody),
+    )
+)]
+async fn get_poll(
+    State(contract): State<SharedVotingContract>,
+    Path(poll_id): Path<String>,
+) -> Result<Json<PollView>, ApiError> {
+    let poll_id: PollId = poll_id.parse()?;
+    let poll = contract.read(|contract| contract.get_poll(&poll_id).map(|poll| PollView::new(poll, contract.now())))?;
+    Ok(Json(poll))
+}
+
+// Cast a vote in a poll
+#[utoipa::path(
+    post,
+    path = "/polls/{poll_id}/votes",
+    params(("poll_id" = String, Path, description = "ID of the poll")),
+    request_body = VoteRequest,
+    responses(
+        (status = 201, description = "Vote cast", body = ReceiptView),
+        (status = 400, description = "Invalid vote", body = ErrorBody),
+        (status = 403, description = "Voter not eligible", body = ErrorBody),
+        (status = 404, description = "No such poll", body = ErrorBody),
+        (status = 409, description = "Already voted or poll closed", body = ErrorBody),
+        (status = 422, description = "Malformed request", body = ErrorBody),
+    )
+)]
+async fn vote(
+    State(contract): State<SharedVotingContract>,
+    Path(poll_id): Path<String>,
+    request: Result<Json<VoteRequest>, JsonRejection>,
+) -> Result<(StatusCode, Json<ReceiptView>), ApiError> {
+    let poll_id: PollId = poll_id.parse()?;
+    let Json(request) = request?;
+    let voter_address: Address = request.voter_address.parse()?;
+    let receipt = contract.vote(&poll_id, voter_address, &request.selected_option)?;
+    Ok((StatusCode::CREATED, Json(receipt.into())))
+}
+
+// Get a poll's results
+#[utoipa::path(
+    get,
+    path = "/polls/{poll_id}/results",
+    params(("poll_id" = String, Path, description = "ID of the poll")),
+    responses(
+        (status = 200, description = "The poll's results", body = PollResults),
+        (status = 400, description = "Malformed poll ID", body = ErrorBody),
+        (status = 404, description = "No such poll", body = ErrorBody),
+        (status = 409, description = "Results hidden until the poll ends", body = ErrorBody),
+    )
+)]
+async fn get_poll_results(
+    State(contract): State<SharedVotingContract>,
+    Path(poll_id): Path<String>,
+) -> Result<Json<PollResults>, ApiError> {
+    let poll_id: PollId = poll_id.parse()?;
+    let results = contract.read(|contract| {
+        let results = contract.get_poll_results(&poll_id)?;
+        let poll = contract.get_poll(&poll_id)?;
+        Ok::<_, VotingError>(
+            poll.voting_options
+                .iter()
+                .filter_map(|option| {
+                    let votes = results.get(option)?;
+                    Some(OptionResult {
+                        option: option.clone(),
+                        votes: votes.to_string(),
+                    })
+                })
+                .collect(),
+        )
+    })?;
+    Ok(Json(PollResults { poll_id: poll_id.into_string(), results }))
+}
+
+// Close a poll (creator or ClosePolls)
+#[utoipa::path(
+    post,
+    path = "/polls/{poll_id}/close",
+    params(("poll_id" = String, Path, description = "ID of the poll")),
+    request_body = ClosePollRequest,
+    responses(
+        (status = 204, description = "Poll closed"),
+        (status = 400, description = "Malformed poll ID", body = ErrorBody),
+        (status = 403, description = "Caller may not close the poll", body = ErrorBody),
+        (status = 404, description = "No such poll", body = ErrorBody),
+        (status = 409, description = "Poll already closed", body = ErrorBody),
+        (status = 422, description = "Malformed request", body = ErrorBody),
+    )
+)]
+async fn close_poll(
+    State(contract): State<SharedVotingContract>,
+    Path(poll_id): Path<String>,
+    request: Result<Json<ClosePollRequest>, JsonRejection>,
+) -> Result<StatusCode, ApiError> {
+    let poll_id: PollId = poll_id.parse()?;
+    let Json(request) = request?;
+    let caller: Address = request.caller.parse()?;
+    contract.close_poll(&poll_id, &caller)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Define the OpenAPI document, generated from the handlers above
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "arch-poll", description = "REST API of the arch-poll voting contract"),
+    paths(list_active_polls, create_poll, get_poll, vote, get_poll_results, close_poll),
+    components(schemas(
+        ErrorBody,
+        CreatePollRequest,
+        CreatedPoll,
+        VoteRequest,
+        ClosePollRequest,
+        PollView,
+        ReceiptView,
+        OptionResult,
+        PollResults
+    ))
+)]
+pub struct ApiDoc;
+
+// Get the OpenAPI document describing the routes
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+async fn openapi_document() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi())
+}
+
+// Build the REST routes over a shared contract, for serving alone or nesting in a larger app
+pub fn router(contract: SharedVotingContract) -> Router {
+    Router::new()
+        .route("/polls", get(list_active_polls).post(create_poll))
+        .route("/polls/:poll_id", get(get_poll))
+        .route("/polls/:poll_id/votes", post(vote))
+        .route("/polls/:poll_id/results", get(get_poll_results))
+        .route("/polls/:poll_id/close", post(close_poll))
+        .route("/openapi.json", get(openapi_document))
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .with_state(contract)
+}
+
+// Serve the REST API on a bound listener until the server fails
+pub async fn serve(contract: SharedVotingContract, listener: TcpListener) -> std::io::Result<()> {
+    axum::serve(listener, router(contract)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VotingContract;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+    
+    // Send a request to the router and read back the status and JSON body
+    async fn send(router: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = Request::builder().method(method).uri(uri).header("content-type", "application/json");
+        let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+        let response = router.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), MAX_BODY_BYTES).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+    
+    #[tokio::test]
+    async fn test_rest_round_trip() {
+        let router = router(SharedVotingContract::new(VotingContract::new("wallet_admin".parse().unwrap())));
+        let poll = json!({
+            "creator_address": "wallet_creator",
+            "poll_title": "Lunch",
+            "poll_options": ["Pizza", "Tacos"],
+            "poll_duration_seconds": 3_600,
+        });
+        let (status, created) = send(&router, "POST", "/polls", Some(poll)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let poll_id = created["poll_id"].as_str().unwrap().to_string();
+        
+        // A vote returns its receipt, and a second one the contract's error in the common body
+        let vote = json!({ "voter_address": "wallet_voter", "selected_option": "Tacos" });
+        let votes_uri = format!("/polls/{}/votes", poll_id);
+        let (status, receipt) = send(&router, "POST", &votes_uri, Some(vote.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(receipt["sequence"], 1);
+        let (status, error) = send(&router, "POST", &votes_uri, Some(vote)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(error["error"], "AlreadyVoted");
+        assert_eq!(error["code"], VotingError::AlreadyVoted.code());
+        let (status, error) = send(&router, "GET", "/polls/missing", None).await;
+        assert_eq!((status, error["error"].as_str()), (StatusCode::NOT_FOUND, Some("PollNotFound")));
+        let (status, error) = send(&router, "GET", "/polls/bad%20id", None).await;
+        assert_eq!((status, error["error"].as_str()), (StatusCode::BAD_REQUEST, Some("InvalidParameter")));
+        
+        // Malformed bodies are refused before they reach the contract
+        let unknown_field = json!({ "voter_address": "wallet_other", "selected_option": "Pizza", "weight": 5 });
+        let (status, error) = send(&router, "POST", &votes_uri, Some(unknown_field)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!((error["error"].as_str(), &error["code"]), (Some("InvalidRequest"), &Value::Null));
+        
+        let (_, results) = send(&router, "GET", &format!("/polls/{}/results", poll_id), None).await;
+        assert_eq!(results["results"][1], json!({ "option": "Tacos", "votes": "1" }));
+        let close = json!({ "caller": "wallet_creator" });
+        let (status, _) = send(&router, "POST", &format!("/polls/{}/close", poll_id), Some(close)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, polls) = send(&router, "GET", "/polls", None).await;
+        assert_eq!(polls, json!([]));
+        
+        // The OpenAPI document lists every route
+        let (_, document) = send(&router, "GET", "/openapi.json", None).await;
+        assert!(document["paths"]["/polls/{poll_id}/votes"]["post"].is_object());
+        assert!(document["components"]["schemas"]["ErrorBody"].is_object());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,376 @@
+// Poll ID generation.
+// Every new poll gets its ID from the contract's IdGenerator. The default hashes the creator,
+// title, creation time and the contract's poll counter, so IDs cannot be enumerated by counting
+// and never repeat (the counter alone is unique). Embedders can plug in their own scheme, such
+// as deriving IDs from on-chain seeds the way program-derived addresses are.
+// PollId and OptionId give callers typed handles on a poll and on one of its options, so a poll
+// ID cannot be passed where an address or option text is expected. An OptionId is the option's
+// position in the poll's ballot order, and a poll keeps its tallies by OptionId. vote resolves the
+// option text to its ID once, by exact match, while vote_option takes the position as given, so
+// options that only differ in case are never mixed up.
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::hash::{sha256, to_hex};
+use crate::prelude::*;
+use crate::{Poll, Result, VotingError};
+
+// Domain tag hashed ahead of every generated ID
+pub const POLL_ID_DOMAIN: &[u8] = b"arch-poll:poll-id:v1";
+
+// Define what a new poll's ID may be derived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdSeed<'a> {
+    pub creator_address: &'a str,
+    pub poll_title: &'a str,
+    pub timestamp: u64,   // Creation time according to the contract's clock
+    pub poll_number: u64, // Creation order, unique within the contract and starting at 1
+}
+
+// Source of poll IDs. Generators must be deterministic for a seed if the contract runs on-chain,
+// and must not return an ID already in use or one that does not parse as a PollId; the contract
+// rejects either with PollCreationFailed rather than overwrite a poll.
+pub trait IdGenerator: Send + Sync {
+    fn poll_id(&self, seed: &IdSeed) -> String;
+}
+
+// Hash-based IDs: "poll_" followed by 32 hex digits (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HashedIds;
+
+impl IdGenerator for HashedIds {
+    fn poll_id(&self, seed: &IdSeed) -> String {
+        let mut encoder = Encoder::new();
+        encoder.write_bytes(POLL_ID_DOMAIN);
+        encoder.write(seed.creator_address);
+        encoder.write(seed.poll_title);
+        encoder.write(&seed.timestamp);
+        encoder.write(&seed.poll_number);
+        format!("poll_{}", to_hex(&sha256(&encoder.finish())[..16]))
+    }
+}
+
+// Sequential IDs: poll_1, poll_2 and so on. Predictable, but short and readable.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SequentialIds;
+
+impl IdGenerator for SequentialIds {
+    fn poll_id(&self, seed: &IdSeed) -> String {
+        format!("poll_{}", seed.poll_number)
+    }
+}
+
+// Define the ID of a poll. Parsing rejects empty IDs and IDs holding whitespace or control
+// characters, which no generator produces, with InvalidParameter.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct PollId(String);
+
+impl PollId {
+    // Sorts before every valid ID, for the lower bound of a range over sorted IDs
+    pub(crate) const MIN: PollId = PollId(String::new());
+    
+    // Get the ID as text
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    
+    // Take the ID as a string
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl FromStr for PollId {
+    type Err = VotingError;
+    
+    fn from_str(poll_id: &str) -> Result<Self> {
+        if poll_id.is_empty() || poll_id.chars().any(|character| character.is_whitespace() || character.is_control()) {
+            return Err(VotingError::InvalidParameter);
+        }
+        Ok(PollId(poll_id.to_string()))
+    }
+}
+
+// Debug prints the quoted text, so events and logs naming a poll read as they did with plain IDs
+impl fmt::Debug for PollId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, formatter)
+    }
+}
+
+impl fmt::Display for PollId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for PollId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// Lets maps keyed by PollId be searched with the ID as text
+impl Borrow<str> for PollId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for PollId {
+    type Target = str;
+    
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for PollId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for PollId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl TryFrom<String> for PollId {
+    type Error = VotingError;
+    
+    fn try_from(poll_id: String) -> Result<Self> {
+        poll_id.parse()
+    }
+}
+
+impl From<PollId> for String {
+    fn from(poll_id: PollId) -> String {
+        poll_id.0
+    }
+}
+
+impl Encode for PollId {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.0);
+    }
+}
+
+impl Decode for PollId {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        decoder.read::<String>()?.parse().map_err(|_| VotingError::CorruptState)
+    }
+}
+
+// Define an option of a poll by its position in the poll's ballot order, from 0. Written and
+// parsed as the bare number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct OptionId(pub u32);
+
+impl FromStr for OptionId {
+    type Err = VotingError;
+    
+    fn from_str(option_id: &str) -> Result<Self> {
+        option_id.parse().map(OptionId).map_err(|_| VotingError::InvalidOption)
+    }
+}
+
+impl fmt::Display for OptionId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+impl Encode for OptionId {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.0);
+    }
+}
+
+impl Decode for OptionId {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(OptionId(decoder.read()?))
+    }
+}
+
+impl Poll {
+    // Get the typed ID of the poll
+    pub fn id(&self) -> PollId {
+        self.poll_id.clone()
+    }
+    
+    // Get the ID of one of the poll's options (None if the poll does not offer it)
+    pub fn option_id(&self, voting_option: &str) -> Option<OptionId> {
+        let position = self.voting_options.iter().position(|other_option| other_option == voting_option)?;
+        Some(OptionId(position as u32))
+    }
+    
+    // Get the option an ID refers to
+    pub fn option(&self, option_id: OptionId) -> Result<&str> {
+        self.voting_options
+            .get(option_id.0 as usize)
+            .map(String::as_str)
+            .ok_or(VotingError::InvalidOption)
+    }
+    
+    // Get the weighted votes recorded for an option (0 when the poll does not offer it)
+    pub fn votes_for(&self, voting_option: &str) -> u128 {
+        self.option_id(voting_option)
+            .and_then(|option_id| self.vote_counts.get(&option_id))
+            .copied()
+            .unwrap_or(0)
+    }
+    
+    // Get the number of voters backing an option (0 when the poll does not offer it)
+    pub fn voters_for(&self, voting_option: &str) -> usize {
+        self.option_id(voting_option)
+            .and_then(|option_id| self.voter_counts.get(&option_id))
+            .copied()
+            .unwrap_or(0)
+    }
+    
+    // Iterate over the options and their weighted votes, in ballot order
+    pub fn option_votes(&self) -> impl Iterator<Item = (&String, u128)> + '_ {
+        self.voting_options
+            .iter()
+            .zip(0..)
+            .filter_map(|(voting_option, position)| Some((voting_option, *self.vote_counts.get(&OptionId(position))?)))
+    }
+}
+
+// Key tallies by option ID, for layouts that keyed them by option text. A tally of an option the
+// poll does not offer is CorruptState.
+pub(crate) fn tallies_by_id<T>(voting_options: &[String], tallies: HashMap<String, T>) -> Result<HashMap<OptionId, T>> {
+    tallies
+        .into_iter()
+        .map(|(voting_option, tally)| {
+            let position = voting_options.iter().position(|other_option| *other_option == voting_option);
+            Ok((OptionId(position.ok_or(VotingError::CorruptState)? as u32), tally))
+        })
+        .collect()
+}
+
+// Key tallies by option text, for layouts older than option IDs
+pub(crate) fn tallies_by_text<T: Copy>(
+    voting_options: &[String],
+    tallies: &HashMap<OptionId, T>,
+) -> HashMap<String, T> {
+    voting_options
+        .iter()
+        .zip(0..)
+        .filter_map(|(voting_option, position)| Some((voting_option.clone(), *tallies.get(&OptionId(position))?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::VotingContract;
+    
+    #[test]
+    fn test_poll_id_generators() {
+        let seed = IdSeed {
+            creator_address: "wallet_creator",
+            poll_title: "Lunch",
+            timestamp: 1_000,
+            poll_number: 1,
+        };
+        let poll_id = HashedIds.poll_id(&seed);
+        assert!(poll_id.starts_with("poll_") && poll_id.len() == 37);
+        assert_eq!(poll_id, HashedIds.poll_id(&seed));
+        assert_ne!(poll_id, HashedIds.poll_id(&IdSeed { poll_number: 2, ..seed }));
+        assert_ne!(poll_id, HashedIds.poll_id(&IdSeed { poll_title: "Dinner", ..seed }));
+        assert_eq!(SequentialIds.poll_id(&seed), "poll_1");
+        
+        // The contract asks its generator, and rejects an ID that is already taken
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(MockClock::new(1_000)));
+        let create = |contract: &mut VotingContract| {
+            contract.create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Lunch".to_string(),
+                "Pick one".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+        };
+        assert_eq!(create(&mut contract).unwrap(), poll_id.as_str());
+        contract.set_id_generator(Box::new(SequentialIds));
+        assert_eq!(create(&mut contract).unwrap(), "poll_2");
+        
+        struct FixedIds;
+        impl IdGenerator for FixedIds {
+            fn poll_id(&self, _seed: &IdSeed) -> String {
+                "poll_2".to_string()
+            }
+        }
+        contract.set_id_generator(Box::new(FixedIds));
+        assert!(matches!(create(&mut contract), Err(VotingError::PollCreationFailed)));
+        assert_eq!(contract.active_polls.len(), 2);
+        
+        // Typed IDs parse from and print as their text, and vote_option votes by position
+        let typed_poll_id: PollId = "poll_2".parse().unwrap();
+        assert_eq!(typed_poll_id.to_string(), "poll_2");
+        assert!(matches!("poll 2".parse::<PollId>(), Err(VotingError::InvalidParameter)));
+        let poll = contract.get_poll(&typed_poll_id).unwrap();
+        assert_eq!(poll.id(), typed_poll_id);
+        let option_id = poll.option_id("No").unwrap();
+        assert_eq!((option_id, "1".parse().unwrap()), (OptionId(1), option_id));
+        contract.vote_option(&typed_poll_id, "wallet_a".parse().unwrap(), option_id).unwrap();
+        assert_eq!(contract.get_poll_results(&typed_poll_id).unwrap()["No"], 1);
+        let result = contract.vote_option(&typed_poll_id, "wallet_b".parse().unwrap(), OptionId(2));
+        assert!(matches!(result, Err(VotingError::InvalidOption)));
+    }
+    
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_typed_ids_serde() {
+        // IDs round-trip as their text and number, and a malformed poll ID is refused
+        let poll_id: PollId = serde_json::from_str("\"poll_1\"").unwrap();
+        assert_eq!(serde_json::to_string(&poll_id).unwrap(), "\"poll_1\"");
+        assert!(serde_json::from_str::<PollId>("\"poll 1\"").is_err());
+        assert_eq!(serde_json::to_string(&OptionId(2)).unwrap(), "2");
+        assert_eq!(serde_json::from_str::<OptionId>("2").unwrap(), OptionId(2));
+    }
+    
+    #[test]
+    fn test_options_differing_in_case_tally_apart() {
+        // A poll created before case_insensitive_options was turned on keeps both spellings
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Spelling".to_string(),
+                "Pick one".to_string(),
+                vec!["Yes".to_string(), "yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.case_insensitive_options = true;
+        let typed_poll_id = poll_id.clone();
+        
+        // Votes by ID land on that position, and votes by text only match exactly
+        contract.vote_option(&typed_poll_id, "wallet_a".parse().unwrap(), OptionId(1)).unwrap();
+        contract.vote(&poll_id, "wallet_b".parse().unwrap(), "Yes").unwrap();
+        contract.vote(&poll_id, "wallet_c".parse().unwrap(), "Yes").unwrap();
+        for near_miss in ["YES", " yes"] {
+            let result = contract.vote(&poll_id, "wallet_d".parse().unwrap(), near_miss);
+            assert!(matches!(result, Err(VotingError::InvalidOption)));
+        }
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let poll = restored.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.vote_counts[&OptionId(0)], poll.vote_counts[&OptionId(1)]), (2, 1));
+        assert_eq!((poll.votes_for("Yes"), poll.votes_for("yes"), poll.votes_for("No")), (2, 1, 0));
+        assert_eq!(poll.votes_by_address["wallet_a"], "yes");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,374 @@
+// Secondary indexes over polls.
+// Polls are grouped by tag and by category so DAOs running many polls at once can find related
+// ones without scanning every poll, and by creator and voter so an address's history can be read
+// directly. The indexes are derived from the polls themselves: they are not stored in snapshots
+// and are rebuilt when a contract is restored. Archived polls leave the tag and category indexes
+// but stay in the address indexes, since history includes them.
+//
+// StatusIndex keeps the open polls (published and not yet closed) ordered by end time, so listing
+// the active polls skips those that closed or ran out, and an expiry sweep visits only the polls
+// that are due instead of every live poll.
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::borrow::Borrow;
+
+use crate::address::Address;
+use crate::collections::{HashMap, HashSet};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{Poll, PollStatus};
+
+// Define the tag, category and address indexes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PollIndex {
+    by_tag: BTreeMap<String, HashSet<PollId>>,      // Maps tags to the IDs of polls carrying them
+    by_category: BTreeMap<String, HashSet<PollId>>, // Maps categories to the IDs of polls in them
+    by_creator: BTreeMap<Address, HashSet<PollId>>, // Maps creators to the IDs of their polls
+    by_voter: BTreeMap<Address, HashSet<PollId>>,   // Maps voters to the IDs of polls holding their ballots
+}
+
+impl PollIndex {
+    pub fn new() -> Self {
+        PollIndex::default()
+    }
+    
+    // Build the indexes for a set of live and archived polls
+    pub fn build<'a>(polls: impl IntoIterator<Item = &'a Poll>) -> Self {
+        let mut poll_index = PollIndex::new();
+        for poll in polls {
+            poll_index.insert(poll);
+            if poll.status == PollStatus::Archived {
+                poll_index.remove(poll);
+            }
+        }
+        poll_index
+    }
+    
+    // Add a poll under its tags, category, creator and voters
+    pub fn insert(&mut self, poll: &Poll) {
+        self.set_tags(&poll.poll_id, &[], &poll.tags);
+        self.set_category(&poll.poll_id, None, poll.category.as_deref());
+        self.by_creator.entry(poll.poll_creator_address.clone()).or_default().insert(poll.poll_id.clone());
+        for voter_address in poll.voters() {
+            self.add_voter(&poll.poll_id, voter_address);
+        }
+    }
+    
+    // Remove an archived poll from the entries of its tags and category
+    pub fn remove(&mut self, poll: &Poll) {
+        self.set_tags(&poll.poll_id, &poll.tags, &[]);
+        self.set_category(&poll.poll_id, poll.category.as_deref(), None);
+    }
+    
+    // Remove a deleted poll from every index
+    pub fn delete(&mut self, poll: &Poll) {
+        self.remove(poll);
+        remove_entry(&mut self.by_creator, &poll.poll_creator_address, &poll.poll_id);
+        for voter_address in poll.voters() {
+            self.remove_voter(&poll.poll_id, voter_address);
+        }
+    }
+    
+    // Move a poll from the entries of its previous tags to those of its new ones
+    pub fn set_tags(&mut self, poll_id: &PollId, previous_tags: &[String], tags: &[String]) {
+        for tag in previous_tags {
+            remove_entry(&mut self.by_tag, tag, poll_id);
+        }
+        for tag in tags {
+            self.by_tag.entry(tag.clone()).or_default().insert(poll_id.clone());
+        }
+    }
+    
+    // Move a poll from the entry of its previous category to that of its new one
+    pub fn set_category(&mut self, poll_id: &PollId, previous_category: Option<&str>, category: Option<&str>) {
+        if let Some(previous_category) = previous_category {
+            remove_entry(&mut self.by_category, previous_category, poll_id);
+        }
+        if let Some(category) = category {
+            self.by_category.entry(category.to_string()).or_default().insert(poll_id.clone());
+        }
+    }
+    
+    // Record that a poll holds a voter's ballot
+    pub fn add_voter(&mut self, poll_id: &PollId, voter_address: &Address) {
+        self.by_voter.entry(voter_address.clone()).or_default().insert(poll_id.clone());
+    }
+    
+    // Record that a voter's ballot was withdrawn from a poll
+    pub fn remove_voter(&mut self, poll_id: &PollId, voter_address: &Address) {
+        remove_entry(&mut self.by_voter, voter_address, poll_id);
+    }
+    
+    // Get the IDs of the polls carrying a tag, in no particular order
+    pub fn polls_with_tag(&self, tag: &str) -> impl Iterator<Item = &PollId> {
+        self.by_tag.get(tag).into_iter().flatten()
+    }
+    
+    // Get the IDs of the polls in a category, in no particular order
+    pub fn polls_in_category(&self, category: &str) -> impl Iterator<Item = &PollId> {
+        self.by_category.get(category).into_iter().flatten()
+    }
+    
+    // Get the IDs of the polls an address created, in no particular order
+    pub fn polls_created_by(&self, creator_address: &Address) -> impl Iterator<Item = &PollId> {
+        self.by_creator.get(creator_address).into_iter().flatten()
+    }
+    
+    // Get the IDs of the polls holding an address's ballot, in no particular order
+    pub fn polls_voted_in(&self, voter_address: &Address) -> impl Iterator<Item = &PollId> {
+        self.by_voter.get(voter_address).into_iter().flatten()
+    }
+    
+    // Count the addresses holding a ballot in any poll
+    pub fn voter_count(&self) -> usize {
+        self.by_voter.len()
+    }
+    
+    // Get every category in use, sorted
+    pub fn categories(&self) -> Vec<&str> {
+        self.by_category.keys().map(String::as_str).collect()
+    }
+}
+
+// Remove a poll ID from one entry, dropping the entry once it is empty
+fn remove_entry<K: Borrow<str> + Ord>(index: &mut BTreeMap<K, HashSet<PollId>>, key: &str, poll_id: &PollId) {
+    if let Some(poll_ids) = index.get_mut(key) {
+        poll_ids.remove(poll_id);
+        if poll_ids.is_empty() {
+            index.remove(key);
+        }
+    }
+}
+
+// Define the status index over open polls
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusIndex {
+    end_timestamps: HashMap<PollId, u64>, // Maps open polls to the end timestamps they are filed under
+    by_end: BTreeSet<(u64, PollId)>,      // Open polls ordered by end timestamp, soonest first
+}
+
+impl StatusIndex {
+    pub fn new() -> Self {
+        StatusIndex::default()
+    }
+    
+    // Build the index for a set of live polls
+    pub fn build<'a>(polls: impl IntoIterator<Item = &'a Poll>) -> Self {
+        let mut status_index = StatusIndex::new();
+        for poll in polls {
+            status_index.refresh(poll);
+        }
+        status_index
+    }
+    
+    // File a poll under its current end timestamp if it is open, or drop it if not
+    pub fn refresh(&mut self, poll: &Poll) {
+        self.remove(&poll.poll_id);
+        if matches!(poll.status, PollStatus::Scheduled | PollStatus::Active | PollStatus::Paused) {
+            self.end_timestamps.insert(poll.poll_id.clone(), poll.poll_end_timestamp);
+            self.by_end.insert((poll.poll_end_timestamp, poll.poll_id.clone()));
+        }
+    }
+    
+    // Drop a poll that left the live polls
+    pub fn remove(&mut self, poll_id: &PollId) {
+        if let Some(poll_end_timestamp) = self.end_timestamps.remove(poll_id) {
+            self.by_end.remove(&(poll_end_timestamp, poll_id.clone()));
+        }
+    }
+    
+    // Get the IDs of the open polls ending at or before a timestamp, soonest first
+    pub fn ending_by(&self, timestamp: u64) -> impl Iterator<Item = &PollId> {
+        self.by_end
+            .iter()
+            .take_while(move |(poll_end_timestamp, _)| *poll_end_timestamp <= timestamp)
+            .map(|(_, poll_id)| poll_id)
+    }
+    
+    // Get the IDs of the open polls ending after a timestamp, soonest first
+    pub fn ending_after(&self, timestamp: u64) -> impl Iterator<Item = &PollId> {
+        timestamp
+            .checked_add(1)
+            .into_iter()
+            .flat_map(move |first_timestamp| self.by_end.range((first_timestamp, PollId::MIN)..))
+            .map(|(_, poll_id)| poll_id)
+    }
+    
+    // Count the open polls
+    pub fn len(&self) -> usize {
+        self.end_timestamps.len()
+    }
+    
+    // Check whether no poll is open
+    pub fn is_empty(&self) -> bool {
+        self.end_timestamps.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::MockClock;
+    use crate::id::PollId;
+    use crate::{VotingContract, VotingError};
+    
+    fn create_labelled_poll(
+        contract: &mut VotingContract,
+        poll_title: &str,
+        tags: &[&str],
+        category: Option<&str>,
+    ) -> PollId {
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                poll_title.to_string(),
+                "Grouped poll".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let tags = tags.iter().map(|tag| tag.to_string()).collect();
+        contract.set_poll_tags(&poll_id, tags, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.set_poll_category(&poll_id, category.map(str::to_string), &"wallet_creator".parse().unwrap()).unwrap();
+        poll_id
+    }
+    
+    #[test]
+    fn test_tag_and_category_queries() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let treasury_poll_id = create_labelled_poll(&mut contract, "Budget", &["treasury", " q3 "], Some("finance"));
+        let grant_poll_id = create_labelled_poll(&mut contract, "Grant", &["treasury", "treasury"], Some("grants"));
+        let other_poll_id = create_labelled_poll(&mut contract, "Logo", &["design"], None);
+        
+        let poll_ids = |polls: Vec<&crate::Poll>| polls.iter().map(|poll| poll.poll_id.clone()).collect::<Vec<_>>();
+        assert_eq!(poll_ids(contract.list_polls_by_tag("treasury")), vec![treasury_poll_id.clone(), grant_poll_id.clone()]);
+        assert_eq!(contract.get_poll(&treasury_poll_id).unwrap().tags, vec!["treasury", "q3"]);
+        assert_eq!(contract.get_poll(&grant_poll_id).unwrap().tags, vec!["treasury"]);
+        assert_eq!(contract.list_categories(), vec!["finance", "grants"]);
+        
+        // Relabelling moves a poll between entries, and emptied entries disappear
+        contract.set_poll_tags(&grant_poll_id, vec!["design".to_string()], &"wallet_creator".parse().unwrap()).unwrap();
+        contract.set_poll_category(&grant_poll_id, None, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(poll_ids(contract.list_polls_by_tag("treasury")), vec![treasury_poll_id.clone()]);
+        assert_eq!(poll_ids(contract.list_polls_by_tag("design")), vec![grant_poll_id.clone(), other_poll_id]);
+        assert_eq!(contract.list_categories(), vec!["finance"]);
+        
+        // Snapshots rebuild the indexes, and archived polls leave them
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(poll_ids(restored.list_polls_by_category("finance")), vec![treasury_poll_id.clone()]);
+        restored.close_poll(&treasury_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        restored.archive_poll(&treasury_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert!(restored.list_polls_by_tag("treasury").is_empty());
+        assert!(restored.list_categories().is_empty());
+        
+        let result = contract.set_poll_tags(&grant_poll_id, vec!["  ".to_string()], &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidTag)));
+        let result = contract.set_poll_category(
+            &grant_poll_id,
+            Some("finance".to_string()),
+            &"wallet_random".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+    }
+    
+    #[test]
+    fn test_address_history() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let first_poll_id = create_labelled_poll(&mut contract, "Budget", &[], None);
+        let ranked_poll_id = contract
+            .create_ranked_poll(
+                "wallet_other".parse().unwrap(),
+                "Logo".to_string(),
+                "Rank them".to_string(),
+                vec!["Red".to_string(), "Blue".to_string()],
+                60,
+            )
+            .unwrap();
+        let changeable_poll_id = contract
+            .create_poll_with_vote_changes(
+                "wallet_creator".parse().unwrap(),
+                "Venue".to_string(),
+                "Changeable".to_string(),
+                vec!["Here".to_string(), "There".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.vote(&first_poll_id, "wallet_voter".parse().unwrap(), "No").unwrap();
+        let ranking = vec!["Blue".to_string(), "Red".to_string()];
+        contract.vote_ranked(&ranked_poll_id, " wallet_voter ".parse().unwrap(), ranking).unwrap();
+        contract.vote(&changeable_poll_id, "wallet_voter".parse().unwrap(), "Here").unwrap();
+        
+        let creator_poll_ids = |contract: &VotingContract| {
+            contract
+                .get_polls_by_creator(&"wallet_creator".parse().unwrap())
+                .iter()
+                .map(|poll| poll.poll_id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(creator_poll_ids(&contract), vec![first_poll_id.clone(), changeable_poll_id.clone()]);
+        let history = contract.get_votes_by_address(&"wallet_voter".parse().unwrap());
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].poll_id, first_poll_id);
+        assert_eq!(history[0].selected_options, vec!["No"]);
+        assert_eq!(history[1].selected_options, vec!["Blue", "Red"]);
+        
+        // Retracted ballots leave the history, and archived polls stay in it after a restore
+        contract.retract_vote(&changeable_poll_id, "wallet_voter".parse().unwrap()).unwrap();
+        contract.close_poll(&first_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.archive_poll(&first_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let history = restored.get_votes_by_address(&"wallet_voter".parse().unwrap());
+        let history_poll_ids: Vec<PollId> = history.iter().map(|record| record.poll_id.clone()).collect();
+        assert_eq!(history_poll_ids, vec![first_poll_id, ranked_poll_id]);
+        assert_eq!(creator_poll_ids(&restored).len(), 2);
+        assert!(restored.get_votes_by_address(&"wallet_nobody".parse().unwrap()).is_empty());
+    }
+    
+    #[test]
+    fn test_status_index() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let create_poll = |contract: &mut VotingContract, poll_title: &str, poll_duration_seconds: u64| {
+            contract
+                .create_poll(
+                    "wallet_creator".parse().unwrap(),
+                    poll_title.to_string(),
+                    String::new(),
+                    vec!["Yes".to_string(), "No".to_string()],
+                    poll_duration_seconds,
+                )
+                .unwrap()
+        };
+        let short_poll_id = create_poll(&mut contract, "Short", 60);
+        let long_poll_id = create_poll(&mut contract, "Long", 600);
+        let draft_poll_id = contract
+            .create_draft_poll(
+                "wallet_creator".parse().unwrap(),
+                "Draft".to_string(),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                30,
+            )
+            .unwrap();
+        
+        // Active polls come soonest-ending first, and drafts are not open yet
+        let poll_ids = |polls: Vec<&crate::Poll>| polls.iter().map(|poll| poll.poll_id.clone()).collect::<Vec<_>>();
+        assert_eq!(poll_ids(contract.get_active_polls()), vec![short_poll_id.clone(), long_poll_id.clone()]);
+        assert_eq!(poll_ids(contract.get_polls_ending_within(60)), vec![short_poll_id.clone()]);
+        
+        // Publishing and extending refile a poll under its new end time
+        contract.publish_poll(&draft_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.extend_poll(&short_poll_id, 3_600, &"wallet_creator".parse().unwrap()).unwrap();
+        let active_poll_ids = poll_ids(contract.get_active_polls());
+        assert_eq!(active_poll_ids, vec![draft_poll_id, long_poll_id, short_poll_id.clone()]);
+        
+        // The sweep closes only the due polls, and a restore rebuilds the index
+        clock.advance(600);
+        assert_eq!(contract.process_expired_polls(&"wallet_admin".parse().unwrap()).unwrap().len(), 2);
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        restored.set_clock(Box::new(clock.clone()));
+        assert_eq!(poll_ids(restored.get_active_polls()), vec![short_poll_id.clone()]);
+        restored.close_poll(&short_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert!(restored.get_active_polls().is_empty());
+        assert!(restored.process_expired_polls(&"wallet_admin".parse().unwrap()).unwrap().is_empty());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,305 @@
+// Instruction ledger.
+// Snapshots capture the state at one moment; the ledger captures how it got there. Every
+// instruction the contract executes successfully (see execute) becomes a LedgerEntry holding its
+// sequence number, the time it ran at, the acting address and the instruction itself, and is
+// handed to each registered LedgerSink before execute returns, so the log is written ahead of any
+// snapshot taken afterwards. Failed instructions change nothing and are not recorded.
+// replay applies entries to a contract in order, running each at its recorded time, so a
+// contract restored from its last snapshot and replayed with the entries after it reaches the
+// state it crashed in, and the log doubles as a reproducible audit trail. Polls given IDs by
+// HashedIds or SequentialIds get the same IDs on replay. MemoryLedger keeps entries in memory and
+// FileLedger appends them to a file (with std). Calls made outside execute are not recorded, so a
+// contract recovered from its ledger must be driven only through instructions.
+use core::mem;
+
+use crate::address::Address;
+use crate::clock::MockClock;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::prelude::*;
+use crate::program::VotingInstruction;
+use crate::{Result, VotingContract, VotingError};
+
+// Define one executed instruction
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub sequence: u64,   // Position in the ledger, starting at 1
+    pub timestamp: u64,  // Contract time the instruction ran at
+    pub caller: Address, // Address the instruction acted for
+    pub instruction: VotingInstruction,
+}
+
+impl Encode for LedgerEntry {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.sequence);
+        encoder.write(&self.timestamp);
+        encoder.write(&self.caller);
+        encoder.write(&self.instruction);
+    }
+}
+
+impl Decode for LedgerEntry {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(LedgerEntry {
+            sequence: decoder.read()?,
+            timestamp: decoder.read()?,
+            caller: decoder.read()?,
+            instruction: decoder.read()?,
+        })
+    }
+}
+
+// Append-only store of ledger entries. Sinks are called while execute is still in progress, and
+// an error makes execute return LedgerWriteFailed even though the instruction took effect.
+pub trait LedgerSink: Send + Sync {
+    fn append(&self, entry: &LedgerEntry) -> Result<()>;
+}
+
+impl VotingContract {
+    // Register a sink that records every instruction executed from now on
+    pub fn add_ledger_sink(&mut self, ledger_sink: Box<dyn LedgerSink>) {
+        self.ledger_sinks.push(ledger_sink);
+    }
+    
+    // Remove every registered ledger sink
+    pub fn clear_ledger_sinks(&mut self) {
+        self.ledger_sinks.clear();
+    }
+    
+    // Get the sequence number of the last instruction executed (0 before the first)
+    pub fn ledger_sequence(&self) -> u64 {
+        self.ledger_sequence
+    }
+    
+    // Apply one instruction for a caller and record it in the ledger. Initialize only sets up an
+    // empty state account and is refused with InvalidInstruction.
+    pub fn execute(&mut self, caller: &Address, instruction: VotingInstruction) -> Result<()> {
+        let timestamp = self.now();
+        match &instruction {
+            VotingInstruction::Initialize => return Err(VotingError::InvalidInstruction),
+            VotingInstruction::CreatePoll {
+                poll_title,
+                poll_description,
+                poll_options,
+                poll_duration_seconds,
+            } => {
+                self.create_poll(
+                    caller.clone(),
+                    poll_title.clone(),
+                    poll_description.clone(),
+                    poll_options.clone(),
+                    *poll_duration_seconds,
+                )?;
+            }
+            VotingInstruction::Vote { poll_id, selected_option } => {
+                self.vote(poll_id, caller.clone(), selected_option)?;
+            }
+            VotingInstruction::ClosePoll { poll_id } => {
+                self.close_poll(poll_id, caller)?;
+            }
+            VotingInstruction::ProcessExpiredPolls => {
+                self.process_expired_polls(caller)?;
+            }
+            VotingInstruction::Tick => {
+                self.tick();
+            }
+        }
+        
+        self.ledger_sequence += 1;
+        let entry = LedgerEntry {
+            sequence: self.ledger_sequence,
+            timestamp,
+            caller: caller.clone(),
+            instruction,
+        };
+        for ledger_sink in &self.ledger_sinks {
+            ledger_sink.append(&entry).map_err(|_| VotingError::LedgerWriteFailed)?;
+        }
+        Ok(())
+    }
+    
+    // Apply ledger entries in order, each at its recorded time, returning how many were applied.
+    // Entries the contract has already executed are skipped, so a contract restored from a
+    // snapshot can be given the whole ledger. A gap in the sequence is CorruptState, and an entry
+    // that fails returns its error with the entries before it applied. Replayed entries are not
+    // passed to the ledger sinks again.
+    pub fn replay(&mut self, entries: &[LedgerEntry]) -> Result<usize> {
+        let replay_clock = MockClock::new(0);
+        let host_clock = mem::replace(&mut self.clock, Box::new(replay_clock.clone()));
+        let ledger_sinks = mem::take(&mut self.ledger_sinks);
+        let result = self.replay_entries(entries, &replay_clock);
+        self.clock = host_clock;
+        self.ledger_sinks = ledger_sinks;
+        result
+    }
+    
+    fn replay_entries(&mut self, entries: &[LedgerEntry], replay_clock: &MockClock) -> Result<usize> {
+        let mut replayed_entries = 0;
+        for entry in entries {
+            if entry.sequence <= self.ledger_sequence {
+                continue;
+            }
+            if entry.sequence != self.ledger_sequence + 1 {
+                return Err(VotingError::CorruptState);
+            }
+            replay_clock.set(entry.timestamp);
+            self.execute(&entry.caller, entry.instruction.clone())?;
+            replayed_entries += 1;
+        }
+        Ok(replayed_entries)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::sinks::{FileLedger, MemoryLedger};
+
+#[cfg(feature = "std")]
+mod sinks {
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Read, Write};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex, PoisonError};
+    
+    use super::{LedgerEntry, LedgerSink};
+    use crate::encoding;
+    use crate::prelude::*;
+    use crate::{Result, VotingError};
+    
+    // Define a sink keeping entries in memory. Clones share the same entries, so a host can keep
+    // a handle while the contract owns another.
+    #[derive(Debug, Clone, Default)]
+    pub struct MemoryLedger {
+        entries: Arc<Mutex<Vec<LedgerEntry>>>,
+    }
+    
+    impl MemoryLedger {
+        pub fn new() -> Self {
+            MemoryLedger::default()
+        }
+        
+        // Get a copy of the entries recorded so far, oldest first
+        pub fn entries(&self) -> Vec<LedgerEntry> {
+            self.entries.lock().unwrap_or_else(PoisonError::into_inner).clone()
+        }
+    }
+    
+    impl LedgerSink for MemoryLedger {
+        fn append(&self, entry: &LedgerEntry) -> Result<()> {
+            self.entries.lock().unwrap_or_else(PoisonError::into_inner).push(entry.clone());
+            Ok(())
+        }
+    }
+    
+    // Define a sink appending entries to a file, each as a u32 length and the encoded entry.
+    // Every entry is synced to disk before execute returns.
+    pub struct FileLedger {
+        file: Mutex<File>,
+    }
+    
+    impl FileLedger {
+        // Open a ledger file for appending, creating it if it does not exist
+        pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            Ok(FileLedger { file: Mutex::new(file) })
+        }
+        
+        // Read every entry in a ledger file, oldest first. A crash while appending can leave
+        // the last entry incomplete, and it is dropped; an entry that does not decode is
+        // InvalidData.
+        pub fn read_entries(path: impl AsRef<Path>) -> io::Result<Vec<LedgerEntry>> {
+            let mut bytes = Vec::new();
+            File::open(path)?.read_to_end(&mut bytes)?;
+            
+            let mut entries = Vec::new();
+            let mut remaining_bytes = bytes.as_slice();
+            while remaining_bytes.len() >= 4 {
+                let (length_bytes, rest) = remaining_bytes.split_at(4);
+                let entry_length = u32::from_le_bytes(length_bytes.try_into().unwrap()) as usize;
+                if rest.len() < entry_length {
+                    break;
+                }
+                let (entry_bytes, rest) = rest.split_at(entry_length);
+                let entry = encoding::from_bytes(entry_bytes)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+                entries.push(entry);
+                remaining_bytes = rest;
+            }
+            Ok(entries)
+        }
+    }
+    
+    impl LedgerSink for FileLedger {
+        fn append(&self, entry: &LedgerEntry) -> Result<()> {
+            let entry_bytes = encoding::to_bytes(entry);
+            let mut record = (entry_bytes.len() as u32).to_le_bytes().to_vec();
+            record.extend_from_slice(&entry_bytes);
+            
+            let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+            file.write_all(&record)
+                .and_then(|_| file.sync_data())
+                .map_err(|_| VotingError::LedgerWriteFailed)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    
+    #[test]
+    fn test_ledger_replay() {
+        let clock = MockClock::new(1_000);
+        let ledger = MemoryLedger::new();
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(clock.clone()));
+        contract.add_ledger_sink(Box::new(ledger.clone()));
+        
+        let create = VotingInstruction::CreatePoll {
+            poll_title: "Lunch".to_string(),
+            poll_description: String::new(),
+            poll_options: vec!["Pizza".to_string(), "Tacos".to_string()],
+            poll_duration_seconds: 60,
+        };
+        contract.execute(&"wallet_creator".parse().unwrap(), create).unwrap();
+        let poll_id = contract.iter_active_polls().next().unwrap().poll_id.clone();
+        clock.advance(10);
+        let vote = VotingInstruction::Vote {
+            poll_id: poll_id.clone(),
+            selected_option: "Tacos".to_string(),
+        };
+        contract.execute(&"wallet_voter".parse().unwrap(), vote.clone()).unwrap();
+        let snapshot = contract.to_snapshot();
+        
+        // Failed instructions are not recorded
+        assert!(matches!(contract.execute(&"wallet_voter".parse().unwrap(), vote), Err(VotingError::AlreadyVoted)));
+        clock.advance(60);
+        contract.execute(&"wallet_admin".parse().unwrap(), VotingInstruction::ProcessExpiredPolls).unwrap();
+        let entries = ledger.entries();
+        assert_eq!(entries.iter().map(|entry| entry.sequence).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(entries[1].timestamp, 1_010);
+        
+        // Replaying the whole ledger from scratch rebuilds the state, whatever the replaying clock says
+        let mut replayed = VotingContract::new("wallet_admin".parse().unwrap());
+        replayed.set_clock(Box::new(MockClock::new(5_000_000)));
+        assert_eq!(replayed.replay(&entries).unwrap(), 3);
+        assert_eq!(replayed.to_snapshot(), contract.to_snapshot());
+        assert_eq!(replayed.now(), 5_000_000);
+        
+        // A restored snapshot skips the entries it already holds, and a gap is refused
+        let mut restored = VotingContract::from_snapshot(&snapshot).unwrap();
+        assert!(matches!(restored.replay(&entries[2..]), Ok(1)));
+        assert_eq!(restored.get_poll(&poll_id).unwrap().status, contract.get_poll(&poll_id).unwrap().status);
+        let mut restored = VotingContract::new("wallet_admin".parse().unwrap());
+        assert!(matches!(restored.replay(&entries[1..]), Err(VotingError::CorruptState)));
+        
+        // A file ledger reads back what it wrote, dropping a torn final entry
+        let path = std::env::temp_dir().join(format!("arch-poll-ledger-{}.log", std::process::id()));
+        let file_ledger = FileLedger::open(&path).unwrap();
+        for entry in &entries {
+            file_ledger.append(entry).unwrap();
+        }
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(&[9, 0, 0, 0, 1]).unwrap();
+        assert_eq!(FileLedger::read_entries(&path).unwrap(), entries);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
\ No newline at end of file
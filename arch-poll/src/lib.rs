@@ -1,433 +1,7015 @@
 // This is a voting contract written in Rust.
 // This enables users to create polls, vote on options, and view results transparently.
 // The contract is tested with unit tests to ensure its functionality and reliability.
-use std::collections::{HashMap, HashSet};
-use std::time::{SystemTime, UNIX_EPOCH};
+// Without the default `std` feature the crate is no_std + alloc, so it builds for on-chain targets.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-// Define the error types for our contract
-#[derive(Debug)]
-pub enum VotingError {
-    PollNotFound,
-    PollClosed,
-    AlreadyVoted,
-    NotAuthorized,
-    InvalidOption,
-    PollCreationFailed,
-    InvalidTimeSettings,
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+
+pub mod access_control;
+pub mod account;
+pub mod address;
+pub mod admin;
+pub mod anonymous;
+#[cfg(feature = "audit")]
+pub mod audit;
+pub mod balance;
+pub mod batch;
+pub mod builder;
+pub mod clock;
+pub mod collections;
+pub mod compaction;
+pub mod config;
+pub mod conviction;
+pub mod creators;
+pub mod delegation;
+pub mod dependency;
+pub mod discussion;
+pub mod editing;
+pub mod election;
+pub mod encryption;
+pub mod encoding;
+pub mod error;
+pub mod execution;
+pub mod finalization;
+pub mod governance;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod hash;
+pub mod history;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod id;
+pub mod index;
+pub mod ledger;
+pub mod merkle;
+pub mod metadata;
+pub mod migrations;
+pub mod moderation;
+pub mod notify;
+pub mod participants;
+pub mod pause;
+pub mod program;
+pub mod rate_limit;
+pub mod rationale;
+pub mod receipt;
+pub mod reopen;
+pub mod reporting;
+pub mod rewards;
+pub mod runoff;
+#[cfg(feature = "server")]
+pub mod rpc;
+#[cfg(feature = "async")]
+pub mod service;
+#[cfg(feature = "std")]
+pub mod shared;
+#[cfg(feature = "signing")]
+pub mod signing;
+pub mod state;
+pub mod stats;
+pub mod tabulation;
+pub mod treasury;
+pub mod visibility;
+pub mod voting_power;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use access_control::{AccessControl, Permission, Role};
+use address::{Address, AddressFormat, AnyAddress};
+use admin::AdminTransfer;
+use anonymous::{AnonymousState, ProofVerifier};
+use balance::BalanceProvider;
+use clock::{default_clock, Clock};
+use collections::{HashMap, HashSet};
+use config::ContractConfig;
+use conviction::ConvictionState;
+use compaction::CompactedPoll;
+use creators::CreatorRecord;
+use discussion::Comment;
+use delegation::DelegationRegistry;
+use dependency::PollDependency;
+use election::Election;
+use encryption::{BallotEncryption, EncryptedBallot};
+use encoding::{Decode, Decoder, Encode, Encoder};
+use ledger::LedgerSink;
+use history::{HistoryTracking, TallySnapshot};
+pub use error::{ContextualError, ErrorKind, VotingError};
+use execution::ExecutionHook;
+use finalization::FinalizationRecord;
+use governance::{ConfigKey, ParameterChange};
+use id::{HashedIds, IdGenerator, IdSeed, OptionId, PollId};
+use index::{PollIndex, StatusIndex};
+use moderation::{ModerationAction, ModerationRecord, PollFlag};
+use notify::Notifier;
+use pause::ContractPause;
+use participants::ParticipantSet;
+use prelude::*;
+use rate_limit::{RateLimitedAction, RateLimiter};
+use rationale::VoteRationale;
+use receipt::VoteReceipt;
+use rewards::RewardPool;
+use runoff::RunoffConfig;
+use state::ContractState;
+use tabulation::TabulationMethod;
+use treasury::{PaymentProvider, Treasury};
+use visibility::ResultsVisibility;
+use voting_power::SnapshotProvider;
+
+// Allocating types and macros the std prelude provides, imported by modules that must also build
+// without std
+pub(crate) mod prelude {
+    pub use alloc::boxed::Box;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec::Vec;
+    pub use alloc::{format, vec};
 }
 
 // Define the result type for our contract functions
-pub type Result<T> = std::result::Result<T, VotingError>;
+pub type Result<T> = core::result::Result<T, VotingError>;
+
+// Default upper bound on how long a poll may run (one year)
+pub const DEFAULT_MAX_POLL_DURATION_SECONDS: u64 = 60 * 60 * 24 * 365;
+
+// Default number of times a poll's deadline may be extended
+pub const DEFAULT_MAX_POLL_EXTENSIONS: u32 = 3;
+
+// Reserved options added to polls created with allow_abstain / allow_nota. They are voted for like
+// any option and count toward turnout, but never win.
+pub const ABSTAIN_OPTION: &str = "Abstain";
+pub const NOTA_OPTION: &str = "None of the above";
+
+// Append the enabled reserved options to a poll's regular options, which need at least two
+// entries and may not use a reserved name themselves
+fn with_reserved_options(mut poll_options: Vec<String>, allow_abstain: bool, allow_nota: bool) -> Result<Vec<String>> {
+    let reserved_options = [(allow_abstain, ABSTAIN_OPTION), (allow_nota, NOTA_OPTION)];
+    if poll_options.len() < 2
+        || reserved_options
+            .iter()
+            .any(|&(enabled, reserved_option)| enabled && poll_options.iter().any(|option| option == reserved_option))
+    {
+        return Err(VotingError::PollCreationFailed);
+    }
+    
+    for (enabled, reserved_option) in reserved_options {
+        if enabled {
+            poll_options.push(reserved_option.to_string());
+        }
+    }
+    Ok(poll_options)
+}
+
+// Trim a poll's options, rejecting blank ones and ones that repeat an earlier option (ignoring
+// case when case_insensitive is set). Repeated options would share one tally.
+pub fn normalize_options(poll_options: Vec<String>, case_insensitive: bool) -> Result<Vec<String>> {
+    let mut normalized_options: Vec<String> = Vec::with_capacity(poll_options.len());
+    for option in poll_options {
+        let option = option.trim();
+        if option.is_empty() {
+            return Err(VotingError::EmptyOption);
+        }
+        let is_duplicate = normalized_options.iter().any(|existing_option| {
+            existing_option == option || (case_insensitive && existing_option.to_lowercase() == option.to_lowercase())
+        });
+        if is_duplicate {
+            return Err(VotingError::DuplicateOption);
+        }
+        normalized_options.push(option.to_string());
+    }
+    Ok(normalized_options)
+}
+
+// Compute the commitment a voter submits before revealing their choice.
+// The option is length-prefixed before the salt is appended, so one commitment can never be
+// revealed as two different options (e.g. "Yes" with salt "!x" and "Yes!" with salt "x").
+pub fn vote_commitment(selected_option: &str, salt: &[u8]) -> [u8; 32] {
+    let mut encoder = Encoder::new();
+    encoder.write(selected_option);
+    encoder.write_bytes(salt);
+    hash::sha256(&encoder.finish())
+}
+
+// Trim a tag or category, rejecting empty ones
+fn normalize_label(label: &str) -> Result<String> {
+    let trimmed_label = label.trim();
+    if trimmed_label.is_empty() {
+        return Err(VotingError::InvalidTag);
+    }
+    
+    Ok(trimmed_label.to_string())
+}
+
+// Trim tags and drop repeats, rejecting empty ones
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>> {
+    let mut normalized_tags: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = normalize_label(tag)?;
+        if !normalized_tags.contains(&tag) {
+            normalized_tags.push(tag);
+        }
+    }
+    Ok(normalized_tags)
+}
+
+// Move a newly built poll's window later, scheduling it if the delay is non-zero. The end stays
+// after the start because the duration is non-zero.
+fn delay_start(new_poll: &mut Poll, start_delay_seconds: u64) -> Result<()> {
+    new_poll.poll_start_timestamp = new_poll
+        .poll_start_timestamp
+        .checked_add(start_delay_seconds)
+        .ok_or(VotingError::InvalidTimeSettings)?;
+    new_poll.poll_end_timestamp = new_poll
+        .poll_end_timestamp
+        .checked_add(start_delay_seconds)
+        .ok_or(VotingError::InvalidTimeSettings)?;
+    if start_delay_seconds > 0 {
+        new_poll.status = PollStatus::Scheduled;
+    }
+    Ok(())
+}
+
+// Define the lifecycle states of a poll
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollStatus {
+    Draft,     // Editable and not open for voting until published
+    Scheduled, // Published with a future start time, not yet marked started
+    Active,    // Accepting votes within its time window
+    Paused,    // Temporarily not accepting votes
+    Closed,    // Closed manually, by an expiry sweep or lazily with auto_expire
+    Archived,  // Closed and moved out of the live map
+}
+
+impl PollStatus {
+    // Check whether the lifecycle allows moving from this status to another
+    pub fn can_transition_to(&self, next_status: PollStatus) -> bool {
+        matches!(
+            (self, next_status),
+            (PollStatus::Draft, PollStatus::Active)
+                | (PollStatus::Scheduled, PollStatus::Active)
+                | (PollStatus::Active, PollStatus::Paused)
+                | (PollStatus::Paused, PollStatus::Active)
+                | (PollStatus::Scheduled | PollStatus::Active | PollStatus::Paused, PollStatus::Closed)
+                | (PollStatus::Closed, PollStatus::Archived)
+        )
+    }
+}
+
+impl Encode for PollStatus {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            PollStatus::Draft => 0,
+            PollStatus::Scheduled => 1,
+            PollStatus::Active => 2,
+            PollStatus::Paused => 3,
+            PollStatus::Closed => 4,
+            PollStatus::Archived => 5,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for PollStatus {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(PollStatus::Draft),
+            1 => Ok(PollStatus::Scheduled),
+            2 => Ok(PollStatus::Active),
+            3 => Ok(PollStatus::Paused),
+            4 => Ok(PollStatus::Closed),
+            5 => Ok(PollStatus::Archived),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+// Define how ballots are cast and tabulated in a poll
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VotingMethod {
+    #[default]
+    SingleChoice,                     // One option per voter
+    RankedChoice,                     // Ordered preferences tabulated by the poll's TabulationMethod
+    Quadratic { credit_budget: u64 }, // N votes on one option cost N² of each voter's credits
+    Approval { max_selections: usize }, // Each voter approves up to max_selections options
+    Conviction,                       // Staked weight builds conviction over time (see conviction.rs)
+}
+
+impl VotingMethod {
+    // Get the name of the voting method as shown in reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            VotingMethod::SingleChoice => "SingleChoice",
+            VotingMethod::RankedChoice => "RankedChoice",
+            VotingMethod::Quadratic { .. } => "Quadratic",
+            VotingMethod::Approval { .. } => "Approval",
+            VotingMethod::Conviction => "Conviction",
+        }
+    }
+}
+
+impl Encode for VotingMethod {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            VotingMethod::SingleChoice => encoder.write(&0u8),
+            VotingMethod::RankedChoice => encoder.write(&1u8),
+            VotingMethod::Quadratic { credit_budget } => {
+                encoder.write(&2u8);
+                encoder.write(credit_budget);
+            }
+            VotingMethod::Approval { max_selections } => {
+                encoder.write(&3u8);
+                encoder.write(max_selections);
+            }
+            VotingMethod::Conviction => encoder.write(&4u8),
+        }
+    }
+}
+
+impl Decode for VotingMethod {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(VotingMethod::SingleChoice),
+            1 => Ok(VotingMethod::RankedChoice),
+            2 => Ok(VotingMethod::Quadratic { credit_budget: decoder.read()? }),
+            3 => Ok(VotingMethod::Approval { max_selections: decoder.read()? }),
+            4 => Ok(VotingMethod::Conviction),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+// Define voting power a delegate cast on behalf of a delegator in one poll
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelegatedVote {
+    pub delegate_address: Address, // Voter whose ballot carried this power
+    pub selected_option: String,
+    pub weight: u128,
+}
+
+impl Encode for DelegatedVote {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.delegate_address);
+        encoder.write(&self.selected_option);
+        encoder.write(&self.weight);
+    }
+}
+
+impl Decode for DelegatedVote {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(DelegatedVote {
+            delegate_address: decoder.read()?,
+            selected_option: decoder.read()?,
+            weight: decoder.read()?,
+        })
+    }
+}
+
+// Define the participation and support a governance poll needs to pass.
+// Percentages are whole numbers from 0 to 100; zero values disable the corresponding check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QuorumConfig {
+    pub min_participants: usize, // Voters needed for the poll to count
+    pub min_turnout_pct: u8,     // Share of eligible_voters that must vote
+    pub pass_threshold_pct: u8,  // Share of the counted votes the leading option needs to pass
+    pub eligible_voters: usize,  // Size of the electorate turnout is measured against
+}
+
+impl QuorumConfig {
+    // Check the percentages are in range and turnout has an electorate to measure against
+    fn validate(&self) -> Result<()> {
+        if self.min_turnout_pct > 100
+            || self.pass_threshold_pct > 100
+            || (self.min_turnout_pct > 0 && self.eligible_voters == 0)
+        {
+            return Err(VotingError::InvalidQuorumConfig);
+        }
+        Ok(())
+    }
+}
+
+impl Encode for QuorumConfig {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.min_participants);
+        encoder.write(&self.min_turnout_pct);
+        encoder.write(&self.pass_threshold_pct);
+        encoder.write(&self.eligible_voters);
+    }
+}
+
+impl Decode for QuorumConfig {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(QuorumConfig {
+            min_participants: decoder.read()?,
+            min_turnout_pct: decoder.read()?,
+            pass_threshold_pct: decoder.read()?,
+            eligible_voters: decoder.read()?,
+        })
+    }
+}
+
+// Define the result of a closed poll measured against its quorum configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollOutcome {
+    Passed,       // Quorum met and a single leading option reached the pass threshold
+    Rejected,     // Quorum met but no option passed (including ties and polls without votes)
+    QuorumNotMet, // Too few voters took part
+}
+
+// Define the outcome of a poll's winner calculation
+#[derive(Debug, Clone, PartialEq)]
+pub enum WinnerOutcome {
+    Winner { option: String, votes: u128 },
+    Tie { options: Vec<String>, votes: u128 },
+    NoVotes,
+}
+
+// Cell holding a poll's cached winner, filled by the first read after the results change and
+// emptied when they change again. With std it is a OnceLock so a shared contract stays Sync;
+// no_std builds have no threads to share with and use a OnceCell.
+#[cfg(feature = "std")]
+pub type WinnerCache = std::sync::OnceLock<WinnerOutcome>;
+#[cfg(not(feature = "std"))]
+pub type WinnerCache = core::cell::OnceCell<WinnerOutcome>;
+
+// Define how a tie for the lead is resolved when a winner is determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    #[default]
+    RerunRequired,  // No winner; the tied options must be voted on again
+    EarliestOption, // The tied option listed first on the ballot wins
+    Seeded(u64),    // A tied option is picked pseudo-randomly from the seed and poll ID
+}
+
+impl Encode for TieBreak {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            TieBreak::RerunRequired => encoder.write(&0u8),
+            TieBreak::EarliestOption => encoder.write(&1u8),
+            TieBreak::Seeded(seed) => {
+                encoder.write(&2u8);
+                encoder.write(seed);
+            }
+        }
+    }
+}
+
+impl Decode for TieBreak {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(TieBreak::RerunRequired),
+            1 => Ok(TieBreak::EarliestOption),
+            2 => Ok(TieBreak::Seeded(decoder.read()?)),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+// Define the winner of a poll with the tie-break applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollWinner {
+    pub winning_option: Option<String>, // None without votes, or on a tie that requires a rerun
+    pub votes: u128,                    // Votes for the leading option(s)
+    pub margin: u128,                   // Lead over the best other option (0 on a tie)
+    pub percentage_bps: u16,            // Leading option's share of the counted votes in basis points
+    pub tied_options: Vec<String>,      // Options that shared the lead, in ballot order (empty without a tie)
+}
+
+// Get a part's share of a total in basis points (0 when the total is 0)
+pub(crate) fn share_bps(part: u128, total: u128) -> u16 {
+    if total == 0 {
+        return 0;
+    }
+    
+    // The total exceeds 10,000 whenever the multiplication overflows
+    part.checked_mul(10_000)
+        .map(|scaled_part| scaled_part / total)
+        .unwrap_or_else(|| part / (total / 10_000)) as u16
+}
+
+// Compare poll titles ignoring surrounding whitespace and case
+fn titles_match(first_title: &str, second_title: &str) -> bool {
+    first_title.trim().to_lowercase() == second_title.trim().to_lowercase()
+}
 
 // Define the Poll structure
 #[derive(Debug, Clone)]
 pub struct Poll {
-    pub poll_id: String,
+    pub poll_id: PollId,
+    pub poll_number: u64,                   // Creation order within the contract, starting at 1
     pub poll_title: String,
     pub poll_description: String,
     pub voting_options: Vec<String>,
-    pub vote_counts: HashMap<String, usize>, // Maps options to vote counts
-    pub participant_addresses: HashSet<String>, // Set of wallet addresses that have voted
-    pub poll_creator_address: String,       // Wallet address of creator
+    pub vote_counts: HashMap<OptionId, u128>, // Maps option IDs to weighted vote counts; keys are fixed at creation and never added by voting
+    pub voter_counts: HashMap<OptionId, usize>, // Maps option IDs to the number of voters backing them
+    pub participant_addresses: ParticipantSet, // Addresses that have voted, or their hashes (see participants.rs)
+    pub poll_creator_address: Address,      // Wallet address of creator
     pub poll_start_timestamp: u64,          // Unix timestamp
     pub poll_end_timestamp: u64,            // Unix timestamp
-    pub poll_is_closed: bool,               // Whether the poll is closed
+    pub status: PollStatus,                 // Lifecycle state, changed only through valid transitions
+    pub quadratic_allocations: HashMap<Address, HashMap<String, usize>>, // Votes each quadratic voter placed per option
+    pub min_winning_votes: u128,            // Votes the leading option needs to win (0 disables)
+    pub creator_can_vote: bool,             // Whether the creator may vote in their own poll
+    pub cached_winner: WinnerCache,         // Last computed winner, empty once the results change
+    pub tags: Vec<String>,                  // Labels used to group related polls
+    pub category: Option<String>,           // Single grouping the poll is filed under
+    pub extension_count: u32,               // Times the deadline has been extended
+    pub receipt_chain: Vec<[u8; 32]>,       // Hash of every receipt issued by vote(), oldest first
+    pub ballot_root: Option<[u8; 32]>,      // Merkle root over the ballots, fixed when the poll closes
+    pub track_history: Option<HistoryTracking>, // How often tallies are snapshotted (None disables)
+    pub history: Vec<TallySnapshot>,        // Tally snapshots recorded while voting ran, oldest first
+    pub action_executed_at: Option<u64>,    // When the poll's attached action ran (see execute_action)
+    pub parameter_change: Option<ParameterChange>, // Configuration change the poll decides, if any
+    pub min_balance_to_vote: u128,          // Token balance voters must hold (0 disables)
+    pub weight_snapshot: Option<u64>,       // Snapshot voting weights are read from (None uses registered weights)
+    pub flags: Vec<PollFlag>,               // Reports awaiting a moderator, oldest first
+    pub hidden: bool,                       // Left out of the public listings by a moderator
+    pub finalization: Option<FinalizationRecord>, // Certified result, after which the poll cannot change
+    pub metadata: BTreeMap<String, String>, // Structured details such as links and IPFS CIDs (see metadata.rs)
+    pub vote_fee: u128,                     // Fee charged per cast or committed ballot (0 disables, see treasury.rs)
+    pub runoff: Option<RunoffConfig>,       // When closing opens a second round (see runoff.rs)
+    pub previous_round: Option<PollId>,     // Round this runoff was opened from
+    pub next_round: Option<PollId>,         // Runoff opened when this round closed
+    pub previous_poll_id: Option<PollId>,   // Poll this one re-runs (see reopen.rs)
+    pub tabulation_method: TabulationMethod, // How ranked ballots are counted (see tabulation.rs)
+    pub conviction: Option<ConvictionState>, // Stakes and conviction of a conviction poll
+    pub depends_on: Vec<PollDependency>,    // Polls that must first end with a required outcome (see dependency.rs)
+    pub dependencies_met: bool,             // Whether the poll may open (always true without dependencies)
+    pub anonymous: Option<AnonymousState>,  // Commitments and nullifiers of an anonymous poll (see anonymous.rs)
+    pub encryption: Option<BallotEncryption>, // Key and decryption progress of an encrypted poll (see encryption.rs)
+    pub ciphertexts: Vec<EncryptedBallot>,  // Encrypted ballots in casting order, counted once decrypted
+    pub encrypted_voters: HashSet<Address>, // Voters holding an encrypted ballot, derived from ciphertexts
+    pub results_visibility: ResultsVisibility, // Who sees the results before the poll ends (see visibility.rs)
+    pub rationales: Vec<VoteRationale>,     // Rationales voters attached to their ballots, oldest first (see rationale.rs)
+    pub voting_method: VotingMethod,        // How ballots are cast and tabulated
+    pub ranked_ballots: HashMap<Address, Vec<String>>, // Maps ranked-choice voters to their preference order
+    pub weighted_voters: HashMap<Address, u128>, // Weight applied to single-choice voters whose weight was not 1
+    pub delegated_votes: HashMap<Address, DelegatedVote>, // Maps delegators who did not vote to the ballot that carried them
+    pub commit_end_timestamp: Option<u64>,  // End of the commit window in commit-reveal polls
+    pub vote_commitments: HashMap<Address, [u8; 32]>, // Commitments not yet revealed, by voter
+    pub quorum_config: Option<QuorumConfig>, // Participation and support needed to pass
+    pub allowlist: Option<HashSet<Address>>, // Only these addresses may vote (None admits everyone)
+    pub denylist: HashSet<Address>,         // Addresses that may not vote
+    pub votes_by_address: HashMap<Address, String>, // Option chosen by each single-choice voter
+    pub allow_vote_changes: bool,           // Whether single-choice votes can be changed or retracted
+    pub tie_break: TieBreak,                // How winner() resolves a tie for the lead
+    pub approval_ballots: HashMap<Address, Vec<String>>, // Options each approval voter approved, in ballot order
+    pub allow_abstain: bool,                // Whether ABSTAIN_OPTION is on the ballot
+    pub allow_nota: bool,                   // Whether NOTA_OPTION is on the ballot
 }
 
 impl Poll {
-    // Check if the poll is currently active
-    pub fn is_active(&self) -> bool {
-        if self.poll_is_closed {
-            return false;
+    // Check if the poll is accepting votes at the given time. Scheduled polls open at their start
+    // time even before process_scheduled_polls marks them started, unless they are held for their
+    // dependencies.
+    pub fn is_active(&self, current_timestamp: u64) -> bool {
+        matches!(self.status, PollStatus::Scheduled | PollStatus::Active)
+            && self.dependencies_met
+            && current_timestamp >= self.poll_start_timestamp
+            && current_timestamp < self.poll_end_timestamp
+    }
+    
+    // Check whether the poll is published but waiting for its start time or its dependencies
+    pub fn is_scheduled(&self, current_timestamp: u64) -> bool {
+        self.status == PollStatus::Scheduled && (current_timestamp < self.poll_start_timestamp || !self.dependencies_met)
+    }
+    
+    // Check whether the poll has been closed (including archived polls)
+    pub fn is_closed(&self) -> bool {
+        matches!(self.status, PollStatus::Closed | PollStatus::Archived)
+    }
+    
+    // Check whether voting is over: the poll is closed or past its end time. Drafts and polls held
+    // for their dependencies have not started their window yet, so they never count as ended.
+    pub fn has_ended(&self, current_timestamp: u64) -> bool {
+        match self.status {
+            PollStatus::Draft => false,
+            PollStatus::Scheduled if !self.dependencies_met => false,
+            PollStatus::Closed | PollStatus::Archived => true,
+            PollStatus::Scheduled | PollStatus::Active | PollStatus::Paused => {
+                current_timestamp >= self.poll_end_timestamp
+            }
+        }
+    }
+    
+    // Check whether a commit-reveal poll is accepting commitments
+    pub fn in_commit_phase(&self, current_timestamp: u64) -> bool {
+        self.commit_end_timestamp.is_some_and(|commit_end_timestamp| {
+            self.is_active(current_timestamp) && current_timestamp < commit_end_timestamp
+        })
+    }
+    
+    // Check whether a commit-reveal poll is accepting reveals
+    pub fn in_reveal_phase(&self, current_timestamp: u64) -> bool {
+        self.commit_end_timestamp.is_some_and(|commit_end_timestamp| {
+            self.is_active(current_timestamp) && current_timestamp >= commit_end_timestamp
+        })
+    }
+    
+    // Check whether an address may vote: on the allowlist (if there is one) and not on the denylist
+    pub fn is_eligible(&self, address: &Address) -> bool {
+        self.allowlist.as_ref().is_none_or(|allowlist| allowlist.contains(address)) && !self.denylist.contains(address)
+    }
+    
+    // Check whether any ballot or commitment has been recorded
+    pub fn voting_started(&self) -> bool {
+        !self.participant_addresses.is_empty()
+            || self.anonymous_ballots() > 0
+            || !self.ciphertexts.is_empty()
+            || !self.vote_commitments.is_empty()
+            || self.conviction.as_ref().is_some_and(|state| !state.stakes.is_empty())
+    }
+    
+    // Get the number of commitments that have not been revealed (and are not counted)
+    pub fn unrevealed_commitments(&self) -> usize {
+        self.vote_commitments.len()
+    }
+    
+    // Get the seconds left until the poll ends, or 0 once it has ended or been closed.
+    // Drafts report their full duration.
+    pub fn time_remaining(&self, current_timestamp: u64) -> u64 {
+        match self.status {
+            PollStatus::Draft => self.poll_end_timestamp - self.poll_start_timestamp,
+            PollStatus::Closed | PollStatus::Archived => 0,
+            PollStatus::Scheduled | PollStatus::Active | PollStatus::Paused => {
+                self.poll_end_timestamp.saturating_sub(current_timestamp)
+            }
+        }
+    }
+    
+    // Get the current results of the poll.
+    // Ranked-choice polls report the results of their tabulation method (the final instant-runoff
+    // round by default, see tabulation.rs), approval polls the approvals per option and conviction
+    // polls the conviction of each option as of its last update. Reserved options are left out
+    // (see reserved_results), so they never lead or win.
+    pub fn get_results(&self) -> HashMap<String, u128> {
+        match self.voting_method {
+            VotingMethod::SingleChoice | VotingMethod::Quadratic { .. } | VotingMethod::Approval { .. } => self
+                .option_votes()
+                .filter(|(option, _)| !self.is_reserved_option(option))
+                .map(|(option, votes)| (option.clone(), votes))
+                .collect(),
+            VotingMethod::RankedChoice => self.ranked_results(),
+            VotingMethod::Conviction => self
+                .conviction
+                .as_ref()
+                .map(|state| state.convictions.clone())
+                .unwrap_or_default(),
         }
+    }
+    
+    // Iterate over the current results in ballot order, borrowing the option names. Yields what
+    // get_results holds without copying the tallies, except that ranked-choice polls tabulate
+    // their ballots first.
+    pub fn results_iter(&self) -> impl Iterator<Item = (&str, u128)> + '_ {
+        let tabulated_results = (self.voting_method == VotingMethod::RankedChoice).then(|| self.ranked_results());
+        self.voting_options.iter().zip(0..).filter_map(move |(option, position)| {
+            let votes = match (&tabulated_results, self.voting_method) {
+                (Some(tabulated_results), _) => tabulated_results.get(option),
+                (None, VotingMethod::Conviction) => self.conviction.as_ref()?.convictions.get(option),
+                _ if self.is_reserved_option(option) => None,
+                _ => self.vote_counts.get(&OptionId(position)),
+            };
+            votes.map(|&votes| (option.as_str(), votes))
+        })
+    }
+    
+    // Get the current results ordered from most to fewest votes, ties in ballot order
+    pub fn results_sorted(&self) -> Vec<(&str, u128)> {
+        let mut sorted_results: Vec<(&str, u128)> = self.results_iter().collect();
+        sorted_results.sort_by(|(_, votes), (_, other_votes)| other_votes.cmp(votes));
+        sorted_results
+    }
+    
+    // Check whether an option is a reserved abstain or none-of-the-above option of this poll
+    pub fn is_reserved_option(&self, option: &str) -> bool {
+        (self.allow_abstain && option == ABSTAIN_OPTION) || (self.allow_nota && option == NOTA_OPTION)
+    }
+    
+    // Get the votes cast for the poll's reserved options (empty when neither is enabled)
+    pub fn reserved_results(&self) -> HashMap<String, u128> {
+        self.option_votes()
+            .filter(|(option, _)| self.is_reserved_option(option))
+            .map(|(option, votes)| (option.clone(), votes))
+            .collect()
+    }
+    
+    // Tabulate ranked ballots by instant runoff, returning the tallies of every round.
+    // Each round counts every ballot for its highest-ranked option still in the running; the
+    // option with the fewest votes is eliminated until one option holds a strict majority of the
+    // ballots still counted. Eliminated options report 0. Ties for last place eliminate the option
+    // listed latest on the ballot, and a round where every remaining option is tied is final.
+    pub fn instant_runoff_rounds(&self) -> Vec<HashMap<String, u128>> {
+        let mut remaining_options = self.voting_options.clone();
+        let mut rounds = Vec::new();
         
-        let current_timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        loop {
+            let mut round_tallies: HashMap<String, u128> =
+                self.voting_options.iter().map(|option| (option.clone(), 0)).collect();
+            for ranking in self.ranked_ballots.values() {
+                if let Some(preferred_option) = ranking.iter().find(|option| remaining_options.contains(option)) {
+                    *round_tallies.entry(preferred_option.clone()).or_insert(0) += 1;
+                }
+            }
+            
+            let counted_ballots: u128 = round_tallies.values().sum();
+            let most_votes = remaining_options.iter().map(|option| round_tallies[option]).max().unwrap_or(0);
+            let fewest_votes = remaining_options.iter().map(|option| round_tallies[option]).min().unwrap_or(0);
+            rounds.push(round_tallies.clone());
+            
+            if remaining_options.len() <= 1 || most_votes * 2 > counted_ballots || most_votes == fewest_votes {
+                return rounds;
+            }
+            
+            if let Some(eliminated_index) = remaining_options
+                .iter()
+                .rposition(|option| round_tallies[option] == fewest_votes)
+            {
+                remaining_options.remove(eliminated_index);
+            }
+        }
+    }
+    
+    // Get the option(s) with the most votes in ballot order, and their vote count
+    // (no options lead while nothing has been voted on)
+    pub fn leading_options(&self) -> (Vec<String>, u128) {
+        let results = self.get_results();
+        let leading_votes = results.values().copied().max().unwrap_or(0);
+        if leading_votes == 0 {
+            return (Vec::new(), 0);
+        }
+        
+        let leading_options = self
+            .voting_options
+            .iter()
+            .filter(|option| results.get(*option) == Some(&leading_votes))
+            .cloned()
+            .collect();
+        (leading_options, leading_votes)
+    }
+    
+    // Check whether the poll met its participation quorum (always true without a quorum config)
+    pub fn quorum_met(&self) -> bool {
+        let quorum_config = self.quorum_config.unwrap_or_default();
+        let participants = self.total_votes();
+        
+        // participants / eligible_voters >= min_turnout_pct / 100, without dividing
+        let turnout_met = participants as u128 * 100
+            >= quorum_config.eligible_voters as u128 * quorum_config.min_turnout_pct as u128;
+        participants >= quorum_config.min_participants && turnout_met
+    }
+    
+    // Get the outcome of the poll once it is no longer active (None while voting is open, or its
+    // encrypted ballots are not yet decrypted). A poll passes when its quorum is met and a single leading option holds at least
+    // pass_threshold_pct of the counted votes. None-of-the-above votes are counted against
+    // every option here; abstentions are not counted.
+    pub fn outcome(&self, current_timestamp: u64) -> Option<PollOutcome> {
+        if !self.has_ended(current_timestamp) || self.decryption_pending() {
+            return None;
+        }
+        
+        if !self.quorum_met() {
+            return Some(PollOutcome::QuorumNotMet);
+        }
+        
+        let (leading_options, leading_votes) = self.leading_options();
+        if leading_options.len() != 1 {
+            return Some(PollOutcome::Rejected);
+        }
+        
+        let pass_threshold_pct = self.quorum_config.unwrap_or_default().pass_threshold_pct as u128;
+        let nota_votes = if self.allow_nota { self.votes_for(NOTA_OPTION) } else { 0 };
+        let counted_votes = self.get_results().values().fold(nota_votes, |total, &votes| total.saturating_add(votes));
+        if leading_votes.saturating_mul(100) >= counted_votes.saturating_mul(pass_threshold_pct) {
+            Some(PollOutcome::Passed)
+        } else {
+            Some(PollOutcome::Rejected)
+        }
+    }
+    
+    // Build a summary of the poll's current state for display
+    pub fn summary(&self, current_timestamp: u64) -> PollSummary {
+        let (leading_options, leading_votes) = self.leading_options();
+        PollSummary {
+            poll_id: self.poll_id.clone(),
+            total_votes: self.total_votes(),
+            is_tie: leading_options.len() > 1,
+            leading_options,
+            leading_votes,
+            quorum_met: self.quorum_met(),
+            is_active: self.is_active(current_timestamp),
+            status: self.status,
+            unrevealed_commitments: self.unrevealed_commitments(),
+        }
+    }
+    
+    // Determine the leading option(s), enforcing the minimum winning vote threshold
+    pub fn get_winner(&self) -> Result<WinnerOutcome> {
+        let (leading_options, leading_votes) = self.leading_options();
+        if leading_votes == 0 {
+            return Ok(WinnerOutcome::NoVotes);
+        }
+        
+        if leading_votes < self.min_winning_votes {
+            return Err(VotingError::ThresholdNotMet);
+        }
+        
+        if leading_options.len() == 1 {
+            Ok(WinnerOutcome::Winner {
+                option: leading_options[0].clone(),
+                votes: leading_votes,
+            })
+        } else {
+            Ok(WinnerOutcome::Tie {
+                options: leading_options,
+                votes: leading_votes,
+            })
+        }
+    }
+    
+    // Determine the winner with its margin and share of the vote, resolving ties with the poll's
+    // tie-break strategy and enforcing the minimum winning vote threshold
+    pub fn winner(&self) -> Result<PollWinner> {
+        let results = self.get_results();
+        let (leading_options, leading_votes) = self.leading_options();
+        if leading_votes == 0 {
+            return Ok(PollWinner {
+                winning_option: None,
+                votes: 0,
+                margin: 0,
+                percentage_bps: 0,
+                tied_options: Vec::new(),
+            });
+        }
+        
+        if leading_votes < self.min_winning_votes {
+            return Err(VotingError::ThresholdNotMet);
+        }
+        
+        let runner_up_votes = results
+            .iter()
+            .filter(|(option, _)| !leading_options.contains(option))
+            .map(|(_, &votes)| votes)
+            .max()
+            .unwrap_or(0);
+        let counted_votes = results.values().fold(0u128, |total, &votes| total.saturating_add(votes));
+        let percentage_bps = share_bps(leading_votes, counted_votes);
+        
+        if leading_options.len() == 1 {
+            return Ok(PollWinner {
+                winning_option: leading_options.into_iter().next(),
+                votes: leading_votes,
+                margin: leading_votes - runner_up_votes,
+                percentage_bps,
+                tied_options: Vec::new(),
+            });
+        }
+        
+        let winning_option = match self.tie_break {
+            TieBreak::RerunRequired => None,
+            TieBreak::EarliestOption => leading_options.first().cloned(),
+            TieBreak::Seeded(seed) => {
+                let mut seed_bytes = seed.to_le_bytes().to_vec();
+                seed_bytes.extend_from_slice(self.poll_id.as_bytes());
+                let digest = hash::sha256(&seed_bytes);
+                let mut draw = [0u8; 8];
+                draw.copy_from_slice(&digest[..8]);
+                let index = (u64::from_le_bytes(draw) % leading_options.len() as u64) as usize;
+                Some(leading_options[index].clone())
+            }
+        };
+        Ok(PollWinner {
+            winning_option,
+            votes: leading_votes,
+            margin: 0,
+            percentage_bps,
+            tied_options: leading_options,
+        })
+    }
+    
+    // Get the winner, reusing the cached outcome while the results have not changed. Otherwise it
+    // is worked out and cached until they do; a poll without a winner (e.g. below
+    // min_winning_votes) is left uncached.
+    pub fn get_cached_winner(&self) -> Result<WinnerOutcome> {
+        if let Some(cached_winner) = self.cached_winner.get() {
+            return Ok(cached_winner.clone());
+        }
+        let winner = self.get_winner()?;
+        // A concurrent read may have filled the cell first, with the same winner
+        let _ = self.cached_winner.set(winner.clone());
+        Ok(winner)
+    }
+    
+    // Mark cached aggregates as stale
+    pub fn invalidate_results(&mut self) {
+        self.cached_winner.take();
+    }
+    
+    // Get the summed weight of all votes, failing instead of wrapping on overflow
+    pub fn total_weight(&self) -> Result<u128> {
+        self.vote_counts
+            .values()
+            .try_fold(0u128, |total, &weight| total.checked_add(weight))
+            .ok_or(VotingError::VoteCountOverflow)
+    }
+    
+    // Get the approvals per option and the number of ballots cast, or WrongVotingMethod outside
+    // approval polls
+    pub fn approval_results(&self) -> Result<ApprovalResults> {
+        if !matches!(self.voting_method, VotingMethod::Approval { .. }) {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        Ok(ApprovalResults {
+            approvals: self.get_results(),
+            ballots: self.approval_ballots.len(),
+        })
+    }
+    
+    // Get the weighted tally and number of backing voters for every option
+    pub fn get_weighted_results(&self) -> HashMap<String, OptionTally> {
+        self.voting_options
+            .iter()
+            .map(|option| {
+                let option_tally = OptionTally {
+                    weight: self.votes_for(option),
+                    voters: self.voters_for(option),
+                };
+                (option.clone(), option_tally)
+            })
+            .collect()
+    }
+    
+    // Add weight to an option's tally, counting a new backing voter when requested.
+    // Nothing is changed unless both counters can be updated.
+    fn add_to_tally(&mut self, option_id: OptionId, weight: u128, new_voter: bool) -> Result<()> {
+        let current_weight = *self.vote_counts.get(&option_id).ok_or(VotingError::InvalidOption)?;
+        let current_voters = self.voter_counts.get(&option_id).copied().unwrap_or(0);
+        let new_weight = current_weight.checked_add(weight).ok_or(VotingError::VoteCountOverflow)?;
+        let new_voters = current_voters
+            .checked_add(new_voter as usize)
+            .ok_or(VotingError::VoteCountOverflow)?;
             
-        current_timestamp >= self.poll_start_timestamp && current_timestamp < self.poll_end_timestamp
+        self.vote_counts.insert(option_id, new_weight);
+        self.voter_counts.insert(option_id, new_voters);
+        self.invalidate_results();
+        Ok(())
+    }
+    
+    // Take weight and one backing voter off an option's tally
+    fn remove_from_tally(&mut self, option_id: OptionId, weight: u128) -> Result<()> {
+        let current_weight = *self.vote_counts.get(&option_id).ok_or(VotingError::InvalidOption)?;
+        let current_voters = self.voter_counts.get(&option_id).copied().unwrap_or(0);
+        let new_weight = current_weight.checked_sub(weight).ok_or(VotingError::CorruptState)?;
+        let new_voters = current_voters.checked_sub(1).ok_or(VotingError::CorruptState)?;
+        
+        self.vote_counts.insert(option_id, new_weight);
+        self.voter_counts.insert(option_id, new_voters);
+        self.invalidate_results();
+        Ok(())
+    }
+    
+    // Get the option a voter chose, reporting ranked and quadratic ballots as the wrong method
+    fn single_choice_vote(&self, voter_address: &Address) -> Result<String> {
+        match self.votes_by_address.get(voter_address) {
+            Some(selected_option) => Ok(selected_option.clone()),
+            None if self.participant_addresses.contains(voter_address) => Err(VotingError::WrongVotingMethod),
+            None => Err(VotingError::NotVoted),
+        }
+    }
+    
+    // Get the options a voter's ballot backs: the single choice, the ranked preferences in order,
+    // the approved options, or the options given quadratic votes in ballot order. None without
+    // a counted ballot.
+    pub fn ballot_options(&self, voter_address: &Address) -> Option<Vec<String>> {
+        match self.voting_method {
+            VotingMethod::SingleChoice => self.votes_by_address.get(voter_address).map(|option| vec![option.clone()]),
+            VotingMethod::RankedChoice => self.ranked_ballots.get(voter_address).cloned(),
+            VotingMethod::Approval { .. } => self.approval_ballots.get(voter_address).cloned(),
+            VotingMethod::Conviction => self
+                .conviction
+                .as_ref()
+                .and_then(|state| state.stakes.get(voter_address))
+                .map(|stake| vec![stake.option.clone()]),
+            VotingMethod::Quadratic { .. } => self.quadratic_allocations.get(voter_address).map(|_| {
+                self.voting_options
+                    .iter()
+                    .filter(|option| self.quadratic_votes(voter_address, option) > 0)
+                    .cloned()
+                    .collect()
+            }),
+        }
     }
     
-    // Get the current results of the poll
-    pub fn get_results(&self) -> HashMap<String, usize> {
-        self.vote_counts.clone()
+    // Get the weight a single-choice voter's ballot carries: their own weight plus the power of
+    // the delegators it carries
+    fn ballot_weight(&self, voter_address: &Address) -> u128 {
+        let own_weight = self.weighted_voters.get(voter_address).copied().unwrap_or(1);
+        self.delegated_votes
+            .values()
+            .filter(|delegated_vote| delegated_vote.delegate_address == *voter_address)
+            .fold(own_weight, |total, delegated_vote| total.saturating_add(delegated_vote.weight))
     }
     
-    // Get the total number of votes cast
+    // Get the total number of votes cast, anonymous ones included
     pub fn total_votes(&self) -> usize {
-        self.participant_addresses.len()
+        self.participant_addresses.len() + self.anonymous_ballots()
     }
     
-    // Close the poll
-    pub fn close(&mut self) {
-        self.poll_is_closed = true;
+    // Get the quadratic voting credits a voter has spent so far (sum of squared votes per option)
+    pub fn credits_spent(&self, voter_address: &Address) -> u64 {
+        self.quadratic_allocations
+            .get(voter_address)
+            .map(|allocations| {
+                allocations
+                    .values()
+                    .map(|&votes| (votes as u64).saturating_mul(votes as u64))
+                    .fold(0u64, |total, cost| total.saturating_add(cost))
+            })
+            .unwrap_or(0)
     }
-}
-
-// Define the voting contract
-pub struct VotingContract {
-    pub active_polls: HashMap<String, Poll>,
-    pub admin_address: String, // The admin wallet address
-}
-
-impl VotingContract {
-    // Create a new voting contract
-    pub fn new(admin_address: String) -> Self {
-        VotingContract {
-            active_polls: HashMap::new(),
-            admin_address,
+    
+    // Get the votes a quadratic voter has placed on one option
+    pub fn quadratic_votes(&self, voter_address: &Address, selected_option: &str) -> usize {
+        self.quadratic_allocations
+            .get(voter_address)
+            .and_then(|allocations| allocations.get(selected_option))
+            .copied()
+            .unwrap_or(0)
+    }
+    
+    // Get the quadratic voting credits a voter has left, or None if the poll is not quadratic
+    pub fn credits_remaining(&self, voter_address: &Address) -> Option<u64> {
+        match self.voting_method {
+            VotingMethod::Quadratic { credit_budget } => {
+                Some(credit_budget.saturating_sub(self.credits_spent(voter_address)))
+            }
+            _ => None,
         }
     }
     
-    // Create a new poll
-    pub fn create_poll(
-        &mut self,
-        creator_address: String,
-        poll_title: String,
-        poll_description: String,
-        poll_options: Vec<String>,
-        poll_duration_seconds: u64,
-    ) -> Result<String> {
-        // Basic validation
-        if poll_options.len() < 2 {
-            return Err(VotingError::PollCreationFailed);
+    // Move the poll to another status, returning InvalidTransition (and changing nothing) when
+    // the lifecycle does not allow it
+    fn transition_to(&mut self, next_status: PollStatus) -> Result<()> {
+        if !self.status.can_transition_to(next_status) {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        self.status = next_status;
+        Ok(())
+    }
+    
+    // Open a draft for voting. The time window keeps its duration but starts now.
+    pub fn publish(&mut self, current_timestamp: u64) -> Result<()> {
+        if self.status != PollStatus::Draft {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        let poll_duration_seconds = self.poll_end_timestamp - self.poll_start_timestamp;
+        let poll_end_timestamp = current_timestamp
+            .checked_add(poll_duration_seconds)
+            .ok_or(VotingError::InvalidTimeSettings)?;
+        let commit_end_timestamp = match self.commit_end_timestamp {
+            Some(commit_end_timestamp) => Some(
+                current_timestamp
+                    .checked_add(commit_end_timestamp - self.poll_start_timestamp)
+                    .ok_or(VotingError::InvalidTimeSettings)?,
+            ),
+            None => None,
+        };
+        
+        self.transition_to(PollStatus::Active)?;
+        self.poll_start_timestamp = current_timestamp;
+        self.poll_end_timestamp = poll_end_timestamp;
+        self.commit_end_timestamp = commit_end_timestamp;
+        Ok(())
+    }
+    
+    // Stop accepting votes until resumed. The end time is not extended.
+    pub fn pause(&mut self) -> Result<()> {
+        self.transition_to(PollStatus::Paused)
+    }
+    
+    pub fn resume(&mut self) -> Result<()> {
+        if self.status != PollStatus::Paused {
+            return Err(VotingError::InvalidTransition);
         }
+        self.transition_to(PollStatus::Active)
+    }
+    
+    // Close the poll, cache its winner and fix its ballot root. Closing twice is an invalid
+    // transition, so results and events are never recorded twice.
+    pub fn close(&mut self) -> Result<()> {
+        self.transition_to(PollStatus::Closed)?;
+        self.invalidate_results();
+        // The results no longer change with votes, so later reads are served from the cache
+        let _ = self.get_cached_winner();
+        self.ballot_root = Some(self.compute_ballot_root());
+        Ok(())
+    }
+    
+    pub fn archive(&mut self) -> Result<()> {
+        self.transition_to(PollStatus::Archived)
+    }
+}
+
+impl Encode for Poll {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.poll_id);
+        encoder.write(&self.poll_title);
+        encoder.write(&self.poll_description);
+        encoder.write(&self.voting_options);
+        // Schema version 14 keyed the tallies by option ID; older layouts key them by option text
+        if encoder.layout() >= 14 {
+            encoder.write(&self.vote_counts);
+            encoder.write(&self.voter_counts);
+        } else {
+            encoder.write(&id::tallies_by_text(&self.voting_options, &self.vote_counts));
+            encoder.write(&id::tallies_by_text(&self.voting_options, &self.voter_counts));
+        }
+        // Schema version 11 tagged the set so it can be compact; older layouts list the voters
+        if encoder.layout() >= 11 {
+            encoder.write(&self.participant_addresses);
+        } else {
+            encoder.write(&self.voters().cloned().collect::<HashSet<Address>>());
+        }
+        encoder.write(&self.poll_creator_address);
+        encoder.write(&self.poll_start_timestamp);
+        encoder.write(&self.poll_end_timestamp);
+        encoder.write(&self.status);
+        encoder.write(&self.quadratic_allocations);
+        encoder.write(&self.min_winning_votes);
+        encoder.write(&self.creator_can_vote);
+        encoder.write(&self.tags);
+        encoder.write(&self.voting_method);
+        encoder.write(&self.ranked_ballots);
+        encoder.write(&self.weighted_voters);
+        encoder.write(&self.delegated_votes);
+        encoder.write(&self.commit_end_timestamp);
+        encoder.write(&self.vote_commitments);
+        encoder.write(&self.quorum_config);
+        encoder.write(&self.allowlist);
+        encoder.write(&self.denylist);
+        encoder.write(&self.votes_by_address);
+        encoder.write(&self.allow_vote_changes);
+        encoder.write(&self.tie_break);
+        encoder.write(&self.allow_abstain);
+        encoder.write(&self.allow_nota);
+        encoder.write(&self.approval_ballots);
+        encoder.write(&self.category);
+        encoder.write(&self.extension_count);
+        encoder.write(&self.receipt_chain);
+        encoder.write(&self.ballot_root);
+        encoder.write(&self.poll_number);
+        encoder.write(&self.track_history);
+        encoder.write(&self.history);
+        encoder.write(&self.action_executed_at);
+        encoder.write(&self.parameter_change);
+        encoder.write(&self.min_balance_to_vote);
+        encoder.write(&self.weight_snapshot);
+        encoder.write(&self.flags);
+        encoder.write(&self.hidden);
+        encoder.write(&self.finalization);
+        // Added in schema version 2
+        if encoder.layout() >= 2 {
+            encoder.write(&self.metadata);
+        }
+        // Added in schema version 3
+        if encoder.layout() >= 3 {
+            encoder.write(&self.vote_fee);
+        }
+        // Added in schema version 4
+        if encoder.layout() >= 4 {
+            encoder.write(&self.runoff);
+            encoder.write(&self.previous_round);
+            encoder.write(&self.next_round);
+        }
+        // Added in schema version 5
+        if encoder.layout() >= 5 {
+            encoder.write(&self.tabulation_method);
+        }
+        // Added in schema version 6
+        if encoder.layout() >= 6 {
+            encoder.write(&self.conviction);
+        }
+        // Added in schema version 7
+        if encoder.layout() >= 7 {
+            encoder.write(&self.depends_on);
+            encoder.write(&self.dependencies_met);
+        }
+        // Added in schema version 8
+        if encoder.layout() >= 8 {
+            encoder.write(&self.anonymous);
+        }
+        // Added in schema version 9
+        if encoder.layout() >= 9 {
+            encoder.write(&self.encryption);
+            encoder.write(&self.ciphertexts);
+        }
+        // Added in schema version 10
+        if encoder.layout() >= 10 {
+            encoder.write(&self.results_visibility);
+        }
+        // Added in schema version 12
+        if encoder.layout() >= 12 {
+            encoder.write(&self.previous_poll_id);
+        }
+        // Added in schema version 13
+        if encoder.layout() >= 13 {
+            encoder.write(&self.rationales);
+        }
+    }
+}
+
+impl Decode for Poll {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let poll_id = decoder.read()?;
+        let poll_title = decoder.read()?;
+        let poll_description = decoder.read()?;
+        let voting_options: Vec<String> = decoder.read()?;
+        let (vote_counts, voter_counts) = if decoder.layout() >= 14 {
+            (decoder.read()?, decoder.read()?)
+        } else {
+            let vote_counts = id::tallies_by_id(&voting_options, decoder.read()?)?;
+            (vote_counts, id::tallies_by_id(&voting_options, decoder.read()?)?)
+        };
+        let mut poll = Poll {
+            poll_id,
+            poll_title,
+            poll_description,
+            voting_options,
+            vote_counts,
+            voter_counts,
+            participant_addresses: if decoder.layout() >= 11 {
+                decoder.read()?
+            } else {
+                ParticipantSet::Full(decoder.read()?)
+            },
+            poll_creator_address: decoder.read()?,
+            poll_start_timestamp: decoder.read()?,
+            poll_end_timestamp: decoder.read()?,
+            status: decoder.read()?,
+            quadratic_allocations: decoder.read()?,
+            min_winning_votes: decoder.read()?,
+            creator_can_vote: decoder.read()?,
+            tags: decoder.read()?,
+            voting_method: decoder.read()?,
+            ranked_ballots: decoder.read()?,
+            weighted_voters: decoder.read()?,
+            delegated_votes: decoder.read()?,
+            commit_end_timestamp: decoder.read()?,
+            vote_commitments: decoder.read()?,
+            quorum_config: decoder.read()?,
+            allowlist: decoder.read()?,
+            denylist: decoder.read()?,
+            votes_by_address: decoder.read()?,
+            allow_vote_changes: decoder.read()?,
+            tie_break: decoder.read()?,
+            allow_abstain: decoder.read()?,
+            allow_nota: decoder.read()?,
+            approval_ballots: decoder.read()?,
+            category: decoder.read()?,
+            extension_count: decoder.read()?,
+            receipt_chain: decoder.read()?,
+            ballot_root: decoder.read()?,
+            poll_number: decoder.read()?,
+            track_history: decoder.read()?,
+            history: decoder.read()?,
+            action_executed_at: decoder.read()?,
+            parameter_change: decoder.read()?,
+            min_balance_to_vote: decoder.read()?,
+            weight_snapshot: decoder.read()?,
+            flags: decoder.read()?,
+            hidden: decoder.read()?,
+            finalization: decoder.read()?,
+            metadata: if decoder.layout() >= 2 { decoder.read()? } else { BTreeMap::new() },
+            vote_fee: if decoder.layout() >= 3 { decoder.read()? } else { 0 },
+            runoff: if decoder.layout() >= 4 { decoder.read()? } else { None },
+            previous_round: if decoder.layout() >= 4 { decoder.read()? } else { None },
+            next_round: if decoder.layout() >= 4 { decoder.read()? } else { None },
+            tabulation_method: if decoder.layout() >= 5 { decoder.read()? } else { TabulationMethod::InstantRunoff },
+            conviction: if decoder.layout() >= 6 { decoder.read()? } else { None },
+            depends_on: if decoder.layout() >= 7 { decoder.read()? } else { Vec::new() },
+            dependencies_met: if decoder.layout() >= 7 { decoder.read()? } else { true },
+            anonymous: if decoder.layout() >= 8 { decoder.read()? } else { None },
+            encryption: if decoder.layout() >= 9 { decoder.read()? } else { None },
+            ciphertexts: if decoder.layout() >= 9 { decoder.read()? } else { Vec::new() },
+            results_visibility: if decoder.layout() >= 10 { decoder.read()? } else { ResultsVisibility::Live },
+            previous_poll_id: if decoder.layout() >= 12 { decoder.read()? } else { None },
+            rationales: if decoder.layout() >= 13 { decoder.read()? } else { Vec::new() },
+            encrypted_voters: HashSet::new(),
+            // Cached aggregates are derived data and are rebuilt on first read
+            cached_winner: WinnerCache::new(),
+        };
+        poll.encrypted_voters = poll.ciphertexts.iter().map(|ballot| ballot.voter_address.clone()).collect();
+        Ok(poll)
+    }
+}
+
+impl Poll {
+    // Get the number of bytes the poll takes when encoded, e.g. to size its account
+    pub fn serialized_size(&self) -> usize {
+        encoding::to_bytes(self).len()
+    }
+    
+    // Check the poll's internal bookkeeping is consistent
+    fn validate_invariants(&self) -> Result<()> {
+        // Polls run for a non-empty window
+        if self.poll_start_timestamp >= self.poll_end_timestamp {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Drafts cannot hold ballots
+        if self.status == PollStatus::Draft && (self.voting_started() || !self.delegated_votes.is_empty()) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Options are distinct and not blank
+        let mut seen_options = HashSet::new();
+        if self.voting_options.iter().any(|option| option.trim().is_empty() || !seen_options.insert(option)) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Every tally must belong to a ballot option
+        if self
+            .vote_counts
+            .keys()
+            .chain(self.voter_counts.keys())
+            .any(|option_id| option_id.0 as usize >= self.voting_options.len())
+        {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Weighted voters are regular single-choice participants
+        if self.weighted_voters.keys().any(|voter_address| {
+            !self.participant_addresses.contains(voter_address)
+                || self.quadratic_allocations.contains_key(voter_address)
+                || self.approval_ballots.contains_key(voter_address)
+        }) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Ranked ballots must belong to participants and only rank ballot options
+        for (voter_address, ranking) in &self.ranked_ballots {
+            if !self.participant_addresses.contains(voter_address)
+                || ranking.iter().any(|option| !self.voting_options.contains(option))
+            {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        
+        // Quadratic allocations must belong to participants and ballot options
+        for (voter_address, allocations) in &self.quadratic_allocations {
+            if !self.participant_addresses.contains(voter_address)
+                || allocations.keys().any(|option| !self.voting_options.contains(option))
+            {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        
+        // Quadratic allocations only exist in quadratic polls, and stay within the credit budget
+        let quadratic_ballots_valid = match self.voting_method {
+            VotingMethod::Quadratic { credit_budget } => {
+                credit_budget > 0
+                    && self.votes_by_address.is_empty()
+                    && self.ranked_ballots.is_empty()
+                    && self.commit_end_timestamp.is_none()
+                    && self
+                        .quadratic_allocations
+                        .keys()
+                        .all(|voter_address| self.credits_spent(voter_address) <= credit_budget)
+            }
+            VotingMethod::SingleChoice
+            | VotingMethod::RankedChoice
+            | VotingMethod::Approval { .. }
+            | VotingMethod::Conviction => self.quadratic_allocations.is_empty(),
+        };
+        if !quadratic_ballots_valid {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Approval ballots only exist in approval polls, and approve between one and max_selections
+        // distinct ballot options
+        let approval_ballots_valid = match self.voting_method {
+            VotingMethod::Approval { max_selections } => {
+                max_selections > 0
+                    && self.votes_by_address.is_empty()
+                    && self.ranked_ballots.is_empty()
+                    && self.commit_end_timestamp.is_none()
+                    && self.approval_ballots.iter().all(|(voter_address, approved_options)| {
+                        let distinct_options: HashSet<&String> = approved_options.iter().collect();
+                        self.participant_addresses.contains(voter_address)
+                            && !approved_options.is_empty()
+                            && approved_options.len() <= max_selections
+                            && distinct_options.len() == approved_options.len()
+                            && approved_options.iter().all(|option| self.voting_options.contains(option))
+                    })
+            }
+            VotingMethod::SingleChoice
+            | VotingMethod::RankedChoice
+            | VotingMethod::Quadratic { .. }
+            | VotingMethod::Conviction => self.approval_ballots.is_empty(),
+        };
+        if !approval_ballots_valid {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Only polls with dependencies can be held for them, and a held poll never opened: it is
+        // still scheduled, or a failed prerequisite closed it
+        let held_status = matches!(self.status, PollStatus::Scheduled | PollStatus::Closed | PollStatus::Archived);
+        if !self.dependencies_met && (!held_status || self.depends_on.is_empty()) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Conviction state only exists in conviction polls, which hold stakes instead of ballots
+        let conviction_valid = match &self.conviction {
+            Some(state) => self.voting_method == VotingMethod::Conviction && state.is_valid(self),
+            None => self.voting_method != VotingMethod::Conviction,
+        };
+        if !conviction_valid {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Reserved options are only offered on single-choice ballots, and are on the ballot
+        let reserved_options_valid = (!self.allow_abstain && !self.allow_nota)
+            || (self.voting_method == VotingMethod::SingleChoice
+                && (!self.allow_abstain || self.voting_options.iter().any(|option| option == ABSTAIN_OPTION))
+                && (!self.allow_nota || self.voting_options.iter().any(|option| option == NOTA_OPTION)));
+        if !reserved_options_valid {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Closed polls carry the root of the ballots they hold, and open polls carry none
+        if self.ballot_root.is_some() != self.is_closed()
+            || self.ballot_root.is_some_and(|ballot_root| ballot_root != self.compute_ballot_root())
+        {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Actions only run once a poll is closed
+        if self.action_executed_at.is_some() && !self.is_closed() {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Only closed polls are finalized, and their records still match their hashes
+        if self.finalization.as_ref().is_some_and(|record| !self.is_closed() || !record.verify(self)) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Every participant cast exactly one kind of ballot
+        let recorded_ballots = self.votes_by_address.len()
+            + self.ranked_ballots.len()
+            + self.quadratic_allocations.len()
+            + self.approval_ballots.len();
+        if recorded_ballots != self.participant_addresses.len() {
+            return Err(VotingError::CorruptState);
+        }
+        for (voter_address, selected_option) in &self.votes_by_address {
+            if !self.participant_addresses.contains(voter_address)
+                || self.ranked_ballots.contains_key(voter_address)
+                || self.quadratic_allocations.contains_key(voter_address)
+                || !self.voting_options.contains(selected_option)
+            {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        
+        // Delegated power is carried by a single-choice ballot on behalf of a non-participant
+        for (delegator_address, delegated_vote) in &self.delegated_votes {
+            if self.participant_addresses.contains(delegator_address)
+                || self.votes_by_address.get(&delegated_vote.delegate_address) != Some(&delegated_vote.selected_option)
+            {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        
+        if let Some(quorum_config) = &self.quorum_config {
+            quorum_config.validate().map_err(|_| VotingError::CorruptState)?;
+        }
+        
+        // Eligibility is fixed once voting starts, so every voter, committer, encrypted ballot and
+        // carried delegator must still be eligible
+        if self
+            .voters()
+            .chain(self.vote_commitments.keys())
+            .chain(self.ciphertexts.iter().map(|encrypted_ballot| &encrypted_ballot.voter_address))
+            .chain(self.delegated_votes.keys())
+            .any(|address| !self.is_eligible(address))
+        {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Commitments only exist in commit-reveal polls, and are dropped once revealed
+        let commit_window_valid = match self.commit_end_timestamp {
+            Some(commit_end_timestamp) => {
+                commit_end_timestamp >= self.poll_start_timestamp && commit_end_timestamp <= self.poll_end_timestamp
+            }
+            None => self.vote_commitments.is_empty(),
+        };
+        if !commit_window_valid {
+            return Err(VotingError::CorruptState);
+        }
+        if self.vote_commitments.keys().any(|voter_address| self.participant_addresses.contains(voter_address)) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Anonymous ballots belong to anonymous polls, which hold no other kind
+        if self.anonymous.as_ref().is_some_and(|state| !state.is_valid(self)) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Encrypted ballots belong to encrypted polls, and only become regular ballots once decrypted
+        let encryption_valid = match &self.encryption {
+            Some(encryption) => encryption.is_valid(self),
+            None => self.ciphertexts.is_empty(),
+        };
+        if !encryption_valid {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Regular voters, anonymous ones included, add their weight (1 unless recorded otherwise)
+        // plus any power delegated to them, quadratic voters add their allocations and approval
+        // voters one per approval
+        let approvals = self.approval_ballots.values().map(|approved_options| approved_options.len()).sum::<usize>();
+        let regular_voters = self.participant_addresses.len() - self.quadratic_allocations.len()
+            - self.approval_ballots.len()
+            + self.anonymous_ballots();
+        let unweighted_votes = (regular_voters - self.weighted_voters.len()) as u128;
+        let quadratic_votes = self
+            .quadratic_allocations
+            .values()
+            .flat_map(|allocations| allocations.values())
+            .map(|&votes| votes as u128);
+        let expected_weight = self
+            .weighted_voters
+            .values()
+            .copied()
+            .chain(self.delegated_votes.values().map(|delegated_vote| delegated_vote.weight))
+            .chain(quadratic_votes)
+            .chain([approvals as u128])
+            .try_fold(unweighted_votes, |total, weight| total.checked_add(weight))
+            .ok_or(VotingError::CorruptState)?;
+        let counted_weight = self.total_weight().map_err(|_| VotingError::CorruptState)?;
+        if counted_weight != expected_weight {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Regular voters back one option, quadratic and approval voters every option they allocated
+        // to or approved
+        let expected_voters = self
+            .quadratic_allocations
+            .values()
+            .map(|allocations| allocations.len())
+            .sum::<usize>()
+            + approvals
+            + regular_voters;
+        let counted_voters = self
+            .voter_counts
+            .values()
+            .try_fold(0usize, |total, &voters| total.checked_add(voters))
+            .ok_or(VotingError::CorruptState)?;
+        if counted_voters != expected_voters {
+            return Err(VotingError::CorruptState);
+        }
+        
+        Ok(())
+    }
+}
+
+// Define the weighted tally and backing voters of a single option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionTally {
+    pub weight: u128,
+    pub voters: usize,
+}
+
+// Define the results of an approval poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalResults {
+    pub approvals: HashMap<String, u128>, // Ballots approving each option
+    pub ballots: usize,                   // Ballots cast
+}
+
+// Define one entry of an address's voting history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteRecord {
+    pub poll_id: PollId,
+    pub selected_options: Vec<String>, // What the ballot backs (see Poll::ballot_options)
+}
+
+// Define a display-ready summary of a poll's results
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollSummary {
+    pub poll_id: PollId,
+    pub total_votes: usize,
+    pub leading_options: Vec<String>, // Options sharing the highest vote count, in ballot order
+    pub leading_votes: u128,
+    pub is_tie: bool,
+    pub quorum_met: bool,
+    pub is_active: bool,
+    pub status: PollStatus,
+    pub unrevealed_commitments: usize, // Commit-reveal ballots committed but not counted yet
+}
+
+// Define the transitions recorded by one tick
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TickSummary {
+    pub started_poll_ids: Vec<PollId>, // Scheduled polls marked started, sorted
+    pub expired_poll_ids: Vec<PollId>, // Polls closed for passing their end time, sorted
+    pub passed_convictions: Vec<(PollId, String)>, // Poll and option of each conviction that passed, sorted
+}
+
+// Define the lifecycle states polls can be filtered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    Draft,     // Not published yet
+    Scheduled, // Published but waiting for its start time
+    Active,    // Accepting votes
+    Paused,    // Temporarily not accepting votes
+    Closed,    // Closed manually, by an expiry sweep or lazily with auto_expire
+    Expired,   // Past its end time but not yet recorded as closed
+    Archived,  // Closed and archived (only listed when asked for)
+}
+
+// Define the criteria a listed poll must match (unset fields match every poll)
+#[derive(Debug, Clone, Default)]
+pub struct PollFilter {
+    pub creator_address: Option<Address>,
+    pub status: Option<StatusFilter>,
+    pub created_after: Option<u64>,     // Inclusive lower bound on the start timestamp
+    pub created_before: Option<u64>,    // Exclusive upper bound on the start timestamp
+    pub title_contains: Option<String>, // Case-insensitive substring of the title
+}
+
+impl PollFilter {
+    // Check whether a poll matches every criterion that is set
+    pub fn matches(&self, poll: &Poll, current_timestamp: u64) -> bool {
+        let status_matches = match self.status {
+            None => poll.status != PollStatus::Archived,
+            Some(StatusFilter::Draft) => poll.status == PollStatus::Draft,
+            Some(StatusFilter::Scheduled) => poll.is_scheduled(current_timestamp),
+            Some(StatusFilter::Active) => poll.is_active(current_timestamp),
+            Some(StatusFilter::Paused) => poll.status == PollStatus::Paused && !poll.has_ended(current_timestamp),
+            Some(StatusFilter::Closed) => poll.status == PollStatus::Closed,
+            Some(StatusFilter::Expired) => !poll.is_closed() && poll.has_ended(current_timestamp),
+            Some(StatusFilter::Archived) => poll.status == PollStatus::Archived,
+        };
+        
+        status_matches
+            && self
+                .creator_address
+                .as_ref()
+                .is_none_or(|creator_address| poll.poll_creator_address == creator_address.trim())
+            && self.created_after.is_none_or(|created_after| poll.poll_start_timestamp >= created_after)
+            && self.created_before.is_none_or(|created_before| poll.poll_start_timestamp < created_before)
+            && self.title_contains.as_ref().is_none_or(|title_fragment| {
+                poll.poll_title.to_lowercase().contains(&title_fragment.to_lowercase())
+            })
+    }
+}
+
+// Define the order of listed polls. Polls that compare equal are ordered by ID, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PollSort {
+    #[default]
+    Newest,        // Latest start time first
+    EndingSoonest, // Earliest end time first
+    MostVotes,     // Most voters first
+}
+
+// Define a reusable template for structurally identical polls
+#[derive(Debug, Clone)]
+pub struct PollTemplate {
+    pub poll_description: String,
+    pub voting_options: Vec<String>,
+    pub poll_duration_seconds: u64,
+    pub tags: Vec<String>,
+}
+
+// Define the events emitted by the contract, one for every state change
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractEvent {
+    PollCreated { poll_id: PollId, creator_address: Address },
+    VoteCast { poll_id: PollId, voter_address: Address, selected_option: String },
+    VoteCommitted { poll_id: PollId, voter_address: Address },
+    PollClosed { poll_id: PollId, closed_by: Address },
+    PollExpired { poll_id: PollId },
+    PollArchived { poll_id: PollId },
+    MinWinningVotesSet { poll_id: PollId, min_winning_votes: u128 },
+    RoleGranted { address: Address, role: Role, granted_by: Address },
+    RoleRevoked { address: Address, role: Role, revoked_by: Address },
+    VoterWeightSet { voter_address: Address, weight: u128 },
+    DelegationSet { delegator_address: Address, delegate_address: Address },
+    DelegationRevoked { delegator_address: Address },
+    EligibilityChanged { poll_id: PollId, voter_address: Address, eligible: bool },
+    VoteChanged { poll_id: PollId, voter_address: Address, previous_option: String, selected_option: String },
+    VoteRetracted { poll_id: PollId, voter_address: Address, previous_option: String },
+    PollStarted { poll_id: PollId },
+    PollPublished { poll_id: PollId },
+    PollPaused { poll_id: PollId, paused_by: Address },
+    PollResumed { poll_id: PollId, resumed_by: Address },
+    DraftUpdated { poll_id: PollId },
+    TieBreakSet { poll_id: PollId, tie_break: TieBreak },
+    TagsSet { poll_id: PollId, tags: Vec<String> },
+    CategorySet { poll_id: PollId, category: Option<String> },
+    PollExtended { poll_id: PollId, extended_by: Address, previous_end_timestamp: u64, new_end_timestamp: u64 },
+    PollFinalizedEarly { poll_id: PollId, finalized_by: Address, scheduled_end_timestamp: u64, finalized_at: u64 },
+    HistoryTrackingSet { poll_id: PollId, tracking: Option<HistoryTracking> },
+    ActionAttached { poll_id: PollId, attached_by: Address },
+    ActionExecuted { poll_id: PollId, executed_at: u64 },
+    ParameterChanged { poll_id: PollId, key: ConfigKey, previous_value: u64, new_value: u64 },
+    MinBalanceToVoteSet { poll_id: PollId, min_balance: u128 },
+    PollFlagged { poll_id: PollId, reporter: Address },
+    PollModerated { poll_id: PollId, action: ModerationAction, moderator: Address },
+    PollResultsFinalized { poll_id: PollId, finalized_by: Address, result_hash: [u8; 32] },
+    MetadataSet { poll_id: PollId, keys: Vec<String> },
+    CreationFeeSet { creation_fee: u128 },
+    VoteFeeSet { poll_id: PollId, vote_fee: u128 },
+    FeesWithdrawn { recipient: Address, amount: u128, withdrawn_by: Address },
+    RewardPoolFunded { poll_id: PollId, funder: Address, amount: u128 },
+    RewardSharesComputed { poll_id: PollId, recipients: u32 },
+    RewardClaimed { poll_id: PollId, address: Address, amount: u128 },
+    RunoffSet { poll_id: PollId, runoff: Option<RunoffConfig> },
+    RunoffStarted { poll_id: PollId, runoff_poll_id: PollId },
+    TabulationMethodSet { poll_id: PollId, tabulation_method: TabulationMethod },
+    ConvictionStaked { poll_id: PollId, voter_address: Address, selected_option: String, amount: u128 },
+    ConvictionUnstaked { poll_id: PollId, voter_address: Address },
+    ConvictionPassed { poll_id: PollId, selected_option: String, conviction: u128 },
+    ElectionCreated { election_id: String, creator_address: Address },
+    BallotCast { election_id: String, voter_address: Address, answers: u32 },
+    PollDependenciesSet { poll_id: PollId, depends_on: Vec<PollDependency> },
+    PollDependencyFailed { poll_id: PollId, dependency_poll_id: PollId },
+    CommitmentRegistered { poll_id: PollId, commitment: [u8; 32] },
+    AnonymousVoteCast { poll_id: PollId, nullifier: [u8; 32], selected_option: String },
+    EncryptedVoteCast { poll_id: PollId, voter_address: Address },
+    DecryptionShareSubmitted { poll_id: PollId, trustee_address: Address },
+    BallotsDecrypted { poll_id: PollId, counted: u32, spoiled: u32 },
+    ResultsVisibilitySet { poll_id: PollId, results_visibility: ResultsVisibility },
+    ParticipantTrackingSet { poll_id: PollId, compact: bool },
+    AdminTransferProposed { current_admin: Address, proposed_admin: Address },
+    AdminTransferAccepted { previous_admin: Address, new_admin: Address },
+    AdminRenounced { admin_address: Address, last_admin: bool },
+    ContractPaused { paused_by: Address, resume_at: Option<u64> },
+    ContractUnpaused { unpaused_by: Address },
+    CreatorQuotaSet { creator_address: Address, poll_quota: Option<usize> },
+    PollUpdated { poll_id: PollId, updated_by: Address },
+    PollReopened { poll_id: PollId, reopened_by: Address, poll_end_timestamp: u64, votes_reset: bool },
+    PollRerun { poll_id: PollId, previous_poll_id: PollId },
+    PollCompacted { poll_id: PollId },
+    RationaleAttached { poll_id: PollId, voter_address: Address },
+    CommentPosted { poll_id: PollId, comment_id: u64, author: Address },
+    CommentDeleted { poll_id: PollId, comment_id: u64, deleted_by: Address },
+}
+
+impl ContractEvent {
+    // Get the name of the event's variant, as used by notifier filters
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContractEvent::PollCreated { .. } => "PollCreated",
+            ContractEvent::VoteCast { .. } => "VoteCast",
+            ContractEvent::VoteCommitted { .. } => "VoteCommitted",
+            ContractEvent::PollClosed { .. } => "PollClosed",
+            ContractEvent::PollExpired { .. } => "PollExpired",
+            ContractEvent::PollArchived { .. } => "PollArchived",
+            ContractEvent::MinWinningVotesSet { .. } => "MinWinningVotesSet",
+            ContractEvent::RoleGranted { .. } => "RoleGranted",
+            ContractEvent::RoleRevoked { .. } => "RoleRevoked",
+            ContractEvent::VoterWeightSet { .. } => "VoterWeightSet",
+            ContractEvent::DelegationSet { .. } => "DelegationSet",
+            ContractEvent::DelegationRevoked { .. } => "DelegationRevoked",
+            ContractEvent::EligibilityChanged { .. } => "EligibilityChanged",
+            ContractEvent::VoteChanged { .. } => "VoteChanged",
+            ContractEvent::VoteRetracted { .. } => "VoteRetracted",
+            ContractEvent::PollStarted { .. } => "PollStarted",
+            ContractEvent::PollPublished { .. } => "PollPublished",
+            ContractEvent::PollPaused { .. } => "PollPaused",
+            ContractEvent::PollResumed { .. } => "PollResumed",
+            ContractEvent::DraftUpdated { .. } => "DraftUpdated",
+            ContractEvent::TieBreakSet { .. } => "TieBreakSet",
+            ContractEvent::TagsSet { .. } => "TagsSet",
+            ContractEvent::CategorySet { .. } => "CategorySet",
+            ContractEvent::PollExtended { .. } => "PollExtended",
+            ContractEvent::PollFinalizedEarly { .. } => "PollFinalizedEarly",
+            ContractEvent::HistoryTrackingSet { .. } => "HistoryTrackingSet",
+            ContractEvent::ActionAttached { .. } => "ActionAttached",
+            ContractEvent::ActionExecuted { .. } => "ActionExecuted",
+            ContractEvent::ParameterChanged { .. } => "ParameterChanged",
+            ContractEvent::MinBalanceToVoteSet { .. } => "MinBalanceToVoteSet",
+            ContractEvent::PollFlagged { .. } => "PollFlagged",
+            ContractEvent::PollModerated { .. } => "PollModerated",
+            ContractEvent::PollResultsFinalized { .. } => "PollResultsFinalized",
+            ContractEvent::MetadataSet { .. } => "MetadataSet",
+            ContractEvent::CreationFeeSet { .. } => "CreationFeeSet",
+            ContractEvent::VoteFeeSet { .. } => "VoteFeeSet",
+            ContractEvent::FeesWithdrawn { .. } => "FeesWithdrawn",
+            ContractEvent::RewardPoolFunded { .. } => "RewardPoolFunded",
+            ContractEvent::RewardSharesComputed { .. } => "RewardSharesComputed",
+            ContractEvent::RewardClaimed { .. } => "RewardClaimed",
+            ContractEvent::RunoffSet { .. } => "RunoffSet",
+            ContractEvent::RunoffStarted { .. } => "RunoffStarted",
+            ContractEvent::TabulationMethodSet { .. } => "TabulationMethodSet",
+            ContractEvent::ConvictionStaked { .. } => "ConvictionStaked",
+            ContractEvent::ConvictionUnstaked { .. } => "ConvictionUnstaked",
+            ContractEvent::ConvictionPassed { .. } => "ConvictionPassed",
+            ContractEvent::ElectionCreated { .. } => "ElectionCreated",
+            ContractEvent::BallotCast { .. } => "BallotCast",
+            ContractEvent::PollDependenciesSet { .. } => "PollDependenciesSet",
+            ContractEvent::PollDependencyFailed { .. } => "PollDependencyFailed",
+            ContractEvent::CommitmentRegistered { .. } => "CommitmentRegistered",
+            ContractEvent::AnonymousVoteCast { .. } => "AnonymousVoteCast",
+            ContractEvent::EncryptedVoteCast { .. } => "EncryptedVoteCast",
+            ContractEvent::DecryptionShareSubmitted { .. } => "DecryptionShareSubmitted",
+            ContractEvent::BallotsDecrypted { .. } => "BallotsDecrypted",
+            ContractEvent::ResultsVisibilitySet { .. } => "ResultsVisibilitySet",
+            ContractEvent::ParticipantTrackingSet { .. } => "ParticipantTrackingSet",
+            ContractEvent::AdminTransferProposed { .. } => "AdminTransferProposed",
+            ContractEvent::AdminTransferAccepted { .. } => "AdminTransferAccepted",
+            ContractEvent::AdminRenounced { .. } => "AdminRenounced",
+            ContractEvent::ContractPaused { .. } => "ContractPaused",
+            ContractEvent::ContractUnpaused { .. } => "ContractUnpaused",
+            ContractEvent::CreatorQuotaSet { .. } => "CreatorQuotaSet",
+            ContractEvent::PollUpdated { .. } => "PollUpdated",
+            ContractEvent::PollReopened { .. } => "PollReopened",
+            ContractEvent::PollRerun { .. } => "PollRerun",
+            ContractEvent::PollCompacted { .. } => "PollCompacted",
+            ContractEvent::RationaleAttached { .. } => "RationaleAttached",
+            ContractEvent::CommentPosted { .. } => "CommentPosted",
+            ContractEvent::CommentDeleted { .. } => "CommentDeleted",
+        }
+    }
+    
+    // Get the poll the event concerns (None for contract-wide events)
+    pub fn poll_id(&self) -> Option<&str> {
+        match self {
+            ContractEvent::PollCreated { poll_id, .. }
+            | ContractEvent::VoteCast { poll_id, .. }
+            | ContractEvent::VoteCommitted { poll_id, .. }
+            | ContractEvent::PollClosed { poll_id, .. }
+            | ContractEvent::PollExpired { poll_id, .. }
+            | ContractEvent::PollArchived { poll_id, .. }
+            | ContractEvent::MinWinningVotesSet { poll_id, .. }
+            | ContractEvent::EligibilityChanged { poll_id, .. }
+            | ContractEvent::VoteChanged { poll_id, .. }
+            | ContractEvent::VoteRetracted { poll_id, .. }
+            | ContractEvent::PollStarted { poll_id, .. }
+            | ContractEvent::PollPublished { poll_id, .. }
+            | ContractEvent::PollPaused { poll_id, .. }
+            | ContractEvent::PollResumed { poll_id, .. }
+            | ContractEvent::DraftUpdated { poll_id, .. }
+            | ContractEvent::PollUpdated { poll_id, .. }
+            | ContractEvent::PollReopened { poll_id, .. }
+            | ContractEvent::PollRerun { poll_id, .. }
+            | ContractEvent::PollCompacted { poll_id, .. }
+            | ContractEvent::RationaleAttached { poll_id, .. }
+            | ContractEvent::CommentPosted { poll_id, .. }
+            | ContractEvent::CommentDeleted { poll_id, .. }
+            | ContractEvent::TieBreakSet { poll_id, .. }
+            | ContractEvent::TagsSet { poll_id, .. }
+            | ContractEvent::CategorySet { poll_id, .. }
+            | ContractEvent::PollExtended { poll_id, .. }
+            | ContractEvent::PollFinalizedEarly { poll_id, .. }
+            | ContractEvent::HistoryTrackingSet { poll_id, .. }
+            | ContractEvent::ActionAttached { poll_id, .. }
+            | ContractEvent::ActionExecuted { poll_id, .. }
+            | ContractEvent::ParameterChanged { poll_id, .. }
+            | ContractEvent::MinBalanceToVoteSet { poll_id, .. }
+            | ContractEvent::PollFlagged { poll_id, .. }
+            | ContractEvent::PollModerated { poll_id, .. }
+            | ContractEvent::PollResultsFinalized { poll_id, .. }
+            | ContractEvent::MetadataSet { poll_id, .. }
+            | ContractEvent::VoteFeeSet { poll_id, .. }
+            | ContractEvent::RewardPoolFunded { poll_id, .. }
+            | ContractEvent::RewardSharesComputed { poll_id, .. }
+            | ContractEvent::RewardClaimed { poll_id, .. }
+            | ContractEvent::RunoffSet { poll_id, .. }
+            | ContractEvent::RunoffStarted { poll_id, .. }
+            | ContractEvent::TabulationMethodSet { poll_id, .. }
+            | ContractEvent::ConvictionStaked { poll_id, .. }
+            | ContractEvent::ConvictionUnstaked { poll_id, .. }
+            | ContractEvent::ConvictionPassed { poll_id, .. }
+            | ContractEvent::PollDependenciesSet { poll_id, .. }
+            | ContractEvent::PollDependencyFailed { poll_id, .. }
+            | ContractEvent::CommitmentRegistered { poll_id, .. }
+            | ContractEvent::AnonymousVoteCast { poll_id, .. }
+            | ContractEvent::EncryptedVoteCast { poll_id, .. }
+            | ContractEvent::DecryptionShareSubmitted { poll_id, .. }
+            | ContractEvent::BallotsDecrypted { poll_id, .. }
+            | ContractEvent::ResultsVisibilitySet { poll_id, .. }
+            | ContractEvent::ParticipantTrackingSet { poll_id, .. } => Some(poll_id),
+            _ => None,
+        }
+    }
+}
+
+impl Encode for ContractEvent {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            ContractEvent::PollCreated { poll_id, creator_address } => {
+                encoder.write(&0u8);
+                encoder.write(poll_id);
+                encoder.write(creator_address);
+            }
+            ContractEvent::VoteCast { poll_id, voter_address, selected_option } => {
+                encoder.write(&1u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+                encoder.write(selected_option);
+            }
+            ContractEvent::VoteCommitted { poll_id, voter_address } => {
+                encoder.write(&2u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+            }
+            ContractEvent::PollClosed { poll_id, closed_by } => {
+                encoder.write(&3u8);
+                encoder.write(poll_id);
+                encoder.write(closed_by);
+            }
+            ContractEvent::PollExpired { poll_id } => {
+                encoder.write(&4u8);
+                encoder.write(poll_id);
+            }
+            ContractEvent::PollArchived { poll_id } => {
+                encoder.write(&5u8);
+                encoder.write(poll_id);
+            }
+            ContractEvent::MinWinningVotesSet { poll_id, min_winning_votes } => {
+                encoder.write(&6u8);
+                encoder.write(poll_id);
+                encoder.write(min_winning_votes);
+            }
+            ContractEvent::RoleGranted { address, role, granted_by } => {
+                encoder.write(&7u8);
+                encoder.write(address);
+                encoder.write(role);
+                encoder.write(granted_by);
+            }
+            ContractEvent::RoleRevoked { address, role, revoked_by } => {
+                encoder.write(&8u8);
+                encoder.write(address);
+                encoder.write(role);
+                encoder.write(revoked_by);
+            }
+            ContractEvent::VoterWeightSet { voter_address, weight } => {
+                encoder.write(&9u8);
+                encoder.write(voter_address);
+                encoder.write(weight);
+            }
+            ContractEvent::DelegationSet { delegator_address, delegate_address } => {
+                encoder.write(&10u8);
+                encoder.write(delegator_address);
+                encoder.write(delegate_address);
+            }
+            ContractEvent::DelegationRevoked { delegator_address } => {
+                encoder.write(&11u8);
+                encoder.write(delegator_address);
+            }
+            ContractEvent::EligibilityChanged { poll_id, voter_address, eligible } => {
+                encoder.write(&12u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+                encoder.write(eligible);
+            }
+            ContractEvent::VoteChanged { poll_id, voter_address, previous_option, selected_option } => {
+                encoder.write(&13u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+                encoder.write(previous_option);
+                encoder.write(selected_option);
+            }
+            ContractEvent::VoteRetracted { poll_id, voter_address, previous_option } => {
+                encoder.write(&14u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+                encoder.write(previous_option);
+            }
+            ContractEvent::PollStarted { poll_id } => {
+                encoder.write(&15u8);
+                encoder.write(poll_id);
+            }
+            ContractEvent::PollPublished { poll_id } => {
+                encoder.write(&16u8);
+                encoder.write(poll_id);
+            }
+            ContractEvent::PollPaused { poll_id, paused_by } => {
+                encoder.write(&17u8);
+                encoder.write(poll_id);
+                encoder.write(paused_by);
+            }
+            ContractEvent::PollResumed { poll_id, resumed_by } => {
+                encoder.write(&18u8);
+                encoder.write(poll_id);
+                encoder.write(resumed_by);
+            }
+            ContractEvent::DraftUpdated { poll_id } => {
+                encoder.write(&19u8);
+                encoder.write(poll_id);
+            }
+            ContractEvent::TieBreakSet { poll_id, tie_break } => {
+                encoder.write(&20u8);
+                encoder.write(poll_id);
+                encoder.write(tie_break);
+            }
+            ContractEvent::TagsSet { poll_id, tags } => {
+                encoder.write(&21u8);
+                encoder.write(poll_id);
+                encoder.write(tags);
+            }
+            ContractEvent::CategorySet { poll_id, category } => {
+                encoder.write(&22u8);
+                encoder.write(poll_id);
+                encoder.write(category);
+            }
+            ContractEvent::PollExtended {
+                poll_id,
+                extended_by,
+                previous_end_timestamp,
+                new_end_timestamp,
+            } => {
+                encoder.write(&23u8);
+                encoder.write(poll_id);
+                encoder.write(extended_by);
+                encoder.write(previous_end_timestamp);
+                encoder.write(new_end_timestamp);
+            }
+            ContractEvent::PollFinalizedEarly {
+                poll_id,
+                finalized_by,
+                scheduled_end_timestamp,
+                finalized_at,
+            } => {
+                encoder.write(&24u8);
+                encoder.write(poll_id);
+                encoder.write(finalized_by);
+                encoder.write(scheduled_end_timestamp);
+                encoder.write(finalized_at);
+            }
+            ContractEvent::HistoryTrackingSet { poll_id, tracking } => {
+                encoder.write(&25u8);
+                encoder.write(poll_id);
+                encoder.write(tracking);
+            }
+            ContractEvent::ActionAttached { poll_id, attached_by } => {
+                encoder.write(&26u8);
+                encoder.write(poll_id);
+                encoder.write(attached_by);
+            }
+            ContractEvent::ActionExecuted { poll_id, executed_at } => {
+                encoder.write(&27u8);
+                encoder.write(poll_id);
+                encoder.write(executed_at);
+            }
+            ContractEvent::ParameterChanged { poll_id, key, previous_value, new_value } => {
+                encoder.write(&28u8);
+                encoder.write(poll_id);
+                encoder.write(key);
+                encoder.write(previous_value);
+                encoder.write(new_value);
+            }
+            ContractEvent::MinBalanceToVoteSet { poll_id, min_balance } => {
+                encoder.write(&29u8);
+                encoder.write(poll_id);
+                encoder.write(min_balance);
+            }
+            ContractEvent::PollFlagged { poll_id, reporter } => {
+                encoder.write(&30u8);
+                encoder.write(poll_id);
+                encoder.write(reporter);
+            }
+            ContractEvent::PollModerated { poll_id, action, moderator } => {
+                encoder.write(&31u8);
+                encoder.write(poll_id);
+                encoder.write(action);
+                encoder.write(moderator);
+            }
+            ContractEvent::PollResultsFinalized { poll_id, finalized_by, result_hash } => {
+                encoder.write(&32u8);
+                encoder.write(poll_id);
+                encoder.write(finalized_by);
+                encoder.write(result_hash);
+            }
+            ContractEvent::MetadataSet { poll_id, keys } => {
+                encoder.write(&33u8);
+                encoder.write(poll_id);
+                encoder.write(keys);
+            }
+            ContractEvent::CreationFeeSet { creation_fee } => {
+                encoder.write(&34u8);
+                encoder.write(creation_fee);
+            }
+            ContractEvent::VoteFeeSet { poll_id, vote_fee } => {
+                encoder.write(&35u8);
+                encoder.write(poll_id);
+                encoder.write(vote_fee);
+            }
+            ContractEvent::FeesWithdrawn { recipient, amount, withdrawn_by } => {
+                encoder.write(&36u8);
+                encoder.write(recipient);
+                encoder.write(amount);
+                encoder.write(withdrawn_by);
+            }
+            ContractEvent::RewardPoolFunded { poll_id, funder, amount } => {
+                encoder.write(&37u8);
+                encoder.write(poll_id);
+                encoder.write(funder);
+                encoder.write(amount);
+            }
+            ContractEvent::RewardSharesComputed { poll_id, recipients } => {
+                encoder.write(&38u8);
+                encoder.write(poll_id);
+                encoder.write(recipients);
+            }
+            ContractEvent::RewardClaimed { poll_id, address, amount } => {
+                encoder.write(&39u8);
+                encoder.write(poll_id);
+                encoder.write(address);
+                encoder.write(amount);
+            }
+            ContractEvent::RunoffSet { poll_id, runoff } => {
+                encoder.write(&40u8);
+                encoder.write(poll_id);
+                encoder.write(runoff);
+            }
+            ContractEvent::RunoffStarted { poll_id, runoff_poll_id } => {
+                encoder.write(&41u8);
+                encoder.write(poll_id);
+                encoder.write(runoff_poll_id);
+            }
+            ContractEvent::TabulationMethodSet { poll_id, tabulation_method } => {
+                encoder.write(&42u8);
+                encoder.write(poll_id);
+                encoder.write(tabulation_method);
+            }
+            ContractEvent::ConvictionStaked { poll_id, voter_address, selected_option, amount } => {
+                encoder.write(&43u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+                encoder.write(selected_option);
+                encoder.write(amount);
+            }
+            ContractEvent::ConvictionUnstaked { poll_id, voter_address } => {
+                encoder.write(&44u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+            }
+            ContractEvent::ConvictionPassed { poll_id, selected_option, conviction } => {
+                encoder.write(&45u8);
+                encoder.write(poll_id);
+                encoder.write(selected_option);
+                encoder.write(conviction);
+            }
+            ContractEvent::ElectionCreated { election_id, creator_address } => {
+                encoder.write(&46u8);
+                encoder.write(election_id);
+                encoder.write(creator_address);
+            }
+            ContractEvent::BallotCast { election_id, voter_address, answers } => {
+                encoder.write(&47u8);
+                encoder.write(election_id);
+                encoder.write(voter_address);
+                encoder.write(answers);
+            }
+            ContractEvent::PollDependenciesSet { poll_id, depends_on } => {
+                encoder.write(&48u8);
+                encoder.write(poll_id);
+                encoder.write(depends_on);
+            }
+            ContractEvent::PollDependencyFailed { poll_id, dependency_poll_id } => {
+                encoder.write(&49u8);
+                encoder.write(poll_id);
+                encoder.write(dependency_poll_id);
+            }
+            ContractEvent::CommitmentRegistered { poll_id, commitment } => {
+                encoder.write(&50u8);
+                encoder.write(poll_id);
+                encoder.write(commitment);
+            }
+            ContractEvent::AnonymousVoteCast { poll_id, nullifier, selected_option } => {
+                encoder.write(&51u8);
+                encoder.write(poll_id);
+                encoder.write(nullifier);
+                encoder.write(selected_option);
+            }
+            ContractEvent::EncryptedVoteCast { poll_id, voter_address } => {
+                encoder.write(&52u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+            }
+            ContractEvent::DecryptionShareSubmitted { poll_id, trustee_address } => {
+                encoder.write(&53u8);
+                encoder.write(poll_id);
+                encoder.write(trustee_address);
+            }
+            ContractEvent::BallotsDecrypted { poll_id, counted, spoiled } => {
+                encoder.write(&54u8);
+                encoder.write(poll_id);
+                encoder.write(counted);
+                encoder.write(spoiled);
+            }
+            ContractEvent::ResultsVisibilitySet { poll_id, results_visibility } => {
+                encoder.write(&55u8);
+                encoder.write(poll_id);
+                encoder.write(results_visibility);
+            }
+            ContractEvent::ParticipantTrackingSet { poll_id, compact } => {
+                encoder.write(&56u8);
+                encoder.write(poll_id);
+                encoder.write(compact);
+            }
+            ContractEvent::AdminTransferProposed { current_admin, proposed_admin } => {
+                encoder.write(&57u8);
+                encoder.write(current_admin);
+                encoder.write(proposed_admin);
+            }
+            ContractEvent::AdminTransferAccepted { previous_admin, new_admin } => {
+                encoder.write(&58u8);
+                encoder.write(previous_admin);
+                encoder.write(new_admin);
+            }
+            ContractEvent::AdminRenounced { admin_address, last_admin } => {
+                encoder.write(&59u8);
+                encoder.write(admin_address);
+                encoder.write(last_admin);
+            }
+            ContractEvent::ContractPaused { paused_by, resume_at } => {
+                encoder.write(&60u8);
+                encoder.write(paused_by);
+                encoder.write(resume_at);
+            }
+            ContractEvent::ContractUnpaused { unpaused_by } => {
+                encoder.write(&61u8);
+                encoder.write(unpaused_by);
+            }
+            ContractEvent::CreatorQuotaSet { creator_address, poll_quota } => {
+                encoder.write(&62u8);
+                encoder.write(creator_address);
+                encoder.write(poll_quota);
+            }
+            ContractEvent::PollUpdated { poll_id, updated_by } => {
+                encoder.write(&63u8);
+                encoder.write(poll_id);
+                encoder.write(updated_by);
+            }
+            ContractEvent::PollReopened {
+                poll_id,
+                reopened_by,
+                poll_end_timestamp,
+                votes_reset,
+            } => {
+                encoder.write(&64u8);
+                encoder.write(poll_id);
+                encoder.write(reopened_by);
+                encoder.write(poll_end_timestamp);
+                encoder.write(votes_reset);
+            }
+            ContractEvent::PollRerun { poll_id, previous_poll_id } => {
+                encoder.write(&65u8);
+                encoder.write(poll_id);
+                encoder.write(previous_poll_id);
+            }
+            ContractEvent::PollCompacted { poll_id } => {
+                encoder.write(&66u8);
+                encoder.write(poll_id);
+            }
+            ContractEvent::RationaleAttached { poll_id, voter_address } => {
+                encoder.write(&67u8);
+                encoder.write(poll_id);
+                encoder.write(voter_address);
+            }
+            ContractEvent::CommentPosted {
+                poll_id,
+                comment_id,
+                author,
+            } => {
+                encoder.write(&68u8);
+                encoder.write(poll_id);
+                encoder.write(comment_id);
+                encoder.write(author);
+            }
+            ContractEvent::CommentDeleted {
+                poll_id,
+                comment_id,
+                deleted_by,
+            } => {
+                encoder.write(&69u8);
+                encoder.write(poll_id);
+                encoder.write(comment_id);
+                encoder.write(deleted_by);
+            }
+        }
+    }
+}
+
+impl Decode for ContractEvent {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let event = match decoder.read::<u8>()? {
+            0 => ContractEvent::PollCreated {
+                poll_id: decoder.read()?,
+                creator_address: decoder.read()?,
+            },
+            1 => ContractEvent::VoteCast {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+                selected_option: decoder.read()?,
+            },
+            2 => ContractEvent::VoteCommitted {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+            },
+            3 => ContractEvent::PollClosed {
+                poll_id: decoder.read()?,
+                closed_by: decoder.read()?,
+            },
+            4 => ContractEvent::PollExpired { poll_id: decoder.read()? },
+            5 => ContractEvent::PollArchived { poll_id: decoder.read()? },
+            6 => ContractEvent::MinWinningVotesSet {
+                poll_id: decoder.read()?,
+                min_winning_votes: decoder.read()?,
+            },
+            7 => ContractEvent::RoleGranted {
+                address: decoder.read()?,
+                role: decoder.read()?,
+                granted_by: decoder.read()?,
+            },
+            8 => ContractEvent::RoleRevoked {
+                address: decoder.read()?,
+                role: decoder.read()?,
+                revoked_by: decoder.read()?,
+            },
+            9 => ContractEvent::VoterWeightSet {
+                voter_address: decoder.read()?,
+                weight: decoder.read()?,
+            },
+            10 => ContractEvent::DelegationSet {
+                delegator_address: decoder.read()?,
+                delegate_address: decoder.read()?,
+            },
+            11 => ContractEvent::DelegationRevoked { delegator_address: decoder.read()? },
+            12 => ContractEvent::EligibilityChanged {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+                eligible: decoder.read()?,
+            },
+            13 => ContractEvent::VoteChanged {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+                previous_option: decoder.read()?,
+                selected_option: decoder.read()?,
+            },
+            14 => ContractEvent::VoteRetracted {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+                previous_option: decoder.read()?,
+            },
+            15 => ContractEvent::PollStarted { poll_id: decoder.read()? },
+            16 => ContractEvent::PollPublished { poll_id: decoder.read()? },
+            17 => ContractEvent::PollPaused {
+                poll_id: decoder.read()?,
+                paused_by: decoder.read()?,
+            },
+            18 => ContractEvent::PollResumed {
+                poll_id: decoder.read()?,
+                resumed_by: decoder.read()?,
+            },
+            19 => ContractEvent::DraftUpdated { poll_id: decoder.read()? },
+            20 => ContractEvent::TieBreakSet {
+                poll_id: decoder.read()?,
+                tie_break: decoder.read()?,
+            },
+            21 => ContractEvent::TagsSet {
+                poll_id: decoder.read()?,
+                tags: decoder.read()?,
+            },
+            22 => ContractEvent::CategorySet {
+                poll_id: decoder.read()?,
+                category: decoder.read()?,
+            },
+            23 => ContractEvent::PollExtended {
+                poll_id: decoder.read()?,
+                extended_by: decoder.read()?,
+                previous_end_timestamp: decoder.read()?,
+                new_end_timestamp: decoder.read()?,
+            },
+            24 => ContractEvent::PollFinalizedEarly {
+                poll_id: decoder.read()?,
+                finalized_by: decoder.read()?,
+                scheduled_end_timestamp: decoder.read()?,
+                finalized_at: decoder.read()?,
+            },
+            25 => ContractEvent::HistoryTrackingSet {
+                poll_id: decoder.read()?,
+                tracking: decoder.read()?,
+            },
+            26 => ContractEvent::ActionAttached {
+                poll_id: decoder.read()?,
+                attached_by: decoder.read()?,
+            },
+            27 => ContractEvent::ActionExecuted {
+                poll_id: decoder.read()?,
+                executed_at: decoder.read()?,
+            },
+            28 => ContractEvent::ParameterChanged {
+                poll_id: decoder.read()?,
+                key: decoder.read()?,
+                previous_value: decoder.read()?,
+                new_value: decoder.read()?,
+            },
+            29 => ContractEvent::MinBalanceToVoteSet {
+                poll_id: decoder.read()?,
+                min_balance: decoder.read()?,
+            },
+            30 => ContractEvent::PollFlagged {
+                poll_id: decoder.read()?,
+                reporter: decoder.read()?,
+            },
+            31 => ContractEvent::PollModerated {
+                poll_id: decoder.read()?,
+                action: decoder.read()?,
+                moderator: decoder.read()?,
+            },
+            32 => ContractEvent::PollResultsFinalized {
+                poll_id: decoder.read()?,
+                finalized_by: decoder.read()?,
+                result_hash: decoder.read()?,
+            },
+            33 => ContractEvent::MetadataSet {
+                poll_id: decoder.read()?,
+                keys: decoder.read()?,
+            },
+            34 => ContractEvent::CreationFeeSet {
+                creation_fee: decoder.read()?,
+            },
+            35 => ContractEvent::VoteFeeSet {
+                poll_id: decoder.read()?,
+                vote_fee: decoder.read()?,
+            },
+            36 => ContractEvent::FeesWithdrawn {
+                recipient: decoder.read()?,
+                amount: decoder.read()?,
+                withdrawn_by: decoder.read()?,
+            },
+            37 => ContractEvent::RewardPoolFunded {
+                poll_id: decoder.read()?,
+                funder: decoder.read()?,
+                amount: decoder.read()?,
+            },
+            38 => ContractEvent::RewardSharesComputed {
+                poll_id: decoder.read()?,
+                recipients: decoder.read()?,
+            },
+            39 => ContractEvent::RewardClaimed {
+                poll_id: decoder.read()?,
+                address: decoder.read()?,
+                amount: decoder.read()?,
+            },
+            40 => ContractEvent::RunoffSet {
+                poll_id: decoder.read()?,
+                runoff: decoder.read()?,
+            },
+            41 => ContractEvent::RunoffStarted {
+                poll_id: decoder.read()?,
+                runoff_poll_id: decoder.read()?,
+            },
+            42 => ContractEvent::TabulationMethodSet {
+                poll_id: decoder.read()?,
+                tabulation_method: decoder.read()?,
+            },
+            43 => ContractEvent::ConvictionStaked {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+                selected_option: decoder.read()?,
+                amount: decoder.read()?,
+            },
+            44 => ContractEvent::ConvictionUnstaked {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+            },
+            45 => ContractEvent::ConvictionPassed {
+                poll_id: decoder.read()?,
+                selected_option: decoder.read()?,
+                conviction: decoder.read()?,
+            },
+            46 => ContractEvent::ElectionCreated {
+                election_id: decoder.read()?,
+                creator_address: decoder.read()?,
+            },
+            47 => ContractEvent::BallotCast {
+                election_id: decoder.read()?,
+                voter_address: decoder.read()?,
+                answers: decoder.read()?,
+            },
+            48 => ContractEvent::PollDependenciesSet {
+                poll_id: decoder.read()?,
+                depends_on: decoder.read()?,
+            },
+            49 => ContractEvent::PollDependencyFailed {
+                poll_id: decoder.read()?,
+                dependency_poll_id: decoder.read()?,
+            },
+            50 => ContractEvent::CommitmentRegistered {
+                poll_id: decoder.read()?,
+                commitment: decoder.read()?,
+            },
+            51 => ContractEvent::AnonymousVoteCast {
+                poll_id: decoder.read()?,
+                nullifier: decoder.read()?,
+                selected_option: decoder.read()?,
+            },
+            52 => ContractEvent::EncryptedVoteCast {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+            },
+            53 => ContractEvent::DecryptionShareSubmitted {
+                poll_id: decoder.read()?,
+                trustee_address: decoder.read()?,
+            },
+            54 => ContractEvent::BallotsDecrypted {
+                poll_id: decoder.read()?,
+                counted: decoder.read()?,
+                spoiled: decoder.read()?,
+            },
+            55 => ContractEvent::ResultsVisibilitySet {
+                poll_id: decoder.read()?,
+                results_visibility: decoder.read()?,
+            },
+            56 => ContractEvent::ParticipantTrackingSet {
+                poll_id: decoder.read()?,
+                compact: decoder.read()?,
+            },
+            57 => ContractEvent::AdminTransferProposed {
+                current_admin: decoder.read()?,
+                proposed_admin: decoder.read()?,
+            },
+            58 => ContractEvent::AdminTransferAccepted {
+                previous_admin: decoder.read()?,
+                new_admin: decoder.read()?,
+            },
+            59 => ContractEvent::AdminRenounced {
+                admin_address: decoder.read()?,
+                last_admin: decoder.read()?,
+            },
+            60 => ContractEvent::ContractPaused {
+                paused_by: decoder.read()?,
+                resume_at: decoder.read()?,
+            },
+            61 => ContractEvent::ContractUnpaused { unpaused_by: decoder.read()? },
+            62 => ContractEvent::CreatorQuotaSet {
+                creator_address: decoder.read()?,
+                poll_quota: decoder.read()?,
+            },
+            63 => ContractEvent::PollUpdated {
+                poll_id: decoder.read()?,
+                updated_by: decoder.read()?,
+            },
+            64 => ContractEvent::PollReopened {
+                poll_id: decoder.read()?,
+                reopened_by: decoder.read()?,
+                poll_end_timestamp: decoder.read()?,
+                votes_reset: decoder.read()?,
+            },
+            65 => ContractEvent::PollRerun {
+                poll_id: decoder.read()?,
+                previous_poll_id: decoder.read()?,
+            },
+            66 => ContractEvent::PollCompacted { poll_id: decoder.read()? },
+            67 => ContractEvent::RationaleAttached {
+                poll_id: decoder.read()?,
+                voter_address: decoder.read()?,
+            },
+            68 => ContractEvent::CommentPosted {
+                poll_id: decoder.read()?,
+                comment_id: decoder.read()?,
+                author: decoder.read()?,
+            },
+            69 => ContractEvent::CommentDeleted {
+                poll_id: decoder.read()?,
+                comment_id: decoder.read()?,
+                deleted_by: decoder.read()?,
+            },
+            _ => return Err(VotingError::CorruptState),
+        };
+        Ok(event)
+    }
+}
+
+// Define an entry in the contract's event log
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequencedEvent {
+    pub sequence: u64, // Position in the log, starting at 1 and never reused
+    pub event: ContractEvent,
+}
+
+impl Encode for SequencedEvent {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.sequence);
+        encoder.write(&self.event);
+    }
+}
+
+impl Decode for SequencedEvent {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(SequencedEvent {
+            sequence: decoder.read()?,
+            event: decoder.read()?,
+        })
+    }
+}
+
+// Callback invoked with every event the contract emits
+pub type EventHandler = Box<dyn Fn(&ContractEvent) + Send + Sync>;
+
+// Define the voting contract
+pub struct VotingContract {
+    pub active_polls: HashMap<PollId, Poll>,
+    pub archived_polls: HashMap<PollId, Poll>, // Archived polls moved out of the live map
+    pub access_control: AccessControl, // Roles held by privileged addresses
+    pub config: ContractConfig,    // Limits on poll contents, open polls per creator and duration
+    pub max_poll_extensions: u32,  // Times extend_poll may push back one poll's deadline
+    pub next_poll_id: u64,     // Number given to the next poll, from which its ID is generated
+    pub reject_duplicate_titles: bool, // Block a creator from opening two active polls with the same title
+    pub case_insensitive_options: bool, // Treat options differing only in case as duplicates
+    pub auto_expire: bool,         // Close polls past their end time as soon as a call touches them
+    pub voter_weights: HashMap<Address, u128>, // Registered voting weights; unregistered voters weigh 1
+    pub delegations: DelegationRegistry, // Standing delegations of voting power
+    signer_nonces: HashMap<Address, u64>, // Last nonce used by each signing address
+    rate_limiter: RateLimiter,      // Recent poll creations and votes of rate-limited addresses
+    moderation_log: Vec<ModerationRecord>, // Every moderation action taken, oldest first
+    poll_index: PollIndex,          // Live polls by tag and category, rebuilt rather than persisted
+    status_index: StatusIndex,      // Open polls by end time, rebuilt rather than persisted
+    event_log: Vec<SequencedEvent>, // Events in sequence order, oldest first
+    next_event_sequence: u64,       // Sequence number given to the next event
+    clock: Box<dyn Clock>,          // Source of the current time (system time unless replaced)
+    address_format: Box<dyn AddressFormat>, // Which addresses are valid (AnyAddress unless replaced)
+    id_generator: Box<dyn IdGenerator>, // Source of new poll IDs (hashed unless replaced)
+    execution_hooks: HashMap<PollId, Box<dyn ExecutionHook>>, // Actions waiting for their polls to pass
+    balance_provider: Option<Box<dyn BalanceProvider>>, // Source of token balances (all 0 when unset)
+    proof_verifier: Option<Box<dyn ProofVerifier>>, // Checks anonymous votes (all rejected when unset)
+    snapshot_provider: Option<Box<dyn SnapshotProvider>>, // Source of voting weights frozen at poll creation
+    on_event: Option<EventHandler>, // Optional event hook, only receives an immutable event
+    notifiers: Vec<Box<dyn Notifier>>, // Watchers told about every event (see notify.rs)
+    treasury: Treasury,             // Fees collected and not yet withdrawn, by poll
+    payment_provider: Option<Box<dyn PaymentProvider>>, // Mover of fee payments (fees fail when unset)
+    reward_pools: HashMap<PollId, RewardPool>, // Funded reward pools by poll (see rewards.rs)
+    elections: HashMap<String, Election>, // Multi-question elections by ID (see election.rs)
+    ledger_sequence: u64,           // Sequence number of the last instruction executed (see ledger.rs)
+    ledger_sinks: Vec<Box<dyn LedgerSink>>, // Write-ahead logs told about every executed instruction
+    pending_admin_transfer: Option<AdminTransfer>, // Handover waiting for the proposed admin (see admin.rs)
+    contract_pause: Option<ContractPause>, // Emergency stop on poll creation and voting (see pause.rs)
+    creators: HashMap<Address, CreatorRecord>, // Poll counts and quotas by creator (see creators.rs)
+    compacted_polls: HashMap<PollId, CompactedPoll>, // What is kept of purged archived polls (see compaction.rs)
+    discussions: HashMap<PollId, Vec<Comment>>, // Comments on each poll, in ID order (see discussion.rs)
+}
+
+impl VotingContract {
+    // Create a new voting contract
+    pub fn new(admin_address: Address) -> Self {
+        VotingContract {
+            active_polls: HashMap::new(),
+            archived_polls: HashMap::new(),
+            access_control: AccessControl::new(admin_address),
+            config: ContractConfig::default(),
+            max_poll_extensions: DEFAULT_MAX_POLL_EXTENSIONS,
+            next_poll_id: 1,
+            reject_duplicate_titles: false,
+            case_insensitive_options: false,
+            auto_expire: false,
+            rate_limiter: RateLimiter::default(),
+            moderation_log: Vec::new(),
+            voter_weights: HashMap::new(),
+            delegations: DelegationRegistry::new(),
+            signer_nonces: HashMap::new(),
+            poll_index: PollIndex::new(),
+            status_index: StatusIndex::new(),
+            event_log: Vec::new(),
+            next_event_sequence: 1,
+            clock: default_clock(),
+            address_format: Box::new(AnyAddress),
+            id_generator: Box::new(HashedIds),
+            execution_hooks: HashMap::new(),
+            balance_provider: None,
+            proof_verifier: None,
+            snapshot_provider: None,
+            on_event: None,
+            notifiers: Vec::new(),
+            treasury: Treasury::default(),
+            payment_provider: None,
+            reward_pools: HashMap::new(),
+            elections: HashMap::new(),
+            ledger_sequence: 0,
+            ledger_sinks: Vec::new(),
+            pending_admin_transfer: None,
+            contract_pause: None,
+            creators: HashMap::new(),
+            compacted_polls: HashMap::new(),
+            discussions: HashMap::new(),
+        }
+    }
+    
+    // Replace the time source, e.g. with block time in a runtime or a MockClock in tests
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+    
+    // Replace the poll ID scheme, e.g. with SequentialIds or on-chain seed derivation
+    pub fn set_id_generator(&mut self, id_generator: Box<dyn IdGenerator>) {
+        self.id_generator = id_generator;
+    }
+    
+    // Get the current time according to the contract's clock
+    pub fn now(&self) -> u64 {
+        self.clock.now()
+    }
+    
+    // Replace the address format, e.g. with HexAddress or Base58Address::bitcoin(). Role holders
+    // and a pending admin handover are rewritten in the new canonical form so permission checks
+    // keep matching them. Any other stored address could stop matching its owner, so the format
+    // can only change before the first poll, election, voting weight, delegation, signed vote or
+    // rate-limited action; WrongPhase is returned after that.
+    pub fn set_address_format(&mut self, address_format: Box<dyn AddressFormat>) -> Result<()> {
+        if self.holds_addresses() {
+            return Err(VotingError::WrongPhase);
+        }
+        self.address_format = address_format;
+        let address_format = self.address_format.as_ref();
+        let canonical = |address: &str| Address::parse(address, address_format).ok();
+        self.access_control.canonicalize(canonical);
+        if let Some(transfer) = &mut self.pending_admin_transfer {
+            for address in [&mut transfer.current_admin, &mut transfer.proposed_admin] {
+                if let Some(canonical_address) = canonical(address) {
+                    *address = canonical_address;
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    // Check whether the contract stores addresses other than role holders
+    fn holds_addresses(&self) -> bool {
+        !self.active_polls.is_empty()
+            || !self.archived_polls.is_empty()
+            || !self.compacted_polls.is_empty()
+            || !self.elections.is_empty()
+            || !self.voter_weights.is_empty()
+            || !self.delegations.is_empty()
+            || !self.signer_nonces.is_empty()
+            || !self.creators.is_empty()
+            || !self.rate_limiter.poll_creations.is_empty()
+            || !self.rate_limiter.votes.is_empty()
+    }
+    
+    // Parse an address in the contract's format (see address.rs)
+    pub fn parse_address(&self, address: &str) -> Result<Address> {
+        Address::parse(address, self.address_format.as_ref())
+    }
+    
+    // Get the address an ed25519 public key signs as in the contract's format, or InvalidAddress
+    // if the format has no address for it
+    pub fn key_address(&self, public_key: &[u8; 32]) -> Result<Address> {
+        let key_address = self.address_format.key_address(public_key).ok_or(VotingError::InvalidAddress)?;
+        self.parse_address(&key_address)
+    }
+    
+    // Normalize an address for a lookup that cannot fail. An invalid address is only trimmed, so
+    // it matches nothing stored in canonical form.
+    pub(crate) fn lookup_address(&self, address: &str) -> Address {
+        self.parse_address(address).unwrap_or_else(|_| Address::unchecked(address.trim().to_string()))
+    }
+    
+    // Serialize the contract state as a versioned snapshot (see state.rs)
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        self.to_state().to_bytes()
+    }
+    
+    // Restore a contract from a snapshot, upgrading older schema versions (see migrations.rs) and
+    // rejecting data that is internally inconsistent
+    pub fn from_snapshot(data: &[u8]) -> Result<Self> {
+        let mut state = ContractState::from_bytes(data)?;
+        migrations::migrate_state(&mut state)?;
+        VotingContract::from_state(&state)
+    }
+    
+    // Check the invariants a restored contract must satisfy
+    fn validate_invariants(&self) -> Result<()> {
+        let mut poll_numbers = HashSet::new();
+        for (poll_id, poll) in self.active_polls.iter().chain(&self.archived_polls) {
+            if *poll_id != poll.poll_id {
+                return Err(VotingError::CorruptState);
+            }
+            
+            // Poll numbers are handed out sequentially, so the counter must be past every existing
+            // one and no two polls share a number
+            if poll.poll_number == 0 || poll.poll_number >= self.next_poll_id || !poll_numbers.insert(poll.poll_number) {
+                return Err(VotingError::CorruptState);
+            }
+            
+            poll.validate_invariants()?;
+        }
+        
+        // Exactly the polls in the archive have the Archived status, and none is also live
+        for (poll_id, poll) in &self.archived_polls {
+            if poll.status != PollStatus::Archived || self.active_polls.contains_key(poll_id) {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        if self.active_polls.values().any(|poll| poll.status == PollStatus::Archived) {
+            return Err(VotingError::CorruptState);
+        }
+        
+        // Logged sequences strictly increase and stay below the counter
+        let mut previous_sequence = 0;
+        for logged_event in &self.event_log {
+            if logged_event.sequence <= previous_sequence || logged_event.sequence >= self.next_event_sequence {
+                return Err(VotingError::CorruptState);
+            }
+            previous_sequence = logged_event.sequence;
+        }
+        
+        Ok(())
+    }
+    
+    // Get an admin address for callers written against the single-admin API
+    // (the lowest sorted SuperAdmin when there are several)
+    pub fn admin_address(&self) -> &str {
+        self.access_control.members(Role::SuperAdmin).first().map_or("", |address| address.as_str())
+    }
+    
+    // Check whether an address holds a role
+    pub fn has_role(&self, address: &Address, role: Role) -> bool {
+        self.parse_address(address)
+            .is_ok_and(|address| self.access_control.has_role(&address, role))
+    }
+    
+    // Check whether an address holds a role granting a permission
+    pub fn has_permission(&self, address: &Address, permission: Permission) -> bool {
+        self.parse_address(address)
+            .is_ok_and(|address| self.access_control.has_permission(&address, permission))
+    }
+    
+    // Check whether an address is an admin (holds the SuperAdmin role)
+    pub fn is_admin(&self, wallet_address: &Address) -> bool {
+        self.has_role(wallet_address, Role::SuperAdmin)
+    }
+    
+    // Give an address a role (requires ManageRoles)
+    pub fn grant_role(&mut self, address: &Address, role: Role, caller: &Address) -> Result<()> {
+        let address = self.parse_address(address)?;
+        let caller = self.parse_address(caller)?;
+        if !self.has_permission(&caller, Permission::ManageRoles) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        if self.access_control.grant(address.clone(), role) {
+            self.emit(ContractEvent::RoleGranted {
+                address,
+                role,
+                granted_by: caller,
+            });
+        }
+        Ok(())
+    }
+    
+    // Take a role from an address (requires ManageRoles, the last SuperAdmin cannot be removed)
+    pub fn revoke_role(&mut self, address: &Address, role: Role, caller: &Address) -> Result<()> {
+        let address = self.parse_address(address)?;
+        let caller = self.parse_address(caller)?;
+        if !self.has_permission(&caller, Permission::ManageRoles) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        if self.access_control.revoke(&address, role)? {
+            self.emit(ContractEvent::RoleRevoked {
+                address,
+                role,
+                revoked_by: caller,
+            });
+        }
+        Ok(())
+    }
+    
+    // Grant admin rights to another address (shorthand for granting SuperAdmin)
+    pub fn add_admin(&mut self, new_admin_address: &Address, caller: &Address) -> Result<()> {
+        self.grant_role(new_admin_address, Role::SuperAdmin, caller)
+    }
+    
+    // Revoke an address's admin rights (shorthand for revoking SuperAdmin)
+    pub fn remove_admin(&mut self, admin_address: &Address, caller: &Address) -> Result<()> {
+        self.revoke_role(admin_address, Role::SuperAdmin, caller)
+    }
+    
+    // Register a callback that receives every contract event
+    pub fn set_event_handler(&mut self, handler: EventHandler) {
+        self.on_event = Some(handler);
+    }
+    
+    // Get the voting weight of an address (1 unless registered otherwise)
+    pub fn voter_weight(&self, voter_address: &Address) -> u128 {
+        self.voter_weights.get(&self.lookup_address(voter_address)).copied().unwrap_or(1)
+    }
+    
+    // Register the voting weight (e.g. token stake) of an address (requires SetVoterWeights).
+    // Weights are applied when a single-choice vote is cast, so later changes do not alter
+    // votes already recorded. Ranked ballots and quadratic votes are not weighted.
+    pub fn set_voter_weight(&mut self, voter_address: &Address, weight: u128, caller: &Address) -> Result<()> {
+        let voter_address = self.parse_address(voter_address)?;
+        if !self.has_permission(caller, Permission::SetVoterWeights) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        if weight == 1 {
+            self.voter_weights.remove(&voter_address);
+        } else {
+            self.voter_weights.insert(voter_address.clone(), weight);
+        }
+        self.emit(ContractEvent::VoterWeightSet { voter_address, weight });
+        Ok(())
+    }
+    
+    // Delegate an address's voting power to another address (replacing any previous delegation)
+    pub fn delegate_vote(&mut self, delegator_address: &Address, delegate_address: &Address) -> Result<()> {
+        let delegator_address = self.parse_address(delegator_address)?;
+        let delegate_address = self.parse_address(delegate_address)?;
+        self.delegations.delegate(delegator_address.clone(), delegate_address.clone())?;
+        self.emit(ContractEvent::DelegationSet {
+            delegator_address,
+            delegate_address,
+        });
+        Ok(())
+    }
+    
+    // Withdraw an address's delegation. Votes already cast on its behalf are unchanged.
+    pub fn revoke_delegation(&mut self, delegator_address: &Address) -> Result<()> {
+        let delegator_address = self.parse_address(delegator_address)?;
+        if self.delegations.revoke(&delegator_address) {
+            self.emit(ContractEvent::DelegationRevoked { delegator_address });
+        }
+        Ok(())
+    }
+    
+    // Look up a poll that can still be modified, reporting archived polls as closed and refusing
+    // finalized ones
+    fn live_poll_mut(&mut self, poll_id: &PollId) -> Result<&mut Poll> {
+        let poll = self.live_or_finalized_poll_mut(poll_id)?;
+        if poll.finalization.is_some() {
+            return Err(VotingError::PollFinalized);
+        }
+        Ok(poll)
+    }
+    
+    // Look up a poll that has not been archived, finalized or not
+    fn live_or_finalized_poll_mut(&mut self, poll_id: &PollId) -> Result<&mut Poll> {
+        self.expire_if_due(poll_id);
+        if self.archived_polls.contains_key(poll_id) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        self.active_polls.get_mut(poll_id).ok_or(VotingError::PollNotFound)
+    }
+    
+    // Append an event to the log and pass it to the event handler
+    fn emit(&mut self, event: ContractEvent) {
+        if let Some(handler) = &self.on_event {
+            handler(&event);
+        }
+        self.notify(&event);
+        
+        self.event_log.push(SequencedEvent {
+            sequence: self.next_event_sequence,
+            event,
+        });
+        self.next_event_sequence += 1;
+    }
+    
+    // Get the logged events with a sequence number greater than the given one, oldest first.
+    // Pass 0 for the whole log, or the last sequence already processed to catch up.
+    pub fn events_since(&self, sequence: u64) -> &[SequencedEvent] {
+        let first_index = self.event_log.partition_point(|logged_event| logged_event.sequence <= sequence);
+        &self.event_log[first_index..]
+    }
+    
+    // Drop logged events up to and including a sequence number once every reader has them,
+    // returning how many were dropped. Sequence numbers are never reused.
+    pub fn prune_events(&mut self, up_to_sequence: u64) -> usize {
+        let pruned_events = self.event_log.partition_point(|logged_event| logged_event.sequence <= up_to_sequence);
+        self.event_log.drain(..pruned_events);
+        pruned_events
+    }
+    
+    // Create a new poll
+    pub fn create_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        let new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create one poll per title from a shared template.
+    // Every poll is validated before any is stored, so either the whole batch is created or none is.
+    pub fn create_from_template(
+        &mut self,
+        creator_address: Address,
+        template: &PollTemplate,
+        poll_titles: Vec<String>,
+    ) -> Result<Vec<PollId>> {
+        let mut new_polls: Vec<Poll> = Vec::new();
+        for (batch_index, poll_title) in poll_titles.into_iter().enumerate() {
+            // Titles in the same batch must not duplicate each other either
+            if self.reject_duplicate_titles
+                && new_polls.iter().any(|new_poll| titles_match(&new_poll.poll_title, &poll_title))
+            {
+                return Err(VotingError::DuplicateTitle);
+            }
+            
+            let mut new_poll = self.build_poll(
+                self.next_poll_id + batch_index as u64,
+                creator_address.clone(),
+                poll_title,
+                template.poll_description.clone(),
+                template.voting_options.clone(),
+                template.poll_duration_seconds,
+            )?;
+            if new_polls.iter().any(|other_poll| other_poll.poll_id == new_poll.poll_id) {
+                return Err(VotingError::PollCreationFailed);
+            }
+            new_poll.tags = template.tags.clone();
+            new_polls.push(new_poll);
+        }
+        
+        // The whole batch counts against the creator's rate limit and poll quota
+        if let Some(new_poll) = new_polls.first() {
+            self.check_rate_limit(RateLimitedAction::PollCreation, &new_poll.poll_creator_address, new_polls.len())?;
+            self.check_poll_quota(&new_poll.poll_creator_address, new_polls.len())?;
+            self.check_creation_fee(&new_poll.poll_creator_address, new_polls.len())?;
+        }
+        
+        Ok(new_polls.into_iter().map(|new_poll| self.store_poll(new_poll)).collect())
+    }
+    
+    // Validate poll parameters and build the poll without storing it
+    fn build_poll(
+        &self,
+        poll_number: u64,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<Poll> {
+        self.check_not_paused()?;
+        let creator_address = self.parse_address(&creator_address)?;
+        self.check_creation_balance(&creator_address)?;
+        self.check_rate_limit(RateLimitedAction::PollCreation, &creator_address, 1)?;
+        self.check_creation_fee(&creator_address, 1)?;
+        
+        // Basic validation
+        if poll_options.len() < 2 {
+            return Err(VotingError::PollCreationFailed);
+        }
+        let poll_options = normalize_options(poll_options, self.case_insensitive_options)?;
+        self.config.check_poll_contents(&poll_title, &poll_description, &poll_options)?;
+        self.config.check_duration(poll_duration_seconds)?;
+        
+        let current_timestamp = self.now();
+        self.check_poll_quota(&creator_address, 1)?;
+        
+        // Closed polls may be re-run under the same title, active ones may not
+        if self.reject_duplicate_titles
+            && self.active_polls.values().any(|poll| {
+                (poll.is_active(current_timestamp) || poll.is_scheduled(current_timestamp))
+                    && poll.poll_creator_address == creator_address
+                    && titles_match(&poll.poll_title, &poll_title)
+            })
+        {
+            return Err(VotingError::DuplicateTitle);
+        }
+        
+        // Set up time boundaries
+        let poll_end_timestamp = current_timestamp
+            .checked_add(poll_duration_seconds)
+            .ok_or(VotingError::InvalidTimeSettings)?;
+        
+        self.new_poll(poll_number, creator_address, poll_title, poll_description, poll_options, poll_end_timestamp)
+    }
+    
+    // Build a poll that starts now from parameters that were already validated, generating its ID
+    pub(crate) fn new_poll(
+        &self,
+        poll_number: u64,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_end_timestamp: u64,
+    ) -> Result<Poll> {
+        let current_timestamp = self.now();
+        
+        // Generate unique ID for the poll
+        let poll_id: PollId = self
+            .id_generator
+            .poll_id(&IdSeed {
+                creator_address: &creator_address,
+                poll_title: &poll_title,
+                timestamp: current_timestamp,
+                poll_number,
+            })
+            .parse()
+            .map_err(|_| VotingError::PollCreationFailed)?;
+        if self.active_polls.contains_key(&poll_id)
+            || self.archived_polls.contains_key(&poll_id)
+            || self.compacted_polls.contains_key(&poll_id)
+        {
+            return Err(VotingError::PollCreationFailed);
+        }
+        
+        // Initialize vote counts for each option
+        let mut option_vote_counts = HashMap::new();
+        let mut option_voter_counts = HashMap::new();
+        for position in 0..poll_options.len() as u32 {
+            option_vote_counts.insert(OptionId(position), 0);
+            option_voter_counts.insert(OptionId(position), 0);
+        }
+        
+        Ok(Poll {
+            poll_id,
+            poll_number,
+            poll_title,
+            poll_description,
+            voting_options: poll_options,
+            vote_counts: option_vote_counts,
+            voter_counts: option_voter_counts,
+            participant_addresses: ParticipantSet::default(),
+            poll_creator_address: creator_address,
+            poll_start_timestamp: current_timestamp,
+            poll_end_timestamp,
+            status: PollStatus::Active,
+            quadratic_allocations: HashMap::new(),
+            min_winning_votes: 0,
+            creator_can_vote: true,
+            cached_winner: WinnerCache::new(),
+            tags: Vec::new(),
+            voting_method: VotingMethod::SingleChoice,
+            ranked_ballots: HashMap::new(),
+            weighted_voters: HashMap::new(),
+            delegated_votes: HashMap::new(),
+            commit_end_timestamp: None,
+            vote_commitments: HashMap::new(),
+            quorum_config: None,
+            allowlist: None,
+            denylist: HashSet::new(),
+            votes_by_address: HashMap::new(),
+            allow_vote_changes: false,
+            tie_break: TieBreak::default(),
+            allow_abstain: false,
+            allow_nota: false,
+            approval_ballots: HashMap::new(),
+            category: None,
+            extension_count: 0,
+            receipt_chain: Vec::new(),
+            ballot_root: None,
+            track_history: None,
+            history: Vec::new(),
+            action_executed_at: None,
+            parameter_change: None,
+            min_balance_to_vote: 0,
+            weight_snapshot: self.current_weight_snapshot(),
+            flags: Vec::new(),
+            hidden: false,
+            finalization: None,
+            metadata: BTreeMap::new(),
+            vote_fee: 0,
+            runoff: None,
+            previous_round: None,
+            next_round: None,
+            previous_poll_id: None,
+            tabulation_method: TabulationMethod::InstantRunoff,
+            conviction: None,
+            depends_on: Vec::new(),
+            dependencies_met: true,
+            anonymous: None,
+            encryption: None,
+            ciphertexts: Vec::new(),
+            encrypted_voters: HashSet::new(),
+            results_visibility: ResultsVisibility::Live,
+            rationales: Vec::new(),
+        })
+    }
+    
+    // Store a poll built for its creator, charging the creation to their rate limit and fee
+    fn store_poll(&mut self, new_poll: Poll) -> PollId {
+        let creator_address = new_poll.poll_creator_address.clone();
+        let poll_id = self.insert_poll(new_poll);
+        self.record_poll_created(&creator_address);
+        self.record_rate_limited(RateLimitedAction::PollCreation, &creator_address);
+        self.collect_fee(&poll_id, &creator_address, self.treasury.creation_fee);
+        poll_id
+    }
+    
+    // Store a built poll, advance the ID counter and announce the new poll
+    pub(crate) fn insert_poll(&mut self, new_poll: Poll) -> PollId {
+        let poll_id = new_poll.poll_id.clone();
+        let creator_address = new_poll.poll_creator_address.clone();
+        
+        self.poll_index.insert(&new_poll);
+        self.status_index.refresh(&new_poll);
+        self.active_polls.insert(poll_id.clone(), new_poll);
+        self.next_poll_id += 1;
+        self.emit(ContractEvent::PollCreated {
+            poll_id: poll_id.clone(),
+            creator_address,
+        });
+        
+        poll_id
+    }
+    
+    // Create a conflict-of-interest poll in which the creator is not allowed to vote
+    pub fn create_poll_without_creator_vote(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        let poll_id = self.create_poll(
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        
+        if let Some(poll) = self.active_polls.get_mut(&poll_id) {
+            poll.creator_can_vote = false;
+        }
+        
+        Ok(poll_id)
+    }
+    
+    // Create an editable draft that opens for voting, for the given duration, once published
+    pub fn create_draft_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.status = PollStatus::Draft;
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Replace a draft's title, description, options and duration (creator or ConfigurePolls)
+    pub fn update_draft(
+        &mut self,
+        poll_id: &PollId,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        caller: &Address,
+    ) -> Result<()> {
+        let config = self.config;
+        let case_insensitive_options = self.case_insensitive_options;
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        
+        if poll.status != PollStatus::Draft {
+            return Err(VotingError::InvalidTransition);
+        }
+        let poll_options = normalize_options(poll_options, case_insensitive_options)?;
+        config.check_poll_contents(&poll_title, &poll_description, &poll_options)?;
+        config.check_duration(poll_duration_seconds)?;
+        
+        // Reserved options stay on the ballot
+        let poll_options = with_reserved_options(poll_options, poll.allow_abstain, poll.allow_nota)?;
+        
+        let poll_end_timestamp = poll
+            .poll_start_timestamp
+            .checked_add(poll_duration_seconds)
+            .ok_or(VotingError::InvalidTimeSettings)?;
+            
+        // Drafts hold no votes, so the tallies are simply rebuilt for the new options
+        poll.vote_counts = (0..poll_options.len() as u32).map(|position| (OptionId(position), 0)).collect();
+        poll.voter_counts = (0..poll_options.len() as u32).map(|position| (OptionId(position), 0)).collect();
+        poll.poll_title = poll_title;
+        poll.poll_description = poll_description;
+        poll.voting_options = poll_options;
+        poll.poll_end_timestamp = poll_end_timestamp;
+        poll.invalidate_results();
+        
+        self.emit(ContractEvent::DraftUpdated { poll_id: poll_id.clone() });
+        Ok(())
+    }
+    
+    // Open a draft for voting from now on (creator or ConfigurePolls)
+    pub fn publish_poll(&mut self, poll_id: &PollId, caller: &Address) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        poll.publish(current_timestamp)?;
+        self.reindex_status(poll_id);
+        
+        self.emit(ContractEvent::PollPublished { poll_id: poll_id.clone() });
+        Ok(())
+    }
+    
+    // Stop an active poll from accepting votes until it is resumed (creator or ClosePolls).
+    // Its end time is not extended.
+    pub fn pause_poll(&mut self, poll_id: &PollId, caller: &Address) -> Result<()> {
+        let caller = self.parse_address(caller)?;
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, &caller, Permission::ClosePolls)?;
+        
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        poll.pause()?;
+        
+        self.emit(ContractEvent::PollPaused {
+            poll_id: poll_id.clone(),
+            paused_by: caller,
+        });
+        Ok(())
+    }
+    
+    // Let a paused poll accept votes again (creator or ClosePolls)
+    pub fn resume_poll(&mut self, poll_id: &PollId, caller: &Address) -> Result<()> {
+        let caller = self.parse_address(caller)?;
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, &caller, Permission::ClosePolls)?;
+        
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        poll.resume()?;
+        
+        self.emit(ContractEvent::PollResumed {
+            poll_id: poll_id.clone(),
+            resumed_by: caller,
+        });
+        Ok(())
+    }
+    
+    // Look up a live poll the caller may manage: its creator, or anyone holding the permission
+    fn managed_poll_mut(&mut self, poll_id: &PollId, caller: &Address, permission: Permission) -> Result<&mut Poll> {
+        let poll = self.managed_or_finalized_poll_mut(poll_id, caller, permission)?;
+        if poll.finalization.is_some() {
+            return Err(VotingError::PollFinalized);
+        }
+        Ok(poll)
+    }
+    
+    // Look up a poll the caller may manage that has not been archived, finalized or not
+    fn managed_or_finalized_poll_mut(
+        &mut self,
+        poll_id: &PollId,
+        caller: &Address,
+        permission: Permission,
+    ) -> Result<&mut Poll> {
+        let caller = self.parse_address(caller)?;
+        let caller_has_permission = self.has_permission(&caller, permission);
+        let poll = self.live_or_finalized_poll_mut(poll_id)?;
+        
+        if !caller_has_permission && caller != poll.poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        Ok(poll)
+    }
+    
+    // Create a poll that opens for voting after a delay and then runs for the given duration
+    pub fn create_scheduled_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        start_delay_seconds: u64,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        delay_start(&mut new_poll, start_delay_seconds)?;
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create a poll in which single-choice voters may change or retract their vote until it ends
+    pub fn create_poll_with_vote_changes(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.allow_vote_changes = true;
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create a poll that must meet a quorum to pass (see Poll::outcome)
+    pub fn create_poll_with_quorum(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        quorum_config: QuorumConfig,
+    ) -> Result<PollId> {
+        quorum_config.validate()?;
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.quorum_config = Some(quorum_config);
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create a poll whose ballot also offers ABSTAIN_OPTION and/or NOTA_OPTION. Voters choosing
+    // them count toward turnout and quorum but not toward any winner (see Poll::get_results).
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_poll_with_reserved_options(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        allow_abstain: bool,
+        allow_nota: bool,
+    ) -> Result<PollId> {
+        let poll_options = with_reserved_options(poll_options, allow_abstain, allow_nota)?;
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.allow_abstain = allow_abstain;
+        new_poll.allow_nota = allow_nota;
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create a membership-gated poll. With an allowlist only listed addresses may vote; listed
+    // denylist addresses may never vote. Either list can be edited until the first vote is cast.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_poll_with_eligibility(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        allowlist: Option<Vec<Address>>,
+        denylist: Vec<Address>,
+    ) -> Result<PollId> {
+        let allowlist = match allowlist {
+            Some(addresses) => {
+                Some(addresses.iter().map(|address| self.parse_address(address)).collect::<Result<_>>()?)
+            }
+            None => None,
+        };
+        let denylist = denylist.iter().map(|address| self.parse_address(address)).collect::<Result<_>>()?;
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.allowlist = allowlist;
+        new_poll.denylist = denylist;
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Make an address eligible in a poll before voting starts (creator or ConfigurePolls).
+    // The address is taken off the denylist and, in allowlisted polls, added to the allowlist.
+    pub fn add_eligible_voter(&mut self, poll_id: &PollId, voter_address: &Address, caller: &Address) -> Result<()> {
+        self.set_eligibility(poll_id, voter_address, true, caller)
+    }
+    
+    // Make an address ineligible in a poll before voting starts (creator or ConfigurePolls).
+    // Allowlisted polls drop the address from the allowlist, open polls add it to the denylist.
+    pub fn remove_eligible_voter(&mut self, poll_id: &PollId, voter_address: &Address, caller: &Address) -> Result<()> {
+        self.set_eligibility(poll_id, voter_address, false, caller)
+    }
+    
+    fn set_eligibility(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: &Address,
+        eligible: bool,
+        caller: &Address,
+    ) -> Result<()> {
+        let voter_address = self.parse_address(voter_address)?;
+        let caller = self.parse_address(caller)?;
+        let caller_can_configure = self.has_permission(&caller, Permission::ConfigurePolls);
+        let current_timestamp = self.now();
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !caller_can_configure && caller != poll.poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        // Changing the electorate after ballots exist would leave them uncounted or unjustified
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        if poll.is_eligible(&voter_address) == eligible {
+            return Ok(());
+        }
+        
+        match (&mut poll.allowlist, eligible) {
+            (Some(allowlist), true) => {
+                allowlist.insert(voter_address.clone());
+                poll.denylist.remove(&voter_address);
+            }
+            (None, true) => {
+                poll.denylist.remove(&voter_address);
+            }
+            (Some(allowlist), false) => {
+                allowlist.remove(&voter_address);
+            }
+            (None, false) => {
+                poll.denylist.insert(voter_address.clone());
+            }
+        }
+        
+        self.emit(ContractEvent::EligibilityChanged {
+            poll_id: poll_id.clone(),
+            voter_address,
+            eligible,
+        });
+        Ok(())
+    }
+    
+    // Create a ranked-choice poll tabulated by instant runoff
+    pub fn create_ranked_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    ) -> Result<PollId> {
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.voting_method = VotingMethod::RankedChoice;
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create a quadratic poll in which every voter gets the same budget of credits to spend.
+    // Placing N votes on one option costs N² credits, so strong preferences are expensive.
+    pub fn create_quadratic_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        credit_budget: u64,
+    ) -> Result<PollId> {
+        if credit_budget == 0 {
+            return Err(VotingError::PollCreationFailed);
+        }
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.voting_method = VotingMethod::Quadratic { credit_budget };
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create an approval poll in which each voter approves up to max_selections options in one
+    // ballot (see vote_multi). The option with the most approvals wins.
+    pub fn create_approval_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+        max_selections: usize,
+    ) -> Result<PollId> {
+        if max_selections == 0 || max_selections > poll_options.len() {
+            return Err(VotingError::PollCreationFailed);
+        }
+        
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.voting_method = VotingMethod::Approval { max_selections };
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Create a commit-reveal poll: voters submit vote_commitment(option, salt) during the commit
+    // window and reveal the option and salt afterwards. Only revealed votes are counted, so no
+    // results are visible while votes can still be committed.
+    pub fn create_commit_reveal_poll(
+        &mut self,
+        creator_address: Address,
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        commit_duration_seconds: u64,
+        reveal_duration_seconds: u64,
+    ) -> Result<PollId> {
+        if commit_duration_seconds == 0 || reveal_duration_seconds == 0 {
+            return Err(VotingError::InvalidTimeSettings);
+        }
+        let poll_duration_seconds = commit_duration_seconds
+            .checked_add(reveal_duration_seconds)
+            .ok_or(VotingError::InvalidTimeSettings)?;
+            
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            creator_address,
+            poll_title,
+            poll_description,
+            poll_options,
+            poll_duration_seconds,
+        )?;
+        new_poll.commit_end_timestamp = Some(new_poll.poll_start_timestamp + commit_duration_seconds);
+        
+        Ok(self.store_poll(new_poll))
+    }
+    
+    // Cast a vote in a poll and return its receipt. The option text must match one of the poll's
+    // options exactly.
+    pub fn vote(&mut self, poll_id: &PollId, voter_address: Address, selected_option: &str) -> Result<VoteReceipt> {
+        self.cast_vote(poll_id, voter_address, |poll| poll.option_id(selected_option))
+    }
+    
+    // Cast a vote for an option by its ID, as vote does
+    pub fn vote_option(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        option_id: OptionId,
+    ) -> Result<VoteReceipt> {
+        self.cast_vote(poll_id, voter_address, |poll| poll.option(option_id).is_ok().then_some(option_id))
+    }
+    
+    // Cast a single-choice vote for the option resolve_option picks from the poll (InvalidOption
+    // when it picks none). Tallies are updated by option ID.
+    fn cast_vote(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        resolve_option: impl FnOnce(&Poll) -> Option<OptionId>,
+    ) -> Result<VoteReceipt> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let voter_weight = self.ballot_weight(poll_id, &voter_address)?;
+        let current_timestamp = self.now();
+        let holds_balance = self.voter_holds_balance(poll_id, &voter_address);
+        let vote_fee = self.vote_fee_of(poll_id);
+        self.check_rate_limit(RateLimitedAction::Vote, &voter_address, 1)?;
+        self.check_fee(&voter_address, vote_fee)?;
+        
+        // Resolve the power delegated to this voter by addresses that have not voted in this poll
+        let carried_delegators: Vec<(Address, u128)> = match self.get_poll(poll_id) {
+            Ok(poll) => self
+                .delegations
+                .delegators_of(&voter_address, |address| poll.participant_addresses.contains(address))
+                .into_iter()
+                .filter(|delegator_address| {
+                    poll.is_eligible(delegator_address) && self.holds_voting_balance(poll, delegator_address)
+                })
+                .map(|delegator_address| {
+                    let delegator_weight = self.weight_in_poll(poll, &delegator_address)?;
+                    Ok((delegator_address, delegator_weight))
+                })
+                .collect::<Result<_>>()?,
+            Err(_) => Vec::new(),
+        };
+        
+        // Retrieve poll or return error
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        // Check if poll is active
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        // Ranked-choice polls take ordered ballots through vote_ranked, commit-reveal polls take
+        // commitments through commit_vote, anonymous polls nullifiers through vote_anonymous and
+        // encrypted polls ciphertexts through vote_encrypted
+        if poll.voting_method != VotingMethod::SingleChoice
+            || poll.commit_end_timestamp.is_some()
+            || poll.anonymous.is_some()
+            || poll.encryption.is_some()
+        {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        // Check the creator is allowed to vote (before the double-vote check so the error is specific)
+        if !poll.creator_can_vote && voter_address == poll.poll_creator_address {
+            return Err(VotingError::CreatorCannotVote);
+        }
+        
+        // Check the voter is on the poll's electorate
+        if !poll.is_eligible(&voter_address) {
+            return Err(VotingError::NotEligible);
+        }
+        
+        if !holds_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        
+        // Check if voter has already voted
+        if poll.participant_addresses.contains(&voter_address) {
+            return Err(VotingError::AlreadyVoted);
+        }
+        
+        // Check if option is valid
+        let option_id = resolve_option(poll).ok_or(VotingError::InvalidOption)?;
+        let selected_option = poll.option(option_id)?.to_string();
+        let selected_option = selected_option.as_str();
+        
+        // Voting directly overrides delegation for this poll: power a delegate already cast for
+        // this voter, or for the delegators now carried by this voter, is taken back first
+        let mut new_vote_counts = poll.vote_counts.clone();
+        let reclaimed_addresses = core::iter::once(&voter_address)
+            .chain(carried_delegators.iter().map(|(delegator_address, _)| delegator_address));
+        for reclaimed_address in reclaimed_addresses {
+            if let Some(delegated_vote) = poll.delegated_votes.get(reclaimed_address) {
+                let delegated_option_id = poll.option_id(&delegated_vote.selected_option);
+                let option_weight = delegated_option_id.and_then(|option_id| new_vote_counts.get_mut(&option_id));
+                if let Some(option_weight) = option_weight {
+                    *option_weight = option_weight.saturating_sub(delegated_vote.weight);
+                }
+            }
+        }
+        
+        // Record the weighted vote against the tally created with the poll, never adding a new bucket
+        let ballot_weight = carried_delegators
+            .iter()
+            .try_fold(voter_weight, |total, (_, delegator_weight)| total.checked_add(*delegator_weight))
+            .ok_or(VotingError::VoteCountOverflow)?;
+        let option_weight = new_vote_counts.get_mut(&option_id).ok_or(VotingError::InvalidOption)?;
+        *option_weight = option_weight.checked_add(ballot_weight).ok_or(VotingError::VoteCountOverflow)?;
+        let option_voters = poll.voter_counts.get(&option_id).copied().unwrap_or(0);
+        let option_voters = option_voters.checked_add(1).ok_or(VotingError::VoteCountOverflow)?;
+        
+        poll.vote_counts = new_vote_counts;
+        poll.voter_counts.insert(option_id, option_voters);
+        poll.invalidate_results();
+        if voter_weight != 1 {
+            poll.weighted_voters.insert(voter_address.clone(), voter_weight);
+        }
+        poll.delegated_votes.remove(&voter_address);
+        for (delegator_address, delegator_weight) in carried_delegators {
+            poll.delegated_votes.insert(delegator_address, DelegatedVote {
+                delegate_address: voter_address.clone(),
+                selected_option: selected_option.to_string(),
+                weight: delegator_weight,
+            });
+        }
+        
+        // Record that this wallet has voted
+        poll.participant_addresses.insert(voter_address.clone());
+        poll.votes_by_address.insert(voter_address.clone(), selected_option.to_string());
+        let receipt = poll.append_receipt(&voter_address, selected_option);
+        
+        self.poll_index.add_voter(poll_id, &voter_address);
+        self.record_rate_limited(RateLimitedAction::Vote, &voter_address);
+        self.collect_fee(poll_id, &voter_address, vote_fee);
+        self.track_tallies(poll_id);
+        self.emit(ContractEvent::VoteCast {
+            poll_id: poll_id.clone(),
+            voter_address,
+            selected_option: selected_option.to_string(),
+        });
+        
+        Ok(receipt)
+    }
+    
+    // Cast an ordered preference list in a ranked-choice poll.
+    // The ranking may list a subset of the options but must not repeat or invent any.
+    pub fn vote_ranked(&mut self, poll_id: &PollId, voter_address: Address, ranked_options: Vec<String>) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let current_timestamp = self.now();
+        let holds_balance = self.voter_holds_balance(poll_id, &voter_address);
+        let vote_fee = self.vote_fee_of(poll_id);
+        self.check_rate_limit(RateLimitedAction::Vote, &voter_address, 1)?;
+        self.check_fee(&voter_address, vote_fee)?;
+        
+        self.config.check_option_count(ranked_options.len())?;
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        if poll.voting_method != VotingMethod::RankedChoice {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        if !poll.creator_can_vote && voter_address == poll.poll_creator_address {
+            return Err(VotingError::CreatorCannotVote);
+        }
+        
+        if !poll.is_eligible(&voter_address) {
+            return Err(VotingError::NotEligible);
+        }
+        
+        if !holds_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        
+        if poll.participant_addresses.contains(&voter_address) {
+            return Err(VotingError::AlreadyVoted);
+        }
+        
+        // Validate the ranking
+        if ranked_options.is_empty() {
+            return Err(VotingError::InvalidRanking);
+        }
+        let mut seen_options = HashSet::new();
+        let mut ranked_option_ids = Vec::with_capacity(ranked_options.len());
+        for ranked_option in &ranked_options {
+            let option_id = poll.option_id(ranked_option).ok_or(VotingError::InvalidOption)?;
+            if !seen_options.insert(option_id) {
+                return Err(VotingError::InvalidRanking);
+            }
+            ranked_option_ids.push(option_id);
+        }
+        
+        // First preferences are kept in vote_counts, the full ranking drives tabulation
+        let first_choice = ranked_options[0].clone();
+        poll.add_to_tally(ranked_option_ids[0], 1, true)?;
+        
+        poll.ranked_ballots.insert(voter_address.clone(), ranked_options);
+        poll.participant_addresses.insert(voter_address.clone());
+        
+        self.poll_index.add_voter(poll_id, &voter_address);
+        self.record_rate_limited(RateLimitedAction::Vote, &voter_address);
+        self.collect_fee(poll_id, &voter_address, vote_fee);
+        self.track_tallies(poll_id);
+        self.emit(ContractEvent::VoteCast {
+            poll_id: poll_id.clone(),
+            voter_address,
+            selected_option: first_choice,
+        });
+        
+        Ok(())
+    }
+    
+    // Approve up to max_selections options of an approval poll in a single ballot.
+    // Every approved option gains one vote; repeating an option returns DuplicateSelection and
+    // approving more than the limit TooManySelections.
+    pub fn vote_multi(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        selected_options: Vec<String>,
+    ) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let current_timestamp = self.now();
+        let holds_balance = self.voter_holds_balance(poll_id, &voter_address);
+        let vote_fee = self.vote_fee_of(poll_id);
+        self.check_rate_limit(RateLimitedAction::Vote, &voter_address, 1)?;
+        self.check_fee(&voter_address, vote_fee)?;
+        
+        self.config.check_option_count(selected_options.len())?;
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        let VotingMethod::Approval { max_selections } = poll.voting_method else {
+            return Err(VotingError::WrongVotingMethod);
+        };
+        
+        if !poll.creator_can_vote && voter_address == poll.poll_creator_address {
+            return Err(VotingError::CreatorCannotVote);
+        }
+        
+        if !poll.is_eligible(&voter_address) {
+            return Err(VotingError::NotEligible);
+        }
+        
+        if !holds_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        
+        if poll.participant_addresses.contains(&voter_address) {
+            return Err(VotingError::AlreadyVoted);
+        }
+        
+        // Validate the ballot
+        if selected_options.is_empty() {
+            return Err(VotingError::InvalidOption);
+        }
+        let mut seen_options = HashSet::new();
+        let mut selected_option_ids = Vec::with_capacity(selected_options.len());
+        for selected_option in &selected_options {
+            let option_id = poll.option_id(selected_option).ok_or(VotingError::InvalidOption)?;
+            if !seen_options.insert(option_id) {
+                return Err(VotingError::DuplicateSelection);
+            }
+            selected_option_ids.push(option_id);
+        }
+        if selected_options.len() > max_selections {
+            return Err(VotingError::TooManySelections);
+        }
+        
+        for option_id in selected_option_ids {
+            poll.add_to_tally(option_id, 1, true)?;
+        }
+        poll.approval_ballots.insert(voter_address.clone(), selected_options.clone());
+        poll.participant_addresses.insert(voter_address.clone());
+        
+        self.poll_index.add_voter(poll_id, &voter_address);
+        self.record_rate_limited(RateLimitedAction::Vote, &voter_address);
+        self.collect_fee(poll_id, &voter_address, vote_fee);
+        self.track_tallies(poll_id);
+        for selected_option in selected_options {
+            self.emit(ContractEvent::VoteCast {
+                poll_id: poll_id.clone(),
+                voter_address: voter_address.clone(),
+                selected_option,
+            });
+        }
+        
+        Ok(())
+    }
+    
+    // Cast quadratic votes in a quadratic poll: N votes on one option cost N² credits from the
+    // budget set at creation. Repeated calls draw down the same budget, and adding votes to an
+    // option that already has some only charges the difference between the new and old squared totals.
+    pub fn vote_quadratic(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        selected_option: &str,
+        num_votes: usize,
+    ) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let current_timestamp = self.now();
+        let holds_balance = self.voter_holds_balance(poll_id, &voter_address);
+        let vote_fee = self.vote_fee_of(poll_id);
+        self.check_rate_limit(RateLimitedAction::Vote, &voter_address, 1)?;
+        self.check_fee(&voter_address, vote_fee)?;
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        let VotingMethod::Quadratic { credit_budget } = poll.voting_method else {
+            return Err(VotingError::WrongVotingMethod);
+        };
+        
+        if !poll.creator_can_vote && voter_address == poll.poll_creator_address {
+            return Err(VotingError::CreatorCannotVote);
+        }
+        
+        if !poll.is_eligible(&voter_address) {
+            return Err(VotingError::NotEligible);
+        }
+        
+        if !holds_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        
+        let option_id = poll.option_id(selected_option).ok_or(VotingError::InvalidOption)?;
+        
+        // Work out the marginal cost of the extra votes on this option
+        let previous_votes = poll.quadratic_votes(&voter_address, selected_option) as u64;
+        let new_votes = previous_votes
+            .checked_add(num_votes as u64)
+            .ok_or(VotingError::InsufficientCredits)?;
+        let additional_cost = new_votes
+            .checked_mul(new_votes)
+            .ok_or(VotingError::InsufficientCredits)?
+            - previous_votes * previous_votes;
+        let total_spent = poll
+            .credits_spent(&voter_address)
+            .checked_add(additional_cost)
+            .ok_or(VotingError::InsufficientCredits)?;
+        if total_spent > credit_budget {
+            return Err(VotingError::InsufficientCredits);
+        }
+        
+        // Record the votes against the tally created with the poll
+        let new_backing_voter = !poll
+            .quadratic_allocations
+            .get(&voter_address)
+            .is_some_and(|allocations| allocations.contains_key(selected_option));
+        poll.add_to_tally(option_id, num_votes as u128, new_backing_voter)?;
+        
+        poll.quadratic_allocations
+            .entry(voter_address.clone())
+            .or_default()
+            .insert(selected_option.to_string(), new_votes as usize);
+        poll.participant_addresses.insert(voter_address.clone());
+        
+        self.poll_index.add_voter(poll_id, &voter_address);
+        self.record_rate_limited(RateLimitedAction::Vote, &voter_address);
+        self.collect_fee(poll_id, &voter_address, vote_fee);
+        self.track_tallies(poll_id);
+        self.emit(ContractEvent::VoteCast {
+            poll_id: poll_id.clone(),
+            voter_address,
+            selected_option: selected_option.to_string(),
+        });
+        
+        Ok(())
+    }
+    
+    // Spend credits on one option of a quadratic poll instead of naming a vote count. The option's
+    // squared total after spending must be a whole number of votes: with 1 vote placed, 3 more
+    // credits buy a second vote (4 = 2²) while 2 credits would buy a fraction of one and are
+    // rejected with FractionalVotes.
+    pub fn spend_quadratic_credits(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        selected_option: &str,
+        credits: u64,
+    ) -> Result<()> {
+        let voter_address = self.parse_address(&voter_address)?;
+        let poll = self.get_poll(poll_id)?;
+        
+        if !matches!(poll.voting_method, VotingMethod::Quadratic { .. }) {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        let previous_votes = poll.quadratic_votes(&voter_address, selected_option) as u64;
+        let squared_total = (previous_votes * previous_votes)
+            .checked_add(credits)
+            .ok_or(VotingError::InsufficientCredits)?;
+        let new_votes = squared_total.isqrt();
+        if new_votes * new_votes != squared_total {
+            return Err(VotingError::FractionalVotes);
+        }
+        
+        self.vote_quadratic(poll_id, voter_address, selected_option, (new_votes - previous_votes) as usize)
+    }
+    
+    // Submit a vote commitment during a commit-reveal poll's commit window
+    pub fn commit_vote(&mut self, poll_id: &PollId, voter_address: Address, commitment: [u8; 32]) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let current_timestamp = self.now();
+        let holds_balance = self.voter_holds_balance(poll_id, &voter_address);
+        let vote_fee = self.vote_fee_of(poll_id);
+        self.check_rate_limit(RateLimitedAction::Vote, &voter_address, 1)?;
+        self.check_fee(&voter_address, vote_fee)?;
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        if poll.commit_end_timestamp.is_none() {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        if !poll.in_commit_phase(current_timestamp) {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        if !poll.creator_can_vote && voter_address == poll.poll_creator_address {
+            return Err(VotingError::CreatorCannotVote);
+        }
+        
+        if !poll.is_eligible(&voter_address) {
+            return Err(VotingError::NotEligible);
+        }
+        
+        if !holds_balance {
+            return Err(VotingError::InsufficientBalance);
+        }
+        
+        if poll.participant_addresses.contains(&voter_address) || poll.vote_commitments.contains_key(&voter_address) {
+            return Err(VotingError::AlreadyVoted);
+        }
+        
+        poll.vote_commitments.insert(voter_address.clone(), commitment);
+        self.record_rate_limited(RateLimitedAction::Vote, &voter_address);
+        self.collect_fee(poll_id, &voter_address, vote_fee);
+        self.emit(ContractEvent::VoteCommitted {
+            poll_id: poll_id.clone(),
+            voter_address,
+        });
+        Ok(())
+    }
+    
+    // Reveal a committed vote during a commit-reveal poll's reveal window.
+    // The voter's registered weight is applied at reveal time; delegations are not carried.
+    pub fn reveal_vote(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        selected_option: &str,
+        salt: &[u8],
+    ) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let voter_weight = self.ballot_weight(poll_id, &voter_address)?;
+        let current_timestamp = self.now();
+        
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        if poll.commit_end_timestamp.is_none() {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        if !poll.in_reveal_phase(current_timestamp) {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        let commitment = poll.vote_commitments.get(&voter_address).ok_or(VotingError::NoCommitment)?;
+        if *commitment != vote_commitment(selected_option, salt) {
+            return Err(VotingError::CommitmentMismatch);
+        }
+        
+        let option_id = poll.option_id(selected_option).ok_or(VotingError::InvalidOption)?;
+        poll.add_to_tally(option_id, voter_weight, true)?;
+        if voter_weight != 1 {
+            poll.weighted_voters.insert(voter_address.clone(), voter_weight);
+        }
+        poll.vote_commitments.remove(&voter_address);
+        poll.participant_addresses.insert(voter_address.clone());
+        poll.votes_by_address.insert(voter_address.clone(), selected_option.to_string());
+        
+        self.poll_index.add_voter(poll_id, &voter_address);
+        self.track_tallies(poll_id);
+        self.emit(ContractEvent::VoteCast {
+            poll_id: poll_id.clone(),
+            voter_address,
+            selected_option: selected_option.to_string(),
+        });
+        
+        Ok(())
+    }
+    
+    // Move a single-choice vote to another option while the poll is active, together with the
+    // power of any delegators it carries (requires allow_vote_changes)
+    pub fn change_vote(&mut self, poll_id: &PollId, voter_address: Address, new_option: &str) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let current_timestamp = self.now();
+        
+        let poll = self.changeable_poll_mut(poll_id, current_timestamp)?;
+        let previous_option = poll.single_choice_vote(&voter_address)?;
+        
+        let new_option_id = poll.option_id(new_option).ok_or(VotingError::InvalidOption)?;
+        
+        if previous_option == new_option {
+            return Ok(());
+        }
+        
+        // Add before removing so an overflow leaves the tally untouched
+        let previous_option_id = poll.option_id(&previous_option).ok_or(VotingError::CorruptState)?;
+        let ballot_weight = poll.ballot_weight(&voter_address);
+        poll.add_to_tally(new_option_id, ballot_weight, true)?;
+        poll.remove_from_tally(previous_option_id, ballot_weight)?;
+        for delegated_vote in poll.delegated_votes.values_mut() {
+            if delegated_vote.delegate_address == voter_address {
+                delegated_vote.selected_option = new_option.to_string();
+            }
+        }
+        poll.votes_by_address.insert(voter_address.clone(), new_option.to_string());
+        
+        self.track_tallies(poll_id);
+        self.emit(ContractEvent::VoteChanged {
+            poll_id: poll_id.clone(),
+            voter_address,
+            previous_option,
+            selected_option: new_option.to_string(),
+        });
+        Ok(())
+    }
+    
+    // Withdraw a single-choice vote while the poll is active (requires allow_vote_changes).
+    // Delegators it carried are released, and the voter may vote again later.
+    pub fn retract_vote(&mut self, poll_id: &PollId, voter_address: Address) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let current_timestamp = self.now();
+        
+        let poll = self.changeable_poll_mut(poll_id, current_timestamp)?;
+        let previous_option = poll.single_choice_vote(&voter_address)?;
+        
+        let previous_option_id = poll.option_id(&previous_option).ok_or(VotingError::CorruptState)?;
+        let ballot_weight = poll.ballot_weight(&voter_address);
+        poll.remove_from_tally(previous_option_id, ballot_weight)?;
+        poll.delegated_votes.retain(|_, delegated_vote| delegated_vote.delegate_address != voter_address);
+        poll.weighted_voters.remove(&voter_address);
+        poll.votes_by_address.remove(&voter_address);
+        poll.rationales.retain(|rationale| rationale.voter_address != voter_address);
+        poll.participant_addresses.remove(&voter_address);
+        self.poll_index.remove_voter(poll_id, &voter_address);
+        self.track_tallies(poll_id);
+        
+        self.emit(ContractEvent::VoteRetracted {
+            poll_id: poll_id.clone(),
+            voter_address,
+            previous_option,
+        });
+        Ok(())
+    }
+    
+    // Look up an active poll whose votes may be changed
+    fn changeable_poll_mut(&mut self, poll_id: &PollId, current_timestamp: u64) -> Result<&mut Poll> {
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        if !poll.allow_vote_changes {
+            return Err(VotingError::VoteChangesDisabled);
+        }
+        
+        Ok(poll)
+    }
+    
+    // Get details of a specific poll
+    pub fn get_poll(&self, poll_id: &PollId) -> Result<&Poll> {
+        self.active_polls
+            .get(poll_id)
+            .or_else(|| self.archived_polls.get(poll_id))
+            .ok_or(VotingError::PollNotFound)
+    }
+    
+    // Get results of a specific poll (ResultsHidden while its results are hidden, see visibility.rs),
+    // compacted polls included
+    pub fn get_poll_results(&self, poll_id: &PollId) -> Result<HashMap<String, u128>> {
+        if let Some(compacted_poll) = self.compacted_polls.get(poll_id) {
+            return Ok(compacted_poll.results.clone());
+        }
+        let poll = self.get_poll(poll_id)?;
+        self.check_results_visible(poll, None)?;
+        Ok(poll.get_results())
+    }
+    
+    // Get the approvals per option and number of ballots of an approval poll
+    pub fn get_approval_results(&self, poll_id: &PollId) -> Result<ApprovalResults> {
+        let poll = self.get_poll(poll_id)?;
+        self.check_results_visible(poll, None)?;
+        poll.approval_results()
+    }
+    
+    // Get the votes cast for a poll's abstain and none-of-the-above options
+    pub fn get_reserved_results(&self, poll_id: &PollId) -> Result<HashMap<String, u128>> {
+        let poll = self.get_poll(poll_id)?;
+        self.check_results_visible(poll, None)?;
+        Ok(poll.reserved_results())
+    }
+    
+    // Get the weighted tally and backing voter count of every option in a poll (reserved ones included)
+    pub fn get_weighted_results(&self, poll_id: &PollId) -> Result<HashMap<String, OptionTally>> {
+        let poll = self.get_poll(poll_id)?;
+        self.check_results_visible(poll, None)?;
+        Ok(poll.get_weighted_results())
+    }
+    
+    // Get a summary of a specific poll (live numbers for open polls, without the leading options
+    // while its results are hidden)
+    pub fn get_poll_summary(&self, poll_id: &PollId) -> Result<PollSummary> {
+        let poll = self.get_poll(poll_id)?;
+        let mut summary = poll.summary(self.now());
+        if self.check_results_visible(poll, None).is_err() {
+            summary.leading_options.clear();
+            summary.leading_votes = 0;
+            summary.is_tie = false;
+        }
+        Ok(summary)
+    }
+    
+    // Get the outcome of a specific poll (None while it is still active)
+    pub fn get_poll_outcome(&self, poll_id: &PollId) -> Result<Option<PollOutcome>> {
+        if let Some(compacted_poll) = self.compacted_polls.get(poll_id) {
+            return Ok(compacted_poll.outcome);
+        }
+        let poll = self.get_poll(poll_id)?;
+        Ok(poll.outcome(self.now()))
+    }
+    
+    // Get the winner of a specific poll, from its cached winner while its results are unchanged.
+    // The first read after a change, or the poll closing, fills the cache again.
+    pub fn get_winner(&self, poll_id: &PollId) -> Result<WinnerOutcome> {
+        self.get_poll(poll_id)?.get_cached_winner()
+    }
+    
+    // Get a poll's winner with the tie-break applied
+    pub fn get_poll_winner(&self, poll_id: &PollId) -> Result<PollWinner> {
+        let poll = self.get_poll(poll_id)?;
+        poll.winner()
+    }
+    
+    // Choose how a poll's ties are broken (creator or ConfigurePolls). The strategy is fixed once
+    // voting starts so it cannot be picked with the results in view.
+    pub fn set_tie_break(&mut self, poll_id: &PollId, tie_break: TieBreak, caller: &Address) -> Result<()> {
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.tie_break = tie_break;
+        self.emit(ContractEvent::TieBreakSet {
+            poll_id: poll_id.clone(),
+            tie_break,
+        });
+        Ok(())
+    }
+    
+    // Replace a poll's tags (creator or ConfigurePolls). Tags are trimmed and repeats dropped;
+    // an empty tag returns InvalidTag.
+    pub fn set_poll_tags(&mut self, poll_id: &PollId, tags: Vec<String>, caller: &Address) -> Result<()> {
+        let normalized_tags = normalize_tags(&tags)?;
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        let previous_tags = core::mem::replace(&mut poll.tags, normalized_tags.clone());
+        self.poll_index.set_tags(poll_id, &previous_tags, &normalized_tags);
+        
+        self.emit(ContractEvent::TagsSet {
+            poll_id: poll_id.clone(),
+            tags: normalized_tags,
+        });
+        Ok(())
+    }
+    
+    // File a poll under a category, or clear it with None (creator or ConfigurePolls)
+    pub fn set_poll_category(&mut self, poll_id: &PollId, category: Option<String>, caller: &Address) -> Result<()> {
+        let category = category.as_deref().map(normalize_label).transpose()?;
+        
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        let previous_category = core::mem::replace(&mut poll.category, category.clone());
+        self.poll_index.set_category(poll_id, previous_category.as_deref(), category.as_deref());
+        
+        self.emit(ContractEvent::CategorySet {
+            poll_id: poll_id.clone(),
+            category,
+        });
+        Ok(())
+    }
+    
+    // Set the votes the leading option needs to win (creator or ConfigurePolls, 0 disables). Like
+    // the tie-break, the threshold is fixed once voting starts.
+    pub fn set_min_winning_votes(
+        &mut self,
+        poll_id: &PollId,
+        min_winning_votes: u128,
+        wallet_address: &Address,
+    ) -> Result<()> {
+        let wallet_address = self.parse_address(wallet_address)?;
+        let caller_can_configure = self.has_permission(&wallet_address, Permission::ConfigurePolls);
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !caller_can_configure && wallet_address != poll.poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.min_winning_votes = min_winning_votes;
+        poll.invalidate_results();
+        self.emit(ContractEvent::MinWinningVotesSet {
+            poll_id: poll_id.clone(),
+            min_winning_votes,
+        });
+        Ok(())
+    }
+    
+    // Get the seconds left until a poll ends
+    pub fn time_remaining(&self, poll_id: &PollId) -> Result<u64> {
+        let poll = self.get_poll(poll_id)?;
+        Ok(poll.time_remaining(self.now()))
+    }
+    
+    // Check if the poll is active
+    pub fn is_poll_active(&self, poll_id: &PollId) -> Result<bool> {
+        let poll = self.get_poll(poll_id)?;
+        Ok(poll.is_active(self.now()))
+    }
+    
+    // Iterate over all polls, including archived ones, without allocating
+    pub fn iter_polls(&self) -> impl Iterator<Item = &Poll> {
+        self.active_polls.values().chain(self.archived_polls.values())
+    }
+    
+    // Iterate over active polls (archived and hidden polls are never listed)
+    pub fn iter_active_polls(&self) -> impl Iterator<Item = &Poll> {
+        let current_timestamp = self.now();
+        self.status_index
+            .ending_after(current_timestamp)
+            .filter_map(|poll_id| self.active_polls.get(poll_id))
+            .filter(move |poll| !poll.hidden && poll.is_active(current_timestamp))
+    }
+    
+    // Get the active polls ending within the given number of seconds, soonest first
+    pub fn get_polls_ending_within(&self, window_seconds: u64) -> Vec<&Poll> {
+        let window_end_timestamp = self.now().saturating_add(window_seconds);
+        self.iter_active_polls()
+            .take_while(|poll| poll.poll_end_timestamp <= window_end_timestamp)
+            .collect()
+    }
+    
+    // Bring a poll's status index entry up to date after its status or end time changed
+    pub(crate) fn reindex_status(&mut self, poll_id: &PollId) {
+        match self.active_polls.get(poll_id) {
+            Some(poll) => self.status_index.refresh(poll),
+            None => self.status_index.remove(poll_id),
+        }
+    }
+    
+    // Get all polls, including archived ones
+    pub fn get_all_polls(&self) -> Vec<&Poll> {
+        self.iter_polls().collect()
+    }
+    
+    // Get all active polls
+    pub fn get_active_polls(&self) -> Vec<&Poll> {
+        self.iter_active_polls().collect()
+    }
+    
+    // List the polls matching a filter in the requested order, skipping `offset` matches and
+    // returning at most `limit`. Archived polls are only listed when the filter asks for them, and
+    // hidden polls never are.
+    pub fn list_polls(&self, filter: &PollFilter, sort: PollSort, offset: usize, limit: usize) -> Vec<&Poll> {
+        let current_timestamp = self.now();
+        let mut matching_polls: Vec<&Poll> = self
+            .iter_polls()
+            .filter(|poll| !poll.hidden && filter.matches(poll, current_timestamp))
+            .collect();
+            
+        matching_polls.sort_by(|first, second| {
+            let ordering = match sort {
+                PollSort::Newest => second.poll_start_timestamp.cmp(&first.poll_start_timestamp),
+                PollSort::EndingSoonest => first.poll_end_timestamp.cmp(&second.poll_end_timestamp),
+                PollSort::MostVotes => second.total_votes().cmp(&first.total_votes()),
+            };
+            ordering.then_with(|| second.poll_number.cmp(&first.poll_number))
+        });
+        
+        matching_polls.into_iter().skip(offset).take(limit).collect()
+    }
+    
+    // List the live polls carrying a tag, oldest first (archived polls are not indexed, and hidden
+    // ones are left out)
+    pub fn list_polls_by_tag(&self, tag: &str) -> Vec<&Poll> {
+        self.indexed_polls(self.poll_index.polls_with_tag(tag.trim()))
+    }
+    
+    // List the live polls filed under a category, oldest first
+    pub fn list_polls_by_category(&self, category: &str) -> Vec<&Poll> {
+        self.indexed_polls(self.poll_index.polls_in_category(category.trim()))
+    }
+    
+    // List the categories used by live polls, sorted
+    pub fn list_categories(&self) -> Vec<String> {
+        self.poll_index.categories().into_iter().map(str::to_string).collect()
+    }
+    
+    // List the polls an address created, live and archived, oldest first
+    pub fn get_polls_by_creator(&self, creator_address: &Address) -> Vec<&Poll> {
+        let mut polls: Vec<&Poll> = self
+            .poll_index
+            .polls_created_by(&self.lookup_address(creator_address))
+            .filter_map(|poll_id| self.get_poll(poll_id).ok())
+            .collect();
+        polls.sort_by_key(|poll| poll.poll_number);
+        polls
+    }
+    
+    // List the polls holding an address's ballot, live and archived, oldest first. Retracted votes
+    // and unrevealed commitments are not included.
+    pub fn get_votes_by_address(&self, voter_address: &Address) -> Vec<VoteRecord> {
+        let voter_address = self.lookup_address(voter_address);
+        let mut polls: Vec<&Poll> = self
+            .poll_index
+            .polls_voted_in(&voter_address)
+            .filter_map(|poll_id| self.get_poll(poll_id).ok())
+            .collect();
+        polls.sort_by_key(|poll| poll.poll_number);
+        polls
+            .into_iter()
+            .map(|poll| VoteRecord {
+                poll_id: poll.poll_id.clone(),
+                selected_options: poll.ballot_options(&voter_address).unwrap_or_default(),
+            })
+            .collect()
+    }
+    
+    fn indexed_polls<'a>(&'a self, poll_ids: impl Iterator<Item = &'a PollId>) -> Vec<&'a Poll> {
+        let mut polls: Vec<&Poll> = poll_ids
+            .filter_map(|poll_id| self.active_polls.get(poll_id))
+            .filter(|poll| !poll.hidden)
+            .collect();
+        polls.sort_by_key(|poll| poll.poll_number);
+        polls
+    }
+    
+    // Move every closed poll into the archive, returning how many were moved
+    pub fn archive_closed_polls(&mut self) -> usize {
+        let mut closed_poll_ids: Vec<PollId> = self
+            .active_polls
+            .values()
+            .filter(|poll| poll.status == PollStatus::Closed)
+            .map(|poll| poll.poll_id.clone())
+            .collect();
+        closed_poll_ids.sort();
+        
+        for poll_id in &closed_poll_ids {
+            self.move_to_archive(poll_id);
+        }
+        
+        closed_poll_ids.len()
+    }
+    
+    // Archive one closed poll (creator or ClosePolls). It stays queryable through get_poll.
+    // Archiving leaves a poll's results alone, so finalized polls can be archived too.
+    pub fn archive_poll(&mut self, poll_id: &PollId, caller: &Address) -> Result<()> {
+        let poll = self.managed_or_finalized_poll_mut(poll_id, caller, Permission::ClosePolls)?;
+        if !poll.status.can_transition_to(PollStatus::Archived) {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        self.move_to_archive(poll_id);
+        Ok(())
+    }
+    
+    fn move_to_archive(&mut self, poll_id: &PollId) {
+        if let Some(mut poll) = self.active_polls.remove(poll_id) {
+            if poll.archive().is_ok() {
+                self.poll_index.remove(&poll);
+                self.status_index.remove(poll_id);
+                self.archived_polls.insert(poll_id.clone(), poll);
+                self.emit(ContractEvent::PollArchived { poll_id: poll_id.clone() });
+            } else {
+                self.active_polls.insert(poll_id.clone(), poll);
+            }
+        }
+    }
+    
+    // Manually close a poll (creator or ClosePolls)
+    pub fn close_poll(&mut self, poll_id: &PollId, wallet_address: &Address) -> Result<()> {
+        let wallet_address = self.parse_address(wallet_address)?;
+        let caller_can_close = self.has_permission(&wallet_address, Permission::ClosePolls);
+        let current_timestamp = self.now();
+        self.expire_if_due(poll_id);
+        if self.archived_polls.contains_key(poll_id) {
+            return Err(VotingError::AlreadyClosed);
+        }
+        let poll = self.active_polls.get_mut(poll_id).ok_or(VotingError::PollNotFound)?;
+        
+        // Only the poll creator or a role allowed to close polls can close the poll
+        if !caller_can_close && wallet_address != poll.poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        // A poll past its end time already ended by expiration; process_expired_polls records that
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::AlreadyClosed);
+        }
+        
+        poll.close()?;
+        self.reindex_status(poll_id);
+        self.emit(ContractEvent::PollClosed {
+            poll_id: poll_id.clone(),
+            closed_by: wallet_address,
+        });
+        self.execute_closed_actions(core::slice::from_ref(poll_id));
+        self.start_runoffs(core::slice::from_ref(poll_id));
+        Ok(())
+    }
+    
+    // Push back a running poll's deadline (creator or ConfigurePolls). A poll can be extended at
+    // most max_poll_extensions times, and its whole window must stay within max_poll_duration.
+    pub fn extend_poll(&mut self, poll_id: &PollId, extra_seconds: u64, caller: &Address) -> Result<()> {
+        let config = self.config;
+        let max_poll_extensions = self.max_poll_extensions;
+        let caller = self.parse_address(caller)?;
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, &caller, Permission::ConfigurePolls)?;
+        
+        // Drafts change their duration through update_draft
+        if poll.status == PollStatus::Draft {
+            return Err(VotingError::InvalidTransition);
+        }
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.extension_count >= max_poll_extensions {
+            return Err(VotingError::ExtensionLimitReached);
+        }
+        
+        let previous_end_timestamp = poll.poll_end_timestamp;
+        let new_end_timestamp = previous_end_timestamp
+            .checked_add(extra_seconds)
+            .ok_or(VotingError::InvalidTimeSettings)?;
+        config.check_duration(extra_seconds)?;
+        config.check_duration(new_end_timestamp - poll.poll_start_timestamp)?;
+        
+        poll.poll_end_timestamp = new_end_timestamp;
+        poll.extension_count += 1;
+        self.reindex_status(poll_id);
+        
+        self.emit(ContractEvent::PollExtended {
+            poll_id: poll_id.clone(),
+            extended_by: caller,
+            previous_end_timestamp,
+            new_end_timestamp,
+        });
+        Ok(())
+    }
+    
+    // Close a running poll before its deadline (creator or ConfigurePolls), recording both the
+    // scheduled end and the actual one. Commit-reveal polls can only be finalized once reveals
+    // have started (WrongPhase before), since committed ballots could never be counted.
+    pub fn finalize_early(&mut self, poll_id: &PollId, caller: &Address) -> Result<()> {
+        let finalized_by = self.parse_address(caller)?;
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, &finalized_by, Permission::ConfigurePolls)?;
+        
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::AlreadyClosed);
+        }
+        if current_timestamp < poll.poll_start_timestamp || poll.in_commit_phase(current_timestamp) {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.close()?;
+        let scheduled_end_timestamp = poll.poll_end_timestamp;
+        self.reindex_status(poll_id);
+        
+        self.emit(ContractEvent::PollFinalizedEarly {
+            poll_id: poll_id.clone(),
+            finalized_by: finalized_by.clone(),
+            scheduled_end_timestamp,
+            finalized_at: current_timestamp,
+        });
+        self.emit(ContractEvent::PollClosed {
+            poll_id: poll_id.clone(),
+            closed_by: finalized_by,
+        });
+        self.execute_closed_actions(core::slice::from_ref(poll_id));
+        self.start_runoffs(core::slice::from_ref(poll_id));
+        Ok(())
+    }
+    
+    // Close every open poll created by the given address (that creator or ClosePolls). Polls past
+    // their end time are left to expiry, as close_poll leaves them, so they are recorded as
+    // PollExpired rather than closed by the caller.
+    pub fn close_polls_by_creator(&mut self, creator_address: &Address, caller: &Address) -> Result<Vec<PollId>> {
+        let creator_address = self.parse_address(creator_address)?;
+        let caller = self.parse_address(caller)?;
+        if !self.has_permission(&caller, Permission::ClosePolls) && caller != creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        let current_timestamp = self.now();
+        let mut closed_poll_ids = Vec::new();
+        
+        for (poll_id, poll) in self.active_polls.iter_mut() {
+            if poll.poll_creator_address == creator_address
+                && !poll.has_ended(current_timestamp)
+                && poll.close().is_ok()
+            {
+                closed_poll_ids.push(poll_id.clone());
+            }
+        }
+        
+        closed_poll_ids.sort();
+        for poll_id in &closed_poll_ids {
+            self.reindex_status(poll_id);
+            self.emit(ContractEvent::PollClosed {
+                poll_id: poll_id.clone(),
+                closed_by: caller.clone(),
+            });
+        }
+        self.execute_closed_actions(&closed_poll_ids);
+        self.start_runoffs(&closed_poll_ids);
+        Ok(closed_poll_ids)
+    }
+    
+    // Mark scheduled polls whose start time has passed as started (requires ProcessScheduledPolls).
+    // Voting already opens at the start time; this records the transition and emits PollStarted.
+    pub fn process_scheduled_polls(&mut self, caller: &Address) -> Result<Vec<PollId>> {
+        if !self.has_permission(caller, Permission::ProcessScheduledPolls) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        Ok(self.start_due_polls())
+    }
+    
+    // Close polls that have passed their end time (requires ProcessExpiredPolls).
+    // Voting already stops at the end time; this records the closure and emits PollExpired.
+    pub fn process_expired_polls(&mut self, caller: &Address) -> Result<Vec<PollId>> {
+        if !self.has_permission(caller, Permission::ProcessExpiredPolls) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        Ok(self.expire_due_polls())
+    }
+    
+    // Record every time-driven transition that is due: scheduled polls past their start time are
+    // started and polls past their end time are closed. Anyone may call it, since it only records
+    // what the clock already decided; keepers can run it periodically so storage matches time.
+    // Rate-limit entries that have left their window are dropped as well, and conviction polls
+    // accrue conviction up to now before any of them expires.
+    pub fn tick(&mut self) -> TickSummary {
+        self.prune_rate_limits();
+        let current_timestamp = self.now();
+        let passed_convictions = self.update_convictions(current_timestamp);
+        TickSummary {
+            started_poll_ids: self.start_due_polls(),
+            expired_poll_ids: self.expire_due_polls(),
+            passed_convictions,
+        }
+    }
+    
+    // Start the scheduled polls whose start time has passed, returning their IDs
+    fn start_due_polls(&mut self) -> Vec<PollId> {
+        self.resolve_dependencies();
+        let current_timestamp = self.now();
+        
+        let mut started_poll_ids = Vec::new();
+        
+        for (poll_id, poll) in self.active_polls.iter_mut() {
+            if poll.status == PollStatus::Scheduled
+                && poll.dependencies_met
+                && current_timestamp >= poll.poll_start_timestamp
+                && poll.transition_to(PollStatus::Active).is_ok()
+            {
+                started_poll_ids.push(poll_id.clone());
+            }
+        }
+        
+        started_poll_ids.sort();
+        for poll_id in &started_poll_ids {
+            self.emit(ContractEvent::PollStarted { poll_id: poll_id.clone() });
+        }
+        
+        started_poll_ids
+    }
+    
+    // Close the polls whose end time has passed, returning their IDs. Only the open polls filed
+    // at or before now are visited (see StatusIndex).
+    fn expire_due_polls(&mut self) -> Vec<PollId> {
+        let current_timestamp = self.now();
+        let due_poll_ids: Vec<PollId> = self.status_index.ending_by(current_timestamp).cloned().collect();
+        
+        let mut closed_poll_ids = Vec::new();
+        
+        for poll_id in due_poll_ids {
+            let closed = self
+                .active_polls
+                .get_mut(&poll_id)
+                .is_some_and(|poll| poll.has_ended(current_timestamp) && poll.close().is_ok());
+            if closed {
+                closed_poll_ids.push(poll_id);
+            }
+        }
+        
+        closed_poll_ids.sort();
+        for poll_id in &closed_poll_ids {
+            self.reindex_status(poll_id);
+            self.emit(ContractEvent::PollExpired { poll_id: poll_id.clone() });
+        }
+        self.execute_closed_actions(&closed_poll_ids);
+        self.start_runoffs(&closed_poll_ids);
+        
+        closed_poll_ids
+    }
+    
+    // Close one poll past its end time when auto_expire is on. The expiry is recorded even if the
+    // call that triggered it then fails, since the poll had ended either way.
+    fn expire_if_due(&mut self, poll_id: &PollId) {
+        let current_timestamp = self.now();
+        let expired = self.auto_expire
+            && self
+                .active_polls
+                .get_mut(poll_id)
+                .is_some_and(|poll| poll.has_ended(current_timestamp) && poll.close().is_ok());
+        if expired {
+            self.reindex_status(poll_id);
+            self.emit(ContractEvent::PollExpired { poll_id: poll_id.clone() });
+            self.execute_closed_actions(core::slice::from_ref(poll_id));
+            self.start_runoffs(core::slice::from_ref(poll_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use clock::MockClock;
+    
+    // Helper function to create a test poll
+    fn create_test_poll(contract: &mut VotingContract) -> PollId {
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_title = "Test Poll".to_string();
+        let poll_description = "This is a test poll".to_string();
+        let poll_options = vec!["Option A".to_string(), "Option B".to_string(), "Option C".to_string()];
+        
+        // Create a poll with a 10 second duration
+        contract.create_poll(creator_address, poll_title, poll_description, poll_options, 10).unwrap()
+    }
+    
+    // Helper function to create a quadratic test poll with the same options
+    fn create_quadratic_test_poll(contract: &mut VotingContract, credit_budget: u64) -> PollId {
+        let poll_options = vec!["Option A".to_string(), "Option B".to_string(), "Option C".to_string()];
+        contract
+            .create_quadratic_poll(
+                "wallet_creator".parse().unwrap(),
+                "Quadratic Poll".to_string(),
+                "Spend credits across options".to_string(),
+                poll_options,
+                10,
+                credit_budget,
+            )
+            .unwrap()
+    }
+    
+    #[test]
+    fn test_create_poll() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        
+        // Verify poll was created
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.poll_title, "Test Poll");
+        assert_eq!(poll.voting_options.len(), 3);
+        assert_eq!(poll.participant_addresses.len(), 0);
+        assert!(poll.is_active(contract.now()));
+    }
+    
+    #[test]
+    fn test_vote() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        
+        // Cast votes
+        let voter1_address = "wallet_voter1".to_string();
+        let voter2_address = "wallet_voter2".to_string();
+        
+        contract.vote(&poll_id, voter1_address.parse().unwrap(), "Option A").unwrap();
+        contract.vote(&poll_id, voter2_address.parse().unwrap(), "Option B").unwrap();
+        
+        // Verify votes were recorded
+        let results = contract.get_poll_results(&poll_id).unwrap();
+        assert_eq!(*results.get("Option A").unwrap(), 1);
+        assert_eq!(*results.get("Option B").unwrap(), 1);
+        assert_eq!(*results.get("Option C").unwrap(), 0);
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.total_votes(), 2);
+    }
+    
+    #[test]
+    fn test_double_voting_prevention() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        
+        // First vote should succeed
+        let voter_address: Address = "wallet_voter".parse().unwrap();
+        contract.vote(&poll_id, voter_address.clone(), "Option A").unwrap();
+        
+        // Second vote should fail
+        let result = contract.vote(&poll_id, voter_address.clone(), "Option B");
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+    }
+    
+    #[test]
+    fn test_invalid_option() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        
+        // Vote for non-existent option
+        let voter_address: Address = "wallet_voter".parse().unwrap();
+        let result = contract.vote(&poll_id, voter_address, "Option D");
+        assert!(matches!(result, Err(VotingError::InvalidOption)));
+    }
+    
+    #[test]
+    fn test_poll_expiration() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        // Create a poll with a very short duration for testing
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_id = contract.create_poll(
+            creator_address,
+            "Short Poll".to_string(),
+            "This poll expires quickly".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            1, // 1 second duration
+        ).unwrap();
+        
+        // Move time past the end of the poll
+        clock.advance(2);
+        
+        // Process expired polls
+        let closed_poll_ids = contract.process_expired_polls(&"wallet_admin".parse().unwrap()).unwrap();
+        assert!(closed_poll_ids.contains(&poll_id));
+        
+        // Verify the poll is now closed
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.status, PollStatus::Closed);
+        
+        // Attempt to vote on expired poll should fail
+        let voter_address: Address = "wallet_voter".parse().unwrap();
+        let result = contract.vote(&poll_id, voter_address, "Yes");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+    }
+    
+    #[test]
+    fn test_manual_poll_closure() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_id = contract.create_poll(
+            creator_address.clone(),
+            "Test Poll".to_string(),
+            "This is a test poll".to_string(),
+            vec!["Option A".to_string(), "Option B".to_string()],
+            60, // 60 second duration
+        ).unwrap();
+        
+        // Creator can close their own poll
+        contract.close_poll(&poll_id, &creator_address).unwrap();
+        
+        // Verify poll is closed
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.status, PollStatus::Closed);
+        
+        // Create another poll for admin closure test
+        let poll_id2 = contract.create_poll(
+            creator_address.clone(),
+            "Admin Test Poll".to_string(),
+            "This poll will be closed by admin".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        ).unwrap();
+        
+        // Admin can close any poll
+        contract.close_poll(&poll_id2, &admin_address).unwrap();
+        
+        // Verify poll is closed
+        let poll = contract.get_poll(&poll_id2).unwrap();
+        assert_eq!(poll.status, PollStatus::Closed);
+    }
+    
+    #[test]
+    fn test_unauthorized_poll_closure() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_id = contract.create_poll(
+            creator_address,
+            "Test Poll".to_string(),
+            "This is a test poll".to_string(),
+            vec!["Option A".to_string(), "Option B".to_string()],
+            60,
+        ).unwrap();
+        
+        // Random user cannot close the poll
+        let random_user_address: Address = "wallet_random".parse().unwrap();
+        let result = contract.close_poll(&poll_id, &random_user_address);
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+    }
+    
+    #[test]
+    fn test_active_polls_filtering() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        // Create two polls
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_id1 = contract.create_poll(
+            creator_address.clone(),
+            "Active Poll".to_string(),
+            "This poll is active".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        ).unwrap();
+        
+        let poll_id2 = contract.create_poll(
+            creator_address.clone(),
+            "Closed Poll".to_string(),
+            "This poll will be closed".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        ).unwrap();
+        
+        // Close one poll
+        contract.close_poll(&poll_id2, &creator_address).unwrap();
+        
+        // Check active polls
+        let active_polls = contract.get_active_polls();
+        assert_eq!(active_polls.len(), 1);
+        assert_eq!(active_polls[0].poll_id, poll_id1);
+    }
+    
+    #[test]
+    fn test_close_polls_by_creator() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        let leaving_creator: Address = "wallet_leaving".parse().unwrap();
+        let other_creator: Address = "wallet_other".parse().unwrap();
+        let mut leaving_poll_ids = Vec::new();
+        for poll_title in ["First Poll", "Second Poll"] {
+            leaving_poll_ids.push(contract.create_poll(
+                leaving_creator.clone(),
+                poll_title.to_string(),
+                "Created by the leaving member".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            ).unwrap());
+        }
+        
+        let other_poll_id = contract.create_poll(
+            other_creator.clone(),
+            "Other Poll".to_string(),
+            "Created by someone else".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        ).unwrap();
+        
+        // Another member cannot close someone else's polls
+        let result = contract.close_polls_by_creator(&leaving_creator, &other_creator);
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        
+        // The creator can close all of their own polls at once
+        let closed_poll_ids = contract.close_polls_by_creator(&leaving_creator, &leaving_creator).unwrap();
+        leaving_poll_ids.sort();
+        assert_eq!(closed_poll_ids, leaving_poll_ids);
+        assert!(contract.is_poll_active(&other_poll_id).unwrap());
+        
+        // Already closed polls are skipped rather than reported again
+        let closed_again = contract.close_polls_by_creator(&leaving_creator, &"wallet_admin".parse().unwrap()).unwrap();
+        assert!(closed_again.is_empty());
+        
+        // A poll past its deadline is left to expiry, as close_poll leaves it
+        let expired_poll_id = contract.create_poll(
+            leaving_creator.clone(),
+            "Third Poll".to_string(),
+            "Runs out first".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            10,
+        ).unwrap();
+        clock.advance(10);
+        assert!(contract.close_polls_by_creator(&leaving_creator, &leaving_creator).unwrap().is_empty());
+        assert_eq!(contract.tick().expired_poll_ids, vec![expired_poll_id.clone()]);
+        let expired_event = ContractEvent::PollExpired { poll_id: expired_poll_id };
+        assert!(contract.events_since(0).iter().any(|event| event.event == expired_event));
+    }
+    
+    #[test]
+    fn test_address_normalization_prevents_double_voting() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        
+        contract.vote(&poll_id, "wallet".parse().unwrap(), "Option A").unwrap();
+        
+        // Padding the same address with whitespace must not allow a second vote
+        let result = contract.vote(&poll_id, " wallet ".parse().unwrap(), "Option B");
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.total_votes(), 1);
+        assert!(poll.participant_addresses.contains(&"wallet".parse().unwrap()));
+    }
+    
+    #[test]
+    fn test_empty_address_rejected() {
+        // Blank addresses never become an Address, so they cannot reach a vote or a poll
+        assert!(matches!("   ".parse::<Address>(), Err(VotingError::InvalidAddress)));
+        assert!(matches!("".parse::<Address>(), Err(VotingError::InvalidAddress)));
+        
+        let contract = VotingContract::new("wallet_admin".parse().unwrap());
+        assert!(matches!(contract.parse_address("   "), Err(VotingError::InvalidAddress)));
+    }
+    
+    // The overflow check needs the clock past zero, which only the system clock guarantees
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_max_poll_duration() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        
+        // A duration above the contract limit is rejected
+        let result = contract.create_poll(
+            creator_address.clone(),
+            "Endless Poll".to_string(),
+            "This poll runs forever".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            u64::MAX,
+        );
+        assert!(matches!(result, Err(VotingError::DurationTooLong)));
+        
+        // Even with the limit lifted, an overflowing end timestamp is rejected instead of wrapping
+        contract.config.max_poll_duration = u64::MAX;
+        let result = contract.create_poll(
+            creator_address.clone(),
+            "Endless Poll".to_string(),
+            "This poll runs forever".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            u64::MAX,
+        );
+        assert!(matches!(result, Err(VotingError::InvalidTimeSettings)));
+        assert_eq!(contract.active_polls.len(), 0);
+    }
+    
+    #[test]
+    fn test_event_handler_receives_events() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let received_events = Arc::new(Mutex::new(Vec::new()));
+        let handler_events = Arc::clone(&received_events);
+        contract.set_event_handler(Box::new(move |event: &ContractEvent| {
+            handler_events.lock().unwrap().push(event.clone());
+        }));
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Option A").unwrap();
+        contract.vote(&poll_id, "wallet_voter2".parse().unwrap(), "Option B").unwrap();
+        
+        // Failed votes must not emit anything
+        let _ = contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Option B");
+        
+        let received_events = received_events.lock().unwrap();
+        assert_eq!(received_events.len(), 3);
+        assert_eq!(received_events[0], ContractEvent::PollCreated {
+            poll_id: poll_id.clone(),
+            creator_address: "wallet_creator".parse().unwrap(),
+        });
+        assert_eq!(received_events[1], ContractEvent::VoteCast {
+            poll_id: poll_id.clone(),
+            voter_address: "wallet_voter1".parse().unwrap(),
+            selected_option: "Option A".to_string(),
+        });
+        assert_eq!(received_events[2], ContractEvent::VoteCast {
+            poll_id,
+            voter_address: "wallet_voter2".parse().unwrap(),
+            selected_option: "Option B".to_string(),
+        });
+    }
+    
+    #[test]
+    fn test_quadratic_voting_across_options() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_quadratic_test_poll(&mut contract, 14);
+        let voter_address: Address = "wallet_voter".parse().unwrap();
+        
+        // 3 votes cost 9 credits, 2 votes cost 4 credits: 13 of a 14 credit budget
+        contract.vote_quadratic(&poll_id, voter_address.clone(), "Option A", 3).unwrap();
+        contract.vote_quadratic(&poll_id, voter_address.clone(), "Option B", 2).unwrap();
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.credits_spent(&voter_address), 13);
+        assert_eq!(poll.votes_for("Option A"), 3);
+        assert_eq!(poll.votes_for("Option B"), 2);
+        assert_eq!(poll.total_votes(), 1);
+        
+        // One more vote on Option A would raise its cost from 9 to 16 credits
+        let result = contract.vote_quadratic(&poll_id, voter_address.clone(), "Option A", 1);
+        assert!(matches!(result, Err(VotingError::InsufficientCredits)));
+        
+        // A single vote on a fresh option still fits the remaining credit
+        contract.vote_quadratic(&poll_id, voter_address.clone(), "Option C", 1).unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().credits_spent(&voter_address), 14);
+    }
+    
+    #[test]
+    fn test_quadratic_voting_rejects_over_budget() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_quadratic_test_poll(&mut contract, 15);
+        
+        // 4 votes cost 16 credits, more than the 15 available
+        let result = contract.vote_quadratic(&poll_id, "wallet_voter".parse().unwrap(), "Option A", 4);
+        assert!(matches!(result, Err(VotingError::InsufficientCredits)));
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.votes_for("Option A"), 0);
+        assert_eq!(poll.total_votes(), 0);
+    }
+    
+    #[test]
+    fn test_time_remaining() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_id = contract.create_poll(
+            creator_address.clone(),
+            "Countdown Poll".to_string(),
+            "This poll runs for a minute".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        ).unwrap();
+        
+        assert_eq!(contract.time_remaining(&poll_id).unwrap(), 60);
+        clock.advance(15);
+        assert_eq!(contract.time_remaining(&poll_id).unwrap(), 45);
+        
+        // A closed poll has no time left even before its end timestamp
+        contract.close_poll(&poll_id, &creator_address).unwrap();
+        assert_eq!(contract.time_remaining(&poll_id).unwrap(), 0);
+        
+        assert!(matches!(contract.time_remaining(&"poll_missing".parse().unwrap()), Err(VotingError::PollNotFound)));
+    }
+    
+    #[test]
+    fn test_time_remaining_expired_poll() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        let poll_id = contract.create_poll(
+            "wallet_creator".parse().unwrap(),
+            "Short Poll".to_string(),
+            "This poll expires quickly".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            1,
+        ).unwrap();
+        
+        clock.advance(2);
+        
+        // Expired but not yet processed polls also report zero
+        assert_eq!(contract.time_remaining(&poll_id).unwrap(), 0);
+    }
+    
+    #[test]
+    fn test_get_winner() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), WinnerOutcome::NoVotes);
+        
+        contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Option A").unwrap();
+        contract.vote(&poll_id, "wallet_voter2".parse().unwrap(), "Option B").unwrap();
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), WinnerOutcome::Tie {
+            options: vec!["Option A".to_string(), "Option B".to_string()],
+            votes: 1,
+        });
+        
+        contract.vote(&poll_id, "wallet_voter3".parse().unwrap(), "Option B").unwrap();
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), WinnerOutcome::Winner {
+            option: "Option B".to_string(),
+            votes: 2,
+        });
+    }
+    
+    #[test]
+    fn test_min_winning_votes_threshold() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.set_min_winning_votes(&poll_id, 4, &"wallet_creator".parse().unwrap()).unwrap();
+        
+        // High turnout, but the leading option only gets 3 of 6 votes
+        for (voter_index, selected_option) in ["Option A", "Option A", "Option A", "Option B", "Option B", "Option C"].iter().enumerate() {
+            contract.vote(&poll_id, format!("wallet_voter{}", voter_index).parse().unwrap(), selected_option).unwrap();
+        }
+        assert_eq!(contract.get_poll(&poll_id).unwrap().total_votes(), 6);
+        assert!(matches!(contract.get_winner(&poll_id), Err(VotingError::ThresholdNotMet)));
+        
+        // Once the leader reaches the floor it wins
+        contract.vote(&poll_id, "wallet_voter6".parse().unwrap(), "Option A").unwrap();
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), WinnerOutcome::Winner {
+            option: "Option A".to_string(),
+            votes: 4,
+        });
+        
+        // Only the admin or creator may change the threshold, and not once votes are in
+        let result = contract.set_min_winning_votes(&poll_id, 0, &"wallet_random".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        let result = contract.set_min_winning_votes(&poll_id, 10, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        let result = contract.set_min_winning_votes(&poll_id, 0, &"wallet_admin".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().min_winning_votes, 4);
+    }
+    
+    #[test]
+    fn test_snapshot_round_trip() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Option A").unwrap();
+        let quadratic_poll_id = create_quadratic_test_poll(&mut contract, 4);
+        contract.vote_quadratic(&quadratic_poll_id, "wallet_voter2".parse().unwrap(), "Option B", 2).unwrap();
+        
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.access_control, contract.access_control);
+        assert_eq!(restored.next_poll_id, 3);
+        
+        let poll = restored.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.get_results(), contract.get_poll_results(&poll_id).unwrap());
+        assert!(poll.participant_addresses.contains(&"wallet_voter1".parse().unwrap()));
+        let quadratic_poll = restored.get_poll(&quadratic_poll_id).unwrap();
+        assert_eq!(quadratic_poll.voting_method, VotingMethod::Quadratic { credit_budget: 4 });
+        assert_eq!(quadratic_poll.credits_spent(&"wallet_voter2".parse().unwrap()), 4);
+    }
+    
+    #[test]
+    fn test_tampered_snapshot_rejected() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Option A").unwrap();
+        
+        // Inflate a tally so it no longer matches the recorded participants
+        contract.active_polls.get_mut(&poll_id).unwrap().vote_counts.insert(OptionId(1), 5);
+        let result = VotingContract::from_snapshot(&contract.to_snapshot());
+        assert!(matches!(result, Err(VotingError::CorruptState)));
+        
+        // A poll counter that would reissue an existing ID is also corrupt
+        contract.active_polls.get_mut(&poll_id).unwrap().vote_counts.insert(OptionId(1), 0);
+        contract.next_poll_id = 1;
+        let result = VotingContract::from_snapshot(&contract.to_snapshot());
+        assert!(matches!(result, Err(VotingError::CorruptState)));
+        
+        // Truncated data fails cleanly
+        contract.next_poll_id = 2;
+        let snapshot = contract.to_snapshot();
+        assert!(VotingContract::from_snapshot(&snapshot).is_ok());
+        let result = VotingContract::from_snapshot(&snapshot[..snapshot.len() - 1]);
+        assert!(matches!(result, Err(VotingError::CorruptState)));
+    }
+    
+    #[test]
+    fn test_second_admin_can_close_polls() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        assert_eq!(contract.admin_address(), "wallet_admin");
+        
+        let poll_id = create_test_poll(&mut contract);
+        
+        // Non-admins cannot grant admin rights
+        let result = contract.add_admin(&"wallet_second".parse().unwrap(), &"wallet_random".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        assert!(!contract.is_admin(&"wallet_second".parse().unwrap()));
+        
+        contract.add_admin(&"wallet_second".parse().unwrap(), &admin_address).unwrap();
+        assert!(contract.is_admin(&"wallet_second".parse().unwrap()));
+        
+        // The new admin can close polls they did not create
+        contract.close_poll(&poll_id, &"wallet_second".parse().unwrap()).unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().status, PollStatus::Closed);
+    }
+    
+    #[test]
+    fn test_last_admin_cannot_be_removed() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        
+        let result = contract.remove_admin(&admin_address, &admin_address);
+        assert!(matches!(result, Err(VotingError::CannotRemoveLastAdmin)));
+        assert!(contract.is_admin(&admin_address));
+        
+        // With a second admin in place the original one can step down
+        contract.add_admin(&"wallet_second".parse().unwrap(), &admin_address).unwrap();
+        contract.remove_admin(&admin_address, &"wallet_second".parse().unwrap()).unwrap();
+        assert!(!contract.is_admin(&admin_address));
+        assert_eq!(contract.admin_address(), "wallet_second");
+        
+        let result = contract.remove_admin(&"wallet_second".parse().unwrap(), &"wallet_second".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::CannotRemoveLastAdmin)));
+    }
+    
+    #[test]
+    fn test_vote_count_overflow() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.active_polls.get_mut(&poll_id).unwrap().vote_counts.insert(OptionId(0), u128::MAX);
+        
+        let result = contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Option A");
+        assert!(matches!(result, Err(VotingError::VoteCountOverflow)));
+        
+        // The failed vote leaves the tally and participants untouched
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.votes_for("Option A"), u128::MAX);
+        assert!(!poll.participant_addresses.contains(&"wallet_voter".parse().unwrap()));
+        
+        let quadratic_poll_id = create_quadratic_test_poll(&mut contract, 1);
+        contract.active_polls.get_mut(&quadratic_poll_id).unwrap().vote_counts.insert(OptionId(0), u128::MAX);
+        let result = contract.vote_quadratic(&quadratic_poll_id, "wallet_voter".parse().unwrap(), "Option A", 1);
+        assert!(matches!(result, Err(VotingError::VoteCountOverflow)));
+    }
+    
+    #[test]
+    fn test_creator_cannot_vote_in_conflict_of_interest_poll() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let restricted_poll_id = contract.create_poll_without_creator_vote(
+            creator_address.clone(),
+            "Budget Approval".to_string(),
+            "The creator's own budget request".to_string(),
+            vec!["Approve".to_string(), "Reject".to_string()],
+            60,
+        ).unwrap();
+        
+        let result = contract.vote(&restricted_poll_id, creator_address.clone(), "Approve");
+        assert!(matches!(result, Err(VotingError::CreatorCannotVote)));
+        
+        // Other voters are unaffected
+        contract.vote(&restricted_poll_id, "wallet_voter".parse().unwrap(), "Approve").unwrap();
+        assert_eq!(contract.get_poll(&restricted_poll_id).unwrap().total_votes(), 1);
+        
+        // In a normal poll the creator can vote
+        let normal_poll_id = create_test_poll(&mut contract);
+        assert!(contract.get_poll(&normal_poll_id).unwrap().creator_can_vote);
+        contract.vote(&normal_poll_id, creator_address.clone(), "Option A").unwrap();
+    }
+    
+    #[test]
+    fn test_archive_closed_polls() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        
+        let open_poll_id = create_test_poll(&mut contract);
+        let closed_poll_id = create_test_poll(&mut contract);
+        let other_closed_poll_id = create_test_poll(&mut contract);
+        contract.vote(&closed_poll_id, "wallet_voter".parse().unwrap(), "Option A").unwrap();
+        contract.close_poll(&closed_poll_id, &admin_address).unwrap();
+        contract.close_poll(&other_closed_poll_id, &admin_address).unwrap();
+        
+        assert_eq!(contract.archive_closed_polls(), 2);
+        assert_eq!(contract.archive_closed_polls(), 0);
+        
+        // Only the open poll remains in the live map scanned for active polls
+        assert_eq!(contract.active_polls.len(), 1);
+        assert!(contract.active_polls.contains_key(&open_poll_id));
+        assert_eq!(contract.get_active_polls().len(), 1);
+        assert_eq!(contract.get_all_polls().len(), 3);
+        
+        // Archived polls still resolve by id and keep their results
+        let archived_poll = contract.get_poll(&closed_poll_id).unwrap();
+        assert_eq!(archived_poll.votes_for("Option A"), 1);
+        
+        // ...but can no longer be voted on
+        let result = contract.vote(&closed_poll_id, "wallet_voter2".parse().unwrap(), "Option A");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+    }
+    
+    #[test]
+    fn test_vote_never_adds_tally_keys() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        
+        // Simulate an option being slipped onto the ballot after creation
+        contract.active_polls.get_mut(&poll_id).unwrap().voting_options.push("Option D".to_string());
+        
+        let result = contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Option D");
+        assert!(matches!(result, Err(VotingError::InvalidOption)));
+        let quadratic_poll_id = create_quadratic_test_poll(&mut contract, 1);
+        contract.active_polls.get_mut(&quadratic_poll_id).unwrap().voting_options.push("Option D".to_string());
+        let result = contract.vote_quadratic(&quadratic_poll_id, "wallet_voter".parse().unwrap(), "Option D", 1);
+        assert!(matches!(result, Err(VotingError::InvalidOption)));
+        assert_eq!(contract.get_poll(&quadratic_poll_id).unwrap().vote_counts.len(), 3);
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.vote_counts.len(), 3);
+        assert!(!poll.vote_counts.contains_key(&OptionId(3)));
+        assert_eq!(poll.total_votes(), 0);
+    }
+    
+    #[test]
+    fn test_winner_cache_recomputes_after_vote() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Option A").unwrap();
+        
+        // A read fills the cache
+        let winner = WinnerOutcome::Winner { option: "Option A".to_string(), votes: 1 };
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), winner);
+        assert_eq!(contract.get_poll(&poll_id).unwrap().cached_winner.get(), Some(&winner));
+        
+        // New votes empty it, so the next read recomputes rather than returning the stale winner
+        contract.vote(&poll_id, "wallet_voter2".parse().unwrap(), "Option B").unwrap();
+        contract.vote(&poll_id, "wallet_voter3".parse().unwrap(), "Option B").unwrap();
+        let winner = WinnerOutcome::Winner { option: "Option B".to_string(), votes: 2 };
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), winner);
+        assert_eq!(contract.get_poll(&poll_id).unwrap().cached_winner.get(), Some(&winner));
+    }
+    
+    #[test]
+    fn test_create_from_template() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let template = PollTemplate {
+            poll_description: "Monthly grant round".to_string(),
+            voting_options: vec!["Fund".to_string(), "Reject".to_string()],
+            poll_duration_seconds: 60,
+            tags: vec!["grants".to_string()],
+        };
+        let poll_titles = vec!["Grant A".to_string(), "Grant B".to_string(), "Grant C".to_string()];
+        
+        let poll_ids = contract
+            .create_from_template("wallet_creator".parse().unwrap(), &template, poll_titles.clone())
+            .unwrap();
+        assert_eq!(poll_ids.len(), 3);
+        
+        let unique_poll_ids: HashSet<&PollId> = poll_ids.iter().collect();
+        assert_eq!(unique_poll_ids.len(), 3);
+        
+        for (poll_id, poll_title) in poll_ids.iter().zip(&poll_titles) {
+            let poll = contract.get_poll(poll_id).unwrap();
+            assert_eq!(&poll.poll_title, poll_title);
+            assert_eq!(poll.voting_options, template.voting_options);
+            assert_eq!(poll.tags, template.tags);
+        }
+    }
+    
+    #[test]
+    fn test_create_from_template_is_atomic() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        // A template that fails validation creates nothing
+        let template = PollTemplate {
+            poll_description: "Broken template".to_string(),
+            voting_options: vec!["Only option".to_string()],
+            poll_duration_seconds: 60,
+            tags: Vec::new(),
+        };
+        let result = contract.create_from_template(
+            "wallet_creator".parse().unwrap(),
+            &template,
+            vec!["Poll A".to_string(), "Poll B".to_string()],
+        );
+        assert!(matches!(result, Err(VotingError::PollCreationFailed)));
+        assert_eq!(contract.active_polls.len(), 0);
+        assert_eq!(contract.next_poll_id, 1);
+    }
+    
+    #[test]
+    fn test_reject_duplicate_titles() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        contract.reject_duplicate_titles = true;
+        
+        let creator_address: Address = "wallet_creator".parse().unwrap();
+        let poll_id = contract.create_poll(
+            creator_address.clone(),
+            "Treasury Vote".to_string(),
+            "First attempt".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        ).unwrap();
+        
+        // Same title after trimming and ignoring case is rejected
+        let result = contract.create_poll(
+            creator_address.clone(),
+            "  treasury VOTE ".to_string(),
+            "Accidental duplicate".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        );
+        assert!(matches!(result, Err(VotingError::DuplicateTitle)));
+        
+        // Once the first poll is closed the creator may re-run it
+        contract.close_poll(&poll_id, &creator_address).unwrap();
+        contract.create_poll(
+            creator_address.clone(),
+            "Treasury Vote".to_string(),
+            "Second run".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            60,
+        ).unwrap();
+    }
+    
+    #[test]
+    fn test_duplicate_titles_allowed_across_creators() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        contract.reject_duplicate_titles = true;
+        
+        for creator_address in ["wallet_creator1", "wallet_creator2"] {
+            contract.create_poll(
+                creator_address.parse().unwrap(),
+                "Treasury Vote".to_string(),
+                "Same title, different creator".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            ).unwrap();
+        }
+        assert_eq!(contract.active_polls.len(), 2);
+    }
+    
+    #[test]
+    fn test_poll_summary_reports_tie() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Option A").unwrap();
+        contract.vote(&poll_id, "wallet_voter2".parse().unwrap(), "Option C").unwrap();
+        
+        let summary = contract.get_poll_summary(&poll_id).unwrap();
+        assert_eq!(summary.total_votes, 2);
+        assert!(summary.is_tie);
+        assert_eq!(summary.leading_options, vec!["Option A".to_string(), "Option C".to_string()]);
+        assert_eq!(summary.leading_votes, 1);
+        assert!(summary.quorum_met);
+        assert!(summary.is_active);
+        
+        // Closed polls keep their final numbers
+        contract.vote(&poll_id, "wallet_voter3".parse().unwrap(), "Option C").unwrap();
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let summary = contract.get_poll_summary(&poll_id).unwrap();
+        assert!(!summary.is_tie);
+        assert_eq!(summary.leading_options, vec!["Option C".to_string()]);
+        assert!(!summary.is_active);
+    }
+    
+    #[test]
+    fn test_close_poll_twice() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        
+        let closed_events = Arc::new(Mutex::new(0));
+        let handler_closed_events = Arc::clone(&closed_events);
+        contract.set_event_handler(Box::new(move |event: &ContractEvent| {
+            if matches!(event, ContractEvent::PollClosed { .. }) {
+                *handler_closed_events.lock().unwrap() += 1;
+            }
+        }));
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Option A").unwrap();
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.get_winner(&poll_id).unwrap();
+        
+        // The second close errors without re-closing, re-emitting or dropping cached results
+        let result = contract.close_poll(&poll_id, &admin_address);
+        assert!(matches!(result, Err(VotingError::AlreadyClosed)));
+        assert_eq!(*closed_events.lock().unwrap(), 1);
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.status, PollStatus::Closed);
+        assert!(poll.cached_winner.get().is_some());
+        
+        // Closing directly on the poll is rejected as well
+        let mut poll = poll.clone();
+        assert!(matches!(poll.close(), Err(VotingError::InvalidTransition)));
+        assert!(poll.cached_winner.get().is_some());
+    }
+    
+    #[test]
+    fn test_poll_iterators_match_collected_versions() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        
+        let open_poll_id = create_test_poll(&mut contract);
+        let closed_poll_id = create_test_poll(&mut contract);
+        create_test_poll(&mut contract);
+        contract.close_poll(&closed_poll_id, &admin_address).unwrap();
+        
+        assert_eq!(contract.iter_polls().count(), contract.get_all_polls().len());
+        assert_eq!(contract.iter_active_polls().count(), contract.get_active_polls().len());
+        assert_eq!(contract.iter_active_polls().count(), 2);
+        
+        let found_poll = contract.iter_polls().find(|poll| poll.poll_id == open_poll_id).unwrap();
+        assert_eq!(found_poll.poll_id, open_poll_id);
+        assert!(contract.iter_active_polls().all(|poll| poll.poll_id != closed_poll_id));
+    }
+    
+    // Helper function to create a ranked-choice poll with four options
+    fn create_ranked_test_poll(contract: &mut VotingContract) -> PollId {
+        contract.create_ranked_poll(
+            "wallet_creator".parse().unwrap(),
+            "Ranked Poll".to_string(),
+            "Rank the options in order of preference".to_string(),
+            vec!["A".to_string(), "B".to_string(), "C".to_string(), "D".to_string()],
+            60,
+        ).unwrap()
+    }
+    
+    fn ranking(options: &[&str]) -> Vec<String> {
+        options.iter().map(|option| option.to_string()).collect()
+    }
+    
+    #[test]
+    fn test_ranked_choice_instant_runoff() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_ranked_test_poll(&mut contract);
+        let ballots = [
+            ranking(&["A", "B"]),
+            ranking(&["A", "C"]),
+            ranking(&["B", "C"]),
+            ranking(&["B", "A"]),
+            ranking(&["C", "B"]),
+            ranking(&["D", "C"]),
+        ];
+        for (voter_index, ballot) in ballots.iter().enumerate() {
+            contract
+                .vote_ranked(
+                    &poll_id,
+                    format!("wallet_voter{}", voter_index).parse().unwrap(),
+                    ballot.clone(),
+                )
+                .unwrap();
+        }
+        
+        // Round 1: A 2, B 2, C 1, D 1 -> D eliminated (latest of the tied last places)
+        // Round 2: A 2, B 2, C 2 -> every remaining option is tied, so tabulation stops
+        let poll = contract.get_poll(&poll_id).unwrap();
+        let rounds = poll.instant_runoff_rounds();
+        assert_eq!(rounds[0]["A"], 2);
+        assert_eq!(rounds[0]["D"], 1);
+        assert_eq!(rounds[1]["D"], 0);
+        assert_eq!(rounds[1]["C"], 2);
+        assert_eq!(rounds.len(), 2);
+        
+        // First preferences are still available through vote_counts
+        assert_eq!(poll.votes_for("A"), 2);
+        assert_eq!(poll.total_votes(), 6);
+        
+        // One more ballot for B: D then C are eliminated, C's ballot moves on to B and
+        // D's ballot is exhausted, leaving B with a majority of the 6 ballots still counted
+        contract.vote_ranked(&poll_id, "wallet_voter6".parse().unwrap(), ranking(&["B"])).unwrap();
+        let results = contract.get_poll_results(&poll_id).unwrap();
+        assert_eq!(results["B"], 4);
+        assert_eq!(results["A"], 2);
+        assert_eq!(results["C"], 0);
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), WinnerOutcome::Winner {
+            option: "B".to_string(),
+            votes: 4,
+        });
+    }
+    
+    #[test]
+    fn test_ranked_choice_ballot_validation() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_ranked_test_poll(&mut contract);
+        
+        let result = contract.vote_ranked(&poll_id, "wallet_voter".parse().unwrap(), ranking(&["A", "B", "A"]));
+        assert!(matches!(result, Err(VotingError::InvalidRanking)));
+        
+        let result = contract.vote_ranked(&poll_id, "wallet_voter".parse().unwrap(), ranking(&["A", "E"]));
+        assert!(matches!(result, Err(VotingError::InvalidOption)));
+        
+        let result = contract.vote_ranked(&poll_id, "wallet_voter".parse().unwrap(), Vec::new());
+        assert!(matches!(result, Err(VotingError::InvalidRanking)));
+        
+        // Ranked polls do not accept single-choice votes, and vice versa
+        let result = contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "A");
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        let single_choice_poll_id = create_test_poll(&mut contract);
+        let result = contract.vote_ranked(
+            &single_choice_poll_id,
+            "wallet_voter".parse().unwrap(),
+            ranking(&["Option A"]),
+        );
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        
+        contract.vote_ranked(&poll_id, "wallet_voter".parse().unwrap(), ranking(&["C", "A"])).unwrap();
+        let result = contract.vote_ranked(&poll_id, "wallet_voter".parse().unwrap(), ranking(&["B"]));
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().total_votes(), 1);
+    }
+    
+    #[test]
+    fn test_weighted_voting() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        
+        // Only admins can register stake
+        let result = contract.set_voter_weight(&"wallet_whale".parse().unwrap(), 100, &"wallet_whale".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        
+        contract.set_voter_weight(&"wallet_whale".parse().unwrap(), 100, &admin_address).unwrap();
+        assert_eq!(contract.voter_weight(&"wallet_whale".parse().unwrap()), 100);
+        assert_eq!(contract.voter_weight(&"wallet_minnow".parse().unwrap()), 1);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_whale".parse().unwrap(), "Option A").unwrap();
+        contract.vote(&poll_id, "wallet_minnow1".parse().unwrap(), "Option B").unwrap();
+        contract.vote(&poll_id, "wallet_minnow2".parse().unwrap(), "Option B").unwrap();
+        
+        // Results expose both the raw weight and how many voters back each option
+        let weighted_results = contract.get_weighted_results(&poll_id).unwrap();
+        assert_eq!(weighted_results["Option A"], OptionTally { weight: 100, voters: 1 });
+        assert_eq!(weighted_results["Option B"], OptionTally { weight: 2, voters: 2 });
+        assert_eq!(weighted_results["Option C"], OptionTally { weight: 0, voters: 0 });
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.total_weight().unwrap(), 102);
+        assert_eq!(poll.total_votes(), 3);
+        assert_eq!(contract.get_winner(&poll_id).unwrap(), WinnerOutcome::Winner {
+            option: "Option A".to_string(),
+            votes: 100,
+        });
+        
+        // Weighted state survives a snapshot round trip and passes validation
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_weighted_results(&poll_id).unwrap(), weighted_results);
+        assert_eq!(restored.voter_weight(&"wallet_whale".parse().unwrap()), 100);
+    }
+    
+    #[test]
+    fn test_total_weight_overflow() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        
+        contract.set_voter_weight(&"wallet_whale1".parse().unwrap(), u128::MAX, &admin_address).unwrap();
+        contract.set_voter_weight(&"wallet_whale2".parse().unwrap(), u128::MAX, &admin_address).unwrap();
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_whale1".parse().unwrap(), "Option A").unwrap();
+        contract.vote(&poll_id, "wallet_whale2".parse().unwrap(), "Option B").unwrap();
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert!(matches!(poll.total_weight(), Err(VotingError::VoteCountOverflow)));
+    }
+    
+    #[test]
+    fn test_delegated_votes_follow_chains() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        // wallet_a -> wallet_b -> wallet_c, and wallet_d -> wallet_c
+        contract.delegate_vote(&"wallet_a".parse().unwrap(), &"wallet_b".parse().unwrap()).unwrap();
+        contract.delegate_vote(&"wallet_b".parse().unwrap(), &"wallet_c".parse().unwrap()).unwrap();
+        contract.delegate_vote(&"wallet_d".parse().unwrap(), &"wallet_c".parse().unwrap()).unwrap();
+        let result = contract.delegate_vote(&"wallet_c".parse().unwrap(), &"wallet_a".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::DelegationCycle)));
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_c".parse().unwrap(), "Option A").unwrap();
+        
+        let weighted_results = contract.get_weighted_results(&poll_id).unwrap();
+        assert_eq!(weighted_results["Option A"], OptionTally { weight: 4, voters: 1 });
+        assert_eq!(contract.get_poll(&poll_id).unwrap().total_votes(), 1);
+        
+        // wallet_b votes directly: its own power and wallet_a's follow it to the new option
+        contract.vote(&poll_id, "wallet_b".parse().unwrap(), "Option B").unwrap();
+        let weighted_results = contract.get_weighted_results(&poll_id).unwrap();
+        assert_eq!(weighted_results["Option A"], OptionTally { weight: 2, voters: 1 });
+        assert_eq!(weighted_results["Option B"], OptionTally { weight: 2, voters: 1 });
+        
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.delegated_votes["wallet_a"].delegate_address, "wallet_b");
+        assert_eq!(poll.delegated_votes["wallet_d"].delegate_address, "wallet_c");
+        
+        // The override only applies to this poll
+        assert_eq!(contract.delegations.delegate_of(&"wallet_b".parse().unwrap()).unwrap(), "wallet_c");
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_weighted_results(&poll_id).unwrap(), weighted_results);
+    }
+    
+    #[test]
+    fn test_delegator_voting_first_keeps_own_power() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        contract.delegate_vote(&"wallet_a".parse().unwrap(), &"wallet_b".parse().unwrap()).unwrap();
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Option C").unwrap();
+        contract.vote(&poll_id, "wallet_b".parse().unwrap(), "Option A").unwrap();
+        
+        let results = contract.get_poll_results(&poll_id).unwrap();
+        assert_eq!(results["Option A"], 1);
+        assert_eq!(results["Option C"], 1);
+        
+        // Revoked delegations are not carried in later polls
+        contract.revoke_delegation(&"wallet_a".parse().unwrap()).unwrap();
+        let second_poll_id = create_test_poll(&mut contract);
+        contract.vote(&second_poll_id, "wallet_b".parse().unwrap(), "Option A").unwrap();
+        assert_eq!(contract.get_poll_results(&second_poll_id).unwrap()["Option A"], 1);
+    }
+    
+    #[test]
+    fn test_commit_reveal_voting() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        let poll_id = contract
+            .create_commit_reveal_poll(
+                "wallet_creator".parse().unwrap(),
+                "Sealed Poll".to_string(),
+                "Votes stay hidden until revealed".to_string(),
+                vec!["Option A".to_string(), "Option B".to_string()],
+                1,
+                10,
+            )
+            .unwrap();
+            
+        contract
+            .commit_vote(&poll_id, "wallet_a".parse().unwrap(), vote_commitment("Option A", b"salt_a"))
+            .unwrap();
+        contract
+            .commit_vote(&poll_id, "wallet_b".parse().unwrap(), vote_commitment("Option B", b"salt_b"))
+            .unwrap();
+            
+        let result = contract.commit_vote(
+            &poll_id,
+            "wallet_a".parse().unwrap(),
+            vote_commitment("Option B", b"salt_a"),
+        );
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        let result = contract.vote(&poll_id, "wallet_c".parse().unwrap(), "Option A");
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        let result = contract.reveal_vote(&poll_id, "wallet_a".parse().unwrap(), "Option A", b"salt_a");
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        
+        // Nothing is counted while the commit window is open
+        let summary = contract.get_poll_summary(&poll_id).unwrap();
+        assert_eq!(summary.total_votes, 0);
+        assert_eq!(summary.unrevealed_commitments, 2);
+        
+        clock.advance(1);
+        
+        let result = contract.commit_vote(
+            &poll_id,
+            "wallet_c".parse().unwrap(),
+            vote_commitment("Option A", b"salt_c"),
+        );
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        let result = contract.reveal_vote(&poll_id, "wallet_a".parse().unwrap(), "Option B", b"salt_a");
+        assert!(matches!(result, Err(VotingError::CommitmentMismatch)));
+        let result = contract.reveal_vote(&poll_id, "wallet_c".parse().unwrap(), "Option A", b"salt_c");
+        assert!(matches!(result, Err(VotingError::NoCommitment)));
+        
+        contract.reveal_vote(&poll_id, "wallet_a".parse().unwrap(), "Option A", b"salt_a").unwrap();
+        
+        let results = contract.get_poll_results(&poll_id).unwrap();
+        assert_eq!(results["Option A"], 1);
+        assert_eq!(results["Option B"], 0);
+        let summary = contract.get_poll_summary(&poll_id).unwrap();
+        assert_eq!(summary.total_votes, 1);
+        assert_eq!(summary.unrevealed_commitments, 1);
+        
+        // The clock is not part of the snapshot
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        restored.set_clock(Box::new(clock));
+        assert_eq!(restored.get_poll_summary(&poll_id).unwrap(), summary);
+    }
+    
+    fn create_quorum_test_poll(contract: &mut VotingContract, quorum_config: QuorumConfig) -> PollId {
+        contract
+            .create_poll_with_quorum(
+                "wallet_creator".parse().unwrap(),
+                "Governance Proposal".to_string(),
+                "Adopt the proposal?".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                10,
+                quorum_config,
+            )
+            .unwrap()
+    }
+    
+    #[test]
+    fn test_quorum_outcomes() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let quorum_config = QuorumConfig {
+            min_participants: 2,
+            min_turnout_pct: 30,
+            pass_threshold_pct: 60,
+            eligible_voters: 10,
+        };
+        
+        // Two of ten eligible voters take part: below 30% turnout
+        let low_turnout_poll_id = create_quorum_test_poll(&mut contract, quorum_config);
+        contract.vote(&low_turnout_poll_id, "wallet_voter1".parse().unwrap(), "Yes").unwrap();
+        contract.vote(&low_turnout_poll_id, "wallet_voter2".parse().unwrap(), "Yes").unwrap();
+        assert_eq!(contract.get_poll_outcome(&low_turnout_poll_id).unwrap(), None);
+        contract.close_poll(&low_turnout_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(contract.get_poll_outcome(&low_turnout_poll_id).unwrap(), Some(PollOutcome::QuorumNotMet));
+        assert!(!contract.get_poll_summary(&low_turnout_poll_id).unwrap().quorum_met);
+        
+        // Three unanimous votes pass; four of seven votes (57%) fall short of the 60% threshold
+        let passed_poll_id = create_quorum_test_poll(&mut contract, quorum_config);
+        let rejected_poll_id = create_quorum_test_poll(&mut contract, quorum_config);
+        for voter_number in 1..=7 {
+            let voter_address = format!("wallet_voter{}", voter_number);
+            if voter_number <= 3 {
+                contract.vote(&passed_poll_id, voter_address.parse().unwrap(), "Yes").unwrap();
+            }
+            let selected_option = if voter_number <= 4 { "Yes" } else { "No" };
+            contract.vote(&rejected_poll_id, voter_address.parse().unwrap(), selected_option).unwrap();
+        }
+        contract.close_poll(&passed_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.close_poll(&rejected_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(contract.get_poll_outcome(&passed_poll_id).unwrap(), Some(PollOutcome::Passed));
+        assert_eq!(contract.get_poll_outcome(&rejected_poll_id).unwrap(), Some(PollOutcome::Rejected));
+        
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&passed_poll_id).unwrap().quorum_config, Some(quorum_config));
+        
+        // Percentages above 100 and turnout without an electorate are rejected
+        let invalid_config = QuorumConfig { pass_threshold_pct: 101, ..QuorumConfig::default() };
+        let result = contract.create_poll_with_quorum(
+            "wallet_creator".parse().unwrap(),
+            "Invalid Proposal".to_string(),
+            "Bad thresholds".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            10,
+            invalid_config,
+        );
+        assert!(matches!(result, Err(VotingError::InvalidQuorumConfig)));
+        let invalid_config = QuorumConfig { min_turnout_pct: 10, ..QuorumConfig::default() };
+        assert!(matches!(invalid_config.validate(), Err(VotingError::InvalidQuorumConfig)));
+    }
+    
+    #[test]
+    fn test_event_log() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Option A").unwrap();
+        contract.set_voter_weight(&"wallet_voter2".parse().unwrap(), 5, &"wallet_admin".parse().unwrap()).unwrap();
+        contract.delegate_vote(&"wallet_voter3".parse().unwrap(), &"wallet_voter2".parse().unwrap()).unwrap();
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.archive_closed_polls();
+        
+        // Failed and no-op calls leave nothing in the log
+        assert!(contract.vote(&poll_id, "wallet_voter2".parse().unwrap(), "Option A").is_err());
+        contract.revoke_delegation(&"wallet_unknown".parse().unwrap()).unwrap();
+        
+        let events = contract.events_since(0);
+        assert_eq!(events.len(), 6);
+        assert_eq!(events.iter().map(|logged_event| logged_event.sequence).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(events[3].event, ContractEvent::DelegationSet {
+            delegator_address: "wallet_voter3".parse().unwrap(),
+            delegate_address: "wallet_voter2".parse().unwrap(),
+        });
+        assert_eq!(events[5].event, ContractEvent::PollArchived { poll_id: poll_id.clone() });
+        
+        // Readers catch up from the last sequence they processed
+        let newer_events = contract.events_since(4);
+        assert_eq!(newer_events.len(), 2);
+        assert_eq!(newer_events[0].sequence, 5);
+        assert!(contract.events_since(6).is_empty());
+        
+        // Pruning keeps sequence numbers stable, and the log survives snapshots
+        assert_eq!(contract.prune_events(3), 3);
+        assert_eq!(contract.events_since(0)[0].sequence, 4);
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.events_since(0), contract.events_since(0));
+        restored.add_admin(&"wallet_admin2".parse().unwrap(), &"wallet_admin".parse().unwrap()).unwrap();
+        assert_eq!(restored.events_since(6)[0].sequence, 7);
+    }
+    
+    #[test]
+    fn test_list_polls() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        
+        let options = vec!["Yes".to_string(), "No".to_string()];
+        let budget_poll_id = contract
+            .create_poll("wallet_alice".parse().unwrap(), "Budget 2025".to_string(), "".to_string(), options.clone(), 100)
+            .unwrap();
+        let roadmap_poll_id = contract
+            .create_poll("wallet_bob".parse().unwrap(), "Roadmap".to_string(), "".to_string(), options.clone(), 50)
+            .unwrap();
+        let budget_review_poll_id = contract
+            .create_poll("wallet_alice".parse().unwrap(), "Budget review".to_string(), "".to_string(), options, 200)
+            .unwrap();
+        contract.vote(&roadmap_poll_id, "wallet_voter1".parse().unwrap(), "Yes").unwrap();
+        contract.vote(&roadmap_poll_id, "wallet_voter2".parse().unwrap(), "No").unwrap();
+        contract.vote(&budget_review_poll_id, "wallet_voter1".parse().unwrap(), "Yes").unwrap();
+        contract.close_poll(&budget_poll_id, &"wallet_alice".parse().unwrap()).unwrap();
+        
+        let poll_ids = |polls: Vec<&Poll>| polls.into_iter().map(|poll| poll.poll_id.clone()).collect::<Vec<_>>();
+        
+        // Polls created in the same second fall back to the newest ID first
+        let all_polls = contract.list_polls(&PollFilter::default(), PollSort::Newest, 0, 10);
+        assert_eq!(poll_ids(all_polls), vec![budget_review_poll_id.clone(), roadmap_poll_id.clone(), budget_poll_id.clone()]);
+        
+        let ending_soonest = contract.list_polls(&PollFilter::default(), PollSort::EndingSoonest, 0, 10);
+        assert_eq!(poll_ids(ending_soonest), vec![roadmap_poll_id.clone(), budget_poll_id.clone(), budget_review_poll_id.clone()]);
+        
+        let most_votes = contract.list_polls(&PollFilter::default(), PollSort::MostVotes, 1, 1);
+        assert_eq!(poll_ids(most_votes), vec![budget_review_poll_id.clone()]);
+        
+        let alice_filter = PollFilter {
+            creator_address: Some("wallet_alice".parse().unwrap()),
+            title_contains: Some("BUDGET".to_string()),
+            ..PollFilter::default()
+        };
+        let alice_polls = contract.list_polls(&alice_filter, PollSort::Newest, 0, 10);
+        assert_eq!(poll_ids(alice_polls), vec![budget_review_poll_id.clone(), budget_poll_id.clone()]);
+        
+        let active_filter = PollFilter { status: Some(StatusFilter::Active), ..alice_filter };
+        let active_polls = contract.list_polls(&active_filter, PollSort::Newest, 0, 10);
+        assert_eq!(poll_ids(active_polls), vec![budget_review_poll_id]);
+        
+        let closed_filter = PollFilter { status: Some(StatusFilter::Closed), ..PollFilter::default() };
+        assert_eq!(poll_ids(contract.list_polls(&closed_filter, PollSort::Newest, 0, 10)), vec![budget_poll_id]);
+        
+        // Nothing was created at or after the far future
+        let future_filter = PollFilter { created_after: Some(u64::MAX), ..PollFilter::default() };
+        assert!(contract.list_polls(&future_filter, PollSort::Newest, 0, 10).is_empty());
+        let expired_filter = PollFilter { status: Some(StatusFilter::Expired), ..PollFilter::default() };
+        assert!(contract.list_polls(&expired_filter, PollSort::Newest, 0, 10).is_empty());
+    }
+    
+    #[test]
+    fn test_mock_clock_drives_poll_lifecycle() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        let poll_id = create_test_poll(&mut contract);
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.poll_start_timestamp, 1_000);
+        assert_eq!(poll.poll_end_timestamp, 1_010);
+        
+        // The poll is open for the last second of its window and closed at its end timestamp
+        clock.set(1_009);
+        assert!(contract.is_poll_active(&poll_id).unwrap());
+        assert!(contract.process_expired_polls(&"wallet_admin".parse().unwrap()).unwrap().is_empty());
+        
+        clock.set(1_010);
+        assert!(!contract.is_poll_active(&poll_id).unwrap());
+        assert_eq!(contract.process_expired_polls(&"wallet_admin".parse().unwrap()).unwrap(), vec![poll_id.clone()]);
+        
+        // Moving time backwards cannot reopen a closed poll
+        clock.set(1_005);
+        assert!(!contract.is_poll_active(&poll_id).unwrap());
+    }
+    
+    #[test]
+    fn test_roles_gate_privileged_methods() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        
+        // Only role managers hand out roles
+        let result = contract.grant_role(
+            &"wallet_manager".parse().unwrap(),
+            Role::PollManager,
+            &"wallet_manager".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.grant_role(&"wallet_manager".parse().unwrap(), Role::PollManager, &admin_address).unwrap();
+        contract.grant_role(&"wallet_moderator".parse().unwrap(), Role::Moderator, &admin_address).unwrap();
+        assert!(contract.has_role(&"wallet_manager".parse().unwrap(), Role::PollManager));
+        
+        let first_poll_id = create_test_poll(&mut contract);
+        let second_poll_id = create_test_poll(&mut contract);
         
-        // Generate unique ID for the poll
-        let poll_id = format!("poll_{}", self.active_polls.len() + 1);
+        // Poll managers configure and close other creators' polls but cannot manage roles or weights
+        contract.set_min_winning_votes(&first_poll_id, 2, &"wallet_manager".parse().unwrap()).unwrap();
+        contract.close_poll(&first_poll_id, &"wallet_manager".parse().unwrap()).unwrap();
+        let result = contract.grant_role(
+            &"wallet_other".parse().unwrap(),
+            Role::Moderator,
+            &"wallet_manager".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        let result = contract.set_voter_weight(&"wallet_voter".parse().unwrap(), 5, &"wallet_manager".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
         
-        // Set up time boundaries
-        let current_timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        // Moderators close polls but do not configure them or process expirations
+        let result = contract.set_min_winning_votes(&second_poll_id, 2, &"wallet_moderator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        clock.advance(10);
+        let result = contract.process_expired_polls(&"wallet_moderator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        assert_eq!(contract.process_expired_polls(&"wallet_manager".parse().unwrap()).unwrap(), vec![second_poll_id]);
+        
+        // Revoked roles stop working immediately
+        contract.revoke_role(&"wallet_manager".parse().unwrap(), Role::PollManager, &admin_address).unwrap();
+        let third_poll_id = create_test_poll(&mut contract);
+        let result = contract.close_poll(&third_poll_id, &"wallet_manager".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert!(restored.has_role(&"wallet_moderator".parse().unwrap(), Role::Moderator));
+        assert!(!restored.has_role(&"wallet_manager".parse().unwrap(), Role::PollManager));
+    }
+    
+    #[test]
+    fn test_eligibility_lists() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address);
+        let poll_options = vec!["Yes".to_string(), "No".to_string()];
+        
+        let gated_poll_id = contract
+            .create_poll_with_eligibility(
+                "wallet_creator".parse().unwrap(),
+                "Members Poll".to_string(),
+                "Only members vote".to_string(),
+                poll_options.clone(),
+                60,
+                Some(vec!["wallet_member".parse().unwrap(), " wallet_banned ".parse().unwrap()]),
+                vec!["wallet_banned".parse().unwrap()],
+            )
+            .unwrap();
             
-        if poll_duration_seconds == 0 {
-            return Err(VotingError::InvalidTimeSettings);
-        }
+        // Only the creator edits the lists, and only before voting starts
+        let result = contract.add_eligible_voter(
+            &gated_poll_id,
+            &"wallet_guest".parse().unwrap(),
+            &"wallet_member".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract
+            .add_eligible_voter(
+                &gated_poll_id,
+                &"wallet_guest".parse().unwrap(),
+                &"wallet_creator".parse().unwrap(),
+            )
+            .unwrap();
+        contract
+            .remove_eligible_voter(
+                &gated_poll_id,
+                &"wallet_member".parse().unwrap(),
+                &"wallet_creator".parse().unwrap(),
+            )
+            .unwrap();
         
-        let poll_end_timestamp = current_timestamp + poll_duration_seconds;
+        let poll = contract.get_poll(&gated_poll_id).unwrap();
+        assert!(poll.is_eligible(&"wallet_guest".parse().unwrap()));
+        assert!(!poll.is_eligible(&"wallet_member".parse().unwrap()));
+        assert!(!poll.is_eligible(&"wallet_banned".parse().unwrap()));
         
-        // Initialize vote counts for each option
-        let mut option_vote_counts = HashMap::new();
-        for voting_option in &poll_options {
-            option_vote_counts.insert(voting_option.clone(), 0);
+        for voter_address in ["wallet_member", "wallet_banned", "wallet_stranger"] {
+            let result = contract.vote(&gated_poll_id, voter_address.parse().unwrap(), "Yes");
+            assert!(matches!(result, Err(VotingError::NotEligible)));
         }
+        contract.vote(&gated_poll_id, "wallet_guest".parse().unwrap(), "Yes").unwrap();
+        let result = contract.add_eligible_voter(
+            &gated_poll_id,
+            &"wallet_member".parse().unwrap(),
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
         
-        // Create and store the poll
-        let new_poll = Poll {
-            poll_id: poll_id.clone(),
-            poll_title,
-            poll_description,
-            voting_options: poll_options,
-            vote_counts: option_vote_counts,
-            participant_addresses: HashSet::new(),
-            poll_creator_address: creator_address,
-            poll_start_timestamp: current_timestamp,
-            poll_end_timestamp,
-            poll_is_closed: false,
-        };
-        
-        self.active_polls.insert(poll_id.clone(), new_poll);
+        // Open polls admit everyone except denied addresses, and ineligible delegators carry no power
+        let open_poll_id = create_test_poll(&mut contract);
+        contract
+            .remove_eligible_voter(
+                &open_poll_id,
+                &"wallet_banned".parse().unwrap(),
+                &"wallet_creator".parse().unwrap(),
+            )
+            .unwrap();
+        contract.delegate_vote(&"wallet_banned".parse().unwrap(), &"wallet_delegate".parse().unwrap()).unwrap();
+        let result = contract.vote(&open_poll_id, "wallet_banned".parse().unwrap(), "Option A");
+        assert!(matches!(result, Err(VotingError::NotEligible)));
+        contract.vote(&open_poll_id, "wallet_delegate".parse().unwrap(), "Option A").unwrap();
+        assert_eq!(contract.get_poll_results(&open_poll_id).unwrap()["Option A"], 1);
         
-        Ok(poll_id)
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert!(!restored.get_poll(&gated_poll_id).unwrap().is_eligible(&"wallet_member".parse().unwrap()));
+        assert!(restored.get_poll(&open_poll_id).unwrap().denylist.contains("wallet_banned"));
     }
     
-    // Cast a vote in a poll
-    pub fn vote(&mut self, poll_id: &str, voter_address: String, selected_option: &str) -> Result<()> {
-        // Retrieve poll or return error
-        let poll = self.active_polls.get_mut(poll_id).ok_or(VotingError::PollNotFound)?;
+    #[test]
+    fn test_change_and_retract_vote() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        let poll_options = vec!["Yes".to_string(), "No".to_string()];
         
-        // Check if poll is active
-        if !poll.is_active() {
-            return Err(VotingError::PollClosed);
-        }
+        // Polls keep votes fixed unless created with vote changes allowed
+        let fixed_poll_id = create_test_poll(&mut contract);
+        contract.vote(&fixed_poll_id, "wallet_voter".parse().unwrap(), "Option A").unwrap();
+        let result = contract.change_vote(&fixed_poll_id, "wallet_voter".parse().unwrap(), "Option B");
+        assert!(matches!(result, Err(VotingError::VoteChangesDisabled)));
         
-        // Check if voter has already voted
-        if poll.participant_addresses.contains(&voter_address) {
-            return Err(VotingError::AlreadyVoted);
-        }
+        let poll_id = contract
+            .create_poll_with_vote_changes(
+                "wallet_creator".parse().unwrap(),
+                "Changeable Poll".to_string(),
+                "Votes can move".to_string(),
+                poll_options,
+                60,
+            )
+            .unwrap();
+        let result = contract.retract_vote(&poll_id, "wallet_voter".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotVoted)));
         
-        // Check if option is valid
-        if !poll.voting_options.contains(&selected_option.to_string()) {
-            return Err(VotingError::InvalidOption);
-        }
+        // A weighted voter carrying a delegator moves all of that power at once
+        contract.set_voter_weight(&"wallet_voter".parse().unwrap(), 3, &admin_address).unwrap();
+        contract.delegate_vote(&"wallet_delegator".parse().unwrap(), &"wallet_voter".parse().unwrap()).unwrap();
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+        contract.vote(&poll_id, "wallet_other".parse().unwrap(), "Yes").unwrap();
+        contract.change_vote(&poll_id, "wallet_voter".parse().unwrap(), "No").unwrap();
         
-        // Record the vote
-        let option_count = poll.vote_counts.entry(selected_option.to_string()).or_insert(0);
-        *option_count += 1;
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.votes_by_address["wallet_voter"], "No");
+        assert_eq!(poll.delegated_votes["wallet_delegator"].selected_option, "No");
+        assert_eq!(poll.get_results(), HashMap::from([("Yes".to_string(), 1), ("No".to_string(), 4)]));
+        assert_eq!(poll.voters_for("No"), 1);
         
-        // Record that this wallet has voted
-        poll.participant_addresses.insert(voter_address);
+        // Retracting removes the ballot and releases the delegator, who can then vote directly
+        contract.retract_vote(&poll_id, "wallet_voter".parse().unwrap()).unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.get_results()["No"], 0);
+        assert!(!poll.participant_addresses.contains(&"wallet_voter".parse().unwrap()));
+        assert!(poll.delegated_votes.is_empty());
+        contract.vote(&poll_id, "wallet_delegator".parse().unwrap(), "No").unwrap();
         
-        Ok(())
-    }
-    
-    // Get details of a specific poll
-    pub fn get_poll(&self, poll_id: &str) -> Result<&Poll> {
-        self.active_polls.get(poll_id).ok_or(VotingError::PollNotFound)
-    }
-    
-    // Get results of a specific poll
-    pub fn get_poll_results(&self, poll_id: &str) -> Result<HashMap<String, usize>> {
-        let poll = self.get_poll(poll_id)?;
-        Ok(poll.get_results())
-    }
-    
-    // Check if the poll is active
-    pub fn is_poll_active(&self, poll_id: &str) -> Result<bool> {
-        let poll = self.get_poll(poll_id)?;
-        Ok(poll.is_active())
-    }
-    
-    // Get all polls
-    pub fn get_all_polls(&self) -> Vec<&Poll> {
-        self.active_polls.values().collect()
-    }
-    
-    // Get all active polls
-    pub fn get_active_polls(&self) -> Vec<&Poll> {
-        self.active_polls.values().filter(|poll| poll.is_active()).collect()
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert!(restored.get_poll(&poll_id).unwrap().allow_vote_changes);
+        
+        let events = contract.events_since(0);
+        assert!(events.iter().any(|logged_event| matches!(
+            &logged_event.event,
+            ContractEvent::VoteRetracted { previous_option, .. } if previous_option == "No"
+        )));
     }
     
-    // Manually close a poll (admin or creator only)
-    pub fn close_poll(&mut self, poll_id: &str, wallet_address: &str) -> Result<()> {
-        let poll = self.active_polls.get_mut(poll_id).ok_or(VotingError::PollNotFound)?;
+    #[test]
+    fn test_scheduled_polls() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
         
-        // Only admin or poll creator can close the poll
-        if wallet_address != &self.admin_address && wallet_address != &poll.poll_creator_address {
-            return Err(VotingError::NotAuthorized);
-        }
+        let poll_id = contract
+            .create_scheduled_poll(
+                "wallet_creator".parse().unwrap(),
+                "Scheduled Poll".to_string(),
+                "Opens later".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                100,
+                50,
+            )
+            .unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.poll_start_timestamp, poll.poll_end_timestamp), (1_100, 1_150));
+        assert!(poll.is_scheduled(clock.now()));
         
-        poll.close();
-        Ok(())
-    }
-    
-    // Automatically check and close polls that have passed their end time
-    pub fn process_expired_polls(&mut self) -> Vec<String> {
-        let current_timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-            
-        let mut closed_poll_ids = Vec::new();
+        let scheduled_filter = PollFilter {
+            status: Some(StatusFilter::Scheduled),
+            ..PollFilter::default()
+        };
+        assert_eq!(contract.list_polls(&scheduled_filter, PollSort::Newest, 0, 10).len(), 1);
         
-        for (poll_id, poll) in self.active_polls.iter_mut() {
-            if !poll.poll_is_closed && current_timestamp >= poll.poll_end_timestamp {
-                poll.close();
-                closed_poll_ids.push(poll_id.clone());
-            }
-        }
+        // Nothing starts or accepts votes before the start time
+        let result = contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+        assert!(contract.process_scheduled_polls(&admin_address).unwrap().is_empty());
         
-        closed_poll_ids
+        clock.advance(100);
+        let result = contract.process_scheduled_polls(&"wallet_voter".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        assert_eq!(contract.process_scheduled_polls(&admin_address).unwrap(), vec![poll_id.clone()]);
+        assert_eq!(contract.get_poll(&poll_id).unwrap().status, PollStatus::Active);
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+        
+        let events = contract.events_since(0);
+        assert!(matches!(&events[events.len() - 2].event, ContractEvent::PollStarted { poll_id: started } if *started == poll_id));
+        
+        // The shifted window must still fit in a u64
+        let result = contract.create_scheduled_poll(
+            "wallet_creator".parse().unwrap(),
+            "Far Future Poll".to_string(),
+            "Never opens".to_string(),
+            vec!["Yes".to_string(), "No".to_string()],
+            u64::MAX - 1_000,
+            50,
+        );
+        assert!(matches!(result, Err(VotingError::InvalidTimeSettings)));
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::thread::sleep;
-    use std::time::Duration;
     
-    // Helper function to create a test poll
-    fn create_test_poll(contract: &mut VotingContract) -> String {
-        let creator_address = "wallet_creator".to_string();
-        let poll_title = "Test Poll".to_string();
-        let poll_description = "This is a test poll".to_string();
-        let poll_options = vec!["Option A".to_string(), "Option B".to_string(), "Option C".to_string()];
+    #[test]
+    fn test_poll_lifecycle_transitions() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
+        let mut contract = VotingContract::new(admin_address.clone());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
         
-        // Create a poll with a 10 second duration
-        contract.create_poll(creator_address, poll_title, poll_description, poll_options, 10).unwrap()
+        // Drafts can be edited but not voted on
+        let poll_id = contract
+            .create_draft_poll(
+                "wallet_creator".parse().unwrap(),
+                "Draft Poll".to_string(),
+                "Work in progress".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let result = contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+        let result = contract.update_draft(
+            &poll_id,
+            "Hijacked".to_string(),
+            String::new(),
+            Vec::new(),
+            60,
+            &"wallet_voter".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract
+            .update_draft(
+                &poll_id,
+                "Final Poll".to_string(),
+                "Ready to go".to_string(),
+                vec!["Yes".to_string(), "No".to_string(), "Abstain".to_string()],
+                100,
+                &"wallet_creator".parse().unwrap(),
+            )
+            .unwrap();
+        assert_eq!(contract.time_remaining(&poll_id).unwrap(), 100);
+        
+        // Publishing starts the window now and freezes the draft
+        clock.advance(500);
+        contract.publish_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.status, poll.poll_start_timestamp, poll.poll_end_timestamp), (PollStatus::Active, 1_500, 1_600));
+        let result = contract.update_draft(
+            &poll_id,
+            "Late".to_string(),
+            String::new(),
+            Vec::new(),
+            60,
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::InvalidTransition)));
+        let result = contract.publish_poll(&poll_id, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidTransition)));
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Abstain").unwrap();
+        
+        // Paused polls reject votes until resumed
+        let result = contract.resume_poll(&poll_id, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidTransition)));
+        contract.pause_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let result = contract.vote(&poll_id, "wallet_other".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+        contract.resume_poll(&poll_id, &admin_address).unwrap();
+        contract.vote(&poll_id, "wallet_other".parse().unwrap(), "Yes").unwrap();
+        
+        // Only closed polls are archived; archived polls leave default listings but stay queryable
+        let result = contract.archive_poll(&poll_id, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidTransition)));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.archive_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().status, PollStatus::Archived);
+        assert!(contract.list_polls(&PollFilter::default(), PollSort::Newest, 0, 10).is_empty());
+        let archived_filter = PollFilter {
+            status: Some(StatusFilter::Archived),
+            ..PollFilter::default()
+        };
+        assert_eq!(contract.list_polls(&archived_filter, PollSort::Newest, 0, 10).len(), 1);
+        
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&poll_id).unwrap().status, PollStatus::Archived);
     }
     
     #[test]
-    fn test_create_poll() {
-        let admin_address = "wallet_admin".to_string();
+    fn test_winner_tie_breaks() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
         let mut contract = VotingContract::new(admin_address);
         
+        // A clear winner reports its margin and share of the vote
         let poll_id = create_test_poll(&mut contract);
+        for (voter_address, selected_option) in [("wallet_1", "Option A"), ("wallet_2", "Option A"), ("wallet_3", "Option B")] {
+            contract.vote(&poll_id, voter_address.parse().unwrap(), selected_option).unwrap();
+        }
+        let winner = contract.get_poll_winner(&poll_id).unwrap();
+        assert_eq!(winner.winning_option.as_deref(), Some("Option A"));
+        assert_eq!((winner.votes, winner.margin, winner.percentage_bps), (2, 1, 6_666));
         
-        // Verify poll was created
-        let poll = contract.get_poll(&poll_id).unwrap();
-        assert_eq!(poll.poll_title, "Test Poll");
-        assert_eq!(poll.voting_options.len(), 3);
-        assert_eq!(poll.participant_addresses.len(), 0);
-        assert!(poll.is_active());
+        // Ties need a rerun unless the poll picks another strategy before voting starts
+        let tied_poll_id = create_test_poll(&mut contract);
+        contract.set_tie_break(&tied_poll_id, TieBreak::EarliestOption, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.vote(&tied_poll_id, "wallet_1".parse().unwrap(), "Option C").unwrap();
+        contract.vote(&tied_poll_id, "wallet_2".parse().unwrap(), "Option B").unwrap();
+        let result = contract.set_tie_break(&tied_poll_id, TieBreak::RerunRequired, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        
+        let mut poll = contract.get_poll(&tied_poll_id).unwrap().clone();
+        let winner = poll.winner().unwrap();
+        assert_eq!(winner.winning_option.as_deref(), Some("Option B"));
+        assert_eq!(winner.tied_options, vec!["Option B".to_string(), "Option C".to_string()]);
+        assert_eq!((winner.margin, winner.percentage_bps), (0, 5_000));
+        
+        poll.tie_break = TieBreak::RerunRequired;
+        assert_eq!(poll.winner().unwrap().winning_option, None);
+        
+        // Seeded draws are repeatable and always pick a tied option
+        poll.tie_break = TieBreak::Seeded(42);
+        let seeded_winner = poll.winner().unwrap().winning_option.unwrap();
+        assert_eq!(poll.winner().unwrap().winning_option.as_deref(), Some(seeded_winner.as_str()));
+        assert!(winner.tied_options.contains(&seeded_winner));
     }
     
     #[test]
-    fn test_vote() {
-        let admin_address = "wallet_admin".to_string();
+    fn test_quadratic_poll_credit_budget() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
         let mut contract = VotingContract::new(admin_address);
         
-        let poll_id = create_test_poll(&mut contract);
+        let result = contract.create_quadratic_poll(
+            "wallet_creator".parse().unwrap(),
+            "No Credits".to_string(),
+            "Nothing to spend".to_string(),
+            vec!["Option A".to_string(), "Option B".to_string()],
+            10,
+            0,
+        );
+        assert!(matches!(result, Err(VotingError::PollCreationFailed)));
         
-        // Cast votes
-        let voter1_address = "wallet_voter1".to_string();
-        let voter2_address = "wallet_voter2".to_string();
+        let poll_id = create_quadratic_test_poll(&mut contract, 10);
+        let voter_address = "wallet_voter".to_string();
         
-        contract.vote(&poll_id, voter1_address, "Option A").unwrap();
-        contract.vote(&poll_id, voter2_address, "Option B").unwrap();
+        // Quadratic polls take no single-choice ballots, and other polls take no quadratic ones
+        let result = contract.vote(&poll_id, voter_address.parse().unwrap(), "Option A");
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        let single_choice_poll_id = create_test_poll(&mut contract);
+        let result = contract.vote_quadratic(&single_choice_poll_id, voter_address.parse().unwrap(), "Option A", 1);
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
         
-        // Verify votes were recorded
-        let results = contract.get_poll_results(&poll_id).unwrap();
-        assert_eq!(*results.get("Option A").unwrap(), 1);
-        assert_eq!(*results.get("Option B").unwrap(), 1);
-        assert_eq!(*results.get("Option C").unwrap(), 0);
+        // 1 credit buys the first vote, 3 more buy the second; 2 would buy a fraction of one
+        contract.spend_quadratic_credits(&poll_id, voter_address.parse().unwrap(), "Option A", 1).unwrap();
+        let result = contract.spend_quadratic_credits(&poll_id, voter_address.parse().unwrap(), "Option A", 2);
+        assert!(matches!(result, Err(VotingError::FractionalVotes)));
+        contract.spend_quadratic_credits(&poll_id, voter_address.parse().unwrap(), "Option A", 3).unwrap();
         
         let poll = contract.get_poll(&poll_id).unwrap();
-        assert_eq!(poll.total_votes(), 2);
+        assert_eq!(poll.quadratic_votes(&voter_address.parse().unwrap(), "Option A"), 2);
+        assert_eq!(poll.credits_remaining(&voter_address.parse().unwrap()), Some(6));
+        assert_eq!(poll.credits_remaining(&"wallet_other".parse().unwrap()), Some(10));
+        
+        // 9 credits would buy 3 votes on a fresh option, but only 6 are left
+        let result = contract.spend_quadratic_credits(&poll_id, voter_address.parse().unwrap(), "Option B", 9);
+        assert!(matches!(result, Err(VotingError::InsufficientCredits)));
+        contract.spend_quadratic_credits(&poll_id, voter_address.parse().unwrap(), "Option B", 4).unwrap();
+        
+        let results = contract.get_poll_results(&poll_id).unwrap();
+        assert_eq!(results.get("Option A"), Some(&2));
+        assert_eq!(results.get("Option B"), Some(&2));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().credits_remaining(&voter_address.parse().unwrap()), Some(2));
+        
+        // Allocations beyond the budget are rejected when restoring a snapshot
+        contract
+            .active_polls
+            .get_mut(&poll_id)
+            .unwrap()
+            .quadratic_allocations
+            .get_mut(voter_address.as_str())
+            .unwrap()
+            .insert("Option C".to_string(), 2);
+        assert!(contract.active_polls[&poll_id].validate_invariants().is_err());
     }
     
     #[test]
-    fn test_double_voting_prevention() {
-        let admin_address = "wallet_admin".to_string();
+    fn test_abstain_and_nota_options() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
         let mut contract = VotingContract::new(admin_address);
+        let poll_id = contract
+            .create_poll_with_reserved_options(
+                "wallet_creator".parse().unwrap(),
+                "Budget".to_string(),
+                "Abstentions allowed".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                3600,
+                true,
+                true,
+            )
+            .unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().voting_options, vec!["Yes", "No", ABSTAIN_OPTION, NOTA_OPTION]);
         
-        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Yes").unwrap();
+        contract.vote(&poll_id, "wallet_voter2".parse().unwrap(), ABSTAIN_OPTION).unwrap();
+        contract.vote(&poll_id, "wallet_voter3".parse().unwrap(), NOTA_OPTION).unwrap();
+        contract.vote(&poll_id, "wallet_voter4".parse().unwrap(), NOTA_OPTION).unwrap();
         
-        // First vote should succeed
-        let voter_address = "wallet_voter".to_string();
-        contract.vote(&poll_id, voter_address.clone(), "Option A").unwrap();
+        // Reserved options are reported apart from the results and cannot win
+        let results = contract.get_poll_results(&poll_id).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results.get("Yes"), Some(&1));
+        let reserved_results = contract.get_reserved_results(&poll_id).unwrap();
+        assert_eq!(reserved_results.get(ABSTAIN_OPTION), Some(&1));
+        assert_eq!(reserved_results.get(NOTA_OPTION), Some(&2));
+        assert_eq!(
+            contract.get_winner(&poll_id).unwrap(),
+            WinnerOutcome::Winner { option: "Yes".to_string(), votes: 1 }
+        );
         
-        // Second vote should fail
-        let result = contract.vote(&poll_id, voter_address, "Option B");
-        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        // Every reserved vote counts toward turnout; none of the above also counts against passing
+        let poll = contract.active_polls.get_mut(&poll_id).unwrap();
+        poll.quorum_config = Some(QuorumConfig {
+            min_participants: 4,
+            min_turnout_pct: 0,
+            pass_threshold_pct: 50,
+            eligible_voters: 0,
+        });
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert!(contract.get_poll_summary(&poll_id).unwrap().quorum_met);
+        assert_eq!(contract.get_poll_outcome(&poll_id).unwrap(), Some(PollOutcome::Rejected));
+        
+        // Regular options may not take a reserved name
+        let result = contract.create_poll_with_reserved_options(
+            "wallet_creator".parse().unwrap(),
+            "Clash".to_string(),
+            "Reserved name reused".to_string(),
+            vec!["Yes".to_string(), ABSTAIN_OPTION.to_string()],
+            3600,
+            true,
+            false,
+        );
+        assert!(matches!(result, Err(VotingError::PollCreationFailed)));
+        let result = contract.create_poll_with_reserved_options(
+            "wallet_creator".parse().unwrap(),
+            "Too few".to_string(),
+            "One real option".to_string(),
+            vec!["Yes".to_string()],
+            3600,
+            true,
+            true,
+        );
+        assert!(matches!(result, Err(VotingError::PollCreationFailed)));
     }
     
     #[test]
-    fn test_invalid_option() {
-        let admin_address = "wallet_admin".to_string();
+    fn test_approval_voting() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
         let mut contract = VotingContract::new(admin_address);
+        let poll_id = contract
+            .create_approval_poll(
+                "wallet_creator".parse().unwrap(),
+                "Committee".to_string(),
+                "Approve up to two candidates".to_string(),
+                vec!["Ana".to_string(), "Ben".to_string(), "Cy".to_string()],
+                3600,
+                2,
+            )
+            .unwrap();
+            
+        contract
+            .vote_multi(
+                &poll_id,
+                "wallet_voter1".parse().unwrap(),
+                vec!["Ana".to_string(), "Ben".to_string()],
+            )
+            .unwrap();
+        contract.vote_multi(&poll_id, "wallet_voter2".parse().unwrap(), vec!["Ben".to_string()]).unwrap();
+        contract
+            .vote_multi(
+                &poll_id,
+                "wallet_voter3".parse().unwrap(),
+                vec!["Cy".to_string(), "Ben".to_string()],
+            )
+            .unwrap();
         
-        let poll_id = create_test_poll(&mut contract);
+        let approval_results = contract.get_approval_results(&poll_id).unwrap();
+        assert_eq!(approval_results.ballots, 3);
+        assert_eq!(approval_results.approvals.get("Ana"), Some(&1));
+        assert_eq!(approval_results.approvals.get("Ben"), Some(&3));
+        assert_eq!(
+            contract.get_winner(&poll_id).unwrap(),
+            WinnerOutcome::Winner { option: "Ben".to_string(), votes: 3 }
+        );
         
-        // Vote for non-existent option
-        let voter_address = "wallet_voter".to_string();
-        let result = contract.vote(&poll_id, voter_address, "Option D");
+        // Ballots must be non-empty, distinct and within the limit, and are cast once
+        let result = contract.vote_multi(
+            &poll_id,
+            "wallet_voter4".parse().unwrap(),
+            vec!["Ana".to_string(), "Ana".to_string()],
+        );
+        assert!(matches!(result, Err(VotingError::DuplicateSelection)));
+        let all_options = vec!["Ana".to_string(), "Ben".to_string(), "Cy".to_string()];
+        let result = contract.vote_multi(&poll_id, "wallet_voter4".parse().unwrap(), all_options);
+        assert!(matches!(result, Err(VotingError::TooManySelections)));
+        let result = contract.vote_multi(&poll_id, "wallet_voter4".parse().unwrap(), Vec::new());
         assert!(matches!(result, Err(VotingError::InvalidOption)));
+        let result = contract.vote_multi(&poll_id, "wallet_voter1".parse().unwrap(), vec!["Cy".to_string()]);
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        let result = contract.vote(&poll_id, "wallet_voter4".parse().unwrap(), "Ana");
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        
+        // Approval ballots survive a snapshot
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_approval_results(&poll_id).unwrap(), approval_results);
+        
+        let poll_id = create_test_poll(&mut contract);
+        let result = contract.vote_multi(&poll_id, "wallet_voter1".parse().unwrap(), vec!["Option A".to_string()]);
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        assert!(matches!(contract.get_approval_results(&poll_id), Err(VotingError::WrongVotingMethod)));
     }
     
     #[test]
-    fn test_poll_expiration() {
-        let admin_address = "wallet_admin".to_string();
+    fn test_extend_and_finalize_early() {
+        let admin_address: Address = "wallet_admin".parse().unwrap();
         let mut contract = VotingContract::new(admin_address);
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        contract.max_poll_extensions = 2;
         
-        // Create a poll with a very short duration for testing
-        let creator_address = "wallet_creator".to_string();
         let poll_id = contract.create_poll(
-            creator_address,
-            "Short Poll".to_string(),
-            "This poll expires quickly".to_string(),
+            "wallet_creator".parse().unwrap(),
+            "Extended Poll".to_string(),
+            "The deadline moves".to_string(),
             vec!["Yes".to_string(), "No".to_string()],
-            1, // 1 second duration
+            60,
         ).unwrap();
         
-        // Sleep to allow the poll to expire
-        sleep(Duration::from_secs(2));
+        // Only the creator or a poll manager may move the deadline, a limited number of times
+        let result = contract.extend_poll(&poll_id, 30, &"wallet_random".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.extend_poll(&poll_id, 30, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.extend_poll(&poll_id, 30, &"wallet_admin".parse().unwrap()).unwrap();
+        let result = contract.extend_poll(&poll_id, 30, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::ExtensionLimitReached)));
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!((poll.poll_end_timestamp, poll.extension_count), (1_120, 2));
+        assert_eq!(
+            contract.events_since(0).last().unwrap().event,
+            ContractEvent::PollExtended {
+                poll_id: poll_id.clone(),
+                extended_by: "wallet_admin".parse().unwrap(),
+                previous_end_timestamp: 1_090,
+                new_end_timestamp: 1_120,
+            }
+        );
         
-        // Process expired polls
-        let closed_poll_ids = contract.process_expired_polls();
-        assert!(closed_poll_ids.contains(&poll_id));
+        // Extensions may not stretch the window past the maximum duration
+        let other_poll_id = create_test_poll(&mut contract);
+        contract.config.max_poll_duration = 100;
+        let result = contract.extend_poll(&other_poll_id, 91, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::DurationTooLong)));
         
-        // Verify the poll is now closed
-        let poll = contract.get_poll(&poll_id).unwrap();
-        assert!(poll.poll_is_closed);
+        // Finalizing closes the poll ahead of time and records the scheduled end
+        clock.advance(10);
+        let result = contract.finalize_early(&poll_id, &"wallet_random".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.finalize_early(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert!(contract.get_poll(&poll_id).unwrap().is_closed());
+        let events = contract.events_since(0);
+        assert_eq!(
+            events[events.len() - 2].event,
+            ContractEvent::PollFinalizedEarly {
+                poll_id: poll_id.clone(),
+                finalized_by: "wallet_creator".parse().unwrap(),
+                scheduled_end_timestamp: 1_120,
+                finalized_at: 1_010,
+            }
+        );
+        assert!(matches!(events[events.len() - 1].event, ContractEvent::PollClosed { .. }));
         
-        // Attempt to vote on expired poll should fail
-        let voter_address = "wallet_voter".to_string();
-        let result = contract.vote(&poll_id, voter_address, "Yes");
+        let result = contract.finalize_early(&poll_id, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::AlreadyClosed)));
+        let result = contract.extend_poll(&poll_id, 30, &"wallet_creator".parse().unwrap());
         assert!(matches!(result, Err(VotingError::PollClosed)));
     }
     
     #[test]
-    fn test_manual_poll_closure() {
-        let admin_address = "wallet_admin".to_string();
-        let mut contract = VotingContract::new(admin_address.clone());
-        
-        let creator_address = "wallet_creator".to_string();
-        let poll_id = contract.create_poll(
-            creator_address.clone(),
-            "Test Poll".to_string(),
-            "This is a test poll".to_string(),
-            vec!["Option A".to_string(), "Option B".to_string()],
-            60, // 60 second duration
-        ).unwrap();
+    fn test_duplicate_and_empty_options() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let create = |contract: &mut VotingContract, poll_options: &[&str]| {
+            contract.create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Options".to_string(),
+                "Checks the option list".to_string(),
+                poll_options.iter().map(|option| option.to_string()).collect(),
+                60,
+            )
+        };
         
-        // Creator can close their own poll
-        contract.close_poll(&poll_id, &creator_address).unwrap();
+        assert!(matches!(create(&mut contract, &["Yes", "Yes", "No"]), Err(VotingError::DuplicateOption)));
+        assert!(matches!(create(&mut contract, &["Yes", " Yes ", "No"]), Err(VotingError::DuplicateOption)));
+        assert!(matches!(create(&mut contract, &["Yes", "No", "  "]), Err(VotingError::EmptyOption)));
+        assert!(matches!(create(&mut contract, &["Yes", ""]), Err(VotingError::EmptyOption)));
         
-        // Verify poll is closed
-        let poll = contract.get_poll(&poll_id).unwrap();
-        assert!(poll.poll_is_closed);
+        // Options are stored trimmed, and differing case is allowed unless configured otherwise
+        let poll_id = create(&mut contract, &[" Yes", "yes "]).unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().voting_options, vec!["Yes", "yes"]);
+        contract.case_insensitive_options = true;
+        assert!(matches!(create(&mut contract, &["Yes", "yes"]), Err(VotingError::DuplicateOption)));
         
-        // Create another poll for admin closure test
-        let poll_id2 = contract.create_poll(
-            creator_address,
-            "Admin Test Poll".to_string(),
-            "This poll will be closed by admin".to_string(),
-            vec!["Yes".to_string(), "No".to_string()],
+        // Drafts are held to the same rules
+        let draft_id = contract
+            .create_draft_poll(
+                "wallet_creator".parse().unwrap(),
+                "Draft".to_string(),
+                "Not open yet".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let result = contract.update_draft(
+            &draft_id,
+            "Draft".to_string(),
+            "Not open yet".to_string(),
+            vec!["Yes".to_string(), "YES".to_string()],
             60,
-        ).unwrap();
-        
-        // Admin can close any poll
-        contract.close_poll(&poll_id2, &admin_address).unwrap();
-        
-        // Verify poll is closed
-        let poll = contract.get_poll(&poll_id2).unwrap();
-        assert!(poll.poll_is_closed);
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::DuplicateOption)));
     }
     
     #[test]
-    fn test_unauthorized_poll_closure() {
-        let admin_address = "wallet_admin".to_string();
-        let mut contract = VotingContract::new(admin_address);
+    fn test_lazy_expiration_and_tick() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Option A").unwrap();
         
-        let creator_address = "wallet_creator".to_string();
-        let poll_id = contract.create_poll(
-            creator_address,
-            "Test Poll".to_string(),
-            "This is a test poll".to_string(),
-            vec!["Option A".to_string(), "Option B".to_string()],
-            60,
-        ).unwrap();
+        // Without auto_expire an ended poll rejects votes but stays Active in storage
+        clock.advance(10);
+        let result = contract.vote(&poll_id, "wallet_late".parse().unwrap(), "Option A");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().status, PollStatus::Active);
         
-        // Random user cannot close the poll
-        let random_user_address = "wallet_random".to_string();
-        let result = contract.close_poll(&poll_id, &random_user_address);
-        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        // With it, the next call touching the poll records the expiry before failing
+        contract.auto_expire = true;
+        let result = contract.vote(&poll_id, "wallet_late".parse().unwrap(), "Option A");
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.status, PollStatus::Closed);
+        assert!(poll.ballot_root.is_some());
+        assert!(matches!(
+            &contract.events_since(0).last().unwrap().event,
+            ContractEvent::PollExpired { poll_id: expired_poll_id } if *expired_poll_id == poll_id
+        ));
+        contract.archive_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        
+        // A tick records every due transition at once, and anyone may run it
+        let scheduled_poll_id = contract
+            .create_scheduled_poll(
+                "wallet_creator".parse().unwrap(),
+                "Later".to_string(),
+                "Opens in a minute".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+                60,
+            )
+            .unwrap();
+        let expiring_poll_id = create_test_poll(&mut contract);
+        assert_eq!(contract.tick(), TickSummary::default());
+        clock.advance(60);
+        assert_eq!(contract.tick(), TickSummary {
+            started_poll_ids: vec![scheduled_poll_id.clone()],
+            expired_poll_ids: vec![expiring_poll_id],
+            passed_convictions: Vec::new(),
+        });
+        assert_eq!(contract.get_poll(&scheduled_poll_id).unwrap().status, PollStatus::Active);
+        
+        // The setting is saved with the contract
+        assert!(VotingContract::from_snapshot(&contract.to_snapshot()).unwrap().auto_expire);
     }
     
     #[test]
-    fn test_active_polls_filtering() {
-        let admin_address = "wallet_admin".to_string();
-        let mut contract = VotingContract::new(admin_address);
-        
-        // Create two polls
-        let creator_address = "wallet_creator".to_string();
-        let poll_id1 = contract.create_poll(
-            creator_address.clone(),
-            "Active Poll".to_string(),
-            "This poll is active".to_string(),
-            vec!["Yes".to_string(), "No".to_string()],
-            60,
-        ).unwrap();
-        
-        let poll_id2 = contract.create_poll(
-            creator_address.clone(),
-            "Closed Poll".to_string(),
-            "This poll will be closed".to_string(),
-            vec!["Yes".to_string(), "No".to_string()],
-            60,
-        ).unwrap();
+    fn test_results_iter() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = create_test_poll(&mut contract);
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Option C").unwrap();
+        contract.vote(&poll_id, "wallet_b".parse().unwrap(), "Option C").unwrap();
+        contract.vote(&poll_id, "wallet_c".parse().unwrap(), "Option A").unwrap();
         
-        // Close one poll
-        contract.close_poll(&poll_id2, &creator_address).unwrap();
+        // Results come in ballot order, or by count with ties in ballot order, and match get_results
+        let poll = contract.get_poll(&poll_id).unwrap();
+        let results: Vec<(&str, u128)> = poll.results_iter().collect();
+        assert_eq!(results, vec![("Option A", 1), ("Option B", 0), ("Option C", 2)]);
+        assert_eq!(poll.results_sorted(), vec![("Option C", 2), ("Option A", 1), ("Option B", 0)]);
+        let collected: HashMap<String, u128> =
+            results.iter().map(|&(option, votes)| (option.to_string(), votes)).collect();
+        assert_eq!(collected, poll.get_results());
         
-        // Check active polls
-        let active_polls = contract.get_active_polls();
-        assert_eq!(active_polls.len(), 1);
-        assert_eq!(active_polls[0].poll_id, poll_id1);
+        // Ranked-choice polls yield their tabulated results
+        let ranked_poll_id = contract
+            .create_ranked_poll(
+                "wallet_creator".parse().unwrap(),
+                "Ranked".to_string(),
+                String::new(),
+                vec!["Option A".to_string(), "Option B".to_string()],
+                60,
+            )
+            .unwrap();
+        let ranking = vec!["Option B".to_string(), "Option A".to_string()];
+        contract.vote_ranked(&ranked_poll_id, "wallet_a".parse().unwrap(), ranking).unwrap();
+        let ranked_poll = contract.get_poll(&ranked_poll_id).unwrap();
+        assert_eq!(ranked_poll.results_sorted()[0].0, "Option B");
+        assert_eq!(ranked_poll.results_iter().count(), ranked_poll.get_results().len());
     }
 }
\ No newline at end of file
@@ -0,0 +1,217 @@
+// Ballot Merkle roots.
+// When a poll closes, every ballot becomes a leaf committing to the voter and the options the
+// ballot backs, and the root of the tree over those leaves is stored on the poll. Anyone holding
+// the root can check a ballot proof from get_ballot_proof without trusting the operator to report
+// ballots honestly, and can recount the tallies from the published leaves.
+// Leaves are sorted by voter. Leaf and node hashes use different prefixes so a node can never be
+// passed off as a leaf, and an unpaired node moves up a level unchanged instead of being doubled.
+use crate::address::Address;
+use crate::encoding::Encoder;
+use crate::hash::sha256;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{Poll, Result, VotingContract, VotingError};
+
+// Prefixes separating leaf hashes from interior node hashes
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+// Root of a poll closed without ballots
+pub const EMPTY_BALLOT_ROOT: [u8; 32] = [0; 32];
+
+// Define the proof that a ballot is a leaf under a poll's ballot root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BallotProof {
+    pub poll_id: PollId,
+    pub voter: Address,
+    pub options: Vec<String>,    // Options the ballot backs, in ballot order
+    pub leaf_index: usize,       // Position of the ballot among the sorted leaves
+    pub leaf_count: usize,       // Number of ballots under the root
+    pub siblings: Vec<[u8; 32]>, // Sibling hashes from the leaf up to the root
+}
+
+impl BallotProof {
+    // Recompute the root the proof leads to
+    pub fn compute_root(&self) -> [u8; 32] {
+        let mut hash = leaf_hash(&self.voter, &self.options);
+        let mut index = self.leaf_index;
+        let mut level_size = self.leaf_count;
+        let mut siblings = self.siblings.iter();
+        while level_size > 1 {
+            // The last node of an odd level has no sibling
+            if index % 2 == 1 || index + 1 < level_size {
+                let Some(sibling) = siblings.next() else {
+                    return EMPTY_BALLOT_ROOT;
+                };
+                hash = if index % 2 == 1 { node_hash(sibling, &hash) } else { node_hash(&hash, sibling) };
+            }
+            index /= 2;
+            level_size = level_size.div_ceil(2);
+        }
+        hash
+    }
+    
+    // Check the proof against a ballot root
+    pub fn verify(&self, ballot_root: &[u8; 32]) -> bool {
+        self.leaf_index < self.leaf_count
+            && self.siblings.len() == proof_length(self.leaf_index, self.leaf_count)
+            && self.compute_root() == *ballot_root
+    }
+}
+
+// Hash one ballot
+pub fn leaf_hash(voter_address: &Address, options: &[String]) -> [u8; 32] {
+    let mut encoder = Encoder::new();
+    encoder.write(&LEAF_PREFIX);
+    encoder.write(voter_address);
+    encoder.write(&options.to_vec());
+    sha256(&encoder.finish())
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hashed_bytes = vec![NODE_PREFIX];
+    hashed_bytes.extend_from_slice(left);
+    hashed_bytes.extend_from_slice(right);
+    sha256(&hashed_bytes)
+}
+
+// Count the siblings on the path from a leaf to the root
+fn proof_length(mut index: usize, mut level_size: usize) -> usize {
+    let mut length = 0;
+    while level_size > 1 {
+        if index % 2 == 1 || index + 1 < level_size {
+            length += 1;
+        }
+        index /= 2;
+        level_size = level_size.div_ceil(2);
+    }
+    length
+}
+
+// Hash each level of the tree into the next, returning every level from the leaves up
+fn tree_levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+        let next_level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(left, right),
+                _ => pair[0],
+            })
+            .collect();
+        levels.push(next_level);
+    }
+    levels
+}
+
+impl Poll {
+    // Get every ballot as (voter, options backed, see ballot_options), sorted by voter. Anonymous
+    // ballots name their hex nullifier as the voter.
+    pub fn ballot_leaves(&self) -> Vec<(Address, Vec<String>)> {
+        let mut leaves: Vec<(Address, Vec<String>)> = self
+            .voters()
+            .filter_map(|voter_address| Some((voter_address.clone(), self.ballot_options(voter_address)?)))
+            .chain(self.anonymous_leaves())
+            .collect();
+        leaves.sort();
+        leaves
+    }
+    
+    // Compute the Merkle root over the poll's current ballots
+    pub fn compute_ballot_root(&self) -> [u8; 32] {
+        let leaves = self
+            .ballot_leaves()
+            .iter()
+            .map(|(voter_address, options)| leaf_hash(voter_address, options))
+            .collect();
+        tree_levels(leaves).last().and_then(|root_level| root_level.first().copied()).unwrap_or(EMPTY_BALLOT_ROOT)
+    }
+    
+    // Build the inclusion proof for a voter's ballot. The proof is against the poll's current
+    // ballots, which match the stored root once the poll is closed.
+    pub fn ballot_proof(&self, voter_address: &Address) -> Result<BallotProof> {
+        let leaves = self.ballot_leaves();
+        let leaf_index = leaves
+            .binary_search_by(|(leaf_voter, _)| leaf_voter.cmp(voter_address))
+            .map_err(|_| VotingError::NotVoted)?;
+        let leaf_count = leaves.len();
+        let levels = tree_levels(leaves.iter().map(|(voter, options)| leaf_hash(voter, options)).collect());
+        
+        let mut siblings = Vec::new();
+        let mut index = leaf_index;
+        for level in &levels[..levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                siblings.push(*sibling);
+            }
+            index /= 2;
+        }
+        
+        let (voter, options) = leaves[leaf_index].clone();
+        Ok(BallotProof { poll_id: self.poll_id.clone(), voter, options, leaf_index, leaf_count, siblings })
+    }
+}
+
+impl VotingContract {
+    // Get the proof that a voter's ballot is included in a closed poll's ballot root (archived
+    // polls included). Returns WrongPhase while the poll is open and NotVoted without a ballot.
+    pub fn get_ballot_proof(&self, poll_id: &PollId, voter_address: &Address) -> Result<BallotProof> {
+        let poll = self.get_poll(poll_id)?;
+        if poll.ballot_root.is_none() {
+            return Err(VotingError::WrongPhase);
+        }
+        poll.ballot_proof(&self.lookup_address(voter_address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_ballot_proofs() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Ballot root".to_string(),
+                "Proofs for every ballot".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let voters = ["wallet_e", "wallet_b", "wallet_d", "wallet_a", "wallet_c"];
+        for (index, voter_address) in voters.iter().enumerate() {
+            let selected_option = if index % 2 == 0 { "Yes" } else { "No" };
+            contract.vote(&poll_id, voter_address.parse().unwrap(), selected_option).unwrap();
+        }
+        
+        // Proofs are only served once the root is fixed
+        let result = contract.get_ballot_proof(&poll_id, &"wallet_a".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let ballot_root = contract.get_poll(&poll_id).unwrap().ballot_root.unwrap();
+        
+        // Five leaves exercise both paired and unpaired nodes
+        for voter_address in voters {
+            let proof = contract.get_ballot_proof(&poll_id, &voter_address.parse().unwrap()).unwrap();
+            assert!(proof.verify(&ballot_root));
+        }
+        let proof = contract.get_ballot_proof(&poll_id, &"wallet_e".parse().unwrap()).unwrap();
+        assert_eq!((proof.leaf_index, proof.options.clone()), (4, vec!["Yes".to_string()]));
+        
+        // Altered proofs do not verify
+        let mut forged_proof = proof.clone();
+        forged_proof.options = vec!["No".to_string()];
+        assert!(!forged_proof.verify(&ballot_root));
+        let mut forged_proof = contract.get_ballot_proof(&poll_id, &"wallet_a".parse().unwrap()).unwrap();
+        forged_proof.leaf_index = 1;
+        assert!(!forged_proof.verify(&ballot_root));
+        
+        let result = contract.get_ballot_proof(&poll_id, &"wallet_f".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotVoted)));
+        
+        // The root is persisted with the poll
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&poll_id).unwrap().ballot_root, Some(ballot_root));
+    }
+}
\ No newline at end of file
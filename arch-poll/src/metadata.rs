@@ -0,0 +1,158 @@
+// Structured poll metadata.
+// A poll's description is free text for people to read. UIs also need fields they can act on, such
+// as a discussion link, a banner image or the IPFS CID of the full proposal, so each poll carries
+// a small map of string keys to string values. Keys are short machine-readable names and values
+// are limited in size, since every poll is stored and serialized in full. Hidden polls are left
+// out of metadata queries, like every other public listing.
+use alloc::collections::BTreeMap;
+
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Most entries one poll's metadata may hold
+pub const MAX_METADATA_ENTRIES: usize = 16;
+
+// Longest metadata key in bytes
+pub const MAX_METADATA_KEY_LEN: usize = 64;
+
+// Longest metadata value in bytes, enough for a URL or content identifier
+pub const MAX_METADATA_VALUE_LEN: usize = 512;
+
+// Conventional keys UIs look for
+pub const METADATA_DISCUSSION_URL: &str = "discussion_url";
+pub const METADATA_IMAGE_URL: &str = "image_url";
+pub const METADATA_IPFS_CID: &str = "ipfs_cid";
+pub const METADATA_LINK: &str = "link";
+
+// Check a key: lowercase ASCII letters, digits, '_', '-', '.' and ':'
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_METADATA_KEY_LEN
+        && key
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || b"_-.:".contains(&byte))
+}
+
+// Check a value: trimmed, non-empty, within the size limit and free of control characters
+fn is_valid_value(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_METADATA_VALUE_LEN
+        && value.trim() == value
+        && !value.chars().any(char::is_control)
+}
+
+// Check a whole metadata map, failing with InvalidMetadata
+pub fn validate_metadata(metadata: &BTreeMap<String, String>) -> Result<()> {
+    if metadata.len() > MAX_METADATA_ENTRIES
+        || !metadata.iter().all(|(key, value)| is_valid_key(key) && is_valid_value(value))
+    {
+        return Err(VotingError::InvalidMetadata);
+    }
+    Ok(())
+}
+
+impl VotingContract {
+    // Replace a poll's metadata (creator or ConfigurePolls); an empty map clears it
+    pub fn set_poll_metadata(
+        &mut self,
+        poll_id: &PollId,
+        metadata: BTreeMap<String, String>,
+        caller: &Address,
+    ) -> Result<()> {
+        validate_metadata(&metadata)?;
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        let keys = metadata.keys().cloned().collect();
+        poll.metadata = metadata;
+        
+        self.emit(ContractEvent::MetadataSet {
+            poll_id: poll_id.clone(),
+            keys,
+        });
+        Ok(())
+    }
+    
+    // Get one metadata value of a poll (archived polls included)
+    pub fn get_poll_metadata(&self, poll_id: &PollId, key: &str) -> Result<Option<&str>> {
+        Ok(self.get_poll(poll_id)?.metadata.get(key).map(String::as_str))
+    }
+    
+    // Get the visible live polls that have a metadata key, optionally with a given value, oldest
+    // first
+    pub fn polls_with_metadata(&self, key: &str, value: Option<&str>) -> Vec<&Poll> {
+        let mut polls: Vec<&Poll> = self
+            .active_polls
+            .values()
+            .filter(|poll| !poll.hidden)
+            .filter(|poll| match (poll.metadata.get(key), value) {
+                (Some(poll_value), Some(value)) => poll_value == value,
+                (Some(_), None) => true,
+                (None, _) => false,
+            })
+            .collect();
+        polls.sort_by_key(|poll| poll.poll_number);
+        polls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_poll_metadata() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let mut create = |poll_title: &str| {
+            contract
+                .create_poll(
+                    "wallet_creator".parse().unwrap(),
+                    poll_title.to_string(),
+                    "See the linked proposal".to_string(),
+                    vec!["Yes".to_string(), "No".to_string()],
+                    60,
+                )
+                .unwrap()
+        };
+        let first_poll_id = create("Treasury");
+        let second_poll_id = create("Grants");
+        
+        let metadata = BTreeMap::from([
+            (
+                METADATA_IPFS_CID.to_string(),
+                "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+            ),
+            (METADATA_DISCUSSION_URL.to_string(), "https://forum.example/t/42".to_string()),
+        ]);
+        contract.set_poll_metadata(&first_poll_id, metadata.clone(), &"wallet_creator".parse().unwrap()).unwrap();
+        let result = contract.set_poll_metadata(&second_poll_id, metadata, &"wallet_voter".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        let link = BTreeMap::from([(METADATA_DISCUSSION_URL.to_string(), "https://forum.example/t/7".to_string())]);
+        contract.set_poll_metadata(&second_poll_id, link, &"wallet_admin".parse().unwrap()).unwrap();
+        
+        // Malformed keys, padded or oversized values and too many entries are rejected
+        for (key, value) in [("Discussion URL", "x"), ("link", " padded "), ("link", &"x".repeat(513)[..])] {
+            let metadata = BTreeMap::from([(key.to_string(), value.to_string())]);
+            let result = contract.set_poll_metadata(&first_poll_id, metadata, &"wallet_creator".parse().unwrap());
+            assert!(matches!(result, Err(VotingError::InvalidMetadata)));
+        }
+        let crowded = (0..=MAX_METADATA_ENTRIES)
+            .map(|index| (format!("key_{}", index), "x".to_string()))
+            .collect();
+        let result = contract.set_poll_metadata(&first_poll_id, crowded, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidMetadata)));
+        
+        // Queries by key and by key and value
+        let cid = contract.get_poll_metadata(&first_poll_id, METADATA_IPFS_CID).unwrap();
+        assert!(cid.unwrap().starts_with("bafy"));
+        let with_discussion = contract.polls_with_metadata(METADATA_DISCUSSION_URL, None);
+        assert_eq!(with_discussion.len(), 2);
+        let matching = contract.polls_with_metadata(METADATA_DISCUSSION_URL, Some("https://forum.example/t/7"));
+        assert_eq!(matching[0].poll_id, second_poll_id);
+        
+        // Metadata is saved in snapshots
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_poll(&first_poll_id).unwrap().metadata.len(), 2);
+    }
+}
\ No newline at end of file
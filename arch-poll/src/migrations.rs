@@ -0,0 +1,329 @@
+// Upgrades of older snapshot layouts.
+// Every change to the persisted layout raises SNAPSHOT_SCHEMA_VERSION and adds a numbered step
+// here that rewrites state of the previous version into the new one, e.g. turning a poll's old
+// `poll_is_closed` flag into a PollStatus. Steps work on a ContractState's sections, so they only
+// need the old encoding of the sections they change. A type that only gained fields reads and
+// writes every layout itself (see Encoder::layout), and its step re-encodes the sections holding
+// it. from_snapshot runs the missing steps in order before restoring, so upgrading a build never
+// strands an existing snapshot.
+use crate::access_control::AccessControl;
+use crate::collections::HashMap;
+use crate::config::ContractConfig;
+use crate::delegation::DelegationRegistry;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::moderation::ModerationRecord;
+use crate::prelude::*;
+use crate::rate_limit::RateLimiter;
+use crate::state::{ContractState, StateSection, SNAPSHOT_MIN_READER_VERSION, SNAPSHOT_SCHEMA_VERSION};
+use crate::{Poll, Result, SequencedEvent, VotingContract, VotingError};
+
+// Section holding a whole unversioned snapshot while it waits for the first step
+pub const LEGACY_SECTION_TAG: u16 = 0;
+
+// Define a step upgrading state to the next schema version
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u16, // Schema version the step produces, from the one before it
+    pub description: &'static str,
+    pub apply: fn(&mut ContractState) -> Result<()>,
+}
+
+// Every step, in version order
+pub const MIGRATIONS: [Migration; 14] = [
+    Migration {
+        version: 1,
+        description: "Split an unversioned snapshot into tagged sections",
+        apply: split_legacy_snapshot,
+    },
+    Migration {
+        version: 2,
+        description: "Add metadata to polls",
+        apply: add_poll_metadata,
+    },
+    Migration {
+        version: 3,
+        description: "Add vote fees to polls",
+        apply: add_poll_vote_fees,
+    },
+    Migration {
+        version: 4,
+        description: "Add runoff rounds to polls",
+        apply: add_poll_runoffs,
+    },
+    Migration {
+        version: 5,
+        description: "Add tabulation methods to polls",
+        apply: add_poll_tabulation_methods,
+    },
+    Migration {
+        version: 6,
+        description: "Add conviction state to polls",
+        apply: add_poll_convictions,
+    },
+    Migration {
+        version: 7,
+        description: "Add dependencies to polls",
+        apply: add_poll_dependencies,
+    },
+    Migration {
+        version: 8,
+        description: "Add anonymous ballots to polls",
+        apply: add_poll_anonymous_ballots,
+    },
+    Migration {
+        version: 9,
+        description: "Add encrypted ballots to polls",
+        apply: add_poll_encrypted_ballots,
+    },
+    Migration {
+        version: 10,
+        description: "Add a results visibility to polls",
+        apply: add_poll_results_visibility,
+    },
+    Migration {
+        version: 11,
+        description: "Tag poll participant sets so they can be compact",
+        apply: tag_poll_participant_sets,
+    },
+    Migration {
+        version: 12,
+        description: "Link re-run polls to the poll they re-run",
+        apply: add_poll_rerun_links,
+    },
+    Migration {
+        version: 13,
+        description: "Give polls a list of vote rationales",
+        apply: add_poll_rationales,
+    },
+    Migration {
+        version: 14,
+        description: "Key poll tallies by option ID",
+        apply: key_poll_tallies_by_option_id,
+    },
+];
+
+// Version 0 snapshots were the persisted fields written back to back, without a header, in the
+// layout of version 1
+fn split_legacy_snapshot(state: &mut ContractState) -> Result<()> {
+    let legacy = state.take_section(LEGACY_SECTION_TAG).ok_or(VotingError::CorruptState)?;
+    let mut decoder = Decoder::with_layout(&legacy, 1);
+    move_value::<AccessControl>(&mut decoder, state, StateSection::AccessControl)?;
+    move_value::<ContractConfig>(&mut decoder, state, StateSection::Config)?;
+    move_value::<u64>(&mut decoder, state, StateSection::NextPollId)?;
+    move_value::<HashMap<String, Poll>>(&mut decoder, state, StateSection::ActivePolls)?;
+    move_value::<HashMap<String, Poll>>(&mut decoder, state, StateSection::ArchivedPolls)?;
+    move_value::<bool>(&mut decoder, state, StateSection::RejectDuplicateTitles)?;
+    move_value::<HashMap<String, u128>>(&mut decoder, state, StateSection::VoterWeights)?;
+    move_value::<DelegationRegistry>(&mut decoder, state, StateSection::Delegations)?;
+    move_value::<Vec<SequencedEvent>>(&mut decoder, state, StateSection::EventLog)?;
+    move_value::<u64>(&mut decoder, state, StateSection::NextEventSequence)?;
+    move_value::<HashMap<String, u64>>(&mut decoder, state, StateSection::SignerNonces)?;
+    move_value::<u32>(&mut decoder, state, StateSection::MaxPollExtensions)?;
+    move_value::<bool>(&mut decoder, state, StateSection::CaseInsensitiveOptions)?;
+    move_value::<bool>(&mut decoder, state, StateSection::AutoExpire)?;
+    move_value::<RateLimiter>(&mut decoder, state, StateSection::RateLimiter)?;
+    move_value::<Vec<ModerationRecord>>(&mut decoder, state, StateSection::ModerationLog)?;
+    decoder.finish()
+}
+
+// Read the next value of an old layout into its own section, keeping its layout
+fn move_value<T: Decode + Encode>(
+    decoder: &mut Decoder,
+    state: &mut ContractState,
+    section: StateSection,
+) -> Result<()> {
+    let value: T = decoder.read()?;
+    let mut encoder = Encoder::with_layout(decoder.layout());
+    encoder.write(&value);
+    state.insert_section(section.tag(), encoder.finish());
+    Ok(())
+}
+
+// Rewrite a section holding a type that gained fields from one layout into the next
+fn reencode<T: Decode + Encode>(
+    state: &mut ContractState,
+    section: StateSection,
+    from_layout: u16,
+) -> Result<()> {
+    let bytes = state.take_section(section.tag()).ok_or(VotingError::CorruptState)?;
+    let mut decoder = Decoder::with_layout(&bytes, from_layout);
+    let value: T = decoder.read()?;
+    decoder.finish()?;
+    let mut encoder = Encoder::with_layout(from_layout + 1);
+    encoder.write(&value);
+    state.insert_section(section.tag(), encoder.finish());
+    Ok(())
+}
+
+// Version 2 appended a metadata map to every poll
+fn add_poll_metadata(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 1)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 1)
+}
+
+// Version 3 appended a vote fee to every poll
+fn add_poll_vote_fees(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 2)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 2)
+}
+
+// Version 4 appended a runoff configuration and round links to every poll
+fn add_poll_runoffs(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 3)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 3)
+}
+
+// Version 5 appended a tabulation method to every poll
+fn add_poll_tabulation_methods(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 4)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 4)
+}
+
+// Version 6 appended conviction state to every poll
+fn add_poll_convictions(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 5)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 5)
+}
+
+// Version 7 appended dependencies to every poll
+fn add_poll_dependencies(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 6)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 6)
+}
+
+// Version 8 appended anonymous ballots to every poll
+fn add_poll_anonymous_ballots(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 7)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 7)
+}
+
+// Version 9 appended encrypted ballots to every poll
+fn add_poll_encrypted_ballots(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 8)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 8)
+}
+
+// Version 10 appended a results visibility to every poll
+fn add_poll_results_visibility(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 9)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 9)
+}
+
+// Version 11 tagged every poll's participant set with whether it is compact
+fn tag_poll_participant_sets(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 10)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 10)
+}
+
+// Version 12 appended a link to the poll it re-runs to every poll
+fn add_poll_rerun_links(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 11)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 11)
+}
+
+// Version 13 appended the rationales attached to its ballots to every poll
+fn add_poll_rationales(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 12)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 12)
+}
+
+// Version 14 keyed every poll's vote and voter counts by option ID instead of option text
+fn key_poll_tallies_by_option_id(state: &mut ContractState) -> Result<()> {
+    reencode::<HashMap<String, Poll>>(state, StateSection::ActivePolls, 13)?;
+    reencode::<HashMap<String, Poll>>(state, StateSection::ArchivedPolls, 13)
+}
+
+// Bring state up to this build's schema version, one step at a time. State from a newer version
+// is left alone; from_bytes has already checked this build can read it.
+pub fn migrate_state(state: &mut ContractState) -> Result<()> {
+    if state.schema_version >= SNAPSHOT_SCHEMA_VERSION {
+        return Ok(());
+    }
+    let from_version = state.schema_version;
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > from_version) {
+        (migration.apply)(state)?;
+        state.schema_version = migration.version;
+    }
+    state.min_reader_version = SNAPSHOT_MIN_READER_VERSION;
+    Ok(())
+}
+
+impl VotingContract {
+    // Restore a contract from a snapshot written with an older schema version. Version 0 is the
+    // unversioned layout, which has no header to say so; versioned snapshots must match from_version.
+    pub fn migrate(data: &[u8], from_version: u16) -> Result<Self> {
+        let mut state = if from_version == 0 {
+            let mut state = ContractState::new();
+            state.schema_version = 0;
+            state.insert_section(LEGACY_SECTION_TAG, data.to_vec());
+            state
+        } else {
+            let state = ContractState::from_bytes(data)?;
+            if state.schema_version != from_version {
+                return Err(VotingError::CorruptState);
+            }
+            state
+        };
+        migrate_state(&mut state)?;
+        VotingContract::from_state(&state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::Encoder;
+    
+    #[test]
+    fn test_migrations() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Legacy".to_string(),
+                "Written before snapshots were versioned".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "No").unwrap();
+        
+        // Steps are numbered consecutively from 1 up to the current version
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, index + 1);
+        }
+        assert_eq!(MIGRATIONS.last().unwrap().version, SNAPSHOT_SCHEMA_VERSION);
+        
+        // A version 1 snapshot is the same state with polls in the layout before metadata
+        let mut state = contract.to_state();
+        for section in [StateSection::ActivePolls, StateSection::ArchivedPolls] {
+            let polls: HashMap<String, Poll> = state.read_required(section).unwrap();
+            let mut encoder = Encoder::with_layout(1);
+            encoder.write(&polls);
+            state.insert_section(section.tag(), encoder.finish());
+        }
+        state.schema_version = 1;
+        state.min_reader_version = 1;
+        let migrated = VotingContract::from_snapshot(&state.to_bytes()).unwrap();
+        assert_eq!(migrated.to_snapshot(), contract.to_snapshot());
+        
+        // An unversioned snapshot is version 1's sections back to back
+        let mut encoder = Encoder::new();
+        for tag in 1..=StateSection::ModerationLog.tag() {
+            encoder.write_bytes(state.section(tag).unwrap());
+        }
+        let legacy = encoder.finish();
+        assert!(matches!(VotingContract::from_snapshot(&legacy), Err(VotingError::CorruptState)));
+        let migrated = VotingContract::migrate(&legacy, 0).unwrap();
+        assert_eq!(migrated.get_poll(&poll_id).unwrap().get_results()["No"], 1);
+        assert_eq!(migrated.to_snapshot(), contract.to_snapshot());
+        
+        // Versioned snapshots must be the version the caller names, and trailing bytes are corrupt
+        let snapshot = contract.to_snapshot();
+        VotingContract::migrate(&snapshot, SNAPSHOT_SCHEMA_VERSION).unwrap();
+        assert!(matches!(VotingContract::migrate(&snapshot, 0), Err(VotingError::CorruptState)));
+        let mut trailing = legacy.clone();
+        trailing.push(0);
+        assert!(matches!(VotingContract::migrate(&trailing, 0), Err(VotingError::CorruptState)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,329 @@
+// Poll moderation: flagging, hiding and deletion.
+// Anyone can flag a poll with a reason, once per poll. Polls with pending flags form the
+// moderation queue, which holders of ModeratePolls work through by hiding the poll, deleting it or
+// dismissing its flags. Hidden polls drop out of the public listings (list_polls, the active polls
+// and the tag and category listings) but stay stored and reachable by ID for audit. Deletion
+// removes the poll for good. Every moderation action is appended to the moderation log together
+// with the flags it resolved, so the log explains each decision even after the poll is gone.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Longest reason a flag may give, in bytes
+pub const MAX_FLAG_REASON_LEN: usize = 500;
+
+// Define a report raised against a poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollFlag {
+    pub reporter: Address,
+    pub reason: String,
+    pub flagged_at: u64,
+}
+
+// Define the decisions a moderator can take on a poll
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationAction {
+    Hide,
+    Unhide,
+    Delete,
+    DismissFlags,
+}
+
+// Define one entry of the moderation log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModerationRecord {
+    pub poll_id: PollId,
+    pub poll_title: String,             // Kept so deleted polls can still be identified
+    pub action: ModerationAction,
+    pub moderator: Address,
+    pub reason: String,
+    pub timestamp: u64,
+    pub resolved_flags: Vec<PollFlag>,  // Flags pending when the action was taken
+}
+
+impl VotingContract {
+    // Flag a poll for moderators to review (anyone, once per poll)
+    pub fn flag_poll(&mut self, poll_id: &PollId, reporter: &Address, reason: &str) -> Result<()> {
+        let reporter = self.parse_address(reporter)?;
+        let reason = reason.trim();
+        if reason.is_empty() || reason.len() > MAX_FLAG_REASON_LEN {
+            return Err(VotingError::InvalidFlagReason);
+        }
+        
+        let current_timestamp = self.now();
+        let poll = self.stored_poll_mut(poll_id)?;
+        if poll.flags.iter().any(|flag| flag.reporter == reporter) {
+            return Err(VotingError::AlreadyFlagged);
+        }
+        
+        poll.flags.push(PollFlag {
+            reporter: reporter.clone(),
+            reason: reason.to_string(),
+            flagged_at: current_timestamp,
+        });
+        self.emit(ContractEvent::PollFlagged {
+            poll_id: poll_id.clone(),
+            reporter,
+        });
+        Ok(())
+    }
+    
+    // Get the visible polls with pending flags, most flagged first
+    pub fn moderation_queue(&self) -> Vec<&Poll> {
+        let mut flagged_polls: Vec<&Poll> = self
+            .iter_polls()
+            .filter(|poll| !poll.hidden && !poll.flags.is_empty())
+            .collect();
+        flagged_polls.sort_by(|first, second| {
+            second.flags.len().cmp(&first.flags.len()).then_with(|| first.poll_number.cmp(&second.poll_number))
+        });
+        flagged_polls
+    }
+    
+    // Get every moderation action taken, oldest first
+    pub fn moderation_log(&self) -> &[ModerationRecord] {
+        &self.moderation_log
+    }
+    
+    // Hide a poll from the public listings (requires ModeratePolls)
+    pub fn hide_poll(&mut self, poll_id: &PollId, reason: &str, caller: &Address) -> Result<()> {
+        self.set_hidden(poll_id, true, reason, caller)
+    }
+    
+    // Show a hidden poll in the public listings again (requires ModeratePolls)
+    pub fn unhide_poll(&mut self, poll_id: &PollId, reason: &str, caller: &Address) -> Result<()> {
+        self.set_hidden(poll_id, false, reason, caller)
+    }
+    
+    // Clear a poll's pending flags without acting on it (requires ModeratePolls)
+    pub fn dismiss_flags(&mut self, poll_id: &PollId, reason: &str, caller: &Address) -> Result<()> {
+        let moderator = self.check_moderator(caller)?;
+        let poll = self.stored_poll_mut(poll_id)?;
+        if poll.flags.is_empty() {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        let resolved_flags = core::mem::take(&mut poll.flags);
+        let poll_title = poll.poll_title.clone();
+        self.log_moderation(poll_id, poll_title, ModerationAction::DismissFlags, reason, moderator, resolved_flags);
+        Ok(())
+    }
+    
+    // Remove a poll, its ballots and its pending action for good (requires ModeratePolls)
+    pub fn delete_poll(&mut self, poll_id: &PollId, reason: &str, caller: &Address) -> Result<()> {
+        let moderator = self.check_moderator(caller)?;
+        self.stored_poll_mut(poll_id)?;
+        let poll = match self.active_polls.remove(poll_id) {
+            Some(poll) => poll,
+            None => self.archived_polls.remove(poll_id).ok_or(VotingError::PollNotFound)?,
+        };
+        
+        self.poll_index.delete(&poll);
+        self.status_index.remove(poll_id);
+        self.execution_hooks.remove(poll_id);
+        self.discussions.remove(poll_id);
+        self.log_moderation(poll_id, poll.poll_title, ModerationAction::Delete, reason, moderator, poll.flags);
+        Ok(())
+    }
+    
+    fn set_hidden(&mut self, poll_id: &PollId, hidden: bool, reason: &str, caller: &Address) -> Result<()> {
+        let moderator = self.check_moderator(caller)?;
+        let poll = self.stored_poll_mut(poll_id)?;
+        if poll.hidden == hidden {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        poll.hidden = hidden;
+        let resolved_flags = core::mem::take(&mut poll.flags);
+        let poll_title = poll.poll_title.clone();
+        let action = if hidden { ModerationAction::Hide } else { ModerationAction::Unhide };
+        self.log_moderation(poll_id, poll_title, action, reason, moderator, resolved_flags);
+        Ok(())
+    }
+    
+    // Check the caller may moderate, returning their canonical address
+    fn check_moderator(&self, caller: &Address) -> Result<Address> {
+        let caller = self.parse_address(caller)?;
+        if !self.has_permission(&caller, Permission::ModeratePolls) {
+            return Err(VotingError::NotAuthorized);
+        }
+        Ok(caller)
+    }
+    
+    // Find a live or archived poll for moderation, which applies whatever state the poll is in
+    // short of finalized
+    fn stored_poll_mut(&mut self, poll_id: &PollId) -> Result<&mut Poll> {
+        let poll = match self.active_polls.get_mut(poll_id) {
+            Some(poll) => poll,
+            None => self.archived_polls.get_mut(poll_id).ok_or(VotingError::PollNotFound)?,
+        };
+        if poll.finalization.is_some() {
+            return Err(VotingError::PollFinalized);
+        }
+        Ok(poll)
+    }
+    
+    fn log_moderation(
+        &mut self,
+        poll_id: &PollId,
+        poll_title: String,
+        action: ModerationAction,
+        reason: &str,
+        moderator: Address,
+        resolved_flags: Vec<PollFlag>,
+    ) {
+        self.moderation_log.push(ModerationRecord {
+            poll_id: poll_id.clone(),
+            poll_title,
+            action,
+            moderator: moderator.clone(),
+            reason: reason.trim().to_string(),
+            timestamp: self.now(),
+            resolved_flags,
+        });
+        self.emit(ContractEvent::PollModerated {
+            poll_id: poll_id.clone(),
+            action,
+            moderator,
+        });
+    }
+}
+
+impl Encode for PollFlag {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.reporter);
+        encoder.write(&self.reason);
+        encoder.write(&self.flagged_at);
+    }
+}
+
+impl Decode for PollFlag {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(PollFlag {
+            reporter: decoder.read()?,
+            reason: decoder.read()?,
+            flagged_at: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for ModerationAction {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            ModerationAction::Hide => 0,
+            ModerationAction::Unhide => 1,
+            ModerationAction::Delete => 2,
+            ModerationAction::DismissFlags => 3,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for ModerationAction {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(ModerationAction::Hide),
+            1 => Ok(ModerationAction::Unhide),
+            2 => Ok(ModerationAction::Delete),
+            3 => Ok(ModerationAction::DismissFlags),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+impl Encode for ModerationRecord {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.poll_id);
+        encoder.write(&self.poll_title);
+        encoder.write(&self.action);
+        encoder.write(&self.moderator);
+        encoder.write(&self.reason);
+        encoder.write(&self.timestamp);
+        encoder.write(&self.resolved_flags);
+    }
+}
+
+impl Decode for ModerationRecord {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(ModerationRecord {
+            poll_id: decoder.read()?,
+            poll_title: decoder.read()?,
+            action: decoder.read()?,
+            moderator: decoder.read()?,
+            reason: decoder.read()?,
+            timestamp: decoder.read()?,
+            resolved_flags: decoder.read()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_control::Role;
+    use crate::{PollFilter, PollSort};
+    
+    #[test]
+    fn test_poll_moderation() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract
+            .grant_role(
+                &"wallet_moderator".parse().unwrap(),
+                Role::Moderator,
+                &"wallet_admin".parse().unwrap(),
+            )
+            .unwrap();
+        let mut create = |poll_title: &str| {
+            contract
+                .create_poll(
+                    "wallet_creator".parse().unwrap(),
+                    poll_title.to_string(),
+                    String::new(),
+                    vec!["Yes".to_string(), "No".to_string()],
+                    60,
+                )
+                .unwrap()
+        };
+        let spam_poll_id = create("Free tokens");
+        let scam_poll_id = create("Send me your keys");
+        let fine_poll_id = create("Lunch");
+        
+        // Anyone may flag a poll once, and the queue puts the most flagged poll first
+        contract.flag_poll(&spam_poll_id, &"wallet_a".parse().unwrap(), "spam").unwrap();
+        contract.flag_poll(&scam_poll_id, &"wallet_a".parse().unwrap(), "scam").unwrap();
+        contract.flag_poll(&scam_poll_id, &"wallet_b".parse().unwrap(), "phishing").unwrap();
+        let result = contract.flag_poll(&scam_poll_id, &"wallet_b".parse().unwrap(), "again");
+        assert!(matches!(result, Err(VotingError::AlreadyFlagged)));
+        let result = contract.flag_poll(&fine_poll_id, &"wallet_c".parse().unwrap(), "   ");
+        assert!(matches!(result, Err(VotingError::InvalidFlagReason)));
+        let queue: Vec<&str> = contract.moderation_queue().iter().map(|poll| poll.poll_id.as_str()).collect();
+        assert_eq!(queue, vec![scam_poll_id.as_str(), spam_poll_id.as_str()]);
+        
+        // Only moderators act; hidden polls leave the listings but stay reachable by ID
+        let result = contract.hide_poll(&scam_poll_id, "phishing", &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.hide_poll(&scam_poll_id, "phishing", &"wallet_moderator".parse().unwrap()).unwrap();
+        let listed = contract.list_polls(&PollFilter::default(), PollSort::Newest, 0, 10);
+        assert!(listed.iter().all(|poll| poll.poll_id != scam_poll_id));
+        assert_eq!(contract.get_active_polls().len(), 2);
+        assert!(contract.get_poll(&scam_poll_id).unwrap().hidden);
+        
+        // Deletion removes the poll, and the log keeps each decision with the flags it resolved
+        contract.delete_poll(&spam_poll_id, "spam", &"wallet_admin".parse().unwrap()).unwrap();
+        assert!(matches!(contract.get_poll(&spam_poll_id), Err(VotingError::PollNotFound)));
+        assert!(contract
+            .get_polls_by_creator(&"wallet_creator".parse().unwrap())
+            .iter()
+            .all(|poll| poll.poll_id != spam_poll_id));
+        assert!(contract.moderation_queue().is_empty());
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let log = restored.moderation_log();
+        assert_eq!(log.len(), 2);
+        assert_eq!((log[0].action, log[0].resolved_flags.len()), (ModerationAction::Hide, 2));
+        assert_eq!((log[1].action, log[1].poll_title.as_str()), (ModerationAction::Delete, "Free tokens"));
+        assert!(restored.get_poll(&scam_poll_id).unwrap().hidden);
+    }
+}
\ No newline at end of file
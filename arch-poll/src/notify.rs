@@ -0,0 +1,277 @@
+// Notifications about contract events.
+// The event handler is a single callback meant for the host that embeds the contract. Notifiers
+// are for people watching polls: any number can be registered, and each is told about every
+// event a successful call emits (failed calls emit nothing). LogNotifier writes one line per event
+// (with std), and WebhookNotifier posts events to a chat or HTTP endpoint (feature `webhook`).
+// Notifiers are code, so like the event handler they are not saved in snapshots.
+use crate::prelude::*;
+use crate::{ContractEvent, VotingContract};
+
+// Receiver of contract events. Notifiers are shared between threads with the contract (see
+// shared.rs) and are called while a call is still in progress, so they must return quickly.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, event: &ContractEvent);
+}
+
+impl<F: Fn(&ContractEvent) + Send + Sync> Notifier for F {
+    fn notify(&self, event: &ContractEvent) {
+        self(event)
+    }
+}
+
+// Define a notifier passing on only some events, e.g. poll openings and closings for a chat
+pub struct FilteredNotifier {
+    event_names: Vec<&'static str>, // Names of the events passed on (see ContractEvent::name)
+    notifier: Box<dyn Notifier>,
+}
+
+impl FilteredNotifier {
+    pub fn new(event_names: &[&'static str], notifier: Box<dyn Notifier>) -> Self {
+        FilteredNotifier {
+            event_names: event_names.to_vec(),
+            notifier,
+        }
+    }
+}
+
+impl Notifier for FilteredNotifier {
+    fn notify(&self, event: &ContractEvent) {
+        if self.event_names.contains(&event.name()) {
+            self.notifier.notify(event);
+        }
+    }
+}
+
+// Describe an event in one line for logs and chat messages
+pub fn describe_event(event: &ContractEvent) -> String {
+    match event.poll_id() {
+        Some(poll_id) => format!("{} on poll {}: {:?}", event.name(), poll_id, event),
+        None => format!("{}: {:?}", event.name(), event),
+    }
+}
+
+impl VotingContract {
+    // Register a notifier that is told about every event from now on
+    pub fn add_notifier(&mut self, notifier: Box<dyn Notifier>) {
+        self.notifiers.push(notifier);
+    }
+    
+    // Remove every registered notifier
+    pub fn clear_notifiers(&mut self) {
+        self.notifiers.clear();
+    }
+    
+    // Tell every notifier about an event
+    pub(crate) fn notify(&self, event: &ContractEvent) {
+        for notifier in &self.notifiers {
+            notifier.notify(event);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::log::LogNotifier;
+
+#[cfg(feature = "std")]
+mod log {
+    use std::io::{self, Write};
+    use std::sync::{Mutex, PoisonError};
+    
+    use super::{describe_event, Notifier};
+    use crate::prelude::*;
+    use crate::ContractEvent;
+    
+    // Define a notifier writing one line per event, e.g. to stderr or a log file
+    pub struct LogNotifier {
+        writer: Mutex<Box<dyn Write + Send>>,
+    }
+    
+    impl LogNotifier {
+        pub fn new(writer: Box<dyn Write + Send>) -> Self {
+            LogNotifier {
+                writer: Mutex::new(writer),
+            }
+        }
+        
+        pub fn stderr() -> Self {
+            LogNotifier::new(Box::new(io::stderr()))
+        }
+    }
+    
+    impl Notifier for LogNotifier {
+        // Failed writes are dropped; a full disk must not fail a vote
+        fn notify(&self, event: &ContractEvent) {
+            let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+            let _ = writeln!(writer, "{}", describe_event(event));
+        }
+    }
+}
+
+#[cfg(feature = "webhook")]
+pub use self::webhook::{WebhookFormat, WebhookNotifier, WEBHOOK_QUEUE_CAPACITY};
+
+#[cfg(feature = "webhook")]
+mod webhook {
+    use std::sync::mpsc::{self, Receiver, SyncSender};
+    use std::thread;
+    use std::time::Duration;
+    
+    use ureq::http::Uri;
+    use ureq::Agent;
+    
+    use super::{describe_event, Notifier};
+    use crate::prelude::*;
+    use crate::reporting::json_string;
+    use crate::{ContractEvent, Result, VotingError};
+    
+    // Time one delivery may take, from connecting to reading the response
+    const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+    
+    // Deliveries waiting for the worker; events arriving while the queue is full are dropped
+    pub const WEBHOOK_QUEUE_CAPACITY: usize = 256;
+    
+    // Define the JSON body a webhook receives
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum WebhookFormat {
+        Json,    // {"event": name, "poll_id": id or null, "message": description}
+        Slack,   // {"text": description}
+        Discord, // {"content": description}
+    }
+    
+    // Define a notifier posting each event to an HTTP or HTTPS endpoint. Bodies are queued for one
+    // worker thread, which sends them in order with ureq; clones share the queue and the worker,
+    // which exits once the last clone is dropped and the queue has drained.
+    #[derive(Debug, Clone)]
+    pub struct WebhookNotifier {
+        format: WebhookFormat,
+        deliveries: SyncSender<String>, // Bodies waiting for the worker
+    }
+    
+    impl WebhookNotifier {
+        // Parse an http:// or https:// URL, failing with InvalidParameter on anything else, and
+        // start the worker
+        pub fn new(url: &str, format: WebhookFormat) -> Result<Self> {
+            let uri: Uri = url.parse().map_err(|_| VotingError::InvalidParameter)?;
+            if !matches!(uri.scheme_str(), Some("http" | "https")) || uri.host().is_none_or(str::is_empty) {
+                return Err(VotingError::InvalidParameter);
+            }
+            let (deliveries, queue) = mpsc::sync_channel(WEBHOOK_QUEUE_CAPACITY);
+            let url = url.to_string();
+            thread::spawn(move || WebhookNotifier::run_worker(&url, queue));
+            Ok(WebhookNotifier { format, deliveries })
+        }
+        
+        // Build the JSON body for an event
+        pub fn body(&self, event: &ContractEvent) -> String {
+            let message = json_string(&describe_event(event));
+            match self.format {
+                WebhookFormat::Json => format!(
+                    "{{\"event\":{},\"poll_id\":{},\"message\":{}}}",
+                    json_string(event.name()),
+                    event.poll_id().map_or("null".to_string(), json_string),
+                    message
+                ),
+                WebhookFormat::Slack => format!("{{\"text\":{}}}", message),
+                WebhookFormat::Discord => format!("{{\"content\":{}}}", message),
+            }
+        }
+        
+        // Post queued bodies one at a time until every sender is gone. Delivery is best effort:
+        // failures and error statuses are not retried or reported.
+        fn run_worker(url: &str, queue: Receiver<String>) {
+            let agent: Agent = Agent::config_builder().timeout_global(Some(DELIVERY_TIMEOUT)).build().into();
+            for body in queue {
+                let _ = agent.post(url).content_type("application/json").send(&body);
+            }
+        }
+    }
+    
+    impl Notifier for WebhookNotifier {
+        // Queuing never blocks, so a slow endpoint never holds up the contract
+        fn notify(&self, event: &ContractEvent) {
+            let _ = self.deliveries.try_send(self.body(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    
+    #[test]
+    fn test_notifiers() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let all_events = Arc::clone(&received);
+        contract.add_notifier(Box::new(move |event: &ContractEvent| {
+            all_events.lock().unwrap().push(describe_event(event));
+        }));
+        let lifecycle_events = Arc::clone(&received);
+        contract.add_notifier(Box::new(FilteredNotifier::new(
+            &["PollCreated", "PollClosed"],
+            Box::new(move |event: &ContractEvent| lifecycle_events.lock().unwrap().push(event.name().to_string())),
+        )));
+        
+        // Every notifier hears about successful calls; the filter only passes openings and closings
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Standup time".to_string(),
+                String::new(),
+                vec!["9:00".to_string(), "10:00".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "9:00").unwrap();
+        assert!(contract.vote(&poll_id, "wallet_a".parse().unwrap(), "9:00").is_err());
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 5);
+        assert!(received[0].starts_with(&format!("PollCreated on poll {}: PollCreated {{", poll_id)));
+        assert_eq!((received[1].as_str(), received[4].as_str()), ("PollCreated", "PollClosed"));
+        
+        #[cfg(feature = "webhook")]
+        {
+            use std::io::{BufRead, BufReader, Read, Write};
+            use std::net::TcpListener;
+            
+            assert!(WebhookNotifier::new("https://hooks.slack.com/x", WebhookFormat::Slack).is_ok());
+            assert!(WebhookNotifier::new("ftp://hooks.example.com/x", WebhookFormat::Slack).is_err());
+            assert!(WebhookNotifier::new("/hooks/polls", WebhookFormat::Slack).is_err());
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/hooks/polls", listener.local_addr().unwrap());
+            let webhook = WebhookNotifier::new(&url, WebhookFormat::Json).unwrap();
+            webhook.notify(&ContractEvent::PollExpired { poll_id: "poll_1".parse().unwrap() });
+            webhook.notify(&ContractEvent::PollExpired { poll_id: "poll_2".parse().unwrap() });
+            
+            // The one worker delivers the events in order
+            for poll_id in ["poll_1", "poll_2"] {
+                let mut stream = BufReader::new(listener.accept().unwrap().0);
+                let mut head = Vec::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    stream.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                    head.push(line);
+                }
+                let mut body = vec![0; content_length];
+                stream.read_exact(&mut body).unwrap();
+                stream.get_mut().write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n").unwrap();
+                assert_eq!(head[0], "POST /hooks/polls HTTP/1.1\r\n");
+                let message = format!("PollExpired on poll {0}: PollExpired {{ poll_id: \\\"{0}\\\" }}", poll_id);
+                let expected_body = format!(
+                    "{{\"event\":\"PollExpired\",\"poll_id\":\"{}\",\"message\":\"{}\"}}",
+                    poll_id, message
+                );
+                assert_eq!(String::from_utf8(body).unwrap(), expected_body);
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,192 @@
+// Participant tracking.
+// A poll remembers who has voted so nobody votes twice. By default it keeps every voter's address
+// in a ParticipantSet, which for polls with millions of voters holds a second copy of every
+// address next to the ballots. A compact set keeps a 16-byte hash of each address instead, which
+// still answers whether an address has voted (a false match needs a 128-bit collision) and how
+// many have. Listing the voters does not need the set either way: voters() reads them from the
+// ballots, which every participant holds exactly one of. A poll chooses compact tracking before
+// voting starts.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashSet;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::hash::sha256;
+use crate::id::PollId;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Domain tag hashed ahead of every compact participant key
+pub const PARTICIPANT_KEY_DOMAIN: &[u8] = b"arch-poll:participant:v1";
+
+// Define the addresses that have voted in a poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParticipantSet {
+    Full(HashSet<Address>),     // Every address as given
+    Compact(HashSet<[u8; 16]>), // The truncated hash of every address
+}
+
+impl Default for ParticipantSet {
+    fn default() -> Self {
+        ParticipantSet::Full(HashSet::new())
+    }
+}
+
+// Compute the key a compact set stores for an address
+fn participant_key(address: &Address) -> [u8; 16] {
+    let mut hashed_bytes = PARTICIPANT_KEY_DOMAIN.to_vec();
+    hashed_bytes.extend_from_slice(address.as_bytes());
+    let mut key = [0u8; 16];
+    key.copy_from_slice(&sha256(&hashed_bytes)[..16]);
+    key
+}
+
+impl ParticipantSet {
+    // Create an empty set holding address hashes
+    pub fn compact() -> Self {
+        ParticipantSet::Compact(HashSet::new())
+    }
+    
+    // Check whether the set holds address hashes rather than addresses
+    pub fn is_compact(&self) -> bool {
+        matches!(self, ParticipantSet::Compact(_))
+    }
+    
+    // Check whether an address has voted
+    pub fn contains(&self, address: &Address) -> bool {
+        match self {
+            ParticipantSet::Full(addresses) => addresses.contains(address),
+            ParticipantSet::Compact(keys) => keys.contains(&participant_key(address)),
+        }
+    }
+    
+    // Record an address, returning whether it was new
+    pub fn insert(&mut self, address: Address) -> bool {
+        match self {
+            ParticipantSet::Full(addresses) => addresses.insert(address),
+            ParticipantSet::Compact(keys) => keys.insert(participant_key(&address)),
+        }
+    }
+    
+    // Forget an address, returning whether it was recorded
+    pub fn remove(&mut self, address: &Address) -> bool {
+        match self {
+            ParticipantSet::Full(addresses) => addresses.remove(address),
+            ParticipantSet::Compact(keys) => keys.remove(&participant_key(address)),
+        }
+    }
+    
+    // Get the number of addresses recorded
+    pub fn len(&self) -> usize {
+        match self {
+            ParticipantSet::Full(addresses) => addresses.len(),
+            ParticipantSet::Compact(keys) => keys.len(),
+        }
+    }
+    
+    // Check whether no address is recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Encode for ParticipantSet {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            ParticipantSet::Full(addresses) => {
+                encoder.write(&0u8);
+                encoder.write(addresses);
+            }
+            ParticipantSet::Compact(keys) => {
+                encoder.write(&1u8);
+                encoder.write(keys);
+            }
+        }
+    }
+}
+
+impl Decode for ParticipantSet {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(ParticipantSet::Full(decoder.read()?)),
+            1 => Ok(ParticipantSet::Compact(decoder.read()?)),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+impl Poll {
+    // Iterate over the addresses holding a ballot, read from the ballots themselves so compact
+    // polls can list them too
+    pub fn voters(&self) -> impl Iterator<Item = &Address> {
+        self.votes_by_address
+            .keys()
+            .chain(self.ranked_ballots.keys())
+            .chain(self.quadratic_allocations.keys())
+            .chain(self.approval_ballots.keys())
+    }
+}
+
+impl VotingContract {
+    // Choose whether a poll tracks its voters by address hash (creator or ConfigurePolls). Only
+    // possible before voting starts.
+    pub fn set_compact_participants(&mut self, poll_id: &PollId, compact: bool, caller: &Address) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.participant_addresses = if compact { ParticipantSet::compact() } else { ParticipantSet::default() };
+        self.emit(ContractEvent::ParticipantTrackingSet {
+            poll_id: poll_id.clone(),
+            compact,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_compact_participants() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Huge poll".to_string(),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.set_compact_participants(&poll_id, true, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Yes").unwrap();
+        contract.vote(&poll_id, "wallet_b".parse().unwrap(), "No").unwrap();
+        let result = contract.set_compact_participants(&poll_id, false, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        
+        // The set keeps hashes only, yet still stops a second vote and lists voters from the ballots
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert!(poll.participant_addresses.is_compact());
+        assert!(
+            poll.participant_addresses.contains(&"wallet_a".parse().unwrap())
+                && !poll.participant_addresses.contains(&"wallet_c".parse().unwrap())
+        );
+        let mut voters: Vec<&Address> = poll.voters().collect();
+        voters.sort();
+        assert_eq!(voters, vec!["wallet_a", "wallet_b"]);
+        let result = contract.vote(&poll_id, "wallet_a".parse().unwrap(), "No");
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        
+        // Compact polls survive a restore and still prove their ballots
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert!(restored.get_poll(&poll_id).unwrap().participant_addresses.is_compact());
+        restored.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let ballot_root = restored.get_poll(&poll_id).unwrap().ballot_root.unwrap();
+        assert!(restored.get_ballot_proof(&poll_id, &"wallet_b".parse().unwrap()).unwrap().verify(&ballot_root));
+    }
+}
\ No newline at end of file
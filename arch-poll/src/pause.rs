@@ -0,0 +1,157 @@
+// Emergency pause.
+// When a vulnerability is found, an admin can stop the contract from taking new polls and
+// ballots while it is investigated. A paused contract refuses poll creation and every call that
+// records, changes or withdraws a ballot with ContractPaused, and leaves everything else alone:
+// queries keep answering, and polls still close and expire on time, so a pause does not stretch
+// any poll's voting window. A pause can name a time at which it lifts by itself, so a forgotten
+// pause does not keep voting shut; until then only unpause_contract lifts it.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::{ContractEvent, Result, VotingContract, VotingError};
+
+// Define a pause in effect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractPause {
+    pub paused_by: Address,
+    pub paused_at: u64,
+    pub resume_at: Option<u64>, // When the pause lifts by itself (None keeps it until unpaused)
+}
+
+impl ContractPause {
+    // Check whether the pause still holds at a time
+    pub fn is_in_effect(&self, current_timestamp: u64) -> bool {
+        self.resume_at.is_none_or(|resume_at| current_timestamp < resume_at)
+    }
+}
+
+impl Encode for ContractPause {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.paused_by);
+        encoder.write(&self.paused_at);
+        encoder.write(&self.resume_at);
+    }
+}
+
+impl Decode for ContractPause {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(ContractPause {
+            paused_by: decoder.read()?,
+            paused_at: decoder.read()?,
+            resume_at: decoder.read()?,
+        })
+    }
+}
+
+impl VotingContract {
+    // Pause poll creation and voting (requires PauseContract), optionally until a future time.
+    // Pausing again replaces the current pause.
+    pub fn pause_contract(&mut self, caller: &Address, resume_at: Option<u64>) -> Result<()> {
+        let paused_by = self.parse_address(caller)?;
+        if !self.has_permission(&paused_by, Permission::PauseContract) {
+            return Err(VotingError::NotAuthorized);
+        }
+        let current_timestamp = self.now();
+        if resume_at.is_some_and(|resume_at| resume_at <= current_timestamp) {
+            return Err(VotingError::InvalidTimeSettings);
+        }
+        
+        self.contract_pause = Some(ContractPause {
+            paused_by: paused_by.clone(),
+            paused_at: current_timestamp,
+            resume_at,
+        });
+        self.emit(ContractEvent::ContractPaused { paused_by, resume_at });
+        Ok(())
+    }
+    
+    // Lift the pause (requires PauseContract). InvalidTransition when no pause is in effect.
+    pub fn unpause_contract(&mut self, caller: &Address) -> Result<()> {
+        let unpaused_by = self.parse_address(caller)?;
+        if !self.has_permission(&unpaused_by, Permission::PauseContract) {
+            return Err(VotingError::NotAuthorized);
+        }
+        if !self.is_paused() {
+            return Err(VotingError::InvalidTransition);
+        }
+        
+        self.contract_pause = None;
+        self.emit(ContractEvent::ContractUnpaused { unpaused_by });
+        Ok(())
+    }
+    
+    // Get the pause in effect, if any
+    pub fn contract_pause(&self) -> Option<&ContractPause> {
+        let current_timestamp = self.now();
+        self.contract_pause.as_ref().filter(|contract_pause| contract_pause.is_in_effect(current_timestamp))
+    }
+    
+    // Check whether poll creation and voting are paused
+    pub fn is_paused(&self) -> bool {
+        self.contract_pause().is_some()
+    }
+    
+    // Refuse a call that creates polls or touches ballots while the contract is paused
+    pub(crate) fn check_not_paused(&self) -> Result<()> {
+        if self.is_paused() {
+            return Err(VotingError::ContractPaused);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_contract_pause() {
+        let clock = MockClock::new(1_000);
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Lunch".to_string(),
+                String::new(),
+                vec!["Pizza".to_string(), "Tacos".to_string()],
+                3_600,
+            )
+            .unwrap();
+        assert!(matches!(
+            contract.pause_contract(&"wallet_creator".parse().unwrap(), None),
+            Err(VotingError::NotAuthorized)
+        ));
+        let result = contract.pause_contract(&"wallet_admin".parse().unwrap(), Some(1_000));
+        assert!(matches!(result, Err(VotingError::InvalidTimeSettings)));
+        
+        // A pause stops new polls and ballots but not reads, and lifts on request
+        contract.pause_contract(&"wallet_admin".parse().unwrap(), None).unwrap();
+        let result = contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Pizza");
+        assert!(matches!(result, Err(VotingError::ContractPaused)));
+        let result = contract
+            .poll("Dinner")
+            .creator(&"wallet_creator".parse().unwrap())
+            .options(["Soup", "Salad"])
+            .duration_secs(60)
+            .build();
+        assert!(matches!(result, Err(VotingError::ContractPaused)));
+        assert_eq!(contract.get_poll_results(&poll_id).unwrap()["Pizza"], 0);
+        contract.unpause_contract(&"wallet_admin".parse().unwrap()).unwrap();
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Pizza").unwrap();
+        assert!(matches!(
+            contract.unpause_contract(&"wallet_admin".parse().unwrap()),
+            Err(VotingError::InvalidTransition)
+        ));
+        
+        // A timed pause survives a restore and lifts by itself
+        contract.pause_contract(&"wallet_admin".parse().unwrap(), Some(1_060)).unwrap();
+        let mut contract = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        contract.set_clock(Box::new(clock.clone()));
+        assert_eq!(contract.contract_pause().unwrap().resume_at, Some(1_060));
+        clock.advance(60);
+        assert!(!contract.is_paused());
+        contract.vote(&poll_id, "wallet_other".parse().unwrap(), "Tacos").unwrap();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,280 @@
+// On-chain program shape for deploying the contract to Arch Network.
+// The whole contract state lives in one program-owned account as a snapshot. Every instruction
+// loads it, applies one operation on behalf of the signing account and writes it back; failed
+// instructions leave the account untouched.
+use core::cell::RefCell;
+
+use crate::address::Address;
+use crate::clock::{default_clock, Clock};
+use crate::encoding::{self, Decode, Decoder, Encode, Encoder};
+use crate::hash::to_hex;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{Result, VotingContract, VotingError};
+
+// 32-byte account address
+pub type Pubkey = [u8; 32];
+
+// Define an account passed to the program, mirroring the runtime's account view
+#[derive(Debug, Clone)]
+pub struct AccountInfo {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub data: RefCell<Vec<u8>>,
+}
+
+impl AccountInfo {
+    pub fn new(key: Pubkey, owner: Pubkey, is_signer: bool, is_writable: bool, data: Vec<u8>) -> Self {
+        AccountInfo {
+            key,
+            owner,
+            is_signer,
+            is_writable,
+            data: RefCell::new(data),
+        }
+    }
+}
+
+// Define the operations the program accepts (applied by VotingContract::execute, see ledger.rs).
+// Accounts for every instruction: [0] the writable state account owned by the program,
+// [1] the signer the instruction acts for (creator, voter or closer).
+#[derive(Debug, Clone, PartialEq)]
+pub enum VotingInstruction {
+    Initialize, // Create the contract state with the signer as its admin
+    CreatePoll {
+        poll_title: String,
+        poll_description: String,
+        poll_options: Vec<String>,
+        poll_duration_seconds: u64,
+    },
+    Vote { poll_id: PollId, selected_option: String },
+    ClosePoll { poll_id: PollId },
+    ProcessExpiredPolls,
+    Tick, // Record due starts and expiries; any signer may send it
+}
+
+impl Encode for VotingInstruction {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            VotingInstruction::Initialize => encoder.write(&0u8),
+            VotingInstruction::CreatePoll {
+                poll_title,
+                poll_description,
+                poll_options,
+                poll_duration_seconds,
+            } => {
+                encoder.write(&1u8);
+                encoder.write(poll_title);
+                encoder.write(poll_description);
+                encoder.write(poll_options);
+                encoder.write(poll_duration_seconds);
+            }
+            VotingInstruction::Vote { poll_id, selected_option } => {
+                encoder.write(&2u8);
+                encoder.write(poll_id);
+                encoder.write(selected_option);
+            }
+            VotingInstruction::ClosePoll { poll_id } => {
+                encoder.write(&3u8);
+                encoder.write(poll_id);
+            }
+            VotingInstruction::ProcessExpiredPolls => encoder.write(&4u8),
+            VotingInstruction::Tick => encoder.write(&5u8),
+        }
+    }
+}
+
+impl Decode for VotingInstruction {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        let instruction = match decoder.read::<u8>()? {
+            0 => VotingInstruction::Initialize,
+            1 => VotingInstruction::CreatePoll {
+                poll_title: decoder.read()?,
+                poll_description: decoder.read()?,
+                poll_options: decoder.read()?,
+                poll_duration_seconds: decoder.read()?,
+            },
+            2 => VotingInstruction::Vote {
+                poll_id: decoder.read()?,
+                selected_option: decoder.read()?,
+            },
+            3 => VotingInstruction::ClosePoll { poll_id: decoder.read()? },
+            4 => VotingInstruction::ProcessExpiredPolls,
+            5 => VotingInstruction::Tick,
+            _ => return Err(VotingError::CorruptState),
+        };
+        Ok(instruction)
+    }
+}
+
+impl VotingInstruction {
+    // Encode the instruction as transaction instruction data
+    pub fn to_instruction_data(&self) -> Vec<u8> {
+        encoding::to_bytes(self)
+    }
+}
+
+// Get the contract address of an account (its key in lowercase hex)
+pub fn address_of(key: &Pubkey) -> Address {
+    Address::unchecked(to_hex(key))
+}
+
+// Program entrypoint called by the runtime
+pub fn entrypoint(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> Result<()> {
+    process_instruction(program_id, accounts, instruction_data, default_clock())
+}
+
+// Decode and apply one instruction, reading time from the given clock
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    clock: Box<dyn Clock>,
+) -> Result<()> {
+    let instruction: VotingInstruction =
+        encoding::from_bytes(instruction_data).map_err(|_| VotingError::InvalidInstruction)?;
+        
+    let [state_account, signer_account, ..] = accounts else {
+        return Err(VotingError::InvalidAccount);
+    };
+    if state_account.owner != *program_id || !state_account.is_writable {
+        return Err(VotingError::InvalidAccount);
+    }
+    if !signer_account.is_signer {
+        return Err(VotingError::NotAuthorized);
+    }
+    let caller = address_of(&signer_account.key);
+    
+    // Initialization is the only instruction that runs against an empty account
+    if instruction == VotingInstruction::Initialize {
+        if !state_account.data.borrow().is_empty() {
+            return Err(VotingError::InvalidAccount);
+        }
+        save_state(state_account, &VotingContract::new(caller));
+        return Ok(());
+    }
+    
+    let mut contract = load_state(state_account)?;
+    contract.set_clock(clock);
+    
+    contract.execute(&caller, instruction)?;
+    save_state(state_account, &contract);
+    Ok(())
+}
+
+// Load the contract from the state account, which must have been initialized
+pub fn load_state(state_account: &AccountInfo) -> Result<VotingContract> {
+    let data = state_account.data.borrow();
+    if data.is_empty() {
+        return Err(VotingError::InvalidAccount);
+    }
+    VotingContract::from_snapshot(&data)
+}
+
+// Write the contract back to the state account
+fn save_state(state_account: &AccountInfo, contract: &VotingContract) {
+    *state_account.data.borrow_mut() = contract.to_snapshot();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::id::{HashedIds, IdGenerator, IdSeed};
+    use crate::PollStatus;
+    
+    const PROGRAM_ID: Pubkey = [7; 32];
+    
+    fn signer(key_byte: u8) -> AccountInfo {
+        AccountInfo::new([key_byte; 32], [0; 32], true, false, Vec::new())
+    }
+    
+    // Run an instruction, carrying the state account's data across calls
+    fn run(state_account: &AccountInfo, signer_account: &AccountInfo, instruction: VotingInstruction) -> Result<()> {
+        let accounts = [state_account.clone(), signer_account.clone()];
+        let result = process_instruction(
+            &PROGRAM_ID,
+            &accounts,
+            &instruction.to_instruction_data(),
+            Box::new(MockClock::new(1_000)),
+        );
+        *state_account.data.borrow_mut() = accounts[0].data.take();
+        result
+    }
+    
+    #[test]
+    fn test_instruction_dispatch() {
+        let state_account = AccountInfo::new([1; 32], PROGRAM_ID, false, true, Vec::new());
+        let admin = signer(2);
+        let voter = signer(3);
+        
+        run(&state_account, &admin, VotingInstruction::Initialize).unwrap();
+        let result = run(&state_account, &admin, VotingInstruction::Initialize);
+        assert!(matches!(result, Err(VotingError::InvalidAccount)));
+        
+        run(&state_account, &admin, VotingInstruction::CreatePoll {
+            poll_title: "On-chain Poll".to_string(),
+            poll_description: "Stored in a program account".to_string(),
+            poll_options: vec!["Yes".to_string(), "No".to_string()],
+            poll_duration_seconds: 60,
+        })
+        .unwrap();
+        
+        // The poll ID is derived deterministically from the instruction and the block time
+        let poll_id: PollId = HashedIds
+            .poll_id(&IdSeed {
+                creator_address: &address_of(&admin.key),
+                poll_title: "On-chain Poll",
+                timestamp: 1_000,
+                poll_number: 1,
+            })
+            .parse()
+            .unwrap();
+        let vote = VotingInstruction::Vote {
+            poll_id: poll_id.clone(),
+            selected_option: "Yes".to_string(),
+        };
+        run(&state_account, &voter, vote.clone()).unwrap();
+        
+        // A failed instruction leaves the stored state untouched
+        let stored_state = state_account.data.borrow().clone();
+        let result = run(&state_account, &voter, vote);
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        assert_eq!(*state_account.data.borrow(), stored_state);
+        
+        // Voters cannot close someone else's poll; the creator can
+        let close = VotingInstruction::ClosePoll { poll_id: poll_id.clone() };
+        let result = run(&state_account, &voter, close.clone());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        run(&state_account, &admin, close).unwrap();
+        
+        let contract = load_state(&state_account).unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert_eq!(poll.poll_creator_address, address_of(&admin.key));
+        assert!(poll.participant_addresses.contains(&address_of(&voter.key)));
+        assert_eq!(poll.status, PollStatus::Closed);
+    }
+    
+    #[test]
+    fn test_account_checks() {
+        let admin = signer(2);
+        
+        // State accounts must be writable and owned by the program
+        let foreign_account = AccountInfo::new([1; 32], [9; 32], false, true, Vec::new());
+        let result = run(&foreign_account, &admin, VotingInstruction::Initialize);
+        assert!(matches!(result, Err(VotingError::InvalidAccount)));
+        
+        // Instructions act for a signer and need an initialized state account
+        let state_account = AccountInfo::new([1; 32], PROGRAM_ID, false, true, Vec::new());
+        let unsigned = AccountInfo::new([2; 32], [0; 32], false, false, Vec::new());
+        let result = run(&state_account, &unsigned, VotingInstruction::Initialize);
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        let result = run(&state_account, &admin, VotingInstruction::ProcessExpiredPolls);
+        assert!(matches!(result, Err(VotingError::InvalidAccount)));
+        
+        let result = process_instruction(&PROGRAM_ID, &[], &[42], default_clock());
+        assert!(matches!(result, Err(VotingError::InvalidInstruction)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,223 @@
+// Per-address rate limits on poll creation and voting.
+// Limits on poll contents (see config) do not stop one address, or a swarm of fresh ones, from
+// flooding the contract with small polls or ballots. A RateLimit allows an address a number of
+// actions within a sliding window of seconds; the contract keeps the time of every recent action
+// per address and refuses one more with RateLimited. Cast and committed ballots count as votes;
+// reveals and vote changes do not. The log is saved in snapshots, so limits survive a restore, and
+// tick drops entries that have aged out of their window.
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::prelude::*;
+use crate::{Result, VotingContract, VotingError};
+
+// Define how many actions an address may take within a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max_actions: u32,    // Actions allowed within any window
+    pub window_seconds: u64, // Length of the sliding window
+}
+
+// Define the actions rate limits apply to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitedAction {
+    PollCreation,
+    Vote,
+}
+
+// Times of each address's recent actions, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlidingWindow {
+    actions: HashMap<Address, Vec<u64>>,
+}
+
+impl SlidingWindow {
+    // Count an address's actions within the window ending now
+    pub fn count(&self, address: &Address, window_seconds: u64, current_timestamp: u64) -> usize {
+        self.actions.get(address).map_or(0, |timestamps| {
+            timestamps
+                .iter()
+                .filter(|&&timestamp| current_timestamp.saturating_sub(timestamp) < window_seconds)
+                .count()
+        })
+    }
+    
+    // Record an action, dropping the address's entries that have left the window
+    pub fn record(&mut self, address: &Address, window_seconds: u64, current_timestamp: u64) {
+        let timestamps = self.actions.entry(address.clone()).or_default();
+        timestamps.retain(|&timestamp| current_timestamp.saturating_sub(timestamp) < window_seconds);
+        timestamps.push(current_timestamp);
+    }
+    
+    // Drop every entry that has left the window, and addresses left without any
+    pub fn prune(&mut self, window_seconds: u64, current_timestamp: u64) {
+        self.actions.retain(|_, timestamps| {
+            timestamps.retain(|&timestamp| current_timestamp.saturating_sub(timestamp) < window_seconds);
+            !timestamps.is_empty()
+        });
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+// Define the recent actions of every rate-limited address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimiter {
+    pub poll_creations: SlidingWindow,
+    pub votes: SlidingWindow,
+}
+
+impl RateLimiter {
+    fn window_mut(&mut self, action: RateLimitedAction) -> &mut SlidingWindow {
+        match action {
+            RateLimitedAction::PollCreation => &mut self.poll_creations,
+            RateLimitedAction::Vote => &mut self.votes,
+        }
+    }
+    
+    fn window(&self, action: RateLimitedAction) -> &SlidingWindow {
+        match action {
+            RateLimitedAction::PollCreation => &self.poll_creations,
+            RateLimitedAction::Vote => &self.votes,
+        }
+    }
+}
+
+impl VotingContract {
+    // Get the limit configured for an action (None when unlimited)
+    pub fn rate_limit(&self, action: RateLimitedAction) -> Option<RateLimit> {
+        match action {
+            RateLimitedAction::PollCreation => self.config.poll_creation_limit,
+            RateLimitedAction::Vote => self.config.vote_limit,
+        }
+    }
+    
+    // Get how many more times an address may take an action right now (None when unlimited)
+    pub fn remaining_actions(&self, action: RateLimitedAction, address: &Address) -> Option<u32> {
+        let rate_limit = self.rate_limit(action)?;
+        let window = self.rate_limiter.window(action);
+        let recent_actions = window.count(&self.lookup_address(address), rate_limit.window_seconds, self.now());
+        Some(rate_limit.max_actions.saturating_sub(recent_actions.min(u32::MAX as usize) as u32))
+    }
+    
+    // Check an address may take an action the given number of times more
+    pub(crate) fn check_rate_limit(
+        &self,
+        action: RateLimitedAction,
+        address: &Address,
+        action_count: usize,
+    ) -> Result<()> {
+        match self.remaining_actions(action, address) {
+            Some(remaining_actions) if (remaining_actions as usize) < action_count => Err(VotingError::RateLimited),
+            _ => Ok(()),
+        }
+    }
+    
+    // Record an action against the address's limit (nothing is kept while the action is unlimited)
+    pub(crate) fn record_rate_limited(&mut self, action: RateLimitedAction, address: &Address) {
+        if let Some(rate_limit) = self.rate_limit(action) {
+            let current_timestamp = self.now();
+            self.rate_limiter.window_mut(action).record(address, rate_limit.window_seconds, current_timestamp);
+        }
+    }
+    
+    // Drop rate-limit entries that can no longer count against any address
+    pub(crate) fn prune_rate_limits(&mut self) {
+        let current_timestamp = self.now();
+        for action in [RateLimitedAction::PollCreation, RateLimitedAction::Vote] {
+            let window_seconds = self.rate_limit(action).map_or(0, |rate_limit| rate_limit.window_seconds);
+            self.rate_limiter.window_mut(action).prune(window_seconds, current_timestamp);
+        }
+    }
+}
+
+impl Encode for RateLimit {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.max_actions);
+        encoder.write(&self.window_seconds);
+    }
+}
+
+impl Decode for RateLimit {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(RateLimit {
+            max_actions: decoder.read()?,
+            window_seconds: decoder.read()?,
+        })
+    }
+}
+
+impl Encode for RateLimiter {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.poll_creations.actions);
+        encoder.write(&self.votes.actions);
+    }
+}
+
+impl Decode for RateLimiter {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(RateLimiter {
+            poll_creations: SlidingWindow { actions: decoder.read()? },
+            votes: SlidingWindow { actions: decoder.read()? },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_rate_limits() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        contract.config.poll_creation_limit = Some(RateLimit { max_actions: 2, window_seconds: 60 });
+        contract.config.vote_limit = Some(RateLimit { max_actions: 1, window_seconds: 10 });
+        let create = |contract: &mut VotingContract, poll_title: &str| {
+            contract.create_poll(
+                "wallet_spammer".parse().unwrap(),
+                poll_title.to_string(),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                600,
+            )
+        };
+        
+        // Two polls fit in the window and a third waits until the first one leaves it
+        let first_poll_id = create(&mut contract, "First").unwrap();
+        clock.advance(30);
+        let second_poll_id = create(&mut contract, "Second").unwrap();
+        assert!(matches!(create(&mut contract, "Third"), Err(VotingError::RateLimited)));
+        assert_eq!(
+            contract.remaining_actions(RateLimitedAction::PollCreation, &"wallet_spammer".parse().unwrap()),
+            Some(0)
+        );
+        assert_eq!(
+            contract.remaining_actions(RateLimitedAction::PollCreation, &"wallet_other".parse().unwrap()),
+            Some(2)
+        );
+        clock.advance(30);
+        create(&mut contract, "Third").unwrap();
+        
+        // Failed ballots do not use up the limit, and the window slides for votes too
+        let result = contract.vote(&first_poll_id, "wallet_voter".parse().unwrap(), "Maybe");
+        assert!(matches!(result, Err(VotingError::InvalidOption)));
+        contract.vote(&first_poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+        let result = contract.vote(&second_poll_id, "wallet_voter".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::RateLimited)));
+        clock.advance(10);
+        contract.vote(&second_poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+        
+        // The log survives a restore, and tick drops entries once their window has passed
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        restored.set_clock(Box::new(clock.clone()));
+        assert_eq!(restored.remaining_actions(RateLimitedAction::Vote, &"wallet_voter".parse().unwrap()), Some(0));
+        clock.advance(60);
+        restored.tick();
+        assert!(restored.rate_limiter.poll_creations.is_empty() && restored.rate_limiter.votes.is_empty());
+    }
+}
\ No newline at end of file
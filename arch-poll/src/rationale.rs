@@ -0,0 +1,190 @@
+// Vote rationales.
+// Tallies say how a poll went but not why, and governance reviewers want both. A voter can attach
+// a rationale to their ballot, either a short text or the hash of a longer statement kept off
+// chain, when voting with vote_with_rationale or afterwards with attach_rationale. Each ballot
+// holds at most one rationale, which its voter may replace while the poll is active; retracting
+// the ballot removes it. Rationales are read back page by page, in the order they were attached,
+// and are hidden together with the results of a poll that hides its results until it ends.
+use crate::address::Address;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Result, VoteReceipt, VotingContract, VotingError};
+
+// Longest rationale text, in bytes
+pub const MAX_RATIONALE_LEN: usize = 1_000;
+
+// Define what a rationale says: the text itself, or the hash of a statement stored off chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RationaleContent {
+    Text(String),
+    ContentHash([u8; 32]),
+}
+
+impl RationaleContent {
+    // Trim a text rationale and check it is neither empty nor longer than MAX_RATIONALE_LEN
+    fn normalized(self) -> Result<Self> {
+        match self {
+            RationaleContent::Text(text) => {
+                let text = text.trim();
+                if text.is_empty() || text.len() > MAX_RATIONALE_LEN {
+                    return Err(VotingError::InvalidRationale);
+                }
+                Ok(RationaleContent::Text(text.to_string()))
+            }
+            RationaleContent::ContentHash(content_hash) => Ok(RationaleContent::ContentHash(content_hash)),
+        }
+    }
+}
+
+impl Encode for RationaleContent {
+    fn encode(&self, encoder: &mut Encoder) {
+        match self {
+            RationaleContent::Text(text) => {
+                encoder.write(&0u8);
+                encoder.write(text);
+            }
+            RationaleContent::ContentHash(content_hash) => {
+                encoder.write(&1u8);
+                encoder.write(content_hash);
+            }
+        }
+    }
+}
+
+impl Decode for RationaleContent {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(RationaleContent::Text(decoder.read()?)),
+            1 => Ok(RationaleContent::ContentHash(decoder.read()?)),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+// Define the rationale attached to a ballot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteRationale {
+    pub voter_address: Address,
+    pub content: RationaleContent,
+    pub attached_at: u64,
+}
+
+impl Encode for VoteRationale {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.voter_address);
+        encoder.write(&self.content);
+        encoder.write(&self.attached_at);
+    }
+}
+
+impl Decode for VoteRationale {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(VoteRationale {
+            voter_address: decoder.read()?,
+            content: decoder.read()?,
+            attached_at: decoder.read()?,
+        })
+    }
+}
+
+impl VotingContract {
+    // Cast a vote as vote does, with a rationale attached. An invalid rationale fails the call
+    // before the vote is cast.
+    pub fn vote_with_rationale(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        selected_option: &str,
+        content: RationaleContent,
+    ) -> Result<VoteReceipt> {
+        let content = content.normalized()?;
+        let receipt = self.vote(poll_id, voter_address.clone(), selected_option)?;
+        self.attach_rationale(poll_id, voter_address, content)?;
+        Ok(receipt)
+    }
+    
+    // Attach a rationale to the voter's ballot in an active poll, replacing any attached before.
+    // NotVoted when the voter has no ballot in the poll.
+    pub fn attach_rationale(
+        &mut self,
+        poll_id: &PollId,
+        voter_address: Address,
+        content: RationaleContent,
+    ) -> Result<()> {
+        self.check_not_paused()?;
+        let voter_address = self.parse_address(&voter_address)?;
+        let content = content.normalized()?;
+        let current_timestamp = self.now();
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.is_active(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if !poll.participant_addresses.contains(&voter_address) {
+            return Err(VotingError::NotVoted);
+        }
+        
+        poll.rationales.retain(|rationale| rationale.voter_address != voter_address);
+        poll.rationales.push(VoteRationale {
+            voter_address: voter_address.clone(),
+            content,
+            attached_at: current_timestamp,
+        });
+        self.emit(ContractEvent::RationaleAttached {
+            poll_id: poll_id.clone(),
+            voter_address,
+        });
+        Ok(())
+    }
+    
+    // Get a page of a poll's rationales, oldest first (ResultsHidden while its results are hidden)
+    pub fn get_vote_rationales(&self, poll_id: &PollId, offset: usize, limit: usize) -> Result<Vec<&VoteRationale>> {
+        let poll = self.get_poll(poll_id)?;
+        self.check_results_visible(poll, None)?;
+        Ok(poll.rationales.iter().skip(offset).take(limit).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_vote_rationales() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .poll("Treasury grant")
+            .creator(&"wallet_creator".parse().unwrap())
+            .options(["Fund", "Reject"])
+            .duration_secs(3_600)
+            .allow_vote_changes()
+            .build()
+            .unwrap();
+        
+        // An invalid rationale fails the whole vote, and only voters can attach one
+        let too_long = RationaleContent::Text("a".repeat(MAX_RATIONALE_LEN + 1));
+        let result = contract.vote_with_rationale(&poll_id, "wallet_a".parse().unwrap(), "Fund", too_long);
+        assert!(matches!(result, Err(VotingError::InvalidRationale)));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().total_votes(), 0);
+        let statement = RationaleContent::ContentHash([7; 32]);
+        let result = contract.attach_rationale(&poll_id, "wallet_b".parse().unwrap(), statement.clone());
+        assert!(matches!(result, Err(VotingError::NotVoted)));
+        
+        let reason = RationaleContent::Text(" Fills a real gap ".to_string());
+        contract.vote_with_rationale(&poll_id, "wallet_a".parse().unwrap(), "Fund", reason).unwrap();
+        contract.vote(&poll_id, "wallet_b".parse().unwrap(), "Reject").unwrap();
+        contract.attach_rationale(&poll_id, "wallet_b".parse().unwrap(), statement.clone()).unwrap();
+        contract.vote(&poll_id, "wallet_c".parse().unwrap(), "Fund").unwrap();
+        
+        // Pages follow attachment order; replacing moves a rationale last and retracting drops it
+        let rationales = contract.get_vote_rationales(&poll_id, 0, 1).unwrap();
+        assert_eq!(rationales[0].content, RationaleContent::Text("Fills a real gap".to_string()));
+        contract.attach_rationale(&poll_id, "wallet_a".parse().unwrap(), statement).unwrap();
+        let rationales = contract.get_vote_rationales(&poll_id, 1, 10).unwrap();
+        assert_eq!(rationales[0].voter_address, "wallet_a");
+        contract.retract_vote(&poll_id, "wallet_b".parse().unwrap()).unwrap();
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_vote_rationales(&poll_id, 0, 10).unwrap().len(), 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+// Vote receipts.
+// Every successful vote() appends a receipt to its poll's receipt chain. Each receipt hash covers
+// the previous receipt hash, so a voter holding a receipt can check that their vote was recorded
+// and that no earlier receipt was rewritten. The chain is append-only: retracting or changing a
+// vote leaves the original receipt in place, so a receipt proves a vote was cast, not that it
+// still counts. The option hash does not hide the choice from anyone who can guess the options.
+use crate::address::Address;
+use crate::encoding::{self, Encode, Encoder};
+use crate::hash::sha256;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{Poll, Result, VotingContract};
+
+// Domain tag hashed ahead of every receipt
+pub const RECEIPT_DOMAIN: &[u8] = b"arch-poll:receipt:v1";
+
+// Define the receipt returned for a recorded vote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteReceipt {
+    pub poll_id: PollId,
+    pub voter: Address,
+    pub option_hash: [u8; 32], // SHA-256 of the encoded option
+    pub sequence: u64,         // Position in the poll's receipt chain, starting at 1
+    pub receipt_hash: [u8; 32], // Hash chaining this receipt to the previous one
+}
+
+impl VoteReceipt {
+    // Check whether the receipt was issued for the given option
+    pub fn matches_option(&self, selected_option: &str) -> bool {
+        self.option_hash == option_hash(selected_option)
+    }
+    
+    // Compute the receipt hash from the previous receipt hash (zeros for the first receipt)
+    pub fn compute_hash(&self, previous_hash: &[u8; 32]) -> [u8; 32] {
+        let mut hashed_bytes = RECEIPT_DOMAIN.to_vec();
+        hashed_bytes.extend_from_slice(previous_hash);
+        hashed_bytes.extend_from_slice(&encoding::to_bytes(self));
+        sha256(&hashed_bytes)
+    }
+}
+
+// The receipt hash is not part of its own preimage
+impl Encode for VoteReceipt {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.poll_id);
+        encoder.write(&self.voter);
+        encoder.write(&self.option_hash);
+        encoder.write(&self.sequence);
+    }
+}
+
+// Hash an option as it appears in receipts
+pub fn option_hash(selected_option: &str) -> [u8; 32] {
+    sha256(&encoding::to_bytes(&selected_option.to_string()))
+}
+
+impl Poll {
+    // Append a receipt for a recorded vote to the poll's chain and return it
+    pub(crate) fn append_receipt(&mut self, voter_address: &Address, selected_option: &str) -> VoteReceipt {
+        let previous_hash = self.receipt_chain.last().copied().unwrap_or([0; 32]);
+        let mut receipt = VoteReceipt {
+            poll_id: self.poll_id.clone(),
+            voter: voter_address.clone(),
+            option_hash: option_hash(selected_option),
+            sequence: self.receipt_chain.len() as u64 + 1,
+            receipt_hash: [0; 32],
+        };
+        receipt.receipt_hash = receipt.compute_hash(&previous_hash);
+        self.receipt_chain.push(receipt.receipt_hash);
+        receipt
+    }
+    
+    // Check a receipt against the poll's receipt chain
+    pub fn verify_receipt(&self, receipt: &VoteReceipt) -> bool {
+        if receipt.poll_id != self.poll_id || receipt.sequence == 0 || receipt.sequence > self.receipt_chain.len() as u64 {
+            return false;
+        }
+        
+        let index = (receipt.sequence - 1) as usize;
+        let previous_hash = if index == 0 { [0; 32] } else { self.receipt_chain[index - 1] };
+        receipt.compute_hash(&previous_hash) == receipt.receipt_hash && self.receipt_chain[index] == receipt.receipt_hash
+    }
+}
+
+impl VotingContract {
+    // Check that a receipt belongs to its poll's receipt chain (archived polls included)
+    pub fn verify_receipt(&self, receipt: &VoteReceipt) -> Result<bool> {
+        let poll = self.get_poll(&receipt.poll_id)?;
+        Ok(poll.verify_receipt(receipt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_receipt_chain() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll_with_vote_changes(
+                "wallet_creator".parse().unwrap(),
+                "Receipts".to_string(),
+                "Every vote gets a receipt".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let first_receipt = contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "Yes").unwrap();
+        let second_receipt = contract.vote(&poll_id, "wallet_voter2".parse().unwrap(), "No").unwrap();
+        
+        assert_eq!((first_receipt.sequence, second_receipt.sequence), (1, 2));
+        assert!(first_receipt.matches_option("Yes") && !first_receipt.matches_option("No"));
+        assert_eq!(second_receipt.receipt_hash, second_receipt.compute_hash(&first_receipt.receipt_hash));
+        assert!(contract.verify_receipt(&first_receipt).unwrap());
+        assert!(contract.verify_receipt(&second_receipt).unwrap());
+        
+        // Altered receipts do not verify
+        let mut forged_receipt = second_receipt.clone();
+        forged_receipt.option_hash = option_hash("Yes");
+        assert!(!contract.verify_receipt(&forged_receipt).unwrap());
+        let mut forged_receipt = second_receipt.clone();
+        forged_receipt.sequence = 3;
+        assert!(!contract.verify_receipt(&forged_receipt).unwrap());
+        
+        // Retracting keeps the receipt, and a new vote extends the chain
+        contract.retract_vote(&poll_id, "wallet_voter1".parse().unwrap()).unwrap();
+        let third_receipt = contract.vote(&poll_id, "wallet_voter1".parse().unwrap(), "No").unwrap();
+        assert_eq!(third_receipt.sequence, 3);
+        assert!(contract.verify_receipt(&first_receipt).unwrap());
+        
+        // The chain is persisted with the poll
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert!(restored.verify_receipt(&third_receipt).unwrap());
+    }
+}
\ No newline at end of file
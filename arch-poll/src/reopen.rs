@@ -0,0 +1,244 @@
+// Reopening and re-running polls.
+// A poll that ended too early, or whose vote was disrupted, can get a second chance in two ways.
+// An admin can reopen it: the same poll takes votes again for a new duration, either keeping the
+// ballots it already holds or starting over from none. Alternatively its creator can re-run it,
+// which opens a fresh copy with the same question and settings and leaves the original untouched
+// as a record; the copy names the original in previous_poll_id.
+// Reopening is refused once the poll's result has been acted on (finalized, archived, its action
+// run or its runoff opened). Commit-reveal, conviction and encrypted polls cannot be reopened, as
+// their phases, accrued conviction and published key shares all belong to the window that ended.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::anonymous::AnonymousState;
+use crate::conviction::ConvictionState;
+use crate::id::PollId;
+use crate::participants::ParticipantSet;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, PollStatus, Result, VotingContract, VotingError, VotingMethod};
+
+impl Poll {
+    // Drop every ballot, leaving the options and settings as they were
+    pub(crate) fn clear_ballots(&mut self) {
+        self.participant_addresses = if self.participant_addresses.is_compact() {
+            ParticipantSet::compact()
+        } else {
+            ParticipantSet::default()
+        };
+        self.vote_counts.values_mut().for_each(|vote_count| *vote_count = 0);
+        self.voter_counts.values_mut().for_each(|voter_count| *voter_count = 0);
+        self.votes_by_address.clear();
+        self.weighted_voters.clear();
+        self.delegated_votes.clear();
+        self.ranked_ballots.clear();
+        self.quadratic_allocations.clear();
+        self.approval_ballots.clear();
+        self.receipt_chain.clear();
+        self.history.clear();
+        self.rationales.clear();
+        if let Some(anonymous) = &mut self.anonymous {
+            anonymous.nullifiers.clear();
+        }
+        self.invalidate_results();
+    }
+}
+
+impl VotingContract {
+    // Take votes in an ended poll again until poll_duration_seconds from now (requires
+    // ReopenPolls). With reset_votes the poll starts over from no ballots, otherwise the ballots
+    // it holds still count and its voters cannot vote again.
+    pub fn reopen_poll(
+        &mut self,
+        poll_id: &PollId,
+        poll_duration_seconds: u64,
+        reset_votes: bool,
+        caller: &Address,
+    ) -> Result<()> {
+        let reopened_by = self.parse_address(caller)?;
+        if !self.has_permission(&reopened_by, Permission::ReopenPolls) {
+            return Err(VotingError::NotAuthorized);
+        }
+        self.config.check_duration(poll_duration_seconds)?;
+        let current_timestamp = self.now();
+        let poll_end_timestamp = current_timestamp
+            .checked_add(poll_duration_seconds)
+            .ok_or(VotingError::InvalidTimeSettings)?;
+        let poll = self.live_poll_mut(poll_id)?;
+        
+        if !poll.has_ended(current_timestamp)
+            || !poll.dependencies_met
+            || poll.next_round.is_some()
+            || poll.action_executed_at.is_some()
+        {
+            return Err(VotingError::InvalidTransition);
+        }
+        if poll.commit_end_timestamp.is_some() || poll.encryption.is_some() || poll.conviction.is_some() {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        let mut former_voters: Vec<Address> = Vec::new();
+        if reset_votes {
+            former_voters = poll.voters().cloned().collect();
+            poll.clear_ballots();
+            poll.poll_start_timestamp = current_timestamp;
+        }
+        poll.status = PollStatus::Active;
+        poll.poll_end_timestamp = poll_end_timestamp;
+        poll.ballot_root = None;
+        poll.invalidate_results();
+        for voter_address in &former_voters {
+            self.poll_index.remove_voter(poll_id, voter_address);
+        }
+        self.reindex_status(poll_id);
+        
+        self.emit(ContractEvent::PollReopened {
+            poll_id: poll_id.clone(),
+            reopened_by,
+            poll_end_timestamp,
+            votes_reset: reset_votes,
+        });
+        Ok(())
+    }
+    
+    // Open a fresh copy of an ended poll, archived or not, for the same duration (creator or
+    // ConfigurePolls). The copy belongs to the original creator and is created as any new poll
+    // is, so it counts toward their limits and pays the creation fee. Its question, options,
+    // eligibility and counting rules are copied; attached actions, governance changes and
+    // dependencies are not. Encrypted polls are refused, since the original's key is public.
+    pub fn rerun_poll(&mut self, poll_id: &PollId, caller: &Address) -> Result<PollId> {
+        let caller = self.parse_address(caller)?;
+        let caller_can_configure = self.has_permission(&caller, Permission::ConfigurePolls);
+        let current_timestamp = self.now();
+        let previous_poll = self.get_poll(poll_id)?;
+        if !caller_can_configure && caller != previous_poll.poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        if !previous_poll.has_ended(current_timestamp) {
+            return Err(VotingError::InvalidTransition);
+        }
+        if previous_poll.encryption.is_some() {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        
+        let previous_poll = previous_poll.clone();
+        let mut new_poll = self.build_poll(
+            self.next_poll_id,
+            previous_poll.poll_creator_address.clone(),
+            previous_poll.poll_title.clone(),
+            previous_poll.poll_description.clone(),
+            previous_poll.voting_options.clone(),
+            previous_poll.poll_end_timestamp - previous_poll.poll_start_timestamp,
+        )?;
+        if previous_poll.participant_addresses.is_compact() {
+            new_poll.participant_addresses = ParticipantSet::compact();
+        }
+        new_poll.commit_end_timestamp = previous_poll.commit_end_timestamp.map(|commit_end_timestamp| {
+            new_poll.poll_start_timestamp + (commit_end_timestamp - previous_poll.poll_start_timestamp)
+        });
+        if previous_poll.voting_method == VotingMethod::Conviction {
+            new_poll.conviction = previous_poll
+                .conviction
+                .as_ref()
+                .map(|state| ConvictionState::new(state.config, new_poll.poll_start_timestamp));
+        }
+        new_poll.anonymous = previous_poll
+            .anonymous
+            .as_ref()
+            .map(|state| AnonymousState::new(state.commitments.clone()));
+        new_poll.voting_method = previous_poll.voting_method;
+        new_poll.min_winning_votes = previous_poll.min_winning_votes;
+        new_poll.creator_can_vote = previous_poll.creator_can_vote;
+        new_poll.tags = previous_poll.tags;
+        new_poll.category = previous_poll.category;
+        new_poll.quorum_config = previous_poll.quorum_config;
+        new_poll.allowlist = previous_poll.allowlist;
+        new_poll.denylist = previous_poll.denylist;
+        new_poll.allow_vote_changes = previous_poll.allow_vote_changes;
+        new_poll.tie_break = previous_poll.tie_break;
+        new_poll.allow_abstain = previous_poll.allow_abstain;
+        new_poll.allow_nota = previous_poll.allow_nota;
+        new_poll.track_history = previous_poll.track_history;
+        new_poll.min_balance_to_vote = previous_poll.min_balance_to_vote;
+        new_poll.metadata = previous_poll.metadata;
+        new_poll.vote_fee = previous_poll.vote_fee;
+        new_poll.runoff = previous_poll.runoff;
+        new_poll.tabulation_method = previous_poll.tabulation_method;
+        new_poll.results_visibility = previous_poll.results_visibility;
+        new_poll.previous_poll_id = Some(poll_id.clone());
+        
+        let new_poll_id = self.store_poll(new_poll);
+        self.emit(ContractEvent::PollRerun {
+            poll_id: new_poll_id.clone(),
+            previous_poll_id: poll_id.clone(),
+        });
+        Ok(new_poll_id)
+    }
+    
+    // Get the polls re-running a poll, oldest first
+    pub fn get_reruns(&self, poll_id: &PollId) -> Vec<&Poll> {
+        let mut reruns: Vec<&Poll> = self
+            .iter_polls()
+            .filter(|poll| poll.previous_poll_id.as_deref() == Some(poll_id))
+            .collect();
+        reruns.sort_by_key(|poll| poll.poll_number);
+        reruns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_reopen_and_rerun() {
+        let clock = MockClock::new(1_000);
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = contract
+            .poll("Lunch")
+            .creator(&"wallet_creator".parse().unwrap())
+            .options(["Pizza", "Tacos"])
+            .duration_secs(60)
+            .tag("food")
+            .build()
+            .unwrap();
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Pizza").unwrap();
+        let result = contract.reopen_poll(&poll_id, 60, false, &"wallet_admin".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidTransition)));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        
+        // Only admins reopen, and kept ballots still count and still bar their voters
+        let result = contract.reopen_poll(&poll_id, 60, false, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.reopen_poll(&poll_id, 120, false, &"wallet_admin".parse().unwrap()).unwrap();
+        assert!(contract.is_poll_active(&poll_id).unwrap());
+        let result = contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Tacos");
+        assert!(matches!(result, Err(VotingError::AlreadyVoted)));
+        contract.vote(&poll_id, "wallet_b".parse().unwrap(), "Tacos").unwrap();
+        assert_eq!(contract.time_remaining(&poll_id).unwrap(), 120);
+        
+        // Reopening with a reset starts over from no ballots
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.reopen_poll(&poll_id, 60, true, &"wallet_admin".parse().unwrap()).unwrap();
+        assert_eq!(contract.get_poll(&poll_id).unwrap().total_votes(), 0);
+        assert!(contract.get_votes_by_address(&"wallet_a".parse().unwrap()).is_empty());
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Tacos").unwrap();
+        
+        // A re-run is a fresh linked copy, and the original keeps its ballots
+        assert!(matches!(
+            contract.rerun_poll(&poll_id, &"wallet_creator".parse().unwrap()),
+            Err(VotingError::InvalidTransition)
+        ));
+        clock.advance(60);
+        let result = contract.rerun_poll(&poll_id, &"wallet_stranger".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        let rerun_poll_id = contract.rerun_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let rerun = contract.get_poll(&rerun_poll_id).unwrap();
+        assert_eq!(rerun.previous_poll_id.as_deref(), Some(poll_id.as_str()));
+        assert_eq!((rerun.total_votes(), rerun.tags.clone()), (0, vec!["food".to_string()]));
+        assert_eq!(rerun.poll_end_timestamp - rerun.poll_start_timestamp, 60);
+        assert_eq!(contract.get_poll(&poll_id).unwrap().total_votes(), 1);
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_reruns(&poll_id).len(), 1);
+    }
+}
\ No newline at end of file
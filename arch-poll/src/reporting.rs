@@ -0,0 +1,263 @@
+// Result exports for off-chain tools and auditors.
+// Reports are built from a poll's public state and rendered as CSV (one row per option) or
+// JSON (one object per poll). Vote totals are written as strings in JSON because u128 values
+// do not fit the number range most JSON readers support.
+use crate::address::Address;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{share_bps, Poll, PollOutcome, Result, VotingContract};
+
+// Define the exported results of one option
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionReport {
+    pub option: String,
+    pub votes: u128,
+    pub voters: usize,
+    pub percentage_bps: u16, // Share of the counted votes in basis points
+}
+
+// Define the exported results of a poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollReport {
+    pub poll_id: PollId,
+    pub poll_title: String,
+    pub poll_description: String,
+    pub creator_address: Address,
+    pub status: String, // Lifecycle status, or "Expired" for ended polls that were not closed yet
+    pub voting_method: String,
+    pub poll_start_timestamp: u64,
+    pub poll_end_timestamp: u64,
+    pub participants: usize,
+    pub total_votes: u128, // Counted votes across every regular option
+    pub options: Vec<OptionReport>, // In ballot order
+    pub reserved_options: Vec<OptionReport>, // Abstain / none of the above; shares are of every vote cast
+    pub winning_option: Option<String>,
+    pub outcome: Option<PollOutcome>,
+}
+
+impl PollReport {
+    // Build the report of a poll as of the given time
+    pub fn new(poll: &Poll, current_timestamp: u64) -> Self {
+        let results = poll.get_results();
+        let reserved_results = poll.reserved_results();
+        let total_votes = results.values().fold(0u128, |total, &votes| total.saturating_add(votes));
+        let votes_cast = reserved_results.values().fold(total_votes, |total, &votes| total.saturating_add(votes));
+        let option_report = |voting_option: &String, votes: u128, total: u128| OptionReport {
+            option: voting_option.clone(),
+            votes,
+            voters: poll.voters_for(voting_option),
+            percentage_bps: share_bps(votes, total),
+        };
+        let options = poll
+            .voting_options
+            .iter()
+            .filter(|voting_option| !poll.is_reserved_option(voting_option))
+            .map(|voting_option| option_report(voting_option, results.get(voting_option).copied().unwrap_or(0), total_votes))
+            .collect();
+        let reserved_options = poll
+            .voting_options
+            .iter()
+            .filter(|voting_option| poll.is_reserved_option(voting_option))
+            .map(|voting_option| option_report(voting_option, reserved_results[voting_option], votes_cast))
+            .collect();
+            
+        PollReport {
+            poll_id: poll.poll_id.clone(),
+            poll_title: poll.poll_title.clone(),
+            poll_description: poll.poll_description.clone(),
+            creator_address: poll.poll_creator_address.clone(),
+            status: status_label(poll, current_timestamp),
+            voting_method: poll.voting_method.name().to_string(),
+            poll_start_timestamp: poll.poll_start_timestamp,
+            poll_end_timestamp: poll.poll_end_timestamp,
+            participants: poll.total_votes(),
+            total_votes,
+            options,
+            reserved_options,
+            winning_option: poll.winner().ok().and_then(|winner| winner.winning_option),
+            outcome: poll.outcome(current_timestamp),
+        }
+    }
+    
+    // Render the report as CSV with a header row and one row per option, reserved options last
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "poll_id,poll_title,status,poll_start_timestamp,poll_end_timestamp,participants,total_votes,option,votes,voters,percentage\n",
+        );
+        for option_report in self.options.iter().chain(&self.reserved_options) {
+            let row = [
+                csv_field(&self.poll_id),
+                csv_field(&self.poll_title),
+                csv_field(&self.status),
+                self.poll_start_timestamp.to_string(),
+                self.poll_end_timestamp.to_string(),
+                self.participants.to_string(),
+                self.total_votes.to_string(),
+                csv_field(&option_report.option),
+                option_report.votes.to_string(),
+                option_report.voters.to_string(),
+                format_percentage(option_report.percentage_bps),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+    
+    // Render the report as a single JSON object
+    pub fn to_json(&self) -> String {
+        let options_json = |option_reports: &[OptionReport]| {
+            let options: Vec<String> = option_reports
+                .iter()
+                .map(|option_report| {
+                    format!(
+                        "{{\"option\":{},\"votes\":\"{}\",\"voters\":{},\"percentage\":{}}}",
+                        json_string(&option_report.option),
+                        option_report.votes,
+                        option_report.voters,
+                        format_percentage(option_report.percentage_bps),
+                    )
+                })
+                .collect();
+            options.join(",")
+        };
+        let winning_option = match &self.winning_option {
+            Some(winning_option) => json_string(winning_option),
+            None => "null".to_string(),
+        };
+        let outcome = match self.outcome {
+            Some(outcome) => json_string(&format!("{:?}", outcome)),
+            None => "null".to_string(),
+        };
+        
+        format!(
+            "{{\"poll_id\":{},\"poll_title\":{},\"poll_description\":{},\"creator_address\":{},\"status\":{},\
+             \"voting_method\":{},\"poll_start_timestamp\":{},\"poll_end_timestamp\":{},\"participants\":{},\
+             \"total_votes\":\"{}\",\"options\":[{}],\"reserved_options\":[{}],\"winning_option\":{},\"outcome\":{}}}",
+            json_string(&self.poll_id),
+            json_string(&self.poll_title),
+            json_string(&self.poll_description),
+            json_string(&self.creator_address),
+            json_string(&self.status),
+            json_string(&self.voting_method),
+            self.poll_start_timestamp,
+            self.poll_end_timestamp,
+            self.participants,
+            self.total_votes,
+            options_json(&self.options),
+            options_json(&self.reserved_options),
+            winning_option,
+            outcome,
+        )
+    }
+}
+
+impl VotingContract {
+    // Build the report of a poll (archived polls included, ResultsHidden while its results are hidden)
+    pub fn poll_report(&self, poll_id: &PollId) -> Result<PollReport> {
+        let poll = self.get_poll(poll_id)?;
+        self.check_results_visible(poll, None)?;
+        Ok(PollReport::new(poll, self.now()))
+    }
+    
+    // Export a poll's results as CSV
+    pub fn export_poll_csv(&self, poll_id: &PollId) -> Result<String> {
+        Ok(self.poll_report(poll_id)?.to_csv())
+    }
+    
+    // Export a poll's results as JSON
+    pub fn export_poll_json(&self, poll_id: &PollId) -> Result<String> {
+        Ok(self.poll_report(poll_id)?.to_json())
+    }
+}
+
+// Get the status shown for a poll: its lifecycle status, or "Expired" once it has ended without
+// being closed
+pub(crate) fn status_label(poll: &Poll, current_timestamp: u64) -> String {
+    if !poll.is_closed() && poll.has_ended(current_timestamp) {
+        "Expired".to_string()
+    } else {
+        format!("{:?}", poll.status)
+    }
+}
+
+// Format basis points as a percentage with two decimals (e.g. 6666 as "66.66")
+fn format_percentage(percentage_bps: u16) -> String {
+    format!("{}.{:02}", percentage_bps / 100, percentage_bps % 100)
+}
+
+// Quote a CSV field when it contains a separator, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Encode a string as a JSON string literal
+pub(crate) fn json_string(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            '\t' => encoded.push_str("\\t"),
+            control if (control as u32) < 0x20 => encoded.push_str(&format!("\\u{:04x}", control as u32)),
+            other => encoded.push(other),
+        }
+    }
+    encoded.push('"');
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::id::SequentialIds;
+    
+    #[test]
+    fn test_exports() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_id_generator(Box::new(SequentialIds));
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Lunch, \"today\"".to_string(),
+                "Pick one\nquickly".to_string(),
+                vec!["Pizza".to_string(), "Soup".to_string(), "Salad".to_string()],
+                60,
+            )
+            .unwrap();
+        for (voter_address, selected_option) in [("wallet_1", "Pizza"), ("wallet_2", "Pizza"), ("wallet_3", "Soup")] {
+            contract.vote(&poll_id, voter_address.parse().unwrap(), selected_option).unwrap();
+        }
+        clock.advance(60);
+        
+        let csv = contract.export_poll_csv(&poll_id).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], "poll_1,\"Lunch, \"\"today\"\"\",Expired,1000,1060,3,3,Pizza,2,2,66.66");
+        assert_eq!(rows[3], "poll_1,\"Lunch, \"\"today\"\"\",Expired,1000,1060,3,3,Salad,0,0,0.00");
+        
+        let json = contract.export_poll_json(&poll_id).unwrap();
+        assert_eq!(
+            json,
+            "{\"poll_id\":\"poll_1\",\"poll_title\":\"Lunch, \\\"today\\\"\",\"poll_description\":\"Pick one\\nquickly\",\
+             \"creator_address\":\"wallet_creator\",\"status\":\"Expired\",\"voting_method\":\"SingleChoice\",\
+             \"poll_start_timestamp\":1000,\"poll_end_timestamp\":1060,\"participants\":3,\"total_votes\":\"3\",\
+             \"options\":[{\"option\":\"Pizza\",\"votes\":\"2\",\"voters\":2,\"percentage\":66.66},\
+             {\"option\":\"Soup\",\"votes\":\"1\",\"voters\":1,\"percentage\":33.33},\
+             {\"option\":\"Salad\",\"votes\":\"0\",\"voters\":0,\"percentage\":0.00}],\"reserved_options\":[],\
+             \"winning_option\":\"Pizza\",\"outcome\":\"Passed\"}"
+        );
+        
+        assert!(contract.export_poll_json(&"poll_missing".parse().unwrap()).is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,306 @@
+// Participation rewards.
+// A poll's creator can fund it with a reward pool, paid into the contract's account through the
+// PaymentProvider (see treasury.rs) and kept apart from the fee treasury. Once the poll is
+// finalized, compute_reward_shares splits the pool among the addresses whose ballots were
+// counted, equally or in proportion to the weight each ballot carried for its own voter, and each
+// share becomes a balance its address can claim. Pools live beside the polls rather than in them,
+// so the split and the claims never touch a finalized poll. Rounding leftovers go back to the
+// funder as a claimable balance, as does the whole pool when nobody voted.
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Result, VotingContract, VotingError};
+
+// Define how a reward pool is split among participants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardSplit {
+    Equal,    // Every participant gets the same share
+    ByWeight, // Shares follow the weight each participant's ballot carried for them
+}
+
+impl Encode for RewardSplit {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            RewardSplit::Equal => 0,
+            RewardSplit::ByWeight => 1,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for RewardSplit {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(RewardSplit::Equal),
+            1 => Ok(RewardSplit::ByWeight),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+// Define a poll's reward pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewardPool {
+    pub funder: Address,                   // Creator who funded the pool and receives leftovers
+    pub amount: u128,                      // Total paid into the pool
+    pub split: RewardSplit,
+    pub shares_computed: bool,             // Whether the pool has been split
+    pub claimable: HashMap<Address, u128>, // Shares not yet claimed, by address
+    pub claimed: u128,                     // Total paid out by claim_reward
+}
+
+impl Encode for RewardPool {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.funder);
+        encoder.write(&self.amount);
+        encoder.write(&self.split);
+        encoder.write(&self.shares_computed);
+        encoder.write(&self.claimable);
+        encoder.write(&self.claimed);
+    }
+}
+
+impl Decode for RewardPool {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(RewardPool {
+            funder: decoder.read()?,
+            amount: decoder.read()?,
+            split: decoder.read()?,
+            shares_computed: decoder.read()?,
+            claimable: decoder.read()?,
+            claimed: decoder.read()?,
+        })
+    }
+}
+
+// Split an amount among weighted recipients, rounding each share down. Returns the shares and
+// what rounding left over.
+fn split_amount(amount: u128, weights: &[(Address, u128)]) -> (HashMap<Address, u128>, u128) {
+    let total_weight = weights.iter().fold(0u128, |total, (_, weight)| total.saturating_add(*weight));
+    let mut shares = HashMap::new();
+    let mut leftover = amount;
+    if total_weight == 0 {
+        return (shares, leftover);
+    }
+    
+    for (address, weight) in weights {
+        // Scale down before multiplying when the product would overflow
+        let share = match amount.checked_mul(*weight) {
+            Some(scaled_amount) => scaled_amount / total_weight,
+            None => amount / total_weight * weight,
+        };
+        if share > 0 {
+            shares.insert(address.clone(), share);
+            leftover -= share;
+        }
+    }
+    (shares, leftover)
+}
+
+impl VotingContract {
+    // Fund a poll's reward pool from its creator's account. Further funding tops the pool up and
+    // must use the same split; pools can no longer change once the poll is finalized.
+    pub fn fund_reward_pool(
+        &mut self,
+        poll_id: &PollId,
+        amount: u128,
+        split: RewardSplit,
+        caller: &Address,
+    ) -> Result<()> {
+        let funder = self.parse_address(caller)?;
+        let poll = self.get_poll(poll_id)?;
+        if funder != poll.poll_creator_address {
+            return Err(VotingError::NotAuthorized);
+        }
+        if poll.finalization.is_some() {
+            return Err(VotingError::PollFinalized);
+        }
+        if amount == 0 {
+            return Err(VotingError::InvalidParameter);
+        }
+        let funded_amount = match self.reward_pools.get(poll_id) {
+            Some(pool) if pool.split != split => return Err(VotingError::InvalidParameter),
+            Some(pool) => pool.amount.checked_add(amount).ok_or(VotingError::VoteCountOverflow)?,
+            None => amount,
+        };
+        
+        let payment_provider = self.payment_provider.as_mut().ok_or(VotingError::PaymentFailed)?;
+        if !payment_provider.can_pay(&funder, amount) {
+            return Err(VotingError::PaymentFailed);
+        }
+        payment_provider.collect(&funder, amount);
+        let pool = self.reward_pools.entry(poll_id.clone()).or_insert_with(|| RewardPool {
+            funder: funder.clone(),
+            amount: 0,
+            split,
+            shares_computed: false,
+            claimable: HashMap::new(),
+            claimed: 0,
+        });
+        pool.amount = funded_amount;
+        self.emit(ContractEvent::RewardPoolFunded {
+            poll_id: poll_id.clone(),
+            funder,
+            amount,
+        });
+        Ok(())
+    }
+    
+    // Get a poll's reward pool (None when it was never funded)
+    pub fn get_reward_pool(&self, poll_id: &PollId) -> Option<&RewardPool> {
+        self.reward_pools.get(poll_id)
+    }
+    
+    // Split a finalized poll's reward pool among its participants, returning the claimable shares.
+    // Anyone may trigger the split; it happens once, and later calls return the same shares.
+    pub fn compute_reward_shares(&mut self, poll_id: &PollId) -> Result<HashMap<Address, u128>> {
+        let pool = self.reward_pools.get(poll_id).ok_or(VotingError::NoReward)?;
+        if pool.shares_computed {
+            return Ok(pool.claimable.clone());
+        }
+        let poll = self.get_poll(poll_id)?;
+        if poll.finalization.is_none() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        let mut weights: Vec<(Address, u128)> = poll
+            .voters()
+            .map(|address| {
+                let weight = match pool.split {
+                    RewardSplit::Equal => 1,
+                    RewardSplit::ByWeight => poll.weighted_voters.get(address).copied().unwrap_or(1),
+                };
+                (address.clone(), weight)
+            })
+            .collect();
+        weights.sort();
+        let (mut shares, leftover) = split_amount(pool.amount, &weights);
+        if leftover > 0 {
+            *shares.entry(pool.funder.clone()).or_insert(0) += leftover;
+        }
+        
+        let recipients = shares.len() as u32;
+        let pool = self.reward_pools.get_mut(poll_id).ok_or(VotingError::NoReward)?;
+        pool.claimable = shares.clone();
+        pool.shares_computed = true;
+        self.emit(ContractEvent::RewardSharesComputed {
+            poll_id: poll_id.clone(),
+            recipients,
+        });
+        Ok(shares)
+    }
+    
+    // Get the reward an address can still claim from a poll (0 before the split)
+    pub fn claimable_reward(&self, poll_id: &PollId, address: &Address) -> u128 {
+        self.reward_pools
+            .get(poll_id)
+            .and_then(|pool| pool.claimable.get(&self.lookup_address(address)))
+            .copied()
+            .unwrap_or(0)
+    }
+    
+    // Pay an address its share of a poll's reward pool, returning the amount. Anyone may trigger
+    // the payment, which always goes to the address itself.
+    pub fn claim_reward(&mut self, poll_id: &PollId, address: &Address) -> Result<u128> {
+        let address = self.parse_address(address)?;
+        let pool = self.reward_pools.get_mut(poll_id).ok_or(VotingError::NoReward)?;
+        if !pool.shares_computed {
+            return Err(VotingError::WrongPhase);
+        }
+        let amount = pool.claimable.get(&address).copied().ok_or(VotingError::NoReward)?;
+        
+        self.payment_provider
+            .as_mut()
+            .ok_or(VotingError::PaymentFailed)?
+            .pay_out(&address, amount)?;
+        let pool = self.reward_pools.get_mut(poll_id).ok_or(VotingError::NoReward)?;
+        pool.claimable.remove(&address);
+        pool.claimed = pool.claimed.saturating_add(amount);
+        self.emit(ContractEvent::RewardClaimed {
+            poll_id: poll_id.clone(),
+            address,
+            amount,
+        });
+        Ok(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_reward_pools() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let balances: HashMap<Address, u128> = HashMap::from([("wallet_creator".parse().unwrap(), 1_000)]);
+        contract.set_payment_provider(Some(Box::new(balances)));
+        contract.set_voter_weight(&"wallet_whale".parse().unwrap(), 2, &"wallet_admin".parse().unwrap()).unwrap();
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Survey".to_string(),
+                "Paid for answers".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        
+        // Only the creator funds, and top-ups keep the split
+        let result = contract.fund_reward_pool(&poll_id, 100, RewardSplit::ByWeight, &"wallet_whale".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.fund_reward_pool(&poll_id, 60, RewardSplit::ByWeight, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.fund_reward_pool(&poll_id, 40, RewardSplit::ByWeight, &"wallet_creator".parse().unwrap()).unwrap();
+        let result = contract.fund_reward_pool(&poll_id, 1, RewardSplit::Equal, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::InvalidParameter)));
+        let result = contract.fund_reward_pool(
+            &poll_id,
+            10_000,
+            RewardSplit::ByWeight,
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::PaymentFailed)));
+        assert_eq!(contract.get_reward_pool(&poll_id).unwrap().amount, 100);
+        
+        // Shares wait for finalization, follow ballot weight, and leftovers go back to the funder
+        for voter_address in ["wallet_whale", "wallet_a", "wallet_b", "wallet_c", "wallet_d"] {
+            contract.vote(&poll_id, voter_address.parse().unwrap(), "Yes").unwrap();
+        }
+        assert!(matches!(contract.compute_reward_shares(&poll_id), Err(VotingError::WrongPhase)));
+        contract.close_poll(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.finalize(&poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let shares = contract.compute_reward_shares(&poll_id).unwrap();
+        assert_eq!(shares["wallet_whale"], 33);
+        assert_eq!(shares["wallet_a"], 16);
+        assert_eq!(shares["wallet_creator"], 3);
+        assert_eq!(contract.compute_reward_shares(&poll_id).unwrap(), shares);
+        
+        // Each share is claimed once, and the pool is saved with the contract
+        assert_eq!(contract.claim_reward(&poll_id, &"wallet_whale".parse().unwrap()).unwrap(), 33);
+        assert!(matches!(
+            contract.claim_reward(&poll_id, &"wallet_whale".parse().unwrap()),
+            Err(VotingError::NoReward)
+        ));
+        assert!(matches!(
+            contract.claim_reward(&poll_id, &"wallet_outsider".parse().unwrap()),
+            Err(VotingError::NoReward)
+        ));
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.get_reward_pool(&poll_id), contract.get_reward_pool(&poll_id));
+        assert_eq!(restored.claimable_reward(&poll_id, &"wallet_a".parse().unwrap()), 16);
+        assert_eq!(restored.get_reward_pool(&poll_id).unwrap().claimed, 33);
+    }
+    
+    #[test]
+    fn test_split_amount() {
+        let weights: Vec<(Address, u128)> =
+            ["wallet_a", "wallet_b", "wallet_c"].iter().map(|address| (address.parse().unwrap(), 1)).collect();
+        let (shares, leftover) = split_amount(10, &weights);
+        assert_eq!(shares.values().sum::<u128>() + leftover, 10);
+        assert_eq!(leftover, 1);
+        let (shares, leftover) = split_amount(u128::MAX, &[("wallet_a".parse().unwrap(), u128::MAX)]);
+        assert_eq!((shares["wallet_a"], leftover), (u128::MAX, 0));
+        assert_eq!(split_amount(5, &[]).1, 5);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,886 @@
+// JSON-RPC 2.0 server over HTTP (feature `server`).
+// Frontends POST a JSON-RPC request and read the response from the body. The server uses only the
+// standard library: a small JSON reader, an HTTP/1.1 reader that serves one request per
+// connection, and a thread per connection working on a SharedVotingContract.
+// Requests name the acting address in their params. The server does not authenticate them, so
+// it belongs behind a gateway that checks the caller controls that address.
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crate::address::Address;
+use crate::hash::to_hex;
+use crate::id::PollId;
+use crate::receipt::VoteReceipt;
+use crate::reporting::{json_string, status_label};
+use crate::shared::SharedVotingContract;
+use crate::{ContextualError, Poll, PollFilter, PollSort, StatusFilter, VotingError};
+
+// Default limit on the size of a request body
+pub const DEFAULT_MAX_BODY_BYTES: usize = 64 * 1024;
+
+// Limit on a single line of the request head
+const MAX_HEADER_LINE_BYTES: u64 = 8 * 1024;
+
+// Nesting limit when parsing JSON, so deeply nested input cannot exhaust the stack
+const MAX_JSON_DEPTH: usize = 32;
+
+// Largest page list_polls returns
+pub const MAX_LIST_LIMIT: usize = 100;
+
+// How long a connection may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Standard JSON-RPC error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+// Define a parsed JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String), // Kept as written, so integers beyond f64 precision survive
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>), // Members in the order they were written
+}
+
+impl JsonValue {
+    // Parse a JSON document, returning None when it is malformed
+    pub fn parse(text: &str) -> Option<JsonValue> {
+        let mut parser = JsonParser {
+            bytes: text.as_bytes(),
+            position: 0,
+        };
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        (parser.position == parser.bytes.len()).then_some(value)
+    }
+    
+    // Get a member of an object (the first one when a key repeats)
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+// Write the value as compact JSON
+impl fmt::Display for JsonValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(formatter, "null"),
+            JsonValue::Bool(value) => write!(formatter, "{}", value),
+            JsonValue::Number(number) => write!(formatter, "{}", number),
+            JsonValue::String(value) => write!(formatter, "{}", json_string(value)),
+            JsonValue::Array(items) => {
+                write!(formatter, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(formatter, ",")?;
+                    }
+                    write!(formatter, "{}", item)?;
+                }
+                write!(formatter, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(formatter, "{{")?;
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(formatter, ",")?;
+                    }
+                    write!(formatter, "{}:{}", json_string(name), value)?;
+                }
+                write!(formatter, "}}")
+            }
+        }
+    }
+}
+
+// Recursive-descent JSON reader (RFC 8259)
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+    
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Some(byte)
+    }
+    
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+    
+    fn parse_value(&mut self, depth: usize) -> Option<JsonValue> {
+        if depth > MAX_JSON_DEPTH {
+            return None;
+        }
+        
+        self.skip_whitespace();
+        match self.peek()? {
+            b'n' => self.parse_literal("null", JsonValue::Null),
+            b't' => self.parse_literal("true", JsonValue::Bool(true)),
+            b'f' => self.parse_literal("false", JsonValue::Bool(false)),
+            b'"' => self.parse_string().map(JsonValue::String),
+            b'[' => self.parse_array(depth),
+            b'{' => self.parse_object(depth),
+            b'-' | b'0'..=b'9' => self.parse_number(),
+            _ => None,
+        }
+    }
+    
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Option<JsonValue> {
+        let end = self.position + literal.len();
+        if self.bytes.get(self.position..end)? != literal.as_bytes() {
+            return None;
+        }
+        self.position = end;
+        Some(value)
+    }
+    
+    // Numbers are validated against the JSON grammar and kept as text
+    fn parse_number(&mut self) -> Option<JsonValue> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.next()? {
+            b'0' => {}
+            b'1'..=b'9' => self.skip_digits(),
+            _ => return None,
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.expect_digits()?;
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            self.expect_digits()?;
+        }
+        
+        let number = std::str::from_utf8(&self.bytes[start..self.position]).ok()?;
+        Some(JsonValue::Number(number.to_string()))
+    }
+    
+    fn skip_digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+    }
+    
+    fn expect_digits(&mut self) -> Option<()> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return None;
+        }
+        self.skip_digits();
+        Some(())
+    }
+    
+    fn parse_string(&mut self) -> Option<String> {
+        self.next()?; // Opening quote
+        let mut bytes = Vec::new();
+        loop {
+            match self.next()? {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = match self.next()? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.parse_unicode_escape()?,
+                        _ => return None,
+                    };
+                    let mut buffer = [0u8; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                // Control characters must be escaped
+                byte if byte < 0x20 => return None,
+                byte => bytes.push(byte),
+            }
+        }
+        // The input is a &str, so unescaped bytes are valid UTF-8 already
+        String::from_utf8(bytes).ok()
+    }
+    
+    // Read the hex digits after \u, combining a surrogate pair into one character
+    fn parse_unicode_escape(&mut self) -> Option<char> {
+        let first = self.parse_hex4()?;
+        if (0xd800..0xdc00).contains(&first) {
+            if self.next()? != b'\\' || self.next()? != b'u' {
+                return None;
+            }
+            let second = self.parse_hex4()?;
+            if !(0xdc00..0xe000).contains(&second) {
+                return None;
+            }
+            return char::from_u32(0x10000 + ((first - 0xd800) << 10) + (second - 0xdc00));
+        }
+        char::from_u32(first)
+    }
+    
+    fn parse_hex4(&mut self) -> Option<u32> {
+        let digits = self.bytes.get(self.position..self.position + 4)?;
+        if !digits.iter().all(u8::is_ascii_hexdigit) {
+            return None;
+        }
+        let value = u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+        self.position += 4;
+        Some(value)
+    }
+    
+    fn parse_array(&mut self, depth: usize) -> Option<JsonValue> {
+        self.next()?; // Opening bracket
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Some(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.parse_value(depth + 1)?);
+            self.skip_whitespace();
+            match self.next()? {
+                b',' => {}
+                b']' => return Some(JsonValue::Array(items)),
+                _ => return None,
+            }
+        }
+    }
+    
+    fn parse_object(&mut self, depth: usize) -> Option<JsonValue> {
+        self.next()?; // Opening brace
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Some(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return None;
+            }
+            let name = self.parse_string()?;
+            self.skip_whitespace();
+            if self.next()? != b':' {
+                return None;
+            }
+            members.push((name, self.parse_value(depth + 1)?));
+            self.skip_whitespace();
+            match self.next()? {
+                b',' => {}
+                b'}' => return Some(JsonValue::Object(members)),
+                _ => return None,
+            }
+        }
+    }
+}
+
+// Define a JSON-RPC error object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<ContextualError>, // Contract error and what it concerns, sent as the error's data
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+    
+    fn invalid_params(message: impl Into<String>) -> Self {
+        RpcError::new(INVALID_PARAMS, message)
+    }
+}
+
+// Contract errors keep their variant name as the message
+impl From<VotingError> for RpcError {
+    fn from(error: VotingError) -> Self {
+        RpcError {
+            data: Some(ContextualError::from(error)),
+            ..RpcError::new(error_code(&error), error.name())
+        }
+    }
+}
+
+// Map a contract error to its code in the JSON-RPC server error range (-32000 to -32099), which
+// offsets the error's stable code: PollNotFound (1) is -32001.
+pub fn error_code(error: &VotingError) -> i64 {
+    -32000 - i64::from(error.code())
+}
+
+// Typed access to a request's named params. Unknown names are rejected so misspelled optional
+// params are reported rather than silently ignored.
+struct Params<'a> {
+    members: &'a [(String, JsonValue)],
+}
+
+impl<'a> Params<'a> {
+    fn new(params: Option<&'a JsonValue>, allowed: &[&str]) -> Result<Self, RpcError> {
+        let members: &[(String, JsonValue)] = match params {
+            None => &[],
+            Some(JsonValue::Object(members)) => members,
+            Some(_) => return Err(RpcError::invalid_params("params must be an object")),
+        };
+        for (index, (name, _)) in members.iter().enumerate() {
+            if !allowed.contains(&name.as_str()) {
+                return Err(RpcError::invalid_params(format!("unknown parameter {}", name)));
+            }
+            if members[..index].iter().any(|(earlier, _)| earlier == name) {
+                return Err(RpcError::invalid_params(format!("duplicate parameter {}", name)));
+            }
+        }
+        Ok(Params { members })
+    }
+    
+    // Get a param, treating null as absent
+    fn optional(&self, name: &str) -> Option<&'a JsonValue> {
+        self.members
+            .iter()
+            .find(|(member_name, _)| member_name == name)
+            .map(|(_, value)| value)
+            .filter(|value| **value != JsonValue::Null)
+    }
+    
+    fn required(&self, name: &str) -> Result<&'a JsonValue, RpcError> {
+        self.optional(name)
+            .ok_or_else(|| RpcError::invalid_params(format!("missing parameter {}", name)))
+    }
+    
+    fn string(&self, name: &str) -> Result<String, RpcError> {
+        as_string(name, self.required(name)?)
+    }
+    
+    fn poll_id(&self, name: &str) -> Result<PollId, RpcError> {
+        Ok(self.string(name)?.parse()?)
+    }
+    
+    fn address(&self, name: &str) -> Result<Address, RpcError> {
+        Ok(self.string(name)?.parse()?)
+    }
+    
+    fn optional_string(&self, name: &str) -> Result<Option<String>, RpcError> {
+        self.optional(name).map(|value| as_string(name, value)).transpose()
+    }
+    
+    fn optional_address(&self, name: &str) -> Result<Option<Address>, RpcError> {
+        Ok(self.optional_string(name)?.map(|address| address.parse()).transpose()?)
+    }
+    
+    fn string_list(&self, name: &str) -> Result<Vec<String>, RpcError> {
+        match self.required(name)? {
+            JsonValue::Array(items) => items.iter().map(|item| as_string(name, item)).collect(),
+            _ => Err(RpcError::invalid_params(format!("{} must be an array of strings", name))),
+        }
+    }
+    
+    fn u64(&self, name: &str) -> Result<u64, RpcError> {
+        as_u64(name, self.required(name)?)
+    }
+    
+    fn optional_u64(&self, name: &str) -> Result<Option<u64>, RpcError> {
+        self.optional(name).map(|value| as_u64(name, value)).transpose()
+    }
+    
+    // Read a 32-byte hash written as 64 hex digits
+    fn hash(&self, name: &str) -> Result<[u8; 32], RpcError> {
+        let text = self.string(name)?;
+        let invalid = || RpcError::invalid_params(format!("{} must be 64 hex digits", name));
+        if text.len() != 64 || !text.is_ascii() {
+            return Err(invalid());
+        }
+        let mut hash = [0u8; 32];
+        for (index, byte) in hash.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[index * 2..index * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(hash)
+    }
+}
+
+fn as_string(name: &str, value: &JsonValue) -> Result<String, RpcError> {
+    match value {
+        JsonValue::String(text) => Ok(text.clone()),
+        _ => Err(RpcError::invalid_params(format!("{} must be a string", name))),
+    }
+}
+
+// Accept non-negative integers written without a fraction or exponent
+fn as_u64(name: &str, value: &JsonValue) -> Result<u64, RpcError> {
+    match value {
+        JsonValue::Number(number) => number
+            .parse()
+            .map_err(|_| RpcError::invalid_params(format!("{} must be a non-negative integer", name))),
+        _ => Err(RpcError::invalid_params(format!("{} must be a non-negative integer", name))),
+    }
+}
+
+fn parse_status(status: &str) -> Result<StatusFilter, RpcError> {
+    match status {
+        "Draft" => Ok(StatusFilter::Draft),
+        "Scheduled" => Ok(StatusFilter::Scheduled),
+        "Active" => Ok(StatusFilter::Active),
+        "Paused" => Ok(StatusFilter::Paused),
+        "Closed" => Ok(StatusFilter::Closed),
+        "Expired" => Ok(StatusFilter::Expired),
+        "Archived" => Ok(StatusFilter::Archived),
+        _ => Err(RpcError::invalid_params(format!("unknown status {}", status))),
+    }
+}
+
+fn parse_sort(sort: &str) -> Result<PollSort, RpcError> {
+    match sort {
+        "Newest" => Ok(PollSort::Newest),
+        "EndingSoonest" => Ok(PollSort::EndingSoonest),
+        "MostVotes" => Ok(PollSort::MostVotes),
+        _ => Err(RpcError::invalid_params(format!("unknown sort {}", sort))),
+    }
+}
+
+// Render the public details of a poll
+fn poll_json(poll: &Poll, current_timestamp: u64) -> String {
+    let voting_options: Vec<String> = poll.voting_options.iter().map(|option| json_string(option)).collect();
+    format!(
+        "{{\"poll_id\":{},\"poll_title\":{},\"poll_description\":{},\"creator_address\":{},\"voting_options\":[{}],\
+         \"status\":{},\"voting_method\":{},\"poll_start_timestamp\":{},\"poll_end_timestamp\":{},\"participants\":{}}}",
+        json_string(&poll.poll_id),
+        json_string(&poll.poll_title),
+        json_string(&poll.poll_description),
+        json_string(&poll.poll_creator_address),
+        voting_options.join(","),
+        json_string(&status_label(poll, current_timestamp)),
+        json_string(poll.voting_method.name()),
+        poll.poll_start_timestamp,
+        poll.poll_end_timestamp,
+        poll.total_votes(),
+    )
+}
+
+// Render a vote receipt with its hashes in hex
+fn receipt_json(receipt: &VoteReceipt) -> String {
+    format!(
+        "{{\"poll_id\":{},\"voter\":{},\"option_hash\":{},\"sequence\":{},\"receipt_hash\":{}}}",
+        json_string(&receipt.poll_id),
+        json_string(&receipt.voter),
+        json_string(&to_hex(&receipt.option_hash)),
+        receipt.sequence,
+        json_string(&to_hex(&receipt.receipt_hash)),
+    )
+}
+
+fn error_response(id: &JsonValue, error: &RpcError) -> String {
+    let data = match &error.data {
+        Some(context) => format!(",\"data\":{}", context_json(context)),
+        None => String::new(),
+    };
+    format!(
+        "{{\"jsonrpc\":\"2.0\",\"id\":{},\"error\":{{\"code\":{},\"message\":{}{}}}}}",
+        id,
+        error.code,
+        json_string(&error.message),
+        data,
+    )
+}
+
+// Render a contract error's details, with null for context the request did not carry
+fn context_json(context: &ContextualError) -> String {
+    let optional_string = |value: &Option<String>| value.as_deref().map_or("null".to_string(), json_string);
+    format!(
+        "{{\"error_code\":{},\"detail\":{},\"poll_id\":{},\"option\":{},\"caller\":{}}}",
+        context.error.code(),
+        json_string(&context.to_string()),
+        optional_string(&context.poll_id),
+        optional_string(&context.option),
+        optional_string(&context.caller),
+    )
+}
+
+// Attach the poll, option and caller named in a request's params to a contract error
+fn add_context(context: ContextualError, params: Option<&JsonValue>) -> ContextualError {
+    let string_param = |names: &[&str]| {
+        names.iter().find_map(|name| match params.and_then(|params| params.get(name)) {
+            Some(JsonValue::String(value)) => Some(value.clone()),
+            _ => None,
+        })
+    };
+    ContextualError {
+        poll_id: string_param(&["poll_id"]),
+        option: string_param(&["selected_option"]),
+        caller: string_param(&["voter_address", "creator_address", "voter", "caller"]),
+        ..context
+    }
+}
+
+// Define a JSON-RPC server for a shared contract
+#[derive(Clone)]
+pub struct RpcServer {
+    contract: SharedVotingContract,
+    max_body_bytes: usize,
+}
+
+impl RpcServer {
+    pub fn new(contract: SharedVotingContract) -> Self {
+        RpcServer {
+            contract,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+        }
+    }
+    
+    // Change the largest request body accepted (larger ones get 413 Payload Too Large)
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+    
+    // Handle one JSON-RPC request, returning the response body.
+    // Notifications (requests without an id) are executed but get no response.
+    pub fn handle_request(&self, body: &str) -> Option<String> {
+        let Some(request) = JsonValue::parse(body) else {
+            return Some(error_response(&JsonValue::Null, &RpcError::new(PARSE_ERROR, "Parse error")));
+        };
+        
+        // Ids must be strings, numbers or null; requests with any other id are answered with a null id
+        let id = request.get("id");
+        if !matches!(id, None | Some(JsonValue::Null | JsonValue::Number(_) | JsonValue::String(_))) {
+            return Some(error_response(&JsonValue::Null, &RpcError::new(INVALID_REQUEST, "Invalid Request")));
+        }
+        let (Some(JsonValue::String(version)), Some(JsonValue::String(method))) =
+            (request.get("jsonrpc"), request.get("method"))
+        else {
+            return Some(error_response(id.unwrap_or(&JsonValue::Null), &RpcError::new(INVALID_REQUEST, "Invalid Request")));
+        };
+        if version != "2.0" {
+            return Some(error_response(id.unwrap_or(&JsonValue::Null), &RpcError::new(INVALID_REQUEST, "Invalid Request")));
+        }
+        
+        let result = self.dispatch(method, request.get("params")).map_err(|mut error| {
+            error.data = error.data.map(|context| add_context(context, request.get("params")));
+            error
+        });
+        let id = id?;
+        Some(match result {
+            Ok(result) => format!("{{\"jsonrpc\":\"2.0\",\"id\":{},\"result\":{}}}", id, result),
+            Err(error) => error_response(id, &error),
+        })
+    }
+    
+    // Run a method, returning its result as JSON
+    fn dispatch(&self, method: &str, params: Option<&JsonValue>) -> Result<String, RpcError> {
+        match method {
+            "create_poll" => {
+                let params = Params::new(params, &[
+                    "creator_address",
+                    "poll_title",
+                    "poll_description",
+                    "poll_options",
+                    "poll_duration_seconds",
+                ])?;
+                let poll_id = self.contract.create_poll(
+                    params.address("creator_address")?,
+                    params.string("poll_title")?,
+                    params.string("poll_description")?,
+                    params.string_list("poll_options")?,
+                    params.u64("poll_duration_seconds")?,
+                )?;
+                Ok(json_string(&poll_id))
+            }
+            "vote" => {
+                let params = Params::new(params, &["poll_id", "voter_address", "selected_option"])?;
+                let receipt = self.contract.vote(
+                    &params.poll_id("poll_id")?,
+                    params.address("voter_address")?,
+                    &params.string("selected_option")?,
+                )?;
+                Ok(receipt_json(&receipt))
+            }
+            "verify_receipt" => {
+                let params = Params::new(params, &["poll_id", "voter", "option_hash", "sequence", "receipt_hash"])?;
+                let receipt = VoteReceipt {
+                    poll_id: params.poll_id("poll_id")?,
+                    voter: params.address("voter")?,
+                    option_hash: params.hash("option_hash")?,
+                    sequence: params.u64("sequence")?,
+                    receipt_hash: params.hash("receipt_hash")?,
+                };
+                Ok(self.contract.verify_receipt(&receipt)?.to_string())
+            }
+            "get_poll" => {
+                let params = Params::new(params, &["poll_id"])?;
+                let poll_id = params.poll_id("poll_id")?;
+                self.contract
+                    .read(|contract| Ok(poll_json(contract.get_poll(&poll_id)?, contract.now())))
+            }
+            "list_polls" => {
+                let params = Params::new(params, &[
+                    "creator_address",
+                    "status",
+                    "title_contains",
+                    "sort",
+                    "offset",
+                    "limit",
+                ])?;
+                let filter = PollFilter {
+                    creator_address: params.optional_address("creator_address")?,
+                    status: params.optional_string("status")?.as_deref().map(parse_status).transpose()?,
+                    title_contains: params.optional_string("title_contains")?,
+                    ..PollFilter::default()
+                };
+                let sort = params.optional_string("sort")?.as_deref().map(parse_sort).transpose()?.unwrap_or_default();
+                let offset = params.optional_u64("offset")?.unwrap_or(0) as usize;
+                let limit = params.optional_u64("limit")?.unwrap_or(MAX_LIST_LIMIT as u64);
+                if limit > MAX_LIST_LIMIT as u64 {
+                    return Err(RpcError::invalid_params(format!("limit must be at most {}", MAX_LIST_LIMIT)));
+                }
+                
+                let polls = self.contract.read(|contract| {
+                    let current_timestamp = contract.now();
+                    contract
+                        .list_polls(&filter, sort, offset, limit as usize)
+                        .into_iter()
+                        .map(|poll| poll_json(poll, current_timestamp))
+                        .collect::<Vec<String>>()
+                });
+                Ok(format!("[{}]", polls.join(",")))
+            }
+            "results" => {
+                let params = Params::new(params, &["poll_id"])?;
+                Ok(self.contract.export_poll_json(&params.poll_id("poll_id")?)?)
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, "Method not found")),
+        }
+    }
+    
+    // Accept connections until the listener fails, serving each on its own thread
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept()?;
+            let server = self.clone();
+            thread::spawn(move || server.handle_connection(stream));
+        }
+    }
+    
+    // Read one HTTP request from a connection and write the response
+    pub fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let response = match read_http_body(&mut reader, self.max_body_bytes) {
+            Ok(body) => match self.handle_request(&body) {
+                Some(response_body) => http_response("200 OK", &response_body),
+                None => http_response("204 No Content", ""),
+            },
+            Err(status) => http_response(status, ""),
+        };
+        (&stream).write_all(response.as_bytes())
+    }
+}
+
+// Read the body of a POST request, or the HTTP status to reject the request with
+fn read_http_body(reader: &mut impl BufRead, max_body_bytes: usize) -> Result<String, &'static str> {
+    let request_line = read_header_line(reader)?;
+    let mut request_parts = request_line.split_whitespace();
+    let (Some(method), Some(_), Some(version)) = (request_parts.next(), request_parts.next(), request_parts.next()) else {
+        return Err("400 Bad Request");
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err("400 Bad Request");
+    }
+    if method != "POST" {
+        return Err("405 Method Not Allowed");
+    }
+    
+    let mut content_length = None;
+    loop {
+        let header = read_header_line(reader)?;
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err("400 Bad Request");
+        };
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.trim().parse::<usize>().map_err(|_| "400 Bad Request")?);
+        } else if name.trim().eq_ignore_ascii_case("transfer-encoding") {
+            // Chunked bodies are not supported; clients must send Content-Length
+            return Err("411 Length Required");
+        }
+    }
+    
+    let content_length = content_length.ok_or("411 Length Required")?;
+    if content_length > max_body_bytes {
+        return Err("413 Payload Too Large");
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|_| "400 Bad Request")?;
+    String::from_utf8(body).map_err(|_| "400 Bad Request")
+}
+
+// Read one line of the request head without its line ending
+fn read_header_line(reader: &mut impl BufRead) -> Result<String, &'static str> {
+    let mut line = String::new();
+    reader
+        .take(MAX_HEADER_LINE_BYTES)
+        .read_line(&mut line)
+        .map_err(|_| "400 Bad Request")?;
+    if !line.ends_with('\n') {
+        return Err("431 Request Header Fields Too Large");
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn http_response(status: &str, body: &str) -> String {
+    let content_type = if body.is_empty() { "" } else { "Content-Type: application/json\r\n" };
+    format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::SequentialIds;
+    use crate::VotingContract;
+    
+    fn call(server: &RpcServer, body: &str) -> JsonValue {
+        JsonValue::parse(&server.handle_request(body).unwrap()).unwrap()
+    }
+    
+    fn error_code_of(response: &JsonValue) -> Option<&JsonValue> {
+        response.get("error").and_then(|error| error.get("code"))
+    }
+    
+    #[test]
+    fn test_rpc_methods() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_id_generator(Box::new(SequentialIds));
+        let server = RpcServer::new(SharedVotingContract::new(contract));
+        
+        let response = call(
+            &server,
+            r#"{"jsonrpc":"2.0","id":1,"method":"create_poll","params":{"creator_address":"wallet_creator",
+               "poll_title":"Lunch é","poll_description":"Pick one","poll_options":["Pizza","Soup"],
+               "poll_duration_seconds":60}}"#,
+        );
+        assert_eq!(response.get("result"), Some(&JsonValue::String("poll_1".to_string())));
+        assert_eq!(response.get("id"), Some(&JsonValue::Number("1".to_string())));
+        
+        let response = call(
+            &server,
+            r#"{"jsonrpc":"2.0","id":"a","method":"vote","params":{"poll_id":"poll_1","voter_address":"wallet_voter","selected_option":"Pizza"}}"#,
+        );
+        let receipt = response.get("result").unwrap().clone();
+        assert_eq!(receipt.get("sequence"), Some(&JsonValue::Number("1".to_string())));
+        
+        // The returned receipt verifies as-is
+        let response = call(&server, &format!(r#"{{"jsonrpc":"2.0","id":"b","method":"verify_receipt","params":{}}}"#, receipt));
+        assert_eq!(response.get("result"), Some(&JsonValue::Bool(true)));
+        
+        // Contract errors keep a stable code and their variant name
+        let response = call(
+            &server,
+            r#"{"jsonrpc":"2.0","id":2,"method":"vote","params":{"poll_id":"poll_1","voter_address":"wallet_voter","selected_option":"Pizza"}}"#,
+        );
+        assert_eq!(error_code_of(&response), Some(&JsonValue::Number("-32003".to_string())));
+        assert_eq!(
+            response.get("error").unwrap().get("message"),
+            Some(&JsonValue::String("AlreadyVoted".to_string()))
+        );
+        
+        // Their data names what the request was about
+        let data = response.get("error").unwrap().get("data").unwrap();
+        assert_eq!(data.get("error_code"), Some(&JsonValue::Number("3".to_string())));
+        assert_eq!(data.get("poll_id"), Some(&JsonValue::String("poll_1".to_string())));
+        assert_eq!(data.get("option"), Some(&JsonValue::String("Pizza".to_string())));
+        assert_eq!(data.get("caller"), Some(&JsonValue::String("wallet_voter".to_string())));
+        
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":3,"method":"get_poll","params":{"poll_id":"poll_1"}}"#);
+        let poll = response.get("result").unwrap();
+        assert_eq!(poll.get("poll_title"), Some(&JsonValue::String("Lunch é".to_string())));
+        assert_eq!(poll.get("participants"), Some(&JsonValue::Number("1".to_string())));
+        
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":4,"method":"list_polls","params":{"status":"Active","limit":10}}"#);
+        assert!(matches!(response.get("result"), Some(JsonValue::Array(polls)) if polls.len() == 1));
+        
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":5,"method":"results","params":{"poll_id":"poll_1"}}"#);
+        let results = response.get("result").unwrap();
+        assert_eq!(results.get("winning_option"), Some(&JsonValue::String("Pizza".to_string())));
+        
+        // Malformed requests and params are rejected with the standard codes
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":6,"method":"get_poll","params":{"poll_id":7}}"#);
+        assert_eq!(error_code_of(&response), Some(&JsonValue::Number("-32602".to_string())));
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":7,"method":"get_poll","params":{"pol_id":"poll_1"}}"#);
+        assert_eq!(error_code_of(&response), Some(&JsonValue::Number("-32602".to_string())));
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":8,"method":"list_polls","params":{"limit":-1}}"#);
+        assert_eq!(error_code_of(&response), Some(&JsonValue::Number("-32602".to_string())));
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":9,"method":"delete_everything"}"#);
+        assert_eq!(error_code_of(&response), Some(&JsonValue::Number("-32601".to_string())));
+        let response = call(&server, r#"{"jsonrpc":"1.0","id":10,"method":"get_poll"}"#);
+        assert_eq!(error_code_of(&response), Some(&JsonValue::Number("-32600".to_string())));
+        let response = call(&server, r#"{"jsonrpc":"2.0","id":11,"method":"get_poll""#);
+        assert_eq!(error_code_of(&response), Some(&JsonValue::Number("-32700".to_string())));
+        assert_eq!(JsonValue::parse(&"[".repeat(MAX_JSON_DEPTH + 2)), None);
+        
+        // Notifications run but get no response
+        let notification = r#"{"jsonrpc":"2.0","method":"vote","params":{"poll_id":"poll_1","voter_address":"wallet_other","selected_option":"Soup"}}"#;
+        assert_eq!(server.handle_request(notification), None);
+        assert_eq!(server.contract.get_poll(&"poll_1".parse().unwrap()).unwrap().total_votes(), 2);
+    }
+    
+    #[test]
+    fn test_http_round_trip() {
+        let server = RpcServer::new(SharedVotingContract::new(VotingContract::new("wallet_admin".parse().unwrap())))
+            .with_max_body_bytes(256);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let background_server = server.clone();
+        thread::spawn(move || background_server.serve(listener));
+        
+        let send = |request: String| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"list_polls"}"#;
+        let response = send(format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(r#"{"jsonrpc":"2.0","id":1,"result":[]}"#));
+        
+        let response = send("GET / HTTP/1.1\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+        let response = send("POST / HTTP/1.1\r\nContent-Length: 257\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+    }
+}
\ No newline at end of file
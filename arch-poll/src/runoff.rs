@@ -0,0 +1,242 @@
+// Two-round runoff elections.
+// A single-choice poll can be given a RunoffConfig. When it closes, by any route, and no option
+// holds more than threshold_bps of the counted votes, the contract opens a second round between
+// the two leading options, with the same creator and electorate (allowlist and denylist). Ties
+// for a place in the runoff go to the option listed first on the ballot. The rounds are linked
+// both ways through previous_round and next_round, and get_linked_polls returns every round of an
+// election in order. The runoff itself is a plain single-choice poll and starts no further round.
+// Runoffs are opened on the contract's behalf, so they are exempt from the creator's rate limit,
+// open-poll limit and creation fee.
+use core::cmp::Reverse;
+
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::anonymous::AnonymousState;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{share_bps, ContractEvent, Poll, Result, VotingContract, VotingError, VotingMethod};
+
+// Define when a poll goes to a runoff and how long the runoff lasts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunoffConfig {
+    pub threshold_bps: u16,           // Share of counted votes an option must exceed to win outright
+    pub runoff_duration_seconds: u64, // How long the runoff round runs
+}
+
+impl Encode for RunoffConfig {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.threshold_bps);
+        encoder.write(&self.runoff_duration_seconds);
+    }
+}
+
+impl Decode for RunoffConfig {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(RunoffConfig {
+            threshold_bps: decoder.read()?,
+            runoff_duration_seconds: decoder.read()?,
+        })
+    }
+}
+
+impl Poll {
+    // Get the two options a closed round sends to a runoff (None when an option cleared the
+    // threshold, nothing was voted on, or the poll has no runoff configured)
+    pub fn runoff_options(&self) -> Option<[String; 2]> {
+        let runoff = self.runoff?;
+        let results = self.get_results();
+        let counted_votes = results.values().fold(0u128, |total, &votes| total.saturating_add(votes));
+        let mut ranked_options: Vec<(&String, u128)> = self
+            .voting_options
+            .iter()
+            .filter(|option| !self.is_reserved_option(option))
+            .map(|option| (option, results.get(option).copied().unwrap_or(0)))
+            .collect();
+        // Stable, so tied options keep their ballot order
+        ranked_options.sort_by_key(|&(_, votes)| Reverse(votes));
+        
+        let (leading_option, leading_votes) = ranked_options.first()?;
+        if *leading_votes == 0 || share_bps(*leading_votes, counted_votes) > runoff.threshold_bps {
+            return None;
+        }
+        let (runner_up_option, _) = ranked_options.get(1)?;
+        Some([leading_option.to_string(), runner_up_option.to_string()])
+    }
+}
+
+impl VotingContract {
+    // Give a single-choice poll a runoff round (creator or ConfigurePolls, None removes it). Like
+    // other rules of the count, it can only change before voting starts.
+    pub fn set_runoff(&mut self, poll_id: &PollId, runoff: Option<RunoffConfig>, caller: &Address) -> Result<()> {
+        if let Some(runoff) = runoff {
+            if runoff.threshold_bps > 10_000 {
+                return Err(VotingError::InvalidParameter);
+            }
+            self.config.check_duration(runoff.runoff_duration_seconds)?;
+        }
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.voting_method != VotingMethod::SingleChoice || poll.previous_round.is_some() || poll.encryption.is_some() {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.runoff = runoff;
+        self.emit(ContractEvent::RunoffSet {
+            poll_id: poll_id.clone(),
+            runoff,
+        });
+        Ok(())
+    }
+    
+    // Get every round of the election a poll belongs to, first round first
+    pub fn get_linked_polls(&self, poll_id: &PollId) -> Result<Vec<&Poll>> {
+        let mut first_round = self.get_poll(poll_id)?;
+        while let Some(previous_round) = &first_round.previous_round {
+            first_round = self.get_poll(previous_round)?;
+        }
+        
+        let mut rounds = vec![first_round];
+        while let Some(next_round) = &rounds[rounds.len() - 1].next_round {
+            rounds.push(self.get_poll(next_round)?);
+        }
+        Ok(rounds)
+    }
+    
+    // Open the runoffs of polls that were just closed. Polls without a runoff configured, with an
+    // outright winner or with a runoff already open are skipped, as are runoffs that could not be
+    // built.
+    pub(crate) fn start_runoffs(&mut self, poll_ids: &[PollId]) {
+        for poll_id in poll_ids {
+            let Some(round) = self.active_polls.get(poll_id) else {
+                continue;
+            };
+            if round.next_round.is_some() {
+                continue;
+            }
+            let (Some(runoff), Some(runoff_options)) = (round.runoff, round.runoff_options()) else {
+                continue;
+            };
+            
+            let Some(poll_end_timestamp) = self.now().checked_add(runoff.runoff_duration_seconds) else {
+                continue;
+            };
+            let Ok(mut runoff_poll) = self.new_poll(
+                self.next_poll_id,
+                round.poll_creator_address.clone(),
+                format!("{} (runoff)", round.poll_title),
+                round.poll_description.clone(),
+                runoff_options.to_vec(),
+                poll_end_timestamp,
+            ) else {
+                continue;
+            };
+            runoff_poll.allowlist = round.allowlist.clone();
+            runoff_poll.denylist = round.denylist.clone();
+            runoff_poll.creator_can_vote = round.creator_can_vote;
+            runoff_poll.anonymous = round.anonymous.as_ref().map(|state| AnonymousState::new(state.commitments.clone()));
+            runoff_poll.previous_round = Some(poll_id.clone());
+            
+            let runoff_poll_id = self.insert_poll(runoff_poll);
+            if let Some(round) = self.active_polls.get_mut(poll_id) {
+                round.next_round = Some(runoff_poll_id.clone());
+            }
+            self.emit(ContractEvent::RunoffStarted {
+                poll_id: poll_id.clone(),
+                runoff_poll_id,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_runoff_elections() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let electorate = ["wallet_a", "wallet_b", "wallet_c", "wallet_d", "wallet_e"];
+        let poll_id = contract
+            .create_poll_with_eligibility(
+                "wallet_creator".parse().unwrap(),
+                "Chair".to_string(),
+                "Majority needed".to_string(),
+                vec!["Ada".to_string(), "Grace".to_string(), "Linus".to_string()],
+                60,
+                Some(electorate.iter().map(|address| address.parse().unwrap()).collect()),
+                Vec::new(),
+            )
+            .unwrap();
+        let runoff = RunoffConfig {
+            threshold_bps: 5_000,
+            runoff_duration_seconds: 120,
+        };
+        let result = contract.set_runoff(
+            &poll_id,
+            Some(RunoffConfig {
+                threshold_bps: 10_001,
+                ..runoff
+            }),
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::InvalidParameter)));
+        contract.set_runoff(&poll_id, Some(runoff), &"wallet_creator".parse().unwrap()).unwrap();
+        
+        // Nobody clears half the vote, so expiry opens a runoff between the two leaders, with
+        // Grace taking second place over Linus by ballot order
+        for (voter_address, option) in [("wallet_a", "Ada"), ("wallet_b", "Ada"), ("wallet_c", "Grace"), ("wallet_d", "Linus")] {
+            contract.vote(&poll_id, voter_address.parse().unwrap(), option).unwrap();
+        }
+        let result = contract.set_runoff(&poll_id, None, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        clock.advance(60);
+        contract.tick();
+        let rounds = contract.get_linked_polls(&poll_id).unwrap();
+        assert_eq!(rounds.len(), 2);
+        let runoff_poll = rounds[1];
+        let runoff_poll_id = runoff_poll.poll_id.clone();
+        assert_eq!(runoff_poll.voting_options, vec!["Ada".to_string(), "Grace".to_string()]);
+        assert_eq!(runoff_poll.poll_end_timestamp, 1_180);
+        assert_eq!(runoff_poll.previous_round.as_deref(), Some(poll_id.as_str()));
+        
+        // The electorate carries over, the runoff starts no round of its own, and the links
+        // survive a restore
+        let result = contract.vote(&runoff_poll_id, "wallet_outsider".parse().unwrap(), "Ada");
+        assert!(matches!(result, Err(VotingError::NotEligible)));
+        contract.vote(&runoff_poll_id, "wallet_e".parse().unwrap(), "Grace").unwrap();
+        contract.close_poll(&runoff_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let rounds: Vec<&str> = restored
+            .get_linked_polls(&runoff_poll_id)
+            .unwrap()
+            .into_iter()
+            .map(|poll| poll.poll_id.as_str())
+            .collect();
+        assert_eq!(rounds, vec![poll_id.as_str(), runoff_poll_id.as_str()]);
+        
+        // A majority winner needs no runoff
+        let decided_poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Treasurer".to_string(),
+                String::new(),
+                vec!["Ada".to_string(), "Grace".to_string(), "Linus".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.set_runoff(&decided_poll_id, Some(runoff), &"wallet_creator".parse().unwrap()).unwrap();
+        contract.vote(&decided_poll_id, "wallet_a".parse().unwrap(), "Linus").unwrap();
+        contract.close_poll(&decided_poll_id, &"wallet_creator".parse().unwrap()).unwrap();
+        assert_eq!(contract.get_linked_polls(&decided_poll_id).unwrap().len(), 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+// Background upkeep for a shared contract (async feature).
+// Integrators running the contract in a long-lived process otherwise each write the same loop to
+// record expired polls. The expiration task wakes on a fixed interval and runs tick on the shared
+// contract, so ended polls are closed and PollExpired (and PollStarted for due scheduled polls)
+// reaches the event handler without a keeper. tick needs no role, so the task holds no address.
+// The task runs on the caller's tokio runtime and is paced by tokio::time::interval. A tick never
+// awaits, so it takes the write lock only for its own length and stopping the task cannot cut one
+// short.
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::shared::SharedVotingContract;
+
+// Define a service running background tasks over a shared contract
+#[derive(Clone)]
+pub struct VotingContractService {
+    contract: SharedVotingContract,
+}
+
+impl VotingContractService {
+    pub fn new(contract: SharedVotingContract) -> Self {
+        VotingContractService { contract }
+    }
+    
+    // Get the shared contract the service runs over
+    pub fn contract(&self) -> &SharedVotingContract {
+        &self.contract
+    }
+    
+    // Start ticking the contract every period until the returned task is stopped or dropped. Must
+    // be called from within a tokio runtime.
+    pub fn spawn_expiration_task(&self, period: Duration) -> ExpirationTask {
+        let contract = self.contract.clone();
+        let handle = tokio::spawn(async move {
+            let mut interval = time::interval(period);
+            // A slow tick pushes the next one back rather than bunching the missed ones up
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            // The first tick completes at once; the first upkeep waits a full period
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                contract.tick();
+            }
+        });
+        ExpirationTask { handle }
+    }
+}
+
+// Handle to a running expiration task
+pub struct ExpirationTask {
+    handle: JoinHandle<()>,
+}
+
+impl ExpirationTask {
+    // Stop the task. The task only yields between ticks, so a tick in progress still finishes.
+    pub fn stop(self) {
+        self.handle.abort();
+    }
+}
+
+impl Drop for ExpirationTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::{ContractEvent, PollStatus, VotingContract};
+    use std::sync::{Arc, Mutex};
+    use tokio::time::Instant;
+    
+    #[tokio::test]
+    async fn test_expiration_task() {
+        let clock = MockClock::new(1_000);
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_clock(Box::new(clock.clone()));
+        let expired_poll_ids = Arc::new(Mutex::new(Vec::new()));
+        let recorded_poll_ids = Arc::clone(&expired_poll_ids);
+        contract.set_event_handler(Box::new(move |event| {
+            if let ContractEvent::PollExpired { poll_id } = event {
+                recorded_poll_ids.lock().unwrap().push(poll_id.clone());
+            }
+        }));
+        let service = VotingContractService::new(SharedVotingContract::new(contract));
+        let poll_id = service
+            .contract()
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Lunch".to_string(),
+                "Closes itself".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+            
+        // Once the poll ends, a later tick closes it without anyone calling in
+        let task = service.spawn_expiration_task(Duration::from_millis(5));
+        clock.advance(60);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while service.contract().get_poll(&poll_id).unwrap().status != PollStatus::Closed {
+            assert!(Instant::now() < deadline, "the expiration task never closed the poll");
+            time::sleep(Duration::from_millis(5)).await;
+        }
+        task.stop();
+        assert_eq!(*expired_poll_ids.lock().unwrap(), vec![poll_id]);
+    }
+}
\ No newline at end of file
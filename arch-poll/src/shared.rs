@@ -0,0 +1,659 @@
+// Thread-safe handle to a contract, for servers that handle requests on many threads.
+// Clones share one contract behind a read-write lock. Queries take the read lock and run in
+// parallel; operations that change state take the write lock for the length of the one call,
+// so concurrent votes on different polls wait only for each other's bookkeeping, never for a
+// caller holding the contract between calls. Queries return owned copies for the same reason.
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::access_control::{Permission, Role};
+use crate::address::{Address, AddressFormat};
+use crate::anonymous::ProofVerifier;
+#[cfg(feature = "audit")]
+use crate::audit::TallyAudit;
+use crate::balance::BalanceProvider;
+use crate::batch::BatchMode;
+use crate::clock::Clock;
+use crate::compaction::CompactedPoll;
+use crate::conviction::ConvictionConfig;
+use crate::creators::CreatorStats;
+use crate::dependency::PollDependency;
+use crate::discussion::Comment;
+use crate::editing::PollUpdate;
+use crate::election::{Election, ElectionQuestion};
+use crate::encryption::{BallotCiphertext, EncryptionConfig};
+use crate::execution::ExecutionHook;
+use crate::finalization::FinalizationRecord;
+use crate::governance::ParameterChange;
+use crate::history::{HistoryTracking, TallySnapshot};
+use crate::id::{OptionId, PollId};
+use crate::ledger::{LedgerEntry, LedgerSink};
+use crate::merkle::BallotProof;
+use crate::moderation::ModerationRecord;
+use crate::notify::Notifier;
+#[cfg(feature = "signing")]
+use crate::program::Pubkey;
+use crate::program::VotingInstruction;
+use crate::rate_limit::{RateLimit, RateLimitedAction};
+use crate::rationale::{RationaleContent, VoteRationale};
+use crate::receipt::VoteReceipt;
+use crate::reporting::PollReport;
+use crate::rewards::{RewardPool, RewardSplit};
+use crate::runoff::RunoffConfig;
+#[cfg(feature = "signing")]
+use crate::signing::VoteMessage;
+use crate::stats::{ContractStats, PollStats};
+use crate::tabulation::TabulationMethod;
+use crate::treasury::{PaymentProvider, Treasury};
+use crate::visibility::ResultsVisibility;
+use crate::voting_power::SnapshotProvider;
+use crate::{
+    ApprovalResults, EventHandler, OptionTally, Poll, PollFilter, PollOutcome, PollSort, PollSummary, PollTemplate,
+    PollWinner, QuorumConfig, Result, SequencedEvent, TickSummary, TieBreak, VoteRecord, VotingContract, WinnerOutcome,
+};
+
+// Define methods that forward to the contract under the read or write lock
+macro_rules! forward {
+    ($guard:ident => $($(#[$attribute:meta])* fn $name:ident($($argument:ident: $argument_type:ty),* $(,)?) -> $output:ty;)*) => {
+        $(
+            $(#[$attribute])*
+            pub fn $name(&self, $($argument: $argument_type),*) -> $output {
+                self.$guard().$name($($argument),*)
+            }
+        )*
+    };
+}
+
+// Define a contract shared between threads
+#[derive(Clone)]
+pub struct SharedVotingContract {
+    contract: Arc<RwLock<VotingContract>>,
+}
+
+impl SharedVotingContract {
+    pub fn new(contract: VotingContract) -> Self {
+        SharedVotingContract {
+            contract: Arc::new(RwLock::new(contract)),
+        }
+    }
+    
+    // Run a closure with read access to the contract, e.g. to borrow polls without copying them
+    pub fn read<T>(&self, operation: impl FnOnce(&VotingContract) -> T) -> T {
+        operation(&self.read_lock())
+    }
+    
+    // Run a closure with write access to the contract, e.g. to apply several operations at once
+    pub fn write<T>(&self, operation: impl FnOnce(&mut VotingContract) -> T) -> T {
+        operation(&mut self.write_lock())
+    }
+    
+    // Take the contract back once no other handle to it exists
+    pub fn try_into_inner(self) -> std::result::Result<VotingContract, Self> {
+        match Arc::try_unwrap(self.contract) {
+            Ok(contract) => Ok(contract.into_inner().unwrap_or_else(PoisonError::into_inner)),
+            Err(contract) => Err(SharedVotingContract { contract }),
+        }
+    }
+    
+    // A panic while the lock was held can only come from an event handler or clock, and events
+    // are emitted after a change is complete, so the contract is still consistent and the
+    // poisoned lock is recovered rather than failing every later call
+    fn read_lock(&self) -> RwLockReadGuard<'_, VotingContract> {
+        self.contract.read().unwrap_or_else(PoisonError::into_inner)
+    }
+    
+    fn write_lock(&self) -> RwLockWriteGuard<'_, VotingContract> {
+        self.contract.write().unwrap_or_else(PoisonError::into_inner)
+    }
+    
+    pub fn set_clock(&self, clock: Box<dyn Clock>) {
+        self.write_lock().set_clock(clock);
+    }
+    
+    pub fn set_event_handler(&self, handler: EventHandler) {
+        self.write_lock().set_event_handler(handler);
+    }
+    
+    pub fn add_notifier(&self, notifier: Box<dyn Notifier>) {
+        self.write_lock().add_notifier(notifier);
+    }
+    
+    pub fn add_ledger_sink(&self, ledger_sink: Box<dyn LedgerSink>) {
+        self.write_lock().add_ledger_sink(ledger_sink);
+    }
+    
+    // Apply ledger entries in order (see VotingContract::replay)
+    pub fn replay(&self, entries: &[LedgerEntry]) -> Result<usize> {
+        self.write_lock().replay(entries)
+    }
+    
+    // Get a copy of a poll (archived polls included)
+    pub fn get_poll(&self, poll_id: &PollId) -> Result<Poll> {
+        self.read_lock().get_poll(poll_id).cloned()
+    }
+    
+    // Get copies of the polls matching a filter, sorted and paginated like list_polls
+    pub fn list_polls(&self, filter: &PollFilter, sort: PollSort, offset: usize, limit: usize) -> Vec<Poll> {
+        self.read_lock()
+            .list_polls(filter, sort, offset, limit)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+    
+    // Get copies of the live polls carrying a tag, oldest first
+    pub fn list_polls_by_tag(&self, tag: &str) -> Vec<Poll> {
+        self.read_lock().list_polls_by_tag(tag).into_iter().cloned().collect()
+    }
+    
+    // Get copies of the live polls filed under a category, oldest first
+    pub fn list_polls_by_category(&self, category: &str) -> Vec<Poll> {
+        self.read_lock().list_polls_by_category(category).into_iter().cloned().collect()
+    }
+    
+    // Recount a poll from its ballots and compare the result with its tallies
+    #[cfg(feature = "audit")]
+    pub fn audit_tally(&self, poll_id: &PollId) -> Result<TallyAudit> {
+        self.read_lock().audit_tally(poll_id)
+    }
+    
+    // Get a copy of one metadata value of a poll
+    pub fn get_poll_metadata(&self, poll_id: &PollId, key: &str) -> Result<Option<String>> {
+        Ok(self.read_lock().get_poll_metadata(poll_id, key)?.map(str::to_string))
+    }
+    
+    // Get copies of the visible live polls having a metadata key, optionally with a given value
+    pub fn polls_with_metadata(&self, key: &str, value: Option<&str>) -> Vec<Poll> {
+        self.read_lock().polls_with_metadata(key, value).into_iter().cloned().collect()
+    }
+    
+    // Get copies of the polls an address created, oldest first
+    pub fn get_polls_by_creator(&self, creator_address: &Address) -> Vec<Poll> {
+        self.read_lock().get_polls_by_creator(creator_address).into_iter().cloned().collect()
+    }
+    
+    // Get a copy of a poll's tally snapshots, oldest first
+    pub fn get_tally_history(&self, poll_id: &PollId) -> Result<Vec<TallySnapshot>> {
+        self.read_lock().get_tally_history(poll_id).map(<[TallySnapshot]>::to_vec)
+    }
+    
+    // Replace the balance source (None treats every balance as 0)
+    pub fn set_balance_provider(&self, balance_provider: Option<Box<dyn BalanceProvider>>) {
+        self.write_lock().set_balance_provider(balance_provider);
+    }
+    
+    // Replace the address format (WrongPhase once addresses other than role holders are stored)
+    pub fn set_address_format(&self, address_format: Box<dyn AddressFormat>) -> Result<()> {
+        self.write_lock().set_address_format(address_format)
+    }
+    
+    // Replace the proof verifier (None rejects every anonymous vote)
+    pub fn set_proof_verifier(&self, proof_verifier: Option<Box<dyn ProofVerifier>>) {
+        self.write_lock().set_proof_verifier(proof_verifier);
+    }
+    
+    // Replace the snapshot source (None leaves new polls on registered weights)
+    pub fn set_snapshot_provider(&self, snapshot_provider: Option<Box<dyn SnapshotProvider>>) {
+        self.write_lock().set_snapshot_provider(snapshot_provider);
+    }
+    
+    // Replace the payment mover (None fails every fee)
+    pub fn set_payment_provider(&self, payment_provider: Option<Box<dyn PaymentProvider>>) {
+        self.write_lock().set_payment_provider(payment_provider);
+    }
+    
+    // Get a copy of the ledger of collected fees
+    pub fn treasury(&self) -> Treasury {
+        self.read_lock().treasury().clone()
+    }
+    
+    // Get copies of every round of the election a poll belongs to, first round first
+    pub fn get_linked_polls(&self, poll_id: &PollId) -> Result<Vec<Poll>> {
+        Ok(self.read_lock().get_linked_polls(poll_id)?.into_iter().cloned().collect())
+    }
+    
+    // Get a copy of what is kept of a compacted poll
+    pub fn get_compacted_poll(&self, poll_id: &PollId) -> Result<CompactedPoll> {
+        self.read_lock().get_compacted_poll(poll_id).cloned()
+    }
+    
+    // Get copies of a page of a poll's rationales, oldest first
+    pub fn get_vote_rationales(&self, poll_id: &PollId, offset: usize, limit: usize) -> Result<Vec<VoteRationale>> {
+        let contract = self.read_lock();
+        Ok(contract.get_vote_rationales(poll_id, offset, limit)?.into_iter().cloned().collect())
+    }
+    
+    // Get copies of a page of the comments opening threads in a poll's discussion, oldest first
+    pub fn get_comments(&self, poll_id: &PollId, offset: usize, limit: usize) -> Result<Vec<Comment>> {
+        let contract = self.read_lock();
+        Ok(contract.get_comments(poll_id, offset, limit)?.into_iter().cloned().collect())
+    }
+    
+    // Get copies of a page of the direct replies to a comment, oldest first
+    pub fn get_replies(&self, poll_id: &PollId, comment_id: u64, offset: usize, limit: usize) -> Result<Vec<Comment>> {
+        let contract = self.read_lock();
+        Ok(contract.get_replies(poll_id, comment_id, offset, limit)?.into_iter().cloned().collect())
+    }
+    
+    // Get a copy of a comment
+    pub fn get_comment(&self, poll_id: &PollId, comment_id: u64) -> Result<Comment> {
+        self.read_lock().get_comment(poll_id, comment_id).cloned()
+    }
+    
+    // Get copies of the polls re-running a poll, oldest first
+    pub fn get_reruns(&self, poll_id: &PollId) -> Vec<Poll> {
+        self.read_lock().get_reruns(poll_id).into_iter().cloned().collect()
+    }
+    
+    // Get a copy of a multi-question election
+    pub fn get_election(&self, election_id: &str) -> Result<Election> {
+        self.read_lock().get_election(election_id).cloned()
+    }
+    
+    // Get a copy of a poll's reward pool (None when it was never funded)
+    pub fn get_reward_pool(&self, poll_id: &PollId) -> Option<RewardPool> {
+        self.read_lock().get_reward_pool(poll_id).cloned()
+    }
+    
+    // Get copies of the visible polls with pending flags, most flagged first
+    pub fn moderation_queue(&self) -> Vec<Poll> {
+        self.read_lock().moderation_queue().into_iter().cloned().collect()
+    }
+    
+    // Get a copy of the moderation log, oldest first
+    pub fn moderation_log(&self) -> Vec<ModerationRecord> {
+        self.read_lock().moderation_log().to_vec()
+    }
+    
+    // Get a copy of a poll's finalization record (None until it is finalized)
+    pub fn get_finalization(&self, poll_id: &PollId) -> Result<Option<FinalizationRecord>> {
+        self.read_lock().get_finalization(poll_id).map(|record| record.cloned())
+    }
+    
+    // Get copies of the events after a sequence number
+    pub fn events_since(&self, sequence: u64) -> Vec<SequencedEvent> {
+        self.read_lock().events_since(sequence).to_vec()
+    }
+    
+    // Queries
+    forward! { read_lock =>
+        fn now() -> u64;
+        fn ledger_sequence() -> u64;
+        fn is_paused() -> bool;
+        fn poll_quota_of(creator_address: &Address) -> usize;
+        fn creator_stats(creator_address: &Address) -> Option<CreatorStats>;
+        fn list_creators() -> Vec<CreatorStats>;
+        fn list_categories() -> Vec<String>;
+        fn get_votes_by_address(voter_address: &Address) -> Vec<VoteRecord>;
+        fn contract_stats() -> ContractStats;
+        fn has_pending_action(poll_id: &PollId) -> bool;
+        fn to_snapshot() -> Vec<u8>;
+        fn has_role(address: &Address, role: Role) -> bool;
+        fn has_permission(address: &Address, permission: Permission) -> bool;
+        fn parse_address(address: &str) -> Result<Address>;
+        fn is_admin(wallet_address: &Address) -> bool;
+        fn voter_weight(voter_address: &Address) -> u128;
+        fn balance_of(address: &Address) -> u128;
+        fn fees_collected(poll_id: &PollId) -> u128;
+        fn claimable_reward(poll_id: &PollId, address: &Address) -> u128;
+        fn rate_limit(action: RateLimitedAction) -> Option<RateLimit>;
+        fn remaining_actions(action: RateLimitedAction, address: &Address) -> Option<u32>;
+        fn voter_weight_in_poll(poll_id: &PollId, voter_address: &Address) -> Result<u128>;
+        #[cfg(feature = "signing")]
+        fn next_nonce(address: &Address) -> u64;
+        fn verify_receipt(receipt: &VoteReceipt) -> Result<bool>;
+        fn get_ballot_proof(poll_id: &PollId, voter_address: &Address) -> Result<BallotProof>;
+        fn get_poll_results(poll_id: &PollId) -> Result<HashMap<String, u128>>;
+        fn get_poll_results_as(poll_id: &PollId, viewer_address: &Address) -> Result<HashMap<String, u128>>;
+        fn get_approval_results(poll_id: &PollId) -> Result<ApprovalResults>;
+        fn get_reserved_results(poll_id: &PollId) -> Result<HashMap<String, u128>>;
+        fn get_weighted_results(poll_id: &PollId) -> Result<HashMap<String, OptionTally>>;
+        fn get_poll_summary(poll_id: &PollId) -> Result<PollSummary>;
+        fn get_poll_outcome(poll_id: &PollId) -> Result<Option<PollOutcome>>;
+        fn get_winner(poll_id: &PollId) -> Result<WinnerOutcome>;
+        fn get_poll_winner(poll_id: &PollId) -> Result<PollWinner>;
+        fn time_remaining(poll_id: &PollId) -> Result<u64>;
+        fn is_poll_active(poll_id: &PollId) -> Result<bool>;
+        fn poll_report(poll_id: &PollId) -> Result<PollReport>;
+        fn poll_stats(poll_id: &PollId) -> Result<PollStats>;
+        fn verify_finalization(poll_id: &PollId) -> Result<bool>;
+        fn export_poll_csv(poll_id: &PollId) -> Result<String>;
+        fn export_poll_json(poll_id: &PollId) -> Result<String>;
+    }
+    
+    // Configuration and administration
+    forward! { write_lock =>
+        fn grant_role(address: &Address, role: Role, caller: &Address) -> Result<()>;
+        fn revoke_role(address: &Address, role: Role, caller: &Address) -> Result<()>;
+        fn add_admin(new_admin_address: &Address, caller: &Address) -> Result<()>;
+        fn remove_admin(admin_address: &Address, caller: &Address) -> Result<()>;
+        fn propose_admin_transfer(new_admin_address: &Address, caller: &Address) -> Result<()>;
+        fn accept_admin_transfer(caller: &Address) -> Result<()>;
+        fn renounce_admin(caller: &Address) -> Result<()>;
+        fn pause_contract(caller: &Address, resume_at: Option<u64>) -> Result<()>;
+        fn unpause_contract(caller: &Address) -> Result<()>;
+        fn set_creator_quota(creator_address: &Address, poll_quota: Option<usize>, caller: &Address) -> Result<()>;
+        fn set_voter_weight(voter_address: &Address, weight: u128, caller: &Address) -> Result<()>;
+        fn delegate_vote(delegator_address: &Address, delegate_address: &Address) -> Result<()>;
+        fn revoke_delegation(delegator_address: &Address) -> Result<()>;
+        fn set_creation_fee(creation_fee: u128, caller: &Address) -> Result<()>;
+        fn withdraw_fees(recipient: &Address, caller: &Address) -> Result<u128>;
+        fn prune_events(up_to_sequence: u64) -> usize;
+        fn clear_notifiers() -> ();
+    }
+    
+    // Poll creation and management
+    forward! { write_lock =>
+        fn create_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn create_from_template(
+            creator_address: Address,
+            template: &PollTemplate,
+            poll_titles: Vec<String>,
+        ) -> Result<Vec<PollId>>;
+        fn create_poll_without_creator_vote(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn create_draft_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn update_draft(
+            poll_id: &PollId,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            caller: &Address,
+        ) -> Result<()>;
+        fn update_poll(poll_id: &PollId, poll_update: PollUpdate, caller: &Address) -> Result<()>;
+        fn reopen_poll(poll_id: &PollId, poll_duration_seconds: u64, reset_votes: bool, caller: &Address) -> Result<()>;
+        fn rerun_poll(poll_id: &PollId, caller: &Address) -> Result<PollId>;
+        fn purge_archived(older_than: u64, caller: &Address) -> Result<usize>;
+        fn post_comment(poll_id: &PollId, author: &Address, body: &str) -> Result<u64>;
+        fn reply_to_comment(poll_id: &PollId, parent_id: u64, author: &Address, body: &str) -> Result<u64>;
+        fn delete_comment(poll_id: &PollId, comment_id: u64, caller: &Address) -> Result<()>;
+        fn create_scheduled_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            start_delay_seconds: u64,
+            poll_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn create_parameter_change_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            change: ParameterChange,
+            poll_duration_seconds: u64,
+            quorum_config: QuorumConfig,
+        ) -> Result<PollId>;
+        fn create_poll_with_vote_changes(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn create_poll_with_quorum(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            quorum_config: QuorumConfig,
+        ) -> Result<PollId>;
+        #[allow(clippy::too_many_arguments)]
+        fn create_poll_with_reserved_options(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            allow_abstain: bool,
+            allow_nota: bool,
+        ) -> Result<PollId>;
+        #[allow(clippy::too_many_arguments)]
+        fn create_poll_with_eligibility(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            allowlist: Option<Vec<Address>>,
+            denylist: Vec<Address>,
+        ) -> Result<PollId>;
+        fn create_ranked_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn create_quadratic_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            credit_budget: u64,
+        ) -> Result<PollId>;
+        fn create_approval_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            max_selections: usize,
+        ) -> Result<PollId>;
+        fn create_conviction_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            config: ConvictionConfig,
+        ) -> Result<PollId>;
+        fn create_election(
+            creator_address: Address,
+            election_title: String,
+            questions: Vec<ElectionQuestion>,
+            poll_duration_seconds: u64,
+            allowlist: Option<Vec<Address>>,
+        ) -> Result<String>;
+        fn create_anonymous_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn create_encrypted_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            poll_duration_seconds: u64,
+            encryption: EncryptionConfig,
+        ) -> Result<PollId>;
+        fn create_commit_reveal_poll(
+            creator_address: Address,
+            poll_title: String,
+            poll_description: String,
+            poll_options: Vec<String>,
+            commit_duration_seconds: u64,
+            reveal_duration_seconds: u64,
+        ) -> Result<PollId>;
+        fn publish_poll(poll_id: &PollId, caller: &Address) -> Result<()>;
+        fn pause_poll(poll_id: &PollId, caller: &Address) -> Result<()>;
+        fn resume_poll(poll_id: &PollId, caller: &Address) -> Result<()>;
+        fn add_eligible_voter(poll_id: &PollId, voter_address: &Address, caller: &Address) -> Result<()>;
+        fn remove_eligible_voter(poll_id: &PollId, voter_address: &Address, caller: &Address) -> Result<()>;
+        fn set_tie_break(poll_id: &PollId, tie_break: TieBreak, caller: &Address) -> Result<()>;
+        fn set_poll_tags(poll_id: &PollId, tags: Vec<String>, caller: &Address) -> Result<()>;
+        fn set_poll_category(poll_id: &PollId, category: Option<String>, caller: &Address) -> Result<()>;
+        fn set_poll_metadata(poll_id: &PollId, metadata: BTreeMap<String, String>, caller: &Address) -> Result<()>;
+        fn set_history_tracking(poll_id: &PollId, tracking: Option<HistoryTracking>, caller: &Address) -> Result<()>;
+        fn attach_action(poll_id: &PollId, hook: Box<dyn ExecutionHook>, caller: &Address) -> Result<()>;
+        fn set_min_balance_to_vote(poll_id: &PollId, min_balance: u128, caller: &Address) -> Result<()>;
+        fn set_vote_fee(poll_id: &PollId, vote_fee: u128, caller: &Address) -> Result<()>;
+        fn set_runoff(poll_id: &PollId, runoff: Option<RunoffConfig>, caller: &Address) -> Result<()>;
+        fn set_tabulation_method(poll_id: &PollId, tabulation_method: TabulationMethod, caller: &Address) -> Result<()>;
+        fn set_results_visibility(
+            poll_id: &PollId,
+            results_visibility: ResultsVisibility,
+            caller: &Address,
+        ) -> Result<()>;
+        fn set_compact_participants(poll_id: &PollId, compact: bool, caller: &Address) -> Result<()>;
+        fn set_poll_dependencies(poll_id: &PollId, depends_on: Vec<PollDependency>, caller: &Address) -> Result<()>;
+        fn register_commitment(poll_id: &PollId, commitment: [u8; 32], caller: &Address) -> Result<()>;
+        fn submit_decryption_share(poll_id: &PollId, trustee_address: &Address, key_share: [u8; 32]) -> Result<()>;
+        fn fund_reward_pool(poll_id: &PollId, amount: u128, split: RewardSplit, caller: &Address) -> Result<()>;
+        fn compute_reward_shares(poll_id: &PollId) -> Result<HashMap<Address, u128>>;
+        fn claim_reward(poll_id: &PollId, address: &Address) -> Result<u128>;
+        fn flag_poll(poll_id: &PollId, reporter: &Address, reason: &str) -> Result<()>;
+        fn hide_poll(poll_id: &PollId, reason: &str, caller: &Address) -> Result<()>;
+        fn unhide_poll(poll_id: &PollId, reason: &str, caller: &Address) -> Result<()>;
+        fn dismiss_flags(poll_id: &PollId, reason: &str, caller: &Address) -> Result<()>;
+        fn delete_poll(poll_id: &PollId, reason: &str, caller: &Address) -> Result<()>;
+        fn execute_action(poll_id: &PollId) -> Result<()>;
+        fn finalize(poll_id: &PollId, caller: &Address) -> Result<FinalizationRecord>;
+        fn set_min_winning_votes(poll_id: &PollId, min_winning_votes: u128, wallet_address: &Address) -> Result<()>;
+        fn archive_closed_polls() -> usize;
+        fn archive_poll(poll_id: &PollId, caller: &Address) -> Result<()>;
+        fn close_poll(poll_id: &PollId, wallet_address: &Address) -> Result<()>;
+        fn extend_poll(poll_id: &PollId, extra_seconds: u64, caller: &Address) -> Result<()>;
+        fn finalize_early(poll_id: &PollId, caller: &Address) -> Result<()>;
+        fn close_polls_by_creator(creator_address: &Address, caller: &Address) -> Result<Vec<PollId>>;
+        fn process_scheduled_polls(caller: &Address) -> Result<Vec<PollId>>;
+        fn process_expired_polls(caller: &Address) -> Result<Vec<PollId>>;
+        fn tick() -> TickSummary;
+        fn execute(caller: &Address, instruction: VotingInstruction) -> Result<()>;
+        fn update_convictions(now: u64) -> Vec<(PollId, String)>;
+    }
+    
+    // Voting
+    forward! { write_lock =>
+        fn vote(poll_id: &PollId, voter_address: Address, selected_option: &str) -> Result<VoteReceipt>;
+        fn vote_option(poll_id: &PollId, voter_address: Address, option_id: OptionId) -> Result<VoteReceipt>;
+        fn vote_batch(poll_id: &PollId, votes: Vec<(Address, String)>, mode: BatchMode) -> Vec<Result<VoteReceipt>>;
+        fn vote_ballot(
+            election_id: &str,
+            voter_address: Address,
+            answers: Vec<(PollId, String)>,
+        ) -> Result<Vec<VoteReceipt>>;
+        fn vote_ranked(poll_id: &PollId, voter_address: Address, ranked_options: Vec<String>) -> Result<()>;
+        fn vote_multi(poll_id: &PollId, voter_address: Address, selected_options: Vec<String>) -> Result<()>;
+        fn vote_quadratic(
+            poll_id: &PollId,
+            voter_address: Address,
+            selected_option: &str,
+            num_votes: usize,
+        ) -> Result<()>;
+        fn spend_quadratic_credits(
+            poll_id: &PollId,
+            voter_address: Address,
+            selected_option: &str,
+            credits: u64,
+        ) -> Result<()>;
+        fn stake_conviction(poll_id: &PollId, voter_address: Address, selected_option: &str) -> Result<()>;
+        fn unstake_conviction(poll_id: &PollId, voter_address: &Address) -> Result<()>;
+        fn vote_anonymous(
+            poll_id: &PollId,
+            nullifier: [u8; 32],
+            selected_option: &str,
+            proof_bytes: &[u8],
+        ) -> Result<VoteReceipt>;
+        fn vote_encrypted(poll_id: &PollId, voter_address: Address, ballot: BallotCiphertext) -> Result<()>;
+        fn commit_vote(poll_id: &PollId, voter_address: Address, commitment: [u8; 32]) -> Result<()>;
+        fn reveal_vote(poll_id: &PollId, voter_address: Address, selected_option: &str, salt: &[u8]) -> Result<()>;
+        fn change_vote(poll_id: &PollId, voter_address: Address, new_option: &str) -> Result<()>;
+        fn retract_vote(poll_id: &PollId, voter_address: Address) -> Result<()>;
+        fn vote_with_rationale(
+            poll_id: &PollId,
+            voter_address: Address,
+            selected_option: &str,
+            content: RationaleContent,
+        ) -> Result<VoteReceipt>;
+        fn attach_rationale(poll_id: &PollId, voter_address: Address, content: RationaleContent) -> Result<()>;
+        #[cfg(feature = "signing")]
+        fn vote_signed(
+            poll_id: &PollId,
+            message: &VoteMessage,
+            signature: &[u8; 64],
+            public_key: &Pubkey,
+        ) -> Result<VoteReceipt>;
+    }
+}
+
+impl From<VotingContract> for SharedVotingContract {
+    fn from(contract: VotingContract) -> Self {
+        SharedVotingContract::new(contract)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[test]
+    fn test_concurrent_votes() {
+        let shared = SharedVotingContract::new(VotingContract::new("wallet_admin".parse().unwrap()));
+        let poll_ids: Vec<PollId> = (0..4)
+            .map(|poll_number| {
+                shared
+                    .create_poll(
+                        "wallet_creator".parse().unwrap(),
+                        format!("Poll {}", poll_number),
+                        "Voted on from several threads".to_string(),
+                        vec!["Yes".to_string(), "No".to_string()],
+                        60,
+                    )
+                    .unwrap()
+            })
+            .collect();
+            
+        // Each thread votes in every poll with its own set of voters
+        let handles: Vec<_> = (0..8)
+            .map(|thread_number| {
+                let shared = shared.clone();
+                let poll_ids = poll_ids.clone();
+                thread::spawn(move || {
+                    for voter_number in 0..25 {
+                        for poll_id in &poll_ids {
+                            let voter_address = format!("wallet_{}_{}", thread_number, voter_number);
+                            shared.vote(poll_id, voter_address.parse().unwrap(), "Yes").unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        
+        for poll_id in &poll_ids {
+            assert_eq!(shared.get_poll_results(poll_id).unwrap().get("Yes"), Some(&200));
+            assert_eq!(shared.get_poll(poll_id).unwrap().total_votes(), 200);
+        }
+        
+        // Every vote was recorded once, in one sequence
+        assert_eq!(shared.events_since(0).len(), 4 + 4 * 200);
+        let contract = shared.try_into_inner().ok().unwrap();
+        assert!(VotingContract::from_snapshot(&contract.to_snapshot()).is_ok());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,283 @@
+// Signed votes.
+// The voter's wallet signs a canonical vote message with its ed25519 key, and the contract takes
+// the voter address from the public key instead of trusting an address passed by the caller.
+// The address is the key written in the contract's address format (see AddressFormat::key_address):
+// lowercase hex under the default format, the same form program accounts use, or base58 under
+// Base58Address. Formats that cannot write a 32-byte key refuse signed votes with InvalidAddress.
+// Each signing address has a nonce counter, and a signed vote must carry the next nonce, so a
+// captured signature cannot be replayed after a retract or once a newer one has been used.
+// Signatures are checked with ed25519-dalek's strict verification, which also refuses weak keys
+// and malleable signatures, so one key cannot hide behind several valid signatures.
+use crate::address::Address;
+use crate::encoding::{self, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::program::Pubkey;
+use crate::receipt::VoteReceipt;
+use crate::{Result, VotingContract, VotingError};
+use ed25519_dalek::{Signature, VerifyingKey};
+
+// Domain tag signed ahead of every vote message, so a vote signature can never be passed off as
+// a signature over some other kind of data
+pub const VOTE_MESSAGE_DOMAIN: &[u8] = b"arch-poll:vote:v1";
+
+// Define the vote a wallet signs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteMessage {
+    pub poll_id: PollId,
+    pub selected_option: String,
+    pub nonce: u64, // Must equal the signer's next nonce (see VotingContract::next_nonce)
+}
+
+impl VoteMessage {
+    pub fn new(poll_id: PollId, selected_option: String, nonce: u64) -> Self {
+        VoteMessage {
+            poll_id,
+            selected_option,
+            nonce,
+        }
+    }
+    
+    // Get the exact bytes a wallet signs: the domain tag followed by the encoded message
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut signing_bytes = VOTE_MESSAGE_DOMAIN.to_vec();
+        signing_bytes.extend_from_slice(&encoding::to_bytes(self));
+        signing_bytes
+    }
+}
+
+impl Encode for VoteMessage {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.poll_id);
+        encoder.write(&self.selected_option);
+        encoder.write(&self.nonce);
+    }
+}
+
+impl VotingContract {
+    // Get the nonce the next signed operation from an address must carry (1 for a new signer)
+    pub fn next_nonce(&self, address: &Address) -> u64 {
+        self.signer_nonces.get(&self.lookup_address(address)).map_or(1, |&nonce| nonce + 1)
+    }
+    
+    // Cast a single-choice vote authorized by an ed25519 signature over the vote message.
+    // The vote counts for the signing key's address; InvalidSignature is returned when the
+    // signature does not match or the message was signed for another poll, and InvalidNonce
+    // when the message does not carry the signer's next nonce, and InvalidAddress when the
+    // contract's address format has no address for the key. The nonce is used up only
+    // when the vote is recorded. Returns the vote's receipt.
+    pub fn vote_signed(
+        &mut self,
+        poll_id: &PollId,
+        message: &VoteMessage,
+        signature: &[u8; 64],
+        public_key: &Pubkey,
+    ) -> Result<VoteReceipt> {
+        if message.poll_id != *poll_id || !verify(public_key, &message.signing_bytes(), signature) {
+            return Err(VotingError::InvalidSignature);
+        }
+        
+        let voter_address = self.key_address(public_key)?;
+        if message.nonce != self.next_nonce(&voter_address) {
+            return Err(VotingError::InvalidNonce);
+        }
+        
+        let receipt = self.vote(poll_id, voter_address, &message.selected_option)?;
+        self.signer_nonces.insert(receipt.voter.clone(), message.nonce);
+        Ok(receipt)
+    }
+}
+
+// Verify an ed25519 signature over a message (RFC 8032). Public keys that are not points, or
+// are of small order, and signatures with a non-canonical S are rejected.
+fn verify(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    VerifyingKey::from_bytes(public_key)
+        .is_ok_and(|verifying_key| verifying_key.verify_strict(message, &Signature::from_bytes(signature)).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{AddressFormat, Base58Address, HexAddress};
+    use crate::id::SequentialIds;
+    use crate::program::address_of;
+    
+    fn from_hex<const N: usize>(hex: &str) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).unwrap();
+        }
+        bytes
+    }
+    
+    #[test]
+    fn test_rfc8032_vectors() {
+        // Test 1: empty message
+        let public_key = from_hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+        let signature = from_hex(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        );
+        assert!(verify(&public_key, b"", &signature));
+        assert!(!verify(&public_key, b"x", &signature));
+        
+        // Test 2: one-byte message
+        let public_key = from_hex("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c");
+        let mut signature = from_hex(
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        );
+        assert!(verify(&public_key, &[0x72], &signature));
+        
+        // Flipped bits in R or S, S pushed past L and a small-order key are rejected
+        signature[0] ^= 1;
+        assert!(!verify(&public_key, &[0x72], &signature));
+        signature[0] ^= 1;
+        signature[63] |= 0xf0;
+        assert!(!verify(&public_key, &[0x72], &signature));
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        assert!(!verify(&identity, &[0x72], &signature));
+    }
+    
+    #[test]
+    fn test_vote_signed() {
+        // The test signatures cover sequential poll IDs
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_id_generator(Box::new(SequentialIds));
+        let poll_id = contract
+            .create_poll_with_vote_changes(
+                "wallet_creator".parse().unwrap(),
+                "Signed Poll".to_string(),
+                "Votes carry a wallet signature".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let other_poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Other Poll".to_string(),
+                "Same options".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+            
+        // Signature by the key derived from the seed 00 01 02 .. 1f over (poll_1, Yes, nonce 1)
+        let public_key: Pubkey = from_hex("03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8");
+        let signature = from_hex(
+            "36c0ed85db7ae61f147d23a1cfcb31183aab58d1aed76c44d975684dd32707f804ee9fd7b08a2c1771b9cef7de526a8ffada7dc707d245902fa3bbf26e37cb0e",
+        );
+        let message = VoteMessage::new(poll_id.clone(), "Yes".to_string(), 1);
+        
+        // The signature only covers the signed option, nonce and poll
+        let tampered = VoteMessage::new(poll_id.clone(), "No".to_string(), 1);
+        let result = contract.vote_signed(&poll_id, &tampered, &signature, &public_key);
+        assert!(matches!(result, Err(VotingError::InvalidSignature)));
+        let result = contract.vote_signed(&other_poll_id, &message, &signature, &public_key);
+        assert!(matches!(result, Err(VotingError::InvalidSignature)));
+        let result = contract.vote_signed(&poll_id, &message, &signature, &[9; 32]);
+        assert!(matches!(result, Err(VotingError::InvalidSignature)));
+        
+        contract.vote_signed(&poll_id, &message, &signature, &public_key).unwrap();
+        let poll = contract.get_poll(&poll_id).unwrap();
+        assert!(poll.participant_addresses.contains(&address_of(&public_key)));
+        assert_eq!(poll.get_results().get("Yes"), Some(&1));
+        
+        assert_eq!(contract.next_nonce(&address_of(&public_key)), 2);
+        
+        // A used nonce cannot be replayed, even after the vote is retracted or in another poll
+        contract.retract_vote(&poll_id, address_of(&public_key)).unwrap();
+        let result = contract.vote_signed(&poll_id, &message, &signature, &public_key);
+        assert!(matches!(result, Err(VotingError::InvalidNonce)));
+        let other_message = VoteMessage::new(other_poll_id.clone(), "Yes".to_string(), 1);
+        let other_signature = from_hex(
+            "2727d64a39535c40f4cc5309cfb2cd9723e61ee5fc6727e859efccc996b59cac793e86f6a2f0bb04dafb9904f4cda22e221c67da2f4157b6e44a34eca942ea05",
+        );
+        let result = contract.vote_signed(&other_poll_id, &other_message, &other_signature, &public_key);
+        assert!(matches!(result, Err(VotingError::InvalidNonce)));
+        
+        // A fresh signature with the next nonce is accepted
+        let message = VoteMessage::new(poll_id.clone(), "No".to_string(), 2);
+        let signature = from_hex(
+            "358e705fcda7f279957eb3e5b4a2362101d77086af73284b366f41fcc852afa0767a244942588e60d75de704c3bfb729722332432ad93f820ab8e9834becf308",
+        );
+        contract.vote_signed(&poll_id, &message, &signature, &public_key).unwrap();
+        assert_eq!(contract.get_poll_results(&poll_id).unwrap().get("No"), Some(&1));
+        assert_eq!(contract.next_nonce(&address_of(&public_key)), 3);
+    }
+    
+    #[test]
+    fn test_nonce_is_kept_under_canonical_address() {
+        // A format whose canonical form differs from the lowercase hex address_of returns
+        struct UpperHex;
+        impl AddressFormat for UpperHex {
+            fn canonicalize(&self, address: &str) -> Option<String> {
+                Some(address.to_uppercase())
+            }
+        }
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        contract.set_id_generator(Box::new(SequentialIds));
+        contract.set_address_format(Box::new(UpperHex)).unwrap();
+        let poll_id = contract
+            .create_poll_with_vote_changes(
+                "wallet_creator".parse().unwrap(),
+                "Signed Poll".to_string(),
+                "Votes carry a wallet signature".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let public_key: Pubkey = from_hex("03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8");
+        let signature = from_hex(
+            "36c0ed85db7ae61f147d23a1cfcb31183aab58d1aed76c44d975684dd32707f804ee9fd7b08a2c1771b9cef7de526a8ffada7dc707d245902fa3bbf26e37cb0e",
+        );
+        let message = VoteMessage::new(poll_id.clone(), "Yes".to_string(), 1);
+        
+        // The used nonce is found whichever spelling of the address is looked up, so a replay
+        // after a retract is still refused
+        let receipt = contract.vote_signed(&poll_id, &message, &signature, &public_key).unwrap();
+        assert_eq!(receipt.voter.as_str(), address_of(&public_key).to_uppercase());
+        assert_eq!(contract.next_nonce(&address_of(&public_key)), 2);
+        contract.retract_vote(&poll_id, address_of(&public_key)).unwrap();
+        let result = contract.vote_signed(&poll_id, &message, &signature, &public_key);
+        assert!(matches!(result, Err(VotingError::InvalidNonce)));
+    }
+    
+    #[test]
+    fn test_vote_signed_follows_address_format() {
+        let signed_contract = |address_format: Box<dyn AddressFormat>, creator_address: &str| {
+            let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+            contract.set_id_generator(Box::new(SequentialIds));
+            contract.set_address_format(address_format).unwrap();
+            let poll_id = contract
+                .create_poll_with_vote_changes(
+                    creator_address.parse().unwrap(),
+                    "Signed Poll".to_string(),
+                    "Votes carry a wallet signature".to_string(),
+                    vec!["Yes".to_string(), "No".to_string()],
+                    60,
+                )
+                .unwrap();
+            (contract, poll_id)
+        };
+        let base58_format = Base58Address { length: 32, checksum: false };
+        let (mut contract, poll_id) = signed_contract(Box::new(base58_format), &"1".repeat(32));
+        let public_key: Pubkey = from_hex("03a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1ddc8664125531b8");
+        let signature = from_hex(
+            "36c0ed85db7ae61f147d23a1cfcb31183aab58d1aed76c44d975684dd32707f804ee9fd7b08a2c1771b9cef7de526a8ffada7dc707d245902fa3bbf26e37cb0e",
+        );
+        let message = VoteMessage::new(poll_id.clone(), "Yes".to_string(), 1);
+        
+        // The vote counts for the key's base58 address, which keeps the signer's nonce
+        let key_address = contract.key_address(&public_key).unwrap();
+        let receipt = contract.vote_signed(&poll_id, &message, &signature, &public_key).unwrap();
+        assert_eq!(receipt.voter, key_address);
+        assert!(contract.get_poll(&poll_id).unwrap().participant_addresses.contains(&key_address));
+        assert_eq!(contract.next_nonce(&key_address), 2);
+        
+        // A format with no address for a 32-byte key refuses signed votes
+        let (mut contract, poll_id) = signed_contract(Box::new(HexAddress { length: 20 }), &"ab".repeat(20));
+        let result = contract.vote_signed(&poll_id, &message, &signature, &public_key);
+        assert!(matches!(result, Err(VotingError::InvalidAddress)));
+    }
+}
\ No newline at end of file
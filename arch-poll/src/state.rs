@@ -0,0 +1,300 @@
+// Versioned contract snapshots.
+// A snapshot opens with a header (magic bytes, the schema version it was written with, and the
+// oldest schema version able to read it), followed by the persisted state split into tagged,
+// length-prefixed sections, one per contract field. Readers skip sections they do not know, so
+// state added by a later version does not stop an older build from restoring the rest; sections a
+// later version adds must therefore have a default when absent. A change an older build cannot
+// read safely, such as a new poll layout, raises min_reader_version, and older builds refuse the
+// snapshot with UnsupportedSnapshotVersion instead of misreading it.
+use alloc::collections::BTreeMap;
+
+use crate::address::AnyAddress;
+use crate::clock::default_clock;
+use crate::creators;
+use crate::collections::HashMap;
+use crate::encoding::{self, Decode, Decoder, Encode, Encoder};
+use crate::id::HashedIds;
+use crate::index::{PollIndex, StatusIndex};
+use crate::prelude::*;
+use crate::{Result, VotingContract, VotingError};
+
+// Bytes every snapshot starts with
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"APOL";
+
+// Schema version this build writes
+pub const SNAPSHOT_SCHEMA_VERSION: u16 = 14;
+
+// Oldest schema version that can read what this build writes
+pub const SNAPSHOT_MIN_READER_VERSION: u16 = 14;
+
+// Define the sections of a snapshot. Tags are stable: new state takes the next free tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateSection {
+    AccessControl = 1,
+    Config = 2,
+    NextPollId = 3,
+    ActivePolls = 4,
+    ArchivedPolls = 5,
+    RejectDuplicateTitles = 6,
+    VoterWeights = 7,
+    Delegations = 8,
+    EventLog = 9,
+    NextEventSequence = 10,
+    SignerNonces = 11,
+    MaxPollExtensions = 12,
+    CaseInsensitiveOptions = 13,
+    AutoExpire = 14,
+    RateLimiter = 15,
+    ModerationLog = 16,
+    Treasury = 17,
+    RewardPools = 18,
+    Elections = 19,
+    LedgerSequence = 20,
+    PendingAdminTransfer = 21,
+    ContractPause = 22,
+    Creators = 23,
+    CompactedPolls = 24,
+    Discussions = 25,
+}
+
+impl StateSection {
+    pub fn tag(self) -> u16 {
+        self as u16
+    }
+}
+
+// Define a snapshot's header and its sections, still encoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractState {
+    pub schema_version: u16,          // Schema the snapshot was written with
+    pub min_reader_version: u16,      // Oldest schema that can read it
+    sections: BTreeMap<u16, Vec<u8>>, // Encoded sections by tag, including unknown ones
+}
+
+impl ContractState {
+    // Create an empty state in this build's schema
+    pub fn new() -> Self {
+        ContractState {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            min_reader_version: SNAPSHOT_MIN_READER_VERSION,
+            sections: BTreeMap::new(),
+        }
+    }
+    
+    // Encode a value into a section, replacing what it held
+    pub fn insert<T: Encode>(&mut self, section: StateSection, value: &T) {
+        self.sections.insert(section.tag(), encoding::to_bytes(value));
+    }
+    
+    // Get a section's encoded bytes by tag (None when the snapshot does not have it)
+    pub fn section(&self, tag: u16) -> Option<&[u8]> {
+        self.sections.get(&tag).map(Vec::as_slice)
+    }
+    
+    // Set a section's encoded bytes by tag (for migrations rewriting raw sections)
+    pub fn insert_section(&mut self, tag: u16, bytes: Vec<u8>) {
+        self.sections.insert(tag, bytes);
+    }
+    
+    // Remove a section, returning its encoded bytes
+    pub fn take_section(&mut self, tag: u16) -> Option<Vec<u8>> {
+        self.sections.remove(&tag)
+    }
+    
+    // Decode a section (None when absent), failing with CorruptState unless it is read exactly
+    pub fn read<T: Decode>(&self, section: StateSection) -> Result<Option<T>> {
+        self.section(section.tag()).map(encoding::from_bytes).transpose()
+    }
+    
+    // Decode a section every supported schema has
+    pub fn read_required<T: Decode>(&self, section: StateSection) -> Result<T> {
+        self.read(section)?.ok_or(VotingError::CorruptState)
+    }
+    
+    // Write the header and the sections in tag order
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        encoder.write(&SNAPSHOT_MAGIC);
+        encoder.write(&self.schema_version);
+        encoder.write(&self.min_reader_version);
+        encoder.write(&(self.sections.len() as u32));
+        for (tag, bytes) in &self.sections {
+            encoder.write(tag);
+            encoder.write(&(bytes.len() as u32));
+            encoder.write_bytes(bytes);
+        }
+        encoder.finish()
+    }
+    
+    // Read a snapshot's header and sections, rejecting snapshots this build cannot read.
+    // Sections are only split here; they are decoded when read.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut decoder = Decoder::new(data);
+        if decoder.read::<[u8; 4]>()? != SNAPSHOT_MAGIC {
+            return Err(VotingError::CorruptState);
+        }
+        let schema_version: u16 = decoder.read()?;
+        let min_reader_version: u16 = decoder.read()?;
+        if schema_version == 0 || min_reader_version > schema_version {
+            return Err(VotingError::CorruptState);
+        }
+        if min_reader_version > SNAPSHOT_SCHEMA_VERSION {
+            return Err(VotingError::UnsupportedSnapshotVersion);
+        }
+        
+        let section_count: u32 = decoder.read()?;
+        let mut sections = BTreeMap::new();
+        for _ in 0..section_count {
+            let tag: u16 = decoder.read()?;
+            let length: u32 = decoder.read()?;
+            let bytes = decoder.read_bytes(length as usize)?;
+            if sections.insert(tag, bytes.to_vec()).is_some() {
+                return Err(VotingError::CorruptState);
+            }
+        }
+        decoder.finish()?;
+        
+        Ok(ContractState {
+            schema_version,
+            min_reader_version,
+            sections,
+        })
+    }
+}
+
+impl Default for ContractState {
+    fn default() -> Self {
+        ContractState::new()
+    }
+}
+
+impl VotingContract {
+    // Capture the persisted state (the event handler, clock, ID generator, execution hooks, ledger
+    // sinks and balance, snapshot and payment providers are not persisted)
+    pub fn to_state(&self) -> ContractState {
+        let mut state = ContractState::new();
+        state.insert(StateSection::AccessControl, &self.access_control);
+        state.insert(StateSection::Config, &self.config);
+        state.insert(StateSection::NextPollId, &self.next_poll_id);
+        state.insert(StateSection::ActivePolls, &self.active_polls);
+        state.insert(StateSection::ArchivedPolls, &self.archived_polls);
+        state.insert(StateSection::RejectDuplicateTitles, &self.reject_duplicate_titles);
+        state.insert(StateSection::VoterWeights, &self.voter_weights);
+        state.insert(StateSection::Delegations, &self.delegations);
+        state.insert(StateSection::EventLog, &self.event_log);
+        state.insert(StateSection::NextEventSequence, &self.next_event_sequence);
+        state.insert(StateSection::SignerNonces, &self.signer_nonces);
+        state.insert(StateSection::MaxPollExtensions, &self.max_poll_extensions);
+        state.insert(StateSection::CaseInsensitiveOptions, &self.case_insensitive_options);
+        state.insert(StateSection::AutoExpire, &self.auto_expire);
+        state.insert(StateSection::RateLimiter, &self.rate_limiter);
+        state.insert(StateSection::ModerationLog, &self.moderation_log);
+        state.insert(StateSection::Treasury, &self.treasury);
+        state.insert(StateSection::RewardPools, &self.reward_pools);
+        state.insert(StateSection::Elections, &self.elections);
+        state.insert(StateSection::LedgerSequence, &self.ledger_sequence);
+        state.insert(StateSection::PendingAdminTransfer, &self.pending_admin_transfer);
+        state.insert(StateSection::ContractPause, &self.contract_pause);
+        state.insert(StateSection::Creators, &self.creators);
+        state.insert(StateSection::CompactedPolls, &self.compacted_polls);
+        state.insert(StateSection::Discussions, &self.discussions);
+        state
+    }
+    
+    // Restore a contract from its state, rejecting state that is internally inconsistent
+    pub fn from_state(state: &ContractState) -> Result<Self> {
+        let mut contract = VotingContract {
+            access_control: state.read_required(StateSection::AccessControl)?,
+            config: state.read_required(StateSection::Config)?,
+            next_poll_id: state.read_required(StateSection::NextPollId)?,
+            active_polls: state.read_required(StateSection::ActivePolls)?,
+            archived_polls: state.read_required(StateSection::ArchivedPolls)?,
+            reject_duplicate_titles: state.read_required(StateSection::RejectDuplicateTitles)?,
+            voter_weights: state.read_required(StateSection::VoterWeights)?,
+            delegations: state.read_required(StateSection::Delegations)?,
+            event_log: state.read_required(StateSection::EventLog)?,
+            next_event_sequence: state.read_required(StateSection::NextEventSequence)?,
+            signer_nonces: state.read_required(StateSection::SignerNonces)?,
+            max_poll_extensions: state.read_required(StateSection::MaxPollExtensions)?,
+            case_insensitive_options: state.read_required(StateSection::CaseInsensitiveOptions)?,
+            auto_expire: state.read_required(StateSection::AutoExpire)?,
+            rate_limiter: state.read_required(StateSection::RateLimiter)?,
+            moderation_log: state.read_required(StateSection::ModerationLog)?,
+            treasury: state.read(StateSection::Treasury)?.unwrap_or_default(),
+            reward_pools: state.read(StateSection::RewardPools)?.unwrap_or_default(),
+            elections: state.read(StateSection::Elections)?.unwrap_or_default(),
+            ledger_sequence: state.read(StateSection::LedgerSequence)?.unwrap_or_default(),
+            pending_admin_transfer: state.read(StateSection::PendingAdminTransfer)?.unwrap_or_default(),
+            contract_pause: state.read(StateSection::ContractPause)?.unwrap_or_default(),
+            creators: HashMap::new(),
+            compacted_polls: state.read(StateSection::CompactedPolls)?.unwrap_or_default(),
+            discussions: state.read(StateSection::Discussions)?.unwrap_or_default(),
+            poll_index: PollIndex::new(),
+            status_index: StatusIndex::new(),
+            clock: default_clock(),
+            address_format: Box::new(AnyAddress),
+            id_generator: Box::new(HashedIds),
+            execution_hooks: HashMap::new(),
+            balance_provider: None,
+            proof_verifier: None,
+            snapshot_provider: None,
+            on_event: None,
+            notifiers: Vec::new(),
+            payment_provider: None,
+            ledger_sinks: Vec::new(),
+        };
+        // Snapshots from before the creator registry get one counted from their stored polls
+        contract.creators = match state.read(StateSection::Creators)? {
+            Some(creators) => creators,
+            None => creators::count_creators(contract.active_polls.values().chain(contract.archived_polls.values())),
+        };
+        
+        contract.validate_invariants()?;
+        contract.poll_index = PollIndex::build(contract.active_polls.values().chain(contract.archived_polls.values()));
+        contract.status_index = StatusIndex::build(contract.active_polls.values());
+        Ok(contract)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_versioned_snapshots() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Upgrade".to_string(),
+                "Survives a schema bump".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        contract.vote(&poll_id, "wallet_voter".parse().unwrap(), "Yes").unwrap();
+        let snapshot = contract.to_snapshot();
+        assert_eq!(&snapshot[..4], b"APOL");
+        
+        // A later version's extra sections are skipped by this build
+        let mut state = ContractState::from_bytes(&snapshot).unwrap();
+        state.schema_version = SNAPSHOT_SCHEMA_VERSION + 1;
+        state.sections.insert(999, vec![1, 2, 3]);
+        let restored = VotingContract::from_snapshot(&state.to_bytes()).unwrap();
+        assert_eq!(restored.get_poll(&poll_id).unwrap().get_results()["Yes"], 1);
+        
+        // ...unless the writer says this build can no longer read it
+        state.min_reader_version = SNAPSHOT_SCHEMA_VERSION + 1;
+        let result = VotingContract::from_snapshot(&state.to_bytes());
+        assert!(matches!(result, Err(VotingError::UnsupportedSnapshotVersion)));
+        
+        // Missing sections, bad magic and unversioned data are corrupt
+        let mut state = ContractState::from_bytes(&snapshot).unwrap();
+        state.sections.remove(&StateSection::Config.tag());
+        assert!(matches!(VotingContract::from_state(&state), Err(VotingError::CorruptState)));
+        let mut bad_magic = snapshot.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(VotingContract::from_snapshot(&bad_magic), Err(VotingError::CorruptState)));
+        assert!(matches!(VotingContract::from_snapshot(&[]), Err(VotingError::CorruptState)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,154 @@
+// Turnout and participation statistics.
+// Aggregates dashboards would otherwise recompute by walking the raw vote maps. Turnout is only
+// known for polls with an electorate: the allowlist when the poll has one, otherwise the
+// eligible_voters of its quorum configuration. Rates and shares are integers (votes per hour,
+// basis points) so the figures are identical on every platform.
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{share_bps, Poll, PollStatus, Result, VotingContract};
+
+// Define the contract-wide statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractStats {
+    pub total_polls: usize,               // Live and archived polls
+    pub open_polls: usize,                // Polls accepting votes right now
+    pub total_unique_voters: usize,       // Addresses holding a ballot in any poll, counted once
+    pub average_turnout_bps: Option<u16>, // Mean turnout of the polls with an electorate
+}
+
+// Define the statistics of one poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollStats {
+    pub poll_id: PollId,
+    pub participants: usize,
+    pub eligible_voters: Option<usize>, // Size of the electorate, when the poll has one
+    pub turnout_bps: Option<u16>,       // Participants as a share of the electorate
+    pub votes_per_hour: u64,            // Participants per hour of voting so far, rounded down
+    pub leading_margin: u128,           // Votes between the leading option and the runner-up
+    pub leading_margin_bps: u16,        // The margin as a share of the counted votes
+}
+
+impl Poll {
+    // Get the size of the poll's electorate (None when turnout cannot be measured)
+    pub fn eligible_voter_count(&self) -> Option<usize> {
+        match &self.allowlist {
+            Some(allowlist) => Some(allowlist.len()),
+            None => self.quorum_config.map(|quorum_config| quorum_config.eligible_voters).filter(|&voters| voters > 0),
+        }
+    }
+    
+    // Get the participants as a share of the electorate, capped at 100%
+    pub fn turnout_bps(&self) -> Option<u16> {
+        let eligible_voters = self.eligible_voter_count()?;
+        Some(share_bps(self.total_votes().min(eligible_voters) as u128, eligible_voters as u128))
+    }
+    
+    // Build the poll's statistics as of the given time
+    pub fn stats(&self, current_timestamp: u64) -> PollStats {
+        let mut tallies: Vec<u128> = self.get_results().into_values().collect();
+        tallies.sort_unstable_by(|first, second| second.cmp(first));
+        let counted_votes = tallies.iter().fold(0u128, |total, &votes| total.saturating_add(votes));
+        let leading_margin = match tallies.as_slice() {
+            [] => 0,
+            [leading_votes] => *leading_votes,
+            [leading_votes, runner_up_votes, ..] => leading_votes - runner_up_votes,
+        };
+        
+        // Drafts and polls yet to start have not spent any time voting
+        let voting_seconds = match self.status {
+            PollStatus::Draft => 0,
+            _ => current_timestamp.min(self.poll_end_timestamp).saturating_sub(self.poll_start_timestamp),
+        };
+        let votes_per_hour = (self.total_votes() as u64 * 3600).checked_div(voting_seconds).unwrap_or(0);
+        
+        PollStats {
+            poll_id: self.poll_id.clone(),
+            participants: self.total_votes(),
+            eligible_voters: self.eligible_voter_count(),
+            turnout_bps: self.turnout_bps(),
+            votes_per_hour,
+            leading_margin,
+            leading_margin_bps: share_bps(leading_margin, counted_votes),
+        }
+    }
+}
+
+impl VotingContract {
+    // Get the contract-wide statistics, archived polls included
+    pub fn contract_stats(&self) -> ContractStats {
+        let current_timestamp = self.now();
+        let polls = || self.active_polls.values().chain(self.archived_polls.values());
+        let turnouts: Vec<u128> = polls().filter_map(|poll| poll.turnout_bps()).map(u128::from).collect();
+        let average_turnout_bps = match turnouts.len() {
+            0 => None,
+            poll_count => Some((turnouts.iter().sum::<u128>() / poll_count as u128) as u16),
+        };
+        
+        ContractStats {
+            total_polls: self.active_polls.len() + self.archived_polls.len(),
+            open_polls: polls().filter(|poll| poll.is_active(current_timestamp)).count(),
+            total_unique_voters: self.poll_index.voter_count(),
+            average_turnout_bps,
+        }
+    }
+    
+    // Get the statistics of a poll (archived polls included)
+    pub fn poll_stats(&self, poll_id: &PollId) -> Result<PollStats> {
+        Ok(self.get_poll(poll_id)?.stats(self.now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::QuorumConfig;
+    
+    #[test]
+    fn test_turnout_and_participation_stats() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let options = vec!["Yes".to_string(), "No".to_string()];
+        let quorum_config = QuorumConfig { eligible_voters: 8, ..QuorumConfig::default() };
+        let measured_poll_id = contract
+            .create_poll_with_quorum(
+                "wallet_creator".parse().unwrap(),
+                "Measured".to_string(),
+                "Participation".to_string(),
+                options.clone(),
+                7_200,
+                quorum_config,
+            )
+            .unwrap();
+        let open_poll_id = contract
+            .create_poll("wallet_creator".parse().unwrap(), "Open".to_string(), String::new(), options, 7_200)
+            .unwrap();
+            
+        for (voter_address, selected_option) in [("wallet_a", "Yes"), ("wallet_b", "Yes"), ("wallet_c", "No")] {
+            contract.vote(&measured_poll_id, voter_address.parse().unwrap(), selected_option).unwrap();
+        }
+        contract.vote(&open_poll_id, "wallet_a".parse().unwrap(), "No").unwrap();
+        
+        // Three of eight eligible voters in the first half hour, leading by one of three votes
+        clock.advance(1_800);
+        let poll_stats = contract.poll_stats(&measured_poll_id).unwrap();
+        assert_eq!(poll_stats.eligible_voters, Some(8));
+        assert_eq!(poll_stats.turnout_bps, Some(3_750));
+        assert_eq!(poll_stats.votes_per_hour, 6);
+        assert_eq!((poll_stats.leading_margin, poll_stats.leading_margin_bps), (1, 3_333));
+        assert_eq!(contract.poll_stats(&open_poll_id).unwrap().turnout_bps, None);
+        
+        // Voters are counted once across polls, and only measured polls enter the average
+        let contract_stats = contract.contract_stats();
+        assert_eq!(contract_stats.total_polls, 2);
+        assert_eq!(contract_stats.open_polls, 2);
+        assert_eq!(contract_stats.total_unique_voters, 3);
+        assert_eq!(contract_stats.average_turnout_bps, Some(3_750));
+        
+        // Ended polls stop accruing voting time and no longer count as open
+        clock.advance(7_200);
+        assert_eq!(contract.poll_stats(&measured_poll_id).unwrap().votes_per_hour, 1);
+        assert_eq!(contract.contract_stats().open_polls, 0);
+    }
+}
\ No newline at end of file
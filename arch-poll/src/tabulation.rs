@@ -0,0 +1,310 @@
+// Alternative tabulations of ranked ballots.
+// Ranked-choice polls count by instant runoff unless their creator picks another TabulationMethod
+// before voting starts. Condorcet compares every pair of options head to head: an option preferred
+// by more ballots than its rival beats it, and options a ballot leaves unranked lose to every
+// option it ranks. When the head-to-head wins form a cycle there is no Condorcet winner, so the
+// Schulze method completes the order by comparing the strongest chains of wins between each pair.
+// Its results are the number of rivals each option beats that way, which only the Condorcet
+// winner, when there is one, can give to every rival. Borda gives an option ranked r-th (from 0)
+// among n options n - 1 - r points per ballot, and unranked options none. Reserved options are
+// never tabulated, so they are skipped wherever a ballot ranks them.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError, VotingMethod};
+
+// Define how a ranked-choice poll turns ballots into results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TabulationMethod {
+    #[default]
+    InstantRunoff, // Eliminate the weakest option until one holds a majority
+    Condorcet,     // Head-to-head comparisons, completed by Schulze on a cycle
+    Borda,         // Points by position on each ballot
+}
+
+impl TabulationMethod {
+    // Get the method's name as used in events and listings
+    pub fn name(&self) -> &'static str {
+        match self {
+            TabulationMethod::InstantRunoff => "InstantRunoff",
+            TabulationMethod::Condorcet => "Condorcet",
+            TabulationMethod::Borda => "Borda",
+        }
+    }
+}
+
+impl Encode for TabulationMethod {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            TabulationMethod::InstantRunoff => 0,
+            TabulationMethod::Condorcet => 1,
+            TabulationMethod::Borda => 2,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for TabulationMethod {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(TabulationMethod::InstantRunoff),
+            1 => Ok(TabulationMethod::Condorcet),
+            2 => Ok(TabulationMethod::Borda),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+impl Poll {
+    // Get the options ranked ballots are tabulated over, in ballot order
+    fn tabulated_options(&self) -> Vec<&String> {
+        self.voting_options
+            .iter()
+            .filter(|option| !self.is_reserved_option(option))
+            .collect()
+    }
+    
+    // Get the tabulated options each ballot ranks, best first
+    fn tabulated_rankings(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        let options = self.tabulated_options();
+        self.ranked_ballots.values().map(move |ranking| {
+            ranking
+                .iter()
+                .filter_map(|ranked_option| options.iter().position(|option| *option == ranked_option))
+                .collect()
+        })
+    }
+    
+    // Count, for every ordered pair of tabulated options, the ballots preferring the first to the
+    // second. Indexes follow tabulated_options.
+    pub(crate) fn pairwise_counts(&self) -> Vec<Vec<u128>> {
+        let option_count = self.tabulated_options().len();
+        let mut counts = vec![vec![0u128; option_count]; option_count];
+        for ranking in self.tabulated_rankings() {
+            for (position, &preferred) in ranking.iter().enumerate() {
+                // Ranked options beat the ones ranked after them and every unranked option
+                for (rival, row) in counts[preferred].iter_mut().enumerate() {
+                    if rival != preferred && !ranking[..position].contains(&rival) {
+                        *row += 1;
+                    }
+                }
+            }
+        }
+        counts
+    }
+    
+    // Get the head-to-head results between every pair of options, as ballots preferring the
+    // first option to the second
+    pub fn pairwise_preferences(&self) -> HashMap<(String, String), u128> {
+        let options = self.tabulated_options();
+        let counts = self.pairwise_counts();
+        let mut preferences = HashMap::new();
+        for (index, option) in options.iter().enumerate() {
+            for (rival, rival_option) in options.iter().enumerate() {
+                if rival != index {
+                    preferences.insert(((*option).clone(), (*rival_option).clone()), counts[index][rival]);
+                }
+            }
+        }
+        preferences
+    }
+    
+    // Get the option that beats every other option head to head (None on a cycle or a tie)
+    pub fn condorcet_winner(&self) -> Option<String> {
+        let options = self.tabulated_options();
+        let counts = self.pairwise_counts();
+        (0..options.len())
+            .find(|&index| (0..options.len()).all(|rival| rival == index || counts[index][rival] > counts[rival][index]))
+            .map(|index| options[index].clone())
+    }
+    
+    // Check whether the head-to-head wins run in a cycle, so that no option can beat every other
+    pub fn has_condorcet_cycle(&self) -> bool {
+        let counts = self.pairwise_counts();
+        let option_count = counts.len();
+        let mut reaches: Vec<Vec<bool>> = (0..option_count)
+            .map(|index| (0..option_count).map(|rival| counts[index][rival] > counts[rival][index]).collect())
+            .collect();
+        for via in 0..option_count {
+            for index in 0..option_count {
+                if reaches[index][via] {
+                    let via_reaches = reaches[via].clone();
+                    for (reached, via_reached) in reaches[index].iter_mut().zip(via_reaches) {
+                        *reached |= via_reached;
+                    }
+                }
+            }
+        }
+        (0..option_count).any(|index| reaches[index][index])
+    }
+    
+    // Tabulate ranked ballots by Condorcet with Schulze completion: the number of rivals each
+    // option beats through its strongest chain of head-to-head wins
+    pub fn schulze_results(&self) -> HashMap<String, u128> {
+        let options = self.tabulated_options();
+        let counts = self.pairwise_counts();
+        let option_count = options.len();
+        let mut strength = vec![vec![0u128; option_count]; option_count];
+        for index in 0..option_count {
+            for rival in 0..option_count {
+                if index != rival && counts[index][rival] > counts[rival][index] {
+                    strength[index][rival] = counts[index][rival];
+                }
+            }
+        }
+        // Widest paths: a chain is as strong as its weakest win
+        for via in 0..option_count {
+            for index in 0..option_count {
+                if index == via {
+                    continue;
+                }
+                for rival in 0..option_count {
+                    if rival != via && rival != index {
+                        let chain = strength[index][via].min(strength[via][rival]);
+                        strength[index][rival] = strength[index][rival].max(chain);
+                    }
+                }
+            }
+        }
+        
+        options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| {
+                let beaten = (0..option_count).filter(|&rival| strength[index][rival] > strength[rival][index]).count();
+                ((*option).clone(), beaten as u128)
+            })
+            .collect()
+    }
+    
+    // Tabulate ranked ballots by Borda count
+    pub fn borda_results(&self) -> HashMap<String, u128> {
+        let options = self.tabulated_options();
+        let mut points = vec![0u128; options.len()];
+        for ranking in self.tabulated_rankings() {
+            for (position, &option) in ranking.iter().enumerate() {
+                points[option] += (options.len() - 1 - position) as u128;
+            }
+        }
+        options.into_iter().cloned().zip(points).collect()
+    }
+    
+    // Tabulate ranked ballots with the poll's tabulation method
+    pub(crate) fn ranked_results(&self) -> HashMap<String, u128> {
+        match self.tabulation_method {
+            TabulationMethod::InstantRunoff => self.instant_runoff_rounds().pop().unwrap_or_default(),
+            TabulationMethod::Condorcet => self.schulze_results(),
+            TabulationMethod::Borda => self.borda_results(),
+        }
+    }
+}
+
+impl VotingContract {
+    // Choose how a ranked-choice poll is tabulated (creator or ConfigurePolls). Like other rules of
+    // the count, it can only change before voting starts.
+    pub fn set_tabulation_method(
+        &mut self,
+        poll_id: &PollId,
+        tabulation_method: TabulationMethod,
+        caller: &Address,
+    ) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.voting_method != VotingMethod::RankedChoice {
+            return Err(VotingError::WrongVotingMethod);
+        }
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.tabulation_method = tabulation_method;
+        poll.invalidate_results();
+        self.emit(ContractEvent::TabulationMethodSet {
+            poll_id: poll_id.clone(),
+            tabulation_method,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_tabulation_methods() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let options = || vec!["Rock".to_string(), "Paper".to_string(), "Scissors".to_string()];
+        let cycle_poll_id = contract
+            .create_ranked_poll("wallet_creator".parse().unwrap(), "Cycle".to_string(), String::new(), options(), 60)
+            .unwrap();
+        let plain_poll_id = contract
+            .create_poll("wallet_creator".parse().unwrap(), "Plain".to_string(), String::new(), options(), 60)
+            .unwrap();
+        let result = contract.set_tabulation_method(
+            &plain_poll_id,
+            TabulationMethod::Borda,
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::WrongVotingMethod)));
+        let result = contract.set_tabulation_method(
+            &cycle_poll_id,
+            TabulationMethod::Condorcet,
+            &"wallet_a".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract
+            .set_tabulation_method(&cycle_poll_id, TabulationMethod::Condorcet, &"wallet_creator".parse().unwrap())
+            .unwrap();
+        
+        // Rock beats Scissors 5-4, Scissors beats Paper 7-2 and Paper beats Rock 6-3, so the wins
+        // cycle and Schulze breaks the weakest one: Rock over Scissors
+        let ballots = [
+            (3, ["Rock", "Scissors", "Paper"]),
+            (2, ["Paper", "Rock", "Scissors"]),
+            (4, ["Scissors", "Paper", "Rock"]),
+        ];
+        let mut voter_number = 0;
+        for (count, ranking) in ballots {
+            for _ in 0..count {
+                voter_number += 1;
+                let ranking = ranking.iter().map(|option| option.to_string()).collect();
+                contract
+                    .vote_ranked(
+                        &cycle_poll_id,
+                        format!("wallet_{}", voter_number).parse().unwrap(),
+                        ranking,
+                    )
+                    .unwrap();
+            }
+        }
+        let result = contract.set_tabulation_method(
+            &cycle_poll_id,
+            TabulationMethod::Borda,
+            &"wallet_creator".parse().unwrap(),
+        );
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        let poll = contract.get_poll(&cycle_poll_id).unwrap();
+        assert!(poll.has_condorcet_cycle());
+        assert_eq!(poll.condorcet_winner(), None);
+        assert_eq!(poll.pairwise_preferences()[&("Paper".to_string(), "Rock".to_string())], 6);
+        assert_eq!(poll.winner().unwrap().winning_option.as_deref(), Some("Scissors"));
+        
+        // Borda scores positions instead: Scissors 4*2 + 3 = 11, Rock 3*2 + 2 = 8, Paper 2*2 + 4 = 8
+        let mut borda_poll = poll.clone();
+        borda_poll.tabulation_method = TabulationMethod::Borda;
+        let results = borda_poll.get_results();
+        assert_eq!((results["Scissors"], results["Rock"], results["Paper"]), (11, 8, 8));
+        
+        // The method is saved with the poll
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let restored_poll = restored.get_poll(&cycle_poll_id).unwrap();
+        assert_eq!(restored_poll.tabulation_method, TabulationMethod::Condorcet);
+    }
+}
\ No newline at end of file
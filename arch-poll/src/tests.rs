@@ -4,7 +4,7 @@ mod tests {
     use std::thread::sleep;
     
     // Helper function to create a standard test poll
-    fn create_test_poll(contract: &mut VotingContract) -> String {
+    fn create_test_poll(contract: &mut VotingContract) -> PollId {
         let creator_address = "wallet_creator".to_string();
         let poll_title = "Test Poll".to_string();
         let poll_description = "This is a test poll".to_string();
@@ -17,12 +17,12 @@ mod tests {
     // Helper function to create a poll with custom parameters
     fn create_custom_poll(
         contract: &mut VotingContract,
-        creator_address: String,
+        creator_address: Address,
         poll_title: String,
         poll_description: String,
         poll_options: Vec<String>,
         poll_duration_seconds: u64,
-    ) -> Result<String> {
+    ) -> Result<PollId> {
         contract.create_poll(
             creator_address,
             poll_title,
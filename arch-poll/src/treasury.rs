@@ -0,0 +1,305 @@
+// Poll fees and the contract treasury.
+// The contract can charge a creation_fee for every new poll, and each poll a vote_fee for every
+// ballot cast or committed in it. Fees move through a PaymentProvider: on-chain deployments
+// transfer lamports or sats into the contract's account, and tests and off-chain tools can use a
+// map of balances. A fee is checked with the provider before anything changes and collected once
+// the poll or ballot is recorded, so a refused payment leaves no trace. The Treasury ledger keeps
+// what every poll has raised and what the contract still holds, and addresses with ManageTreasury
+// withdraw the held fees. Without a provider every fee fails with PaymentFailed; free polls and
+// ballots never consult it.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Result, VotingContract, VotingError};
+
+// Mover of funds between payers and the contract's account
+pub trait PaymentProvider: Send + Sync {
+    // Check a payer can cover an amount
+    fn can_pay(&self, payer: &Address, amount: u128) -> bool;
+    
+    // Move an amount from a payer into the contract's account. Only called after can_pay
+    // approved the same amount within the same contract call.
+    fn collect(&mut self, payer: &Address, amount: u128);
+    
+    // Move an amount out of the contract's account to a recipient
+    fn pay_out(&mut self, recipient: &Address, amount: u128) -> Result<()>;
+}
+
+// A map of balances; unlisted addresses hold nothing and the contract's account is not tracked
+impl PaymentProvider for HashMap<Address, u128> {
+    fn can_pay(&self, payer: &Address, amount: u128) -> bool {
+        self.get(payer).copied().unwrap_or(0) >= amount
+    }
+    
+    fn collect(&mut self, payer: &Address, amount: u128) {
+        if let Some(balance) = self.get_mut(payer) {
+            *balance = balance.saturating_sub(amount);
+        }
+    }
+    
+    fn pay_out(&mut self, recipient: &Address, amount: u128) -> Result<()> {
+        let balance = self.entry(recipient.clone()).or_insert(0);
+        *balance = balance.checked_add(amount).ok_or(VotingError::PaymentFailed)?;
+        Ok(())
+    }
+}
+
+// Define the contract's record of collected fees
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Treasury {
+    pub creation_fee: u128,                       // Charged to the creator of every new poll (0 disables)
+    pub collected_by_poll: HashMap<PollId, u128>, // Fees each poll raised, its creation fee included
+    pub balance: u128,                            // Collected fees not yet withdrawn
+    pub total_withdrawn: u128,                    // Fees paid out by withdraw_fees
+}
+
+impl Encode for Treasury {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write(&self.creation_fee);
+        encoder.write(&self.collected_by_poll);
+        encoder.write(&self.balance);
+        encoder.write(&self.total_withdrawn);
+    }
+}
+
+impl Decode for Treasury {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        Ok(Treasury {
+            creation_fee: decoder.read()?,
+            collected_by_poll: decoder.read()?,
+            balance: decoder.read()?,
+            total_withdrawn: decoder.read()?,
+        })
+    }
+}
+
+impl VotingContract {
+    // Replace the payment mover (None fails every fee)
+    pub fn set_payment_provider(&mut self, payment_provider: Option<Box<dyn PaymentProvider>>) {
+        self.payment_provider = payment_provider;
+    }
+    
+    // Get the ledger of collected fees
+    pub fn treasury(&self) -> &Treasury {
+        &self.treasury
+    }
+    
+    // Get the fees a poll has raised so far
+    pub fn fees_collected(&self, poll_id: &PollId) -> u128 {
+        self.treasury.collected_by_poll.get(poll_id).copied().unwrap_or(0)
+    }
+    
+    // Set the fee charged for creating a poll (requires ManageTreasury, 0 disables)
+    pub fn set_creation_fee(&mut self, creation_fee: u128, caller: &Address) -> Result<()> {
+        if !self.has_permission(caller, Permission::ManageTreasury) {
+            return Err(VotingError::NotAuthorized);
+        }
+        
+        self.treasury.creation_fee = creation_fee;
+        self.emit(ContractEvent::CreationFeeSet { creation_fee });
+        Ok(())
+    }
+    
+    // Set the fee a poll charges per ballot (creator or ConfigurePolls, 0 disables). Like the
+    // balance gate, the fee can only change before voting starts so every voter pays the same.
+    pub fn set_vote_fee(&mut self, poll_id: &PollId, vote_fee: u128, caller: &Address) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        if poll.voting_started() {
+            return Err(VotingError::WrongPhase);
+        }
+        
+        poll.vote_fee = vote_fee;
+        self.emit(ContractEvent::VoteFeeSet {
+            poll_id: poll_id.clone(),
+            vote_fee,
+        });
+        Ok(())
+    }
+    
+    // Pay every held fee out to a recipient (requires ManageTreasury), returning the amount.
+    // Nothing is paid, and nothing logged, when the treasury is empty.
+    pub fn withdraw_fees(&mut self, recipient: &Address, caller: &Address) -> Result<u128> {
+        let recipient = self.parse_address(recipient)?;
+        let withdrawn_by = self.parse_address(caller)?;
+        if !self.has_permission(&withdrawn_by, Permission::ManageTreasury) {
+            return Err(VotingError::NotAuthorized);
+        }
+        let amount = self.treasury.balance;
+        if amount == 0 {
+            return Ok(0);
+        }
+        
+        self.payment_provider
+            .as_mut()
+            .ok_or(VotingError::PaymentFailed)?
+            .pay_out(&recipient, amount)?;
+        self.treasury.balance = 0;
+        self.treasury.total_withdrawn = self.treasury.total_withdrawn.saturating_add(amount);
+        self.emit(ContractEvent::FeesWithdrawn {
+            recipient,
+            amount,
+            withdrawn_by,
+        });
+        Ok(amount)
+    }
+    
+    // Check a payer can cover a fee and the treasury can hold it
+    pub(crate) fn check_fee(&self, payer: &Address, amount: u128) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        self.treasury.balance.checked_add(amount).ok_or(VotingError::VoteCountOverflow)?;
+        match &self.payment_provider {
+            Some(payment_provider) if payment_provider.can_pay(payer, amount) => Ok(()),
+            _ => Err(VotingError::PaymentFailed),
+        }
+    }
+    
+    // Collect a fee already checked with check_fee and credit it to a poll
+    pub(crate) fn collect_fee(&mut self, poll_id: &PollId, payer: &Address, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        if let Some(payment_provider) = self.payment_provider.as_mut() {
+            payment_provider.collect(payer, amount);
+        }
+        self.treasury.balance = self.treasury.balance.saturating_add(amount);
+        let collected = self.treasury.collected_by_poll.entry(poll_id.clone()).or_insert(0);
+        *collected = collected.saturating_add(amount);
+    }
+    
+    // Return a collected fee to its payer, e.g. when a batch it paid into is rolled back. A refund
+    // the provider refuses stays in the treasury.
+    pub(crate) fn refund_fee(&mut self, poll_id: &PollId, payer: &Address, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        let Some(payment_provider) = self.payment_provider.as_mut() else {
+            return;
+        };
+        if payment_provider.pay_out(payer, amount).is_ok() {
+            self.treasury.balance = self.treasury.balance.saturating_sub(amount);
+            if let Some(collected) = self.treasury.collected_by_poll.get_mut(poll_id) {
+                *collected = collected.saturating_sub(amount);
+            }
+        }
+    }
+    
+    // Check a creator can pay the creation fee for a number of polls
+    pub(crate) fn check_creation_fee(&self, creator_address: &Address, poll_count: usize) -> Result<()> {
+        let total_fee = self
+            .treasury
+            .creation_fee
+            .checked_mul(poll_count as u128)
+            .ok_or(VotingError::VoteCountOverflow)?;
+        self.check_fee(creator_address, total_fee)
+    }
+    
+    // Get the fee a poll charges per ballot (missing polls charge nothing and are reported by the
+    // caller)
+    pub(crate) fn vote_fee_of(&self, poll_id: &PollId) -> u128 {
+        self.get_poll(poll_id).map_or(0, |poll| poll.vote_fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::BatchMode;
+    use std::sync::{Arc, Mutex};
+    
+    // Balances shared with the test after the contract takes the provider
+    struct SharedBalances(Arc<Mutex<HashMap<Address, u128>>>);
+    
+    impl PaymentProvider for SharedBalances {
+        fn can_pay(&self, payer: &Address, amount: u128) -> bool {
+            self.0.lock().unwrap().can_pay(payer, amount)
+        }
+        
+        fn collect(&mut self, payer: &Address, amount: u128) {
+            self.0.lock().unwrap().collect(payer, amount)
+        }
+        
+        fn pay_out(&mut self, recipient: &Address, amount: u128) -> Result<()> {
+            self.0.lock().unwrap().pay_out(recipient, amount)
+        }
+    }
+    
+    #[test]
+    fn test_fees_and_treasury() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let create = |contract: &mut VotingContract| {
+            contract.create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Paid".to_string(),
+                "Costs to run".to_string(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+        };
+        
+        // Only ManageTreasury sets the creation fee, and without a provider fees cannot be paid
+        let result = contract.set_creation_fee(10, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract.set_creation_fee(10, &"wallet_admin".parse().unwrap()).unwrap();
+        assert!(matches!(create(&mut contract), Err(VotingError::PaymentFailed)));
+        let balances: HashMap<Address, u128> = [("wallet_creator", 25), ("wallet_rich", 5), ("wallet_poor", 1)]
+            .into_iter()
+            .map(|(address, balance)| (address.parse().unwrap(), balance))
+            .collect();
+        let balances = Arc::new(Mutex::new(balances));
+        contract.set_payment_provider(Some(Box::new(SharedBalances(Arc::clone(&balances)))));
+        let poll_id = create(&mut contract).unwrap();
+        assert_eq!(balances.lock().unwrap()["wallet_creator"], 15);
+        
+        // Voters pay the poll's fee; a voter who cannot pay leaves no ballot behind
+        contract.set_vote_fee(&poll_id, 2, &"wallet_creator".parse().unwrap()).unwrap();
+        contract.vote(&poll_id, "wallet_rich".parse().unwrap(), "Yes").unwrap();
+        let result = contract.vote(&poll_id, "wallet_poor".parse().unwrap(), "Yes");
+        assert!(matches!(result, Err(VotingError::PaymentFailed)));
+        assert_eq!(contract.get_poll(&poll_id).unwrap().total_votes(), 1);
+        assert_eq!(balances.lock().unwrap()["wallet_poor"], 1);
+        let result = contract.set_vote_fee(&poll_id, 0, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::WrongPhase)));
+        
+        // A rolled-back batch refunds the fees it collected
+        let votes = vec![
+            ("wallet_creator".parse().unwrap(), "No".to_string()),
+            ("wallet_poor".parse().unwrap(), "No".to_string()),
+        ];
+        let results = contract.vote_batch(&poll_id, votes, BatchMode::AllOrNothing);
+        assert!(matches!(results[0], Err(VotingError::BatchAborted)));
+        assert_eq!(balances.lock().unwrap()["wallet_creator"], 15);
+        assert_eq!(contract.fees_collected(&poll_id), 12);
+        
+        // Held fees are withdrawn in full by ManageTreasury, and the ledger survives a restore
+        assert!(matches!(
+            contract.withdraw_fees(&"wallet_vault".parse().unwrap(), &"wallet_rich".parse().unwrap()),
+            Err(VotingError::NotAuthorized)
+        ));
+        assert_eq!(
+            contract
+                .withdraw_fees(&"wallet_vault".parse().unwrap(), &"wallet_admin".parse().unwrap())
+                .unwrap(),
+            12
+        );
+        assert_eq!(
+            contract
+                .withdraw_fees(&"wallet_vault".parse().unwrap(), &"wallet_admin".parse().unwrap())
+                .unwrap(),
+            0
+        );
+        assert_eq!(balances.lock().unwrap()["wallet_vault"], 12);
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        assert_eq!(restored.treasury(), contract.treasury());
+        assert_eq!(restored.treasury().total_withdrawn, 12);
+        assert_eq!(restored.get_poll(&poll_id).unwrap().vote_fee, 2);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,171 @@
+// Results visibility.
+// Live tallies can sway the voters still to come, so a poll's creator may hide its results while
+// voting runs. A hidden poll's results queries (get_poll_results, the weighted, reserved and
+// approval results, and reports and exports) return ResultsHidden, and its summary leaves out the
+// leading options. AfterVote shows the results to addresses that have voted, through
+// get_poll_results_as; AfterClose shows them to no one. Admins always see them, and once the poll
+// ends they are public. Hiding only gates these queries: the event log and receipts still carry
+// each choice, so it keeps tallies out of interfaces rather than secret.
+use crate::access_control::Permission;
+use crate::address::Address;
+use crate::collections::HashMap;
+use crate::encoding::{Decode, Decoder, Encode, Encoder};
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{ContractEvent, Poll, Result, VotingContract, VotingError};
+
+// Define who may see a poll's results before it ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultsVisibility {
+    #[default]
+    Live,       // Anyone, at any time
+    AfterVote,  // Addresses that have voted in the poll
+    AfterClose, // No one until the poll ends
+}
+
+impl ResultsVisibility {
+    // Get the setting's name as used in events and listings
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResultsVisibility::Live => "Live",
+            ResultsVisibility::AfterVote => "AfterVote",
+            ResultsVisibility::AfterClose => "AfterClose",
+        }
+    }
+}
+
+impl Encode for ResultsVisibility {
+    fn encode(&self, encoder: &mut Encoder) {
+        let tag: u8 = match self {
+            ResultsVisibility::Live => 0,
+            ResultsVisibility::AfterVote => 1,
+            ResultsVisibility::AfterClose => 2,
+        };
+        encoder.write(&tag);
+    }
+}
+
+impl Decode for ResultsVisibility {
+    fn decode(decoder: &mut Decoder) -> Result<Self> {
+        match decoder.read::<u8>()? {
+            0 => Ok(ResultsVisibility::Live),
+            1 => Ok(ResultsVisibility::AfterVote),
+            2 => Ok(ResultsVisibility::AfterClose),
+            _ => Err(VotingError::CorruptState),
+        }
+    }
+}
+
+impl VotingContract {
+    // Choose who may see a poll's results before it ends (creator or ConfigurePolls)
+    pub fn set_results_visibility(
+        &mut self,
+        poll_id: &PollId,
+        results_visibility: ResultsVisibility,
+        caller: &Address,
+    ) -> Result<()> {
+        let current_timestamp = self.now();
+        let poll = self.managed_poll_mut(poll_id, caller, Permission::ConfigurePolls)?;
+        if poll.has_ended(current_timestamp) {
+            return Err(VotingError::PollClosed);
+        }
+        
+        poll.results_visibility = results_visibility;
+        self.emit(ContractEvent::ResultsVisibilitySet {
+            poll_id: poll_id.clone(),
+            results_visibility,
+        });
+        Ok(())
+    }
+    
+    // Get results of a specific poll as seen by an address, which may be a voter of an AfterVote
+    // poll or an admin
+    pub fn get_poll_results_as(&self, poll_id: &PollId, viewer_address: &Address) -> Result<HashMap<String, u128>> {
+        let poll = self.get_poll(poll_id)?;
+        self.check_results_visible(poll, Some(viewer_address))?;
+        Ok(poll.get_results())
+    }
+    
+    // Check that a viewer (None for the public) may see a poll's results
+    pub(crate) fn check_results_visible(&self, poll: &Poll, viewer_address: Option<&Address>) -> Result<()> {
+        if poll.results_visibility == ResultsVisibility::Live || poll.has_ended(self.now()) {
+            return Ok(());
+        }
+        let Some(viewer_address) = viewer_address else {
+            return Err(VotingError::ResultsHidden);
+        };
+        let has_voted = self.parse_address(viewer_address)
+            .is_ok_and(|viewer_address| poll.participant_addresses.contains(&viewer_address));
+        if self.is_admin(viewer_address) || (poll.results_visibility == ResultsVisibility::AfterVote && has_voted) {
+            Ok(())
+        } else {
+            Err(VotingError::ResultsHidden)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    
+    #[test]
+    fn test_results_visibility() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let clock = MockClock::new(1_000);
+        contract.set_clock(Box::new(clock.clone()));
+        let poll_id = contract
+            .create_poll(
+                "wallet_creator".parse().unwrap(),
+                "Hidden tally".to_string(),
+                String::new(),
+                vec!["Yes".to_string(), "No".to_string()],
+                60,
+            )
+            .unwrap();
+        let result =
+            contract.set_results_visibility(&poll_id, ResultsVisibility::AfterVote, &"wallet_a".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::NotAuthorized)));
+        contract
+            .set_results_visibility(
+                &poll_id,
+                ResultsVisibility::AfterVote,
+                &"wallet_creator".parse().unwrap(),
+            )
+            .unwrap();
+        contract.vote(&poll_id, "wallet_a".parse().unwrap(), "Yes").unwrap();
+        
+        // Only the voter and the admin see an AfterVote tally while the poll runs
+        assert!(matches!(contract.get_poll_results(&poll_id), Err(VotingError::ResultsHidden)));
+        assert!(matches!(
+            contract.get_poll_results_as(&poll_id, &"wallet_b".parse().unwrap()),
+            Err(VotingError::ResultsHidden)
+        ));
+        assert!(matches!(contract.export_poll_json(&poll_id), Err(VotingError::ResultsHidden)));
+        assert_eq!(contract.get_poll_results_as(&poll_id, &"wallet_a".parse().unwrap()).unwrap()["Yes"], 1);
+        assert_eq!(contract.get_poll_results_as(&poll_id, &"wallet_admin".parse().unwrap()).unwrap()["Yes"], 1);
+        assert!(contract.get_poll_summary(&poll_id).unwrap().leading_options.is_empty());
+        
+        // AfterClose hides it from voters too, and the setting survives a restore
+        contract
+            .set_results_visibility(
+                &poll_id,
+                ResultsVisibility::AfterClose,
+                &"wallet_creator".parse().unwrap(),
+            )
+            .unwrap();
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        restored.set_clock(Box::new(clock.clone()));
+        assert!(matches!(
+            restored.get_poll_results_as(&poll_id, &"wallet_a".parse().unwrap()),
+            Err(VotingError::ResultsHidden)
+        ));
+        
+        // Once the poll ends anyone sees the results
+        clock.advance(60);
+        assert_eq!(contract.get_poll_results(&poll_id).unwrap()["Yes"], 1);
+        let result =
+            contract.set_results_visibility(&poll_id, ResultsVisibility::Live, &"wallet_creator".parse().unwrap());
+        assert!(matches!(result, Err(VotingError::PollClosed)));
+    }
+}
\ No newline at end of file
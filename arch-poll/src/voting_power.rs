@@ -0,0 +1,119 @@
+// Voting power frozen at poll creation.
+// Registered weights are read when a ballot is cast, so buying tokens mid-poll buys votes. With a
+// SnapshotProvider set, every new poll records the provider's current snapshot id (e.g. a block
+// height) and all of its weight lookups, delegators included, read weights as of that snapshot.
+// Polls created without a provider keep using registered weights. The provider is code and is not
+// persisted, so a poll holding a snapshot refuses ballots with SnapshotUnavailable until one is set.
+use crate::address::Address;
+use crate::id::PollId;
+use crate::prelude::*;
+use crate::{Poll, Result, VotingContract, VotingError};
+
+// Source of voting weights as of past snapshots
+pub trait SnapshotProvider: Send + Sync {
+    // Id of the latest snapshot
+    fn current_snapshot(&self) -> u64;
+    
+    // Voting weight of an address as of a snapshot
+    fn weight_at(&self, snapshot_id: u64, address: &Address) -> u128;
+}
+
+impl VotingContract {
+    // Replace the snapshot source (None leaves new polls on registered weights)
+    pub fn set_snapshot_provider(&mut self, snapshot_provider: Option<Box<dyn SnapshotProvider>>) {
+        self.snapshot_provider = snapshot_provider;
+    }
+    
+    // Get the weight an address votes with in a poll (archived polls included)
+    pub fn voter_weight_in_poll(&self, poll_id: &PollId, voter_address: &Address) -> Result<u128> {
+        self.weight_in_poll(self.get_poll(poll_id)?, &self.lookup_address(voter_address))
+    }
+    
+    // Get the snapshot id new polls are created with
+    pub(crate) fn current_weight_snapshot(&self) -> Option<u64> {
+        self.snapshot_provider.as_ref().map(|snapshot_provider| snapshot_provider.current_snapshot())
+    }
+    
+    // Get an address's weight in a poll, from its snapshot when it has one
+    pub(crate) fn weight_in_poll(&self, poll: &Poll, address: &Address) -> Result<u128> {
+        match (poll.weight_snapshot, &self.snapshot_provider) {
+            (None, _) => Ok(self.voter_weight(address)),
+            (Some(snapshot_id), Some(snapshot_provider)) => Ok(snapshot_provider.weight_at(snapshot_id, address)),
+            (Some(_), None) => Err(VotingError::SnapshotUnavailable),
+        }
+    }
+    
+    // Get a voter's weight for a ballot, treating missing polls as weight 1 so the caller reports them
+    pub(crate) fn ballot_weight(&self, poll_id: &PollId, voter_address: &Address) -> Result<u128> {
+        self.get_poll(poll_id).map_or(Ok(1), |poll| self.weight_in_poll(poll, voter_address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+    
+    // Token balances recorded at each block
+    #[derive(Clone, Default)]
+    struct MockLedger {
+        blocks: Arc<RwLock<Vec<HashMap<Address, u128>>>>,
+    }
+    
+    impl MockLedger {
+        fn mine(&self, balances: &[(&str, u128)]) {
+            let block = balances.iter().map(|(address, balance)| (address.parse().unwrap(), *balance)).collect();
+            self.blocks.write().unwrap().push(block);
+        }
+    }
+    
+    impl SnapshotProvider for MockLedger {
+        fn current_snapshot(&self) -> u64 {
+            self.blocks.read().unwrap().len() as u64 - 1
+        }
+        
+        fn weight_at(&self, snapshot_id: u64, address: &Address) -> u128 {
+            self.blocks.read().unwrap()[snapshot_id as usize].get(address).copied().unwrap_or(0)
+        }
+    }
+    
+    #[test]
+    fn test_snapshot_voting_power() {
+        let mut contract = VotingContract::new("wallet_admin".parse().unwrap());
+        let create = |contract: &mut VotingContract| {
+            contract
+                .create_poll(
+                    "wallet_creator".parse().unwrap(),
+                    "Budget".to_string(),
+                    "Token weighted".to_string(),
+                    vec!["Yes".to_string(), "No".to_string()],
+                    60,
+                )
+                .unwrap()
+        };
+        let unfrozen_poll_id = create(&mut contract);
+        let ledger = MockLedger::default();
+        ledger.mine(&[("wallet_holder", 40), ("wallet_delegator", 2)]);
+        contract.set_snapshot_provider(Some(Box::new(ledger.clone())));
+        let poll_id = create(&mut contract);
+        assert_eq!(contract.get_poll(&poll_id).unwrap().weight_snapshot, Some(0));
+        assert_eq!(contract.get_poll(&unfrozen_poll_id).unwrap().weight_snapshot, None);
+        
+        // Tokens acquired after creation carry no weight, and delegators weigh as of the snapshot
+        ledger.mine(&[("wallet_holder", 40), ("wallet_delegator", 2), ("wallet_buyer", 1_000)]);
+        contract.vote(&poll_id, "wallet_buyer".parse().unwrap(), "No").unwrap();
+        contract.delegate_vote(&"wallet_delegator".parse().unwrap(), &"wallet_holder".parse().unwrap()).unwrap();
+        contract.vote(&poll_id, "wallet_holder".parse().unwrap(), "Yes").unwrap();
+        assert_eq!(contract.get_poll_results(&poll_id).unwrap()["Yes"], 42);
+        assert_eq!(contract.get_poll_results(&poll_id).unwrap()["No"], 0);
+        assert_eq!(contract.voter_weight_in_poll(&unfrozen_poll_id, &"wallet_buyer".parse().unwrap()).unwrap(), 1);
+        
+        // The snapshot id is saved with the poll, but the provider has to be set again
+        let mut restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        let result = restored.vote(&poll_id, "wallet_late".parse().unwrap(), "No");
+        assert!(matches!(result, Err(VotingError::SnapshotUnavailable)));
+        restored.set_snapshot_provider(Some(Box::new(ledger)));
+        assert_eq!(restored.voter_weight_in_poll(&poll_id, &"wallet_buyer".parse().unwrap()).unwrap(), 0);
+    }
+}
\ No newline at end of file
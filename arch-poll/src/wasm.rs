@@ -0,0 +1,126 @@
+// WebAssembly bindings for browsers and JS test harnesses (feature `wasm`).
+// The contract is exported to JS through wasm-bindgen as a WasmVotingContract class. Requests and
+// responses are the JSON-RPC documents of the rpc module, handed over as JS values, so a dapp can
+// switch between an in-browser contract and a server without changing its calls. Contract errors
+// come back as JSON-RPC error responses; only params that cannot be turned into JSON, and a
+// malformed admin address given to the constructor, throw.
+// wasm32-unknown-unknown has no system clock, so the contract runs on a clock the host sets with
+// set_time (seconds since the Unix epoch).
+use js_sys::JSON;
+use wasm_bindgen::prelude::*;
+
+use crate::address::Address;
+use crate::clock::MockClock;
+use crate::reporting::json_string;
+use crate::rpc::RpcServer;
+use crate::shared::SharedVotingContract;
+use crate::{VotingContract, VotingError};
+
+// Define a contract driven through JSON, as exposed to JS
+#[wasm_bindgen]
+pub struct WasmVotingContract {
+    server: RpcServer,
+    clock: MockClock,
+}
+
+#[wasm_bindgen]
+impl WasmVotingContract {
+    #[wasm_bindgen(constructor)]
+    pub fn new(admin_address: &str) -> Result<WasmVotingContract, JsValue> {
+        let admin_address: Address = admin_address
+            .parse()
+            .map_err(|error: VotingError| JsValue::from_str(&error.to_string()))?;
+        let clock = MockClock::new(0);
+        let mut contract = VotingContract::new(admin_address);
+        contract.set_clock(Box::new(clock.clone()));
+        Ok(WasmVotingContract {
+            server: RpcServer::new(SharedVotingContract::new(contract)),
+            clock,
+        })
+    }
+    
+    // Set the contract's current time (JS passes BigInt(Math.floor(Date.now() / 1000)))
+    pub fn set_time(&self, timestamp: u64) {
+        self.clock.set(timestamp);
+    }
+    
+    // Run any rpc method with its params object, returning the JSON-RPC response object
+    pub fn call(&self, method: &str, params: JsValue) -> Result<JsValue, JsValue> {
+        let params_json = String::from(JSON::stringify(&params)?);
+        JSON::parse(&self.call_json(method, &params_json))
+    }
+    
+    // Run a JSON-RPC request object or batch, returning the response (null for notifications)
+    pub fn request(&self, request: JsValue) -> Result<JsValue, JsValue> {
+        let body = String::from(JSON::stringify(&request)?);
+        JSON::parse(&self.request_json(&body))
+    }
+    
+    pub fn create_poll(&self, params: JsValue) -> Result<JsValue, JsValue> {
+        self.call("create_poll", params)
+    }
+    
+    pub fn vote(&self, params: JsValue) -> Result<JsValue, JsValue> {
+        self.call("vote", params)
+    }
+    
+    pub fn results(&self, params: JsValue) -> Result<JsValue, JsValue> {
+        self.call("results", params)
+    }
+}
+
+impl WasmVotingContract {
+    // Run any rpc method with its params as JSON text, returning the JSON-RPC response text
+    pub fn call_json(&self, method: &str, params_json: &str) -> String {
+        let request = format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":{},\"params\":{}}}",
+            json_string(method),
+            params_json
+        );
+        self.request_json(&request)
+    }
+    
+    // Run a JSON-RPC request document, returning the response ("null" for notifications)
+    pub fn request_json(&self, body: &str) -> String {
+        self.server.handle_request(body).unwrap_or_else(|| "null".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // JS values only exist inside a WebAssembly host, so the tests drive the JSON text layer the
+    // exported methods wrap
+    #[test]
+    fn test_wasm_bindings() {
+        let contract = WasmVotingContract::new("wallet_admin").unwrap();
+        contract.set_time(1_000);
+        let response = contract.call_json(
+            "create_poll",
+            r#"{"creator_address":"wallet_creator","poll_title":"Lunch","poll_description":"",
+                "poll_options":["Pizza","Tacos"],"poll_duration_seconds":60}"#,
+        );
+        let poll_id = response.split("\"result\":\"").nth(1).unwrap().trim_end_matches("\"}");
+        let response = contract.call_json(
+            "vote",
+            &format!(r#"{{"poll_id":"{}","voter_address":"wallet_a","selected_option":"Tacos"}}"#, poll_id),
+        );
+        assert!(response.contains("\"receipt_hash\""));
+        let response = contract.call_json("results", &format!(r#"{{"poll_id":"{}"}}"#, poll_id));
+        assert!(response.contains("\"Tacos\""));
+        let response = contract.call_json(
+            "vote",
+            &format!(r#"{{"poll_id":"{}","voter_address":"wallet_a","selected_option":"Pizza"}}"#, poll_id),
+        );
+        assert!(response.contains("\"message\":\"AlreadyVoted\""));
+        
+        // Raw request documents pass straight through, and notifications get no response
+        let request = r#"{"jsonrpc":"2.0","id":7,"method":"get_poll","params":{"poll_id":"missing"}}"#;
+        let response = contract.request_json(request);
+        assert!(response.starts_with("{\"jsonrpc\":\"2.0\",\"id\":7,\"error\""));
+        assert!(response.contains("PollNotFound"));
+        let notification = r#"{"jsonrpc":"2.0","method":"get_poll","params":{"poll_id":"missing"}}"#;
+        assert_eq!(contract.request_json(notification), "null");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+// Property tests.
+// Random sequences of poll creations, votes, closures, clock advances and expiry sweeps run against
+// one contract, checking after every step that the tallies agree with the recorded voters, that
+// no tally ever shrinks and that ended polls refuse votes. Every sequence finishes with a snapshot
+// round trip, whose restore re-checks the contract's own invariants. A second property feeds
+// arbitrary bytes to the on-chain program, which must reject them without panicking.
+#![cfg(feature = "std")]
+
+use std::collections::HashMap;
+
+use arch_poll::address::Address;
+use arch_poll::clock::MockClock;
+use arch_poll::encoding;
+use arch_poll::id::{OptionId, PollId};
+use arch_poll::program::{process_instruction, AccountInfo, VotingInstruction};
+use arch_poll::VotingContract;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+// Options on every generated poll; votes may also name one that is not on the ballot
+const OPTIONS: [&str; 3] = ["Yes", "No", "Maybe"];
+
+// Define one step of a generated sequence
+#[derive(Debug, Clone)]
+enum Operation {
+    Create { creator: u8, poll_duration_seconds: u64 },
+    Vote { poll: Index, voter: u8, option: usize },
+    Close { poll: Index, caller: u8 },
+    Advance { seconds: u64 },
+    ProcessExpired,
+    Tick,
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        2 => (0..3u8, 1..120u64).prop_map(|(creator, poll_duration_seconds)| Operation::Create {
+            creator,
+            poll_duration_seconds,
+        }),
+        6 => (any::<Index>(), 0..16u8, 0..=OPTIONS.len()).prop_map(|(poll, voter, option)| Operation::Vote {
+            poll,
+            voter,
+            option,
+        }),
+        1 => (any::<Index>(), 0..3u8).prop_map(|(poll, caller)| Operation::Close { poll, caller }),
+        2 => (1..60u64).prop_map(|seconds| Operation::Advance { seconds }),
+        1 => Just(Operation::ProcessExpired),
+        1 => Just(Operation::Tick),
+    ]
+}
+
+fn address(text: String) -> Address {
+    text.parse().unwrap()
+}
+
+// Check that every poll's tallies agree with its voters and that none shrank since the last step
+fn check_tallies(contract: &VotingContract, previous_counts: &mut HashMap<PollId, HashMap<OptionId, u128>>) {
+    for poll in contract.get_all_polls() {
+        let backing_voters: usize = poll.voter_counts.values().sum();
+        let counted_votes: u128 = poll.vote_counts.values().sum();
+        assert_eq!(backing_voters, poll.participant_addresses.len(), "voters of {}", poll.poll_id);
+        assert_eq!(counted_votes, poll.participant_addresses.len() as u128, "votes of {}", poll.poll_id);
+        
+        if let Some(previous_counts) = previous_counts.get(&poll.poll_id) {
+            for (option, &previous_votes) in previous_counts {
+                assert!(poll.vote_counts[option] >= previous_votes, "{} lost votes for {:?}", poll.poll_id, option);
+            }
+        }
+        previous_counts.insert(poll.poll_id.clone(), poll.vote_counts.clone());
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_operations_keep_tallies_consistent(operations in prop::collection::vec(operation(), 1..60)) {
+        let clock = MockClock::new(1_000);
+        let mut contract = VotingContract::new(address("wallet_admin".to_string()));
+        contract.set_clock(Box::new(clock.clone()));
+        let mut poll_ids: Vec<PollId> = Vec::new();
+        let mut previous_counts = HashMap::new();
+        
+        for operation in operations {
+            match operation {
+                Operation::Create { creator, poll_duration_seconds } => {
+                    let created = contract.create_poll(
+                        address(format!("wallet_creator{}", creator)),
+                        format!("Poll {}", poll_ids.len()),
+                        String::new(),
+                        OPTIONS.iter().map(|option| option.to_string()).collect(),
+                        poll_duration_seconds,
+                    );
+                    poll_ids.extend(created);
+                }
+                Operation::Vote { poll, voter, option } if !poll_ids.is_empty() => {
+                    let poll_id = &poll_ids[poll.index(poll_ids.len())];
+                    let was_active = contract.is_poll_active(poll_id).unwrap();
+                    let selected_option = OPTIONS.get(option).copied().unwrap_or("Not an option");
+                    let voted = contract.vote(poll_id, address(format!("wallet_voter{}", voter)), selected_option);
+                    prop_assert!(was_active || voted.is_err(), "ended poll {} took a vote", poll_id);
+                }
+                Operation::Close { poll, caller } if !poll_ids.is_empty() => {
+                    let poll_id = &poll_ids[poll.index(poll_ids.len())];
+                    let _ = contract.close_poll(poll_id, &address(format!("wallet_creator{}", caller)));
+                }
+                Operation::Advance { seconds } => clock.advance(seconds),
+                Operation::ProcessExpired => {
+                    contract.process_expired_polls(&address("wallet_admin".to_string())).unwrap();
+                }
+                Operation::Tick => {
+                    contract.tick();
+                }
+                Operation::Vote { .. } | Operation::Close { .. } => {}
+            }
+            check_tallies(&contract, &mut previous_counts);
+        }
+        
+        let restored = VotingContract::from_snapshot(&contract.to_snapshot()).unwrap();
+        for poll_id in &poll_ids {
+            let restored_counts = &restored.get_poll(poll_id).unwrap().vote_counts;
+            prop_assert_eq!(restored_counts, &contract.get_poll(poll_id).unwrap().vote_counts);
+        }
+    }
+    
+    #[test]
+    fn arbitrary_instruction_data_is_rejected_cleanly(instruction_data in prop::collection::vec(any::<u8>(), 0..256)) {
+        let _ = encoding::from_bytes::<VotingInstruction>(&instruction_data);
+        
+        let program_id = [7u8; 32];
+        let state_account = AccountInfo::new([1; 32], program_id, false, true, Vec::new());
+        let signer_account = AccountInfo::new([2; 32], [0; 32], true, false, Vec::new());
+        let accounts = [state_account, signer_account];
+        let initialize = VotingInstruction::Initialize.to_instruction_data();
+        process_instruction(&program_id, &accounts, &initialize, Box::new(MockClock::new(1_000))).unwrap();
+        let _ = process_instruction(&program_id, &accounts, &instruction_data, Box::new(MockClock::new(1_000)));
+    }
+}
\ No newline at end of file